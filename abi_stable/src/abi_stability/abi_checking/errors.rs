@@ -151,7 +151,8 @@ impl fmt::Display for AbiInstabilityError {
                     v.display_str(),
                 ),
                 AI::FnQualifierMismatch(v) => (
-                    "function pointers have different qualifiers (`unsafe`, etc.)",
+                    "function pointers have different qualifiers \
+                     (`unsafe`, `extern \"C-unwind\"`, etc.)",
                     v.display_str(),
                 ),
                 AI::FieldLifetimeMismatch(v) => {
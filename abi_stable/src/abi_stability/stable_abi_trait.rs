@@ -1334,7 +1334,10 @@ unsafe impl StableAbi for core_extensions::Void {
 
 /// The layout of `extern "C" fn()` and `unsafe extern "C" fn()`
 macro_rules! empty_extern_fn_layout {
-    ($this:ty) => {{
+    ($this:ty) => {
+        empty_extern_fn_layout!($this, "AFunctionPointer")
+    };
+    ($this:ty, $name:literal) => {{
         make_shared_vars! {
             impl[] $this;
 
@@ -1342,7 +1345,7 @@ macro_rules! empty_extern_fn_layout {
         }
         const MONO_TL_EXTERN_FN: &'static MonoTypeLayout = &MonoTypeLayout::new(
             *mono_shared_vars,
-            rstr!("AFunctionPointer"),
+            rstr!($name),
             make_item_info!(),
             MonoTLData::Opaque,
         tl_genparams!(;;),
@@ -1384,6 +1387,40 @@ unsafe impl StableAbi for unsafe extern "C" fn() {
     const LAYOUT: &'static TypeLayout = empty_extern_fn_layout!(unsafe extern "C" fn());
 }
 
+/// This is the only `extern "C-unwind"` function type that implements StableAbi
+/// so as to make it more obvious that functions involving lifetimes
+/// cannot implement this trait directly (because of higher ranked trait bounds).
+///
+/// `#[derive(StableAbi)]` stores the ABI of function pointers in the fields of
+/// the type in the `TLFunction` for each function pointer,
+/// which is why it doesn't require this impl.
+#[cfg(feature = "rust_1_71")]
+unsafe impl GetStaticEquivalent_ for extern "C-unwind" fn() {
+    type StaticEquivalent = Self;
+}
+#[cfg(feature = "rust_1_71")]
+unsafe impl StableAbi for extern "C-unwind" fn() {
+    type IsNonZeroType = True;
+
+    const LAYOUT: &'static TypeLayout =
+        empty_extern_fn_layout!(extern "C-unwind" fn(), "AnUnwindingFunctionPointer");
+}
+
+/// This is the only `extern "C-unwind"` function type that implements StableAbi
+/// so as to make it more obvious that functions involving lifetimes
+/// cannot implement this trait directly (because of higher ranked trait bounds).
+#[cfg(feature = "rust_1_71")]
+unsafe impl GetStaticEquivalent_ for unsafe extern "C-unwind" fn() {
+    type StaticEquivalent = Self;
+}
+#[cfg(feature = "rust_1_71")]
+unsafe impl StableAbi for unsafe extern "C-unwind" fn() {
+    type IsNonZeroType = True;
+
+    const LAYOUT: &'static TypeLayout =
+        empty_extern_fn_layout!(unsafe extern "C-unwind" fn(), "AnUnwindingFunctionPointer");
+}
+
 /// A function that returns the TypeLayout of an `unsafe extern "C" fn()`
 #[doc(hidden)]
 pub const UNSAFE_EXTERN_FN_LAYOUT: extern "C" fn() -> &'static TypeLayout =
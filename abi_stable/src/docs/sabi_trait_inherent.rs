@@ -34,12 +34,20 @@ generated by the [`sabi_trait`] attribute.
 
 -[`from_const`](#from_const-method)
 
+-[`from_ptr_with_capabilities`](#from_ptr_with_capabilities-method)
+
+-[`from_value_with_capabilities`](#from_value_with_capabilities-method)
+
 -[`from_sabi`](#from_sabi-method)
 
 -[`sabi_reborrow_mut`](#sabi_reborrow_mut-method)
 
 -[`sabi_reborrow`](#sabi_reborrow-method)
 
+-[`upcast_to_<supertrait>`](#upcast_to_supertrait-methods)
+
+-[`sabi_has_<method>`](#sabi_has_method-methods)
+
 
 
 
@@ -59,6 +67,9 @@ The `can_it_downcast` parameter describes whether the trait object can be
 converted back into the original type or not.<br>
 Its possible values are [`TD_CanDowncast`] and [`TD_Opaque`].
 
+If the trait has methods that return `Self`,
+the pointer must implement [`PtrFromValue`](crate::pointer_trait::PtrFromValue).
+
 [Method docs for `Action_TO::from_ptr`
 ](../../sabi_trait/doc_examples/struct.Action_TO.html#method.from_ptr)
 
@@ -180,6 +191,8 @@ impl<'lt, 'sub, …> Trait_TO<'lt, RRef<'sub, ()>, …> {
 Const-constructs `<trait>_TO` from a constant that
 implements `<trait>`,
 
+This can't be called for traits with methods that return `Self`.
+
 The `can_it_downcast` parameter describes whether the trait object can be
 converted back into the original type or not.<br>
 Its possible values are [`TD_CanDowncast`] and [`TD_Opaque`].
@@ -202,6 +215,102 @@ assert_eq!(TO.get(), 200);
 ```
 
 
+## `from_ptr_with_capabilities` method
+
+```text
+impl<'lt, ErasedPtr, …> Trait_TO<'lt, ErasedPtr, …> {
+    pub fn from_ptr_with_capabilities<Ptr, Downcasting, Caps>(
+        pointer: Ptr,
+        can_it_downcast: Downcasting,
+        capabilities: Capabilities<Caps>,
+    ) -> Self
+```
+
+Constructs `<trait>_TO` like [`from_ptr`](#from_ptr-method) does,
+also registering the vtables of the trait objects in `Caps` (a tuple of trait objects),
+so that they can be queried with the [`RObject::query`],
+[`RObject::query_mut`], and [`RObject::query_into`] methods.
+
+The pointed-to type must implement the traits of all the trait objects in `Caps`.
+
+Only trait objects of traits annotated with the [`sabi_trait`] attribute
+that have neither lifetime parameters nor associated types,
+and don't use the `#[sabi(use_dyntrait)]` attribute,
+can be registered as capabilities.
+This method isn't generated for traits that use the `#[sabi(use_dyntrait)]` attribute.
+
+The trait objects returned by querying can be downcasted if `<trait>_TO` could,
+but have no capabilities of their own.
+
+[Method docs for `Action_TO::from_ptr_with_capabilities`
+](../../sabi_trait/doc_examples/struct.Action_TO.html#method.from_ptr_with_capabilities)
+
+**Example**:
+```rust
+use abi_stable::{
+    sabi_trait::{
+        doc_examples::{Action_TO, Doer_TO},
+        Capabilities,
+    },
+    std_types::RArc,
+    type_level::downcasting::TD_CanDowncast,
+};
+
+let object: Action_TO<'static, RArc<()>> = Action_TO::from_ptr_with_capabilities(
+    RArc::new(3_usize),
+    TD_CanDowncast,
+    Capabilities::<(Doer_TO<'_, RArc<()>>,)>::NEW,
+);
+
+let doer: Doer_TO<'static, RArc<()>> = object.obj.query_into().ok().unwrap();
+assert_eq!(doer.value(), 3);
+assert_eq!(doer.obj.downcast_as::<usize>().ok(), Some(&3));
+
+```
+
+## `from_value_with_capabilities` method
+
+```text
+impl<'lt, …> Trait_TO<'lt, RBox<()>, …> {
+    pub fn from_value_with_capabilities<_OrigPtr, Downcasting, Caps>(
+        pointer: _OrigPtr,
+        can_it_downcast: Downcasting,
+        capabilities: Capabilities<Caps>,
+    ) -> Self
+```
+
+Constructs `<trait>_TO` like [`from_value`](#from_value-method) does,
+also registering the vtables of the trait objects in `Caps`,
+with the same restrictions as
+[`from_ptr_with_capabilities`](#from_ptr_with_capabilities-method).
+
+[Method docs for `Action_TO::from_value_with_capabilities`
+](../../sabi_trait/doc_examples/struct.Action_TO.html#method.from_value_with_capabilities)
+
+**Example**:
+```rust
+use abi_stable::{
+    sabi_trait::{
+        doc_examples::{Action_TO, Doer_TO},
+        Capabilities,
+    },
+    std_types::RBox,
+    type_level::downcasting::TD_Opaque,
+    RRef,
+};
+
+let object = Action_TO::from_value_with_capabilities(
+    100_usize,
+    TD_Opaque,
+    Capabilities::<(Doer_TO<'_, RBox<()>>,)>::NEW,
+);
+
+let doer = object.obj.query::<Doer_TO<'_, RRef<'_, ()>>>().unwrap();
+assert_eq!(doer.do_it(5), 105);
+
+```
+
+
 ## `from_sabi` method
 
 ```text
@@ -356,6 +465,135 @@ where
 
 ```
 
+## `upcast_to_<supertrait>` methods
+
+```text
+impl<'lt, ErasedPtr, …> Trait_TO<'lt, ErasedPtr, …> {
+    pub fn upcast_to_Supertrait(self) -> Supertrait_TO<'lt, ErasedPtr, …> {
+```
+
+Converts a `<trait>_TO` into the trait object of one of its supertraits
+that is also annotated with the [`sabi_trait`] attribute.
+
+One of these methods is generated for each such supertrait.
+
+The vtable of `<trait>_TO` contains the vtable of the supertrait,
+so this conversion is free,
+and the returned trait object can be downcasted if `<trait>_TO` could.
+
+The module that the [`sabi_trait`] attribute generates for the supertrait
+(`<supertrait>_trait`) must be accessible through the path used in the supertrait bound,
+eg: `trait Storage: io::Reader` requires `io::Reader_trait` to be accessible.
+
+This can't upcast into supertraits with associated types,
+nor into supertraits that use the `#[sabi(use_dyntrait)]` attribute.
+
+**Example**:
+```rust
+use abi_stable::{
+    sabi_trait, std_types::RBox, type_level::downcasting::TD_CanDowncast,
+};
+
+#[sabi_trait]
+pub trait Reader {
+    fn read(&self) -> u32;
+}
+
+#[sabi_trait]
+#[sabi(supertrait(Reader))]
+pub trait Storage: Reader {
+    fn write(&mut self, value: u32);
+}
+
+impl Reader for u32 {
+    fn read(&self) -> u32 {
+        *self
+    }
+}
+
+impl Storage for u32 {
+    fn write(&mut self, value: u32) {
+        *self = value;
+    }
+}
+
+# fn main() {
+let mut storage: Storage_TO<'static, RBox<()>> =
+    Storage_TO::from_value(3_u32, TD_CanDowncast);
+
+storage.write(5);
+
+let reader: Reader_TO<'static, RBox<()>> = storage.upcast_to_Reader();
+assert_eq!(reader.read(), 5);
+
+assert_eq!(reader.obj.downcast_into::<u32>().unwrap(), RBox::new(5));
+# }
+```
+
+## `sabi_has_<method>` methods
+
+```text
+impl<'lt, ErasedPtr, …> Trait_TO<'lt, ErasedPtr, …> {
+    pub fn sabi_has_method(&self) -> bool {
+```
+
+Queries whether the vtable of the trait object has the `method` method.
+
+One of these methods is generated for each method in the trait.
+
+This returns false when the trait object was constructed in a dynamic library
+compiled against an older version of the trait,
+which didn't have the method yet.
+Calling such a method on the trait object uses the default implementation
+(within the trait definition that [`sabi_trait`] can see) if there is one,
+otherwise it panics.
+
+This uses the field accessibility of the prefix-type vtable,
+it doesn't check whether the implementor overrides the default implementation of the method.
+
+**Example**:
+```rust
+use abi_stable::{
+    sabi_trait, std_types::RBox, type_level::downcasting::TD_Opaque,
+};
+
+#[sabi_trait]
+pub trait Plugin {
+    #[sabi(last_prefix_field)]
+    fn run(&self) -> u32;
+
+    // added in a minor version
+    fn reload(&self) -> u32;
+}
+
+fn reload_or_run(plugin: &Plugin_TO<'_, RBox<()>>) -> u32 {
+    if plugin.sabi_has_reload() {
+        plugin.reload()
+    } else {
+        plugin.run()
+    }
+}
+
+struct Counter;
+
+impl Plugin for Counter {
+    fn run(&self) -> u32 {
+        3
+    }
+    fn reload(&self) -> u32 {
+        5
+    }
+}
+
+# fn main() {
+let plugin = Plugin_TO::from_value(Counter, TD_Opaque);
+
+assert!(plugin.sabi_has_run());
+assert!(plugin.sabi_has_reload());
+assert_eq!(reload_or_run(&plugin), 5);
+# }
+```
+
 
 
 
@@ -378,6 +616,12 @@ where
 
 [`RBox`]: ../../std_types/struct.RBox.html
 
+[`RObject::query`]: ../../sabi_trait/struct.RObject.html#method.query
+
+[`RObject::query_mut`]: ../../sabi_trait/struct.RObject.html#method.query_mut
+
+[`RObject::query_into`]: ../../sabi_trait/struct.RObject.html#method.query_into
+
 [`Action_TO`]: ../../sabi_trait/doc_examples/struct.Action_TO.html
 
 [`TD_CanDowncast`]: ../../type_level/downcasting/struct.TD_CanDowncast.html
@@ -29,6 +29,8 @@ pub(crate) mod type_info;
 
 pub(crate) mod iterator;
 
+pub(crate) mod key_fns;
+
 pub(crate) mod dyn_trait;
 
 #[macro_use]
@@ -42,8 +44,8 @@ pub use crate::DynTrait;
 pub use self::{
     dyn_trait::UneraseError,
     traits::{
-        DeserializeDyn, InterfaceType, IteratorItem, IteratorItemOrDefault, SerializeProxyType,
-        SerializeType,
+        DeserializeDyn, InterfaceType, IteratorItem, IteratorItemOrDefault, SabiKey, SabiKeyType,
+        SerializeProxyType, SerializeType,
     },
     type_info::TypeInfo,
     vtable::{MakeRequiredTraits, RequiredTraits},
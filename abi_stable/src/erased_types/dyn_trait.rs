@@ -137,6 +137,10 @@ mod priv_ {
     /// - [`serde::Serialize`]:
     ///     first calls the objects' Deserialize impl, then serializes that as a string.
     ///
+    /// - [`SabiKey`](crate::erased_types::SabiKey):
+    ///     makes `PartialEq`/`Ord`/`PartialOrd`/`Hash` compare and hash the keys of the values,
+    ///     even when they are of different types.
+    ///
     /// ###  Deconstruction
     ///
     /// `DynTrait<_>` can then be unwrapped into a concrete type,
@@ -242,6 +246,12 @@ mod priv_ {
     ///
     /// ```
     ///
+    /// DynTraits wrapping values of different types are never equal,
+    /// unless the [`InterfaceType`] requires the
+    /// [`SabiKey`](crate::erased_types::SabiKey) trait,
+    /// in which case the keys of the values are compared instead
+    /// (the [`SabiKeyType`](crate::erased_types::SabiKeyType) docs have an example).
+    ///
     /// ###  Writing to a DynTrait
     ///
     /// This is an example of using the `write!()` macro with DynTrait.
@@ -829,6 +839,54 @@ mod priv_ {
                     .type_info()
                     .is_compatible(other.sabi_vtable().type_info())
         }
+
+        /// Compares the keys of 2 `DynTrait`s that wrap different types,
+        /// returning `false` if `I` doesn't require `SabiKey`.
+        pub(super) fn sabi_eq_different_types<P2, EV2>(
+            &self,
+            other: &DynTrait<'static, P2, I, EV2>,
+        ) -> bool
+        where
+            I: InterfaceType,
+            P: AsPtr,
+            P2: AsPtr,
+        {
+            match (self.sabi_vtable().key_fns(), other.sabi_vtable().key_fns()) {
+                (Some(key_fns), Some(other_fns)) => unsafe {
+                    (key_fns.eq)(
+                        self.sabi_erased_ref(),
+                        other.sabi_erased_ref(),
+                        other_fns.key,
+                    )
+                },
+                _ => false,
+            }
+        }
+
+        /// Compares the keys of 2 `DynTrait`s that wrap different types,
+        /// falling back to comparing the addresses of their vtables
+        /// if `I` doesn't require `SabiKey`.
+        pub(super) fn sabi_cmp_different_types<P2, EV2>(
+            &self,
+            other: &DynTrait<'static, P2, I, EV2>,
+        ) -> Ordering
+        where
+            I: InterfaceType,
+            P: AsPtr,
+            P2: AsPtr,
+        {
+            match (self.sabi_vtable().key_fns(), other.sabi_vtable().key_fns()) {
+                (Some(key_fns), Some(other_fns)) => unsafe {
+                    (key_fns.cmp)(
+                        self.sabi_erased_ref(),
+                        other.sabi_erased_ref(),
+                        other_fns.key,
+                    )
+                    .into()
+                },
+                _ => self.sabi_vtable_address().cmp(&other.sabi_vtable_address()),
+            }
+        }
     }
 
     impl<'borr, P, I, EV> DynTrait<'borr, P, I, PrefixRef<EV>>
@@ -1591,6 +1649,19 @@ mod priv_ {
         }
     }
 
+    impl<'lt, I, EV: Clone> DynTrait<'lt, crate::std_types::RRc<()>, I, EV> {
+        /// Does a shallow clone of the object, just incrementing the reference counter
+        pub fn shallow_clone(&self) -> Self {
+            Self {
+                object: self.object.clone(),
+                vtable: self.vtable,
+                extra_value: self.extra_value.clone(),
+                _marker: self._marker,
+                _marker2: self._marker2,
+            }
+        }
+    }
+
     impl<'borr, P, I, EV> Drop for DynTrait<'borr, P, I, EV>
     where
         P: GetPointerKind,
@@ -1771,7 +1842,7 @@ where
     fn eq(&self, other: &DynTrait<'static, P2, I, EV2>) -> bool {
         // unsafe: must check that the vtable is the same, otherwise return a sensible value.
         if !self.sabi_is_same_type(other) {
-            return false;
+            return self.sabi_eq_different_types(other);
         }
 
         unsafe { self.sabi_vtable().partial_eq()(self.sabi_erased_ref(), other.sabi_erased_ref()) }
@@ -1787,7 +1858,7 @@ where
     fn cmp(&self, other: &Self) -> Ordering {
         // unsafe: must check that the vtable is the same, otherwise return a sensible value.
         if !self.sabi_is_same_type(other) {
-            return self.sabi_vtable_address().cmp(&other.sabi_vtable_address());
+            return self.sabi_cmp_different_types(other);
         }
 
         unsafe { self.sabi_vtable().cmp()(self.sabi_erased_ref(), other.sabi_erased_ref()).into() }
@@ -1804,7 +1875,7 @@ where
     fn partial_cmp(&self, other: &DynTrait<'static, P2, I, EV2>) -> Option<Ordering> {
         // unsafe: must check that the vtable is the same, otherwise return a sensible value.
         if !self.sabi_is_same_type(other) {
-            return Some(self.sabi_vtable_address().cmp(&other.sabi_vtable_address()));
+            return Some(self.sabi_cmp_different_types(other));
         }
 
         unsafe {
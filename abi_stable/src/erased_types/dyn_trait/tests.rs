@@ -16,7 +16,7 @@ use serde_json;
 #[allow(unused_imports)]
 use crate::{
     erased_types::{DynTrait, InterfaceType, IteratorItem},
-    std_types::{RArc, RBox, RBoxError, RCow, RNone, ROption, RSome, RStr, RString},
+    std_types::{RArc, RBox, RBoxError, RCow, RNone, ROption, RRc, RSome, RStr, RString},
     test_utils::{GetImpls, GetImplsHelper},
     traits::IntoReprC,
     type_level::bools::{False, True},
@@ -416,6 +416,111 @@ fn hash_test() {
     }
 }
 
+//////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(impl_InterfaceType(Debug, SabiKey, PartialEq, Eq, Ord, PartialOrd, Hash))]
+struct KeyInterface;
+
+impl crate::erased_types::SabiKeyType for KeyInterface {
+    type Key = RString;
+}
+
+#[derive(Debug)]
+struct KeyedNumber(u32);
+
+impl crate::erased_types::SabiKey<KeyInterface> for KeyedNumber {
+    fn sabi_key(&self) -> RString {
+        self.0.to_string().into()
+    }
+}
+
+#[derive(Debug)]
+struct KeyedName(&'static str);
+
+impl crate::erased_types::SabiKey<KeyInterface> for KeyedName {
+    fn sabi_key(&self) -> RString {
+        self.0.into()
+    }
+}
+
+#[test]
+fn key_cmp_test() {
+    let num_10 = DynTraitBox::<KeyInterface>::from_value(KeyedNumber(10));
+    let num_3 = DynTraitBox::<KeyInterface>::from_value(KeyedNumber(3));
+    let name_10 = DynTraitBox::<KeyInterface>::from_value(KeyedName("10"));
+    let name_5 = DynTraitBox::<KeyInterface>::from_value(KeyedName("5"));
+
+    // values of the same type
+    assert_eq!(num_10, DynTraitBox::from_value(KeyedNumber(10)));
+    assert_eq!(num_10.cmp(&num_3), Ordering::Less);
+    assert_eq!(num_3.partial_cmp(&num_10), Some(Ordering::Greater));
+    assert_ne!(num_10, num_3);
+
+    // values of different types
+    assert_eq!(num_10, name_10);
+    assert_eq!(name_10, num_10);
+    assert_eq!(num_10.cmp(&name_10), Ordering::Equal);
+    assert_eq!(num_3.cmp(&name_5), Ordering::Less);
+    assert_eq!(name_5.cmp(&num_3), Ordering::Greater);
+    assert_eq!(name_5.partial_cmp(&num_10), Some(Ordering::Greater));
+    assert_ne!(num_3, name_5);
+
+    {
+        let reborrow_num = num_10.reborrow();
+        let reborrow_name = name_10.reborrow();
+        assert_eq!(reborrow_num, reborrow_name);
+        assert_eq!(num_10, reborrow_name);
+    }
+
+    let mut sorted = vec![name_5, num_3, name_10, num_10];
+    sorted.sort();
+    assert_eq!(
+        sorted
+            .iter()
+            .map(|x| format!("{:?}", x))
+            .collect::<Vec<_>>(),
+        [
+            "KeyedName(\"10\")",
+            "KeyedNumber(10)",
+            "KeyedNumber(3)",
+            "KeyedName(\"5\")",
+        ],
+    );
+}
+
+#[test]
+fn key_hash_test() {
+    use crate::std_types::RHashMap;
+
+    fn hash_value<H: Hash>(v: &H) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        v.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let num = DynTraitBox::<KeyInterface>::from_value(KeyedNumber(8));
+    let name = DynTraitBox::<KeyInterface>::from_value(KeyedName("8"));
+
+    assert_eq!(hash_value(&num), hash_value(&RString::from("8")));
+    assert_eq!(hash_value(&num), hash_value(&name));
+
+    let mut map = RHashMap::new();
+    map.insert(num, 'a');
+    map.insert(DynTraitBox::from_value(KeyedName("foo")), 'b');
+
+    assert_eq!(map.get(&name), Some(&'a'));
+    assert_eq!(
+        map.get(&DynTraitBox::from_value(KeyedName("foo"))),
+        Some(&'b')
+    );
+    assert_eq!(map.get(&DynTraitBox::from_value(KeyedNumber(9))), None);
+
+    assert_eq!(map.insert(name, 'c'), RSome('a'));
+    assert_eq!(map.len(), 2);
+}
+
 #[test]
 fn to_any_test() {
     let mut wrapped = DynTrait::from_value(new_foo()).interface(FooInterface);
@@ -448,6 +553,26 @@ fn to_any_test() {
     }
 }
 
+#[test]
+fn rc_pointer_test() {
+    let wrapped: DynTrait<'static, RRc<()>, FooInterface> =
+        DynTrait::from_ptr(RRc::new(new_foo())).interface(FooInterface);
+
+    let shallow = wrapped.shallow_clone();
+    assert!(std::ptr::eq(
+        wrapped.sabi_erased_ref().get(),
+        shallow.sabi_erased_ref().get()
+    ));
+    assert_eq!(wrapped, shallow);
+    assert_eq!(format!("{}", wrapped), format!("{}", new_foo()));
+
+    drop(shallow);
+    assert_eq!(
+        wrapped.downcast_into::<Foo<String>>().unwrap(),
+        RRc::new(new_foo())
+    );
+}
+
 //////////////////////////////////////////////////////////////////////
 
 mod borrowing {
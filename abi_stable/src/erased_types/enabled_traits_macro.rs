@@ -5,7 +5,12 @@ macro_rules! declare_enabled_traits {
         ]
 
         regular_traits[
-            $(($regular_trait:ident, $regular_trait_query:ident, $regular_trait_path:path)),* $(,)*
+            $((
+                $regular_trait:ident,
+                $regular_trait_query:ident,
+                $regular_trait_path:path
+                $(, $regular_trait_bit:expr)?
+            )),* $(,)*
         ]
     ) => (
         use crate::{
@@ -30,9 +35,11 @@ macro_rules! declare_enabled_traits {
 
         #[allow(non_upper_case_globals)]
         mod regular_trait_mask{
+            // The bits of traits that were added after `Deserialize` are explicit,
+            // so that the bits of the other traits don't change.
             #[repr(u32)]
             enum __Index {
-                $($regular_trait,)*
+                $($regular_trait $(= $regular_trait_bit)?,)*
             }
             $(pub(super) const $regular_trait: u64 = 1u64 << __Index::$regular_trait as u32;)*
         }
@@ -82,6 +82,38 @@ pub struct IoWriteInterface;
 
 //////////////////////////////////////////////
 
+/// Implements `InterfaceType`, requiring `Send + std::io::Write`
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(impl_InterfaceType(Send, IoWrite))]
+pub struct IoWriteSendInterface;
+
+//////////////////////////////////////////////
+
+/// Implements `InterfaceType`, requiring `Send + std::io::Read`
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(impl_InterfaceType(Send, IoRead))]
+pub struct IoReadInterface;
+
+//////////////////////////////////////////////
+
+/// Implements `InterfaceType`, requiring `Send + std::io::Read + std::io::Seek`
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(impl_InterfaceType(Send, IoRead, IoSeek))]
+pub struct IoReadSeekInterface;
+
+//////////////////////////////////////////////
+
+/// Implements `InterfaceType`, requiring `Send + std::io::BufRead`
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(impl_InterfaceType(Send, IoRead, IoBufRead))]
+pub struct IoBufReadInterface;
+
+//////////////////////////////////////////////
+
 /// Implements `InterfaceType`, requiring `Send + Sync + Debug + Display`
 #[repr(C)]
 #[derive(StableAbi)]
@@ -0,0 +1,280 @@
+//! The functions that `DynTrait` uses to compare and hash values by key,
+//! when its `InterfaceType` requires the `SabiKey` trait.
+
+use std::hash::Hash;
+
+use super::{
+    c_functions::{cmp_ord, hash_Hash, partial_cmp_ord, partial_eq_impl},
+    trait_objects::HasherObject,
+    traits::{SabiKey, SabiKeyType},
+};
+
+use crate::{
+    marker_type::{ErasedObject, NonOwningPhantom},
+    sabi_types::RRef,
+    std_types::{RCmpOrdering, ROption, RSome},
+    traits::IntoReprC,
+    type_level::{
+        impl_enum::{Implemented, Unimplemented},
+        trait_marker,
+    },
+    utils::Transmuter,
+};
+
+///////////////////////////////////////////////////////////////////////////////////
+
+/// The functions used to compare values of different types by key.
+///
+/// The `other_key` parameters are the `key` function of the vtable of `other`,
+/// since `other` may be of a type that this function doesn't know about.
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct KeyFns<K> {
+    pub(super) key: KeyFn<K>,
+    pub(super) eq: unsafe extern "C" fn(
+        RRef<'_, ErasedObject>,
+        RRef<'_, ErasedObject>,
+        other_key: KeyFn<K>,
+    ) -> bool,
+    pub(super) cmp: unsafe extern "C" fn(
+        RRef<'_, ErasedObject>,
+        RRef<'_, ErasedObject>,
+        other_key: KeyFn<K>,
+    ) -> RCmpOrdering,
+}
+
+impl<K> Copy for KeyFns<K> {}
+impl<K> Clone for KeyFns<K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// Gets the key of an erased value.
+#[repr(transparent)]
+#[derive(StableAbi)]
+pub struct KeyFn<K> {
+    func: unsafe extern "C" fn(RRef<'_, ErasedObject>) -> K,
+}
+
+impl<K> Copy for KeyFn<K> {}
+impl<K> Clone for KeyFn<K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////
+
+pub struct MakeKeyFns<T, I>(NonOwningPhantom<(T, I)>);
+
+impl<T, I> MakeKeyFns<T, I>
+where
+    T: SabiKey<I>,
+    I: SabiKeyType,
+{
+    const FNS: KeyFns<I::Key> = KeyFns {
+        key: KeyFn { func: key::<T, I> },
+        eq: key_eq::<T, I>,
+        cmp: key_cmp::<T, I>,
+    };
+
+    pub(super) const NEW: KeyFns<()> = unsafe { Transmuter { from: Self::FNS }.to };
+}
+
+///////////////////////////////////////////////////////////////////////////////////
+
+// These traits choose the vtable entries for the `PartialEq`, `Ord`, `PartialOrd`,
+// and `Hash` traits, which go through the key when `I` requires the `SabiKey` trait.
+
+#[doc(hidden)]
+pub trait KeyOrPartialEq<T, I> {
+    const FN: unsafe extern "C" fn(RRef<'_, ErasedObject>, RRef<'_, ErasedObject>) -> bool;
+}
+
+impl<T, I> KeyOrPartialEq<T, I> for Unimplemented<trait_marker::SabiKey>
+where
+    T: PartialEq,
+{
+    const FN: unsafe extern "C" fn(RRef<'_, ErasedObject>, RRef<'_, ErasedObject>) -> bool =
+        partial_eq_impl::<T>;
+}
+
+impl<T, I> KeyOrPartialEq<T, I> for Implemented<trait_marker::SabiKey>
+where
+    T: SabiKey<I>,
+    I: SabiKeyType,
+{
+    const FN: unsafe extern "C" fn(RRef<'_, ErasedObject>, RRef<'_, ErasedObject>) -> bool =
+        same_type_key_eq::<T, I>;
+}
+
+#[doc(hidden)]
+pub trait KeyOrOrd<T, I> {
+    const FN: unsafe extern "C" fn(RRef<'_, ErasedObject>, RRef<'_, ErasedObject>) -> RCmpOrdering;
+}
+
+impl<T, I> KeyOrOrd<T, I> for Unimplemented<trait_marker::SabiKey>
+where
+    T: Ord,
+{
+    const FN: unsafe extern "C" fn(RRef<'_, ErasedObject>, RRef<'_, ErasedObject>) -> RCmpOrdering =
+        cmp_ord::<T>;
+}
+
+impl<T, I> KeyOrOrd<T, I> for Implemented<trait_marker::SabiKey>
+where
+    T: SabiKey<I>,
+    I: SabiKeyType,
+{
+    const FN: unsafe extern "C" fn(RRef<'_, ErasedObject>, RRef<'_, ErasedObject>) -> RCmpOrdering =
+        same_type_key_cmp::<T, I>;
+}
+
+#[doc(hidden)]
+pub trait KeyOrPartialOrd<T, I> {
+    const FN: unsafe extern "C" fn(
+        RRef<'_, ErasedObject>,
+        RRef<'_, ErasedObject>,
+    ) -> ROption<RCmpOrdering>;
+}
+
+impl<T, I> KeyOrPartialOrd<T, I> for Unimplemented<trait_marker::SabiKey>
+where
+    T: PartialOrd,
+{
+    const FN: unsafe extern "C" fn(
+        RRef<'_, ErasedObject>,
+        RRef<'_, ErasedObject>,
+    ) -> ROption<RCmpOrdering> = partial_cmp_ord::<T>;
+}
+
+impl<T, I> KeyOrPartialOrd<T, I> for Implemented<trait_marker::SabiKey>
+where
+    T: SabiKey<I>,
+    I: SabiKeyType,
+{
+    const FN: unsafe extern "C" fn(
+        RRef<'_, ErasedObject>,
+        RRef<'_, ErasedObject>,
+    ) -> ROption<RCmpOrdering> = same_type_key_partial_cmp::<T, I>;
+}
+
+#[doc(hidden)]
+pub trait KeyOrHash<T, I> {
+    const FN: unsafe extern "C" fn(RRef<'_, ErasedObject>, HasherObject<'_>);
+}
+
+impl<T, I> KeyOrHash<T, I> for Unimplemented<trait_marker::SabiKey>
+where
+    T: Hash,
+{
+    const FN: unsafe extern "C" fn(RRef<'_, ErasedObject>, HasherObject<'_>) = hash_Hash::<T>;
+}
+
+impl<T, I> KeyOrHash<T, I> for Implemented<trait_marker::SabiKey>
+where
+    T: SabiKey<I>,
+    I: SabiKeyType,
+{
+    const FN: unsafe extern "C" fn(RRef<'_, ErasedObject>, HasherObject<'_>) = key_hash::<T, I>;
+}
+
+///////////////////////////////////////////////////////////////////////////////////
+
+unsafe extern "C" fn key<T, I>(this: RRef<'_, ErasedObject>) -> I::Key
+where
+    T: SabiKey<I>,
+    I: SabiKeyType,
+{
+    extern_fn_panic_handling! {no_early_return;
+        let this = unsafe { this.transmute_into_ref::<T>() };
+        this.sabi_key()
+    }
+}
+
+unsafe extern "C" fn key_eq<T, I>(
+    this: RRef<'_, ErasedObject>,
+    other: RRef<'_, ErasedObject>,
+    other_key: KeyFn<I::Key>,
+) -> bool
+where
+    T: SabiKey<I>,
+    I: SabiKeyType,
+{
+    extern_fn_panic_handling! {no_early_return;
+        let this = unsafe { this.transmute_into_ref::<T>() };
+        this.sabi_key() == unsafe { (other_key.func)(other) }
+    }
+}
+
+unsafe extern "C" fn key_cmp<T, I>(
+    this: RRef<'_, ErasedObject>,
+    other: RRef<'_, ErasedObject>,
+    other_key: KeyFn<I::Key>,
+) -> RCmpOrdering
+where
+    T: SabiKey<I>,
+    I: SabiKeyType,
+{
+    extern_fn_panic_handling! {no_early_return;
+        let this = unsafe { this.transmute_into_ref::<T>() };
+        this.sabi_key().cmp(&unsafe { (other_key.func)(other) }).into_c()
+    }
+}
+
+unsafe extern "C" fn same_type_key_eq<T, I>(
+    this: RRef<'_, ErasedObject>,
+    other: RRef<'_, ErasedObject>,
+) -> bool
+where
+    T: SabiKey<I>,
+    I: SabiKeyType,
+{
+    extern_fn_panic_handling! {no_early_return;
+        let this = unsafe { this.transmute_into_ref::<T>() };
+        let other = unsafe { other.transmute_into_ref::<T>() };
+        this.sabi_key() == other.sabi_key()
+    }
+}
+
+unsafe extern "C" fn same_type_key_cmp<T, I>(
+    this: RRef<'_, ErasedObject>,
+    other: RRef<'_, ErasedObject>,
+) -> RCmpOrdering
+where
+    T: SabiKey<I>,
+    I: SabiKeyType,
+{
+    extern_fn_panic_handling! {no_early_return;
+        let this = unsafe { this.transmute_into_ref::<T>() };
+        let other = unsafe { other.transmute_into_ref::<T>() };
+        this.sabi_key().cmp(&other.sabi_key()).into_c()
+    }
+}
+
+unsafe extern "C" fn same_type_key_partial_cmp<T, I>(
+    this: RRef<'_, ErasedObject>,
+    other: RRef<'_, ErasedObject>,
+) -> ROption<RCmpOrdering>
+where
+    T: SabiKey<I>,
+    I: SabiKeyType,
+{
+    extern_fn_panic_handling! {no_early_return;
+        let this = unsafe { this.transmute_into_ref::<T>() };
+        let other = unsafe { other.transmute_into_ref::<T>() };
+        RSome(this.sabi_key().cmp(&other.sabi_key()).into_c())
+    }
+}
+
+unsafe extern "C" fn key_hash<T, I>(this: RRef<'_, ErasedObject>, mut state: HasherObject<'_>)
+where
+    T: SabiKey<I>,
+    I: SabiKeyType,
+{
+    extern_fn_panic_handling! {no_early_return;
+        let this = unsafe { this.transmute_into_ref::<T>() };
+        this.sabi_key().hash(&mut state);
+    }
+}
@@ -1,6 +1,8 @@
 //! Traits for types wrapped in `DynTrait<_>`
 
-use crate::std_types::RBoxError;
+use std::hash::Hash;
+
+use crate::{std_types::RBoxError, StableAbi};
 
 #[allow(unused_imports)]
 use crate::type_level::{
@@ -121,6 +123,8 @@ declare_InterfaceType! {
     ///     // type IoBufRead = Unimplemented<trait_marker::IoBufRead>;
     ///
     ///     // type Error = Unimplemented<trait_marker::Error>;
+    ///
+    ///     // type SabiKey = Unimplemented<trait_marker::SabiKey>;
     /// }
     /// */
     ///
@@ -198,6 +202,10 @@ declare_InterfaceType! {
 
         /// For the `std::error::Error` trait
         type Error;
+
+        /// For comparing and hashing values through the key returned by [`SabiKey`],
+        /// instead of the `PartialEq`/`Ord`/`PartialOrd`/`Hash` impls of the values.
+        type SabiKey;
     ]
 
 
@@ -318,6 +326,129 @@ where
 
 ///////////////////////////////////////
 
+/// Determines the key that [`DynTrait`]s are compared and hashed by,
+/// for [`InterfaceType`]s that require the [`SabiKey`] trait.
+///
+/// With the key, [`DynTrait`]s that wrap different types
+/// (which can come from different dynamic libraries)
+/// are equal if their keys are equal, and are ordered and hashed by their keys.
+///
+/// The `PartialEq`, `Ord`, `PartialOrd`, and `Hash` traits of the [`InterfaceType`]
+/// go through the key, and don't need to be implemented by the wrapped types.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::{
+///     erased_types::{SabiKey, SabiKeyType},
+///     std_types::{RBox, RHashMap, RString},
+///     DynTrait, StableAbi,
+/// };
+///
+/// use std::collections::BTreeSet;
+///
+/// type PluginBox = DynTrait<'static, RBox<()>, PluginInterface>;
+///
+/// #[repr(C)]
+/// #[derive(StableAbi)]
+/// #[sabi(impl_InterfaceType(Send, Sync, Debug, SabiKey, PartialEq, Eq, Ord, PartialOrd, Hash))]
+/// pub struct PluginInterface;
+///
+/// impl SabiKeyType for PluginInterface {
+///     type Key = RString;
+/// }
+///
+/// /////////////
+/// // everything below could be defined in different implementation crates
+///
+/// #[derive(Debug)]
+/// struct Logger {
+///     name: &'static str,
+/// }
+///
+/// impl SabiKey<PluginInterface> for Logger {
+///     fn sabi_key(&self) -> RString {
+///         self.name.into()
+///     }
+/// }
+///
+/// #[derive(Debug)]
+/// struct Counter {
+///     id: u32,
+/// }
+///
+/// impl SabiKey<PluginInterface> for Counter {
+///     fn sabi_key(&self) -> RString {
+///         format!("counter_{}", self.id).into()
+///     }
+/// }
+///
+/// let mut map = RHashMap::<PluginBox, u32>::new();
+/// map.insert(DynTrait::from_value(Logger { name: "logger" }), 0);
+/// map.insert(DynTrait::from_value(Counter { id: 3 }), 1);
+///
+/// assert_eq!(map[&DynTrait::from_value(Counter { id: 3 })], 1);
+///
+/// // Different types with the same key are equal
+/// assert_eq!(map[&DynTrait::from_value(Logger { name: "counter_3" })], 1);
+///
+/// let set = map.into_iter().map(|tuple| tuple.0).collect::<BTreeSet<PluginBox>>();
+///
+/// assert_eq!(
+///     set.iter().map(|x| format!("{:?}", x)).collect::<Vec<_>>(),
+///     [r#"Counter { id: 3 }"#, r#"Logger { name: "logger" }"#],
+/// );
+/// ```
+///
+/// [`DynTrait`]: ../struct.DynTrait.html
+pub trait SabiKeyType: InterfaceType<SabiKey = Implemented<trait_marker::SabiKey>> {
+    /// The key type, which is compared and hashed in place of the wrapped value.
+    type Key: Ord + Hash + StableAbi;
+}
+
+/// Gets the key that a value is compared and hashed by,
+/// when it's wrapped in a `DynTrait<_, I>` whose `I` requires the `SabiKey` trait.
+///
+/// For an example, [look here](./trait.SabiKeyType.html#example).
+pub trait SabiKey<I: SabiKeyType> {
+    /// Gets the key of `self`.
+    fn sabi_key(&self) -> I::Key;
+}
+
+#[doc(hidden)]
+pub trait GetSabiKeyType: InterfaceType {
+    type KeyType;
+}
+
+impl<I, K> GetSabiKeyType for I
+where
+    I: InterfaceType,
+    I: GetSabiKeyTypeHelper<<I as InterfaceType>::SabiKey, KeyType = K>,
+{
+    type KeyType = K;
+}
+
+#[doc(hidden)]
+pub trait GetSabiKeyTypeHelper<IS>: InterfaceType {
+    type KeyType;
+}
+
+impl<I> GetSabiKeyTypeHelper<Implemented<trait_marker::SabiKey>> for I
+where
+    I: SabiKeyType,
+{
+    type KeyType = <I as SabiKeyType>::Key;
+}
+
+impl<I> GetSabiKeyTypeHelper<Unimplemented<trait_marker::SabiKey>> for I
+where
+    I: InterfaceType,
+{
+    type KeyType = ();
+}
+
+///////////////////////////////////////
+
 /// Describes how `D` is deserialized, using a proxy to do so.
 ///
 /// Generally this delegates to a library function,
@@ -10,7 +10,11 @@ use std::{
 use super::{
     c_functions::*,
     iterator::{DoubleEndedIteratorFns, IteratorFns, MakeDoubleEndedIteratorFns, MakeIteratorFns},
-    traits::{GetSerializeProxyType, IteratorItemOrDefault, SerializeType},
+    key_fns::{KeyFns, KeyOrHash, KeyOrOrd, KeyOrPartialEq, KeyOrPartialOrd, MakeKeyFns},
+    traits::{
+        GetSabiKeyType, GetSerializeProxyType, IteratorItemOrDefault, SabiKey, SabiKeyType,
+        SerializeType,
+    },
     type_info::TypeInfoFor,
     *,
 };
@@ -77,6 +81,7 @@ macro_rules! declare_meta_vtable {
             option=$option_ty:ident,$some_constr:ident,$none_constr:ident;
             field_index=$field_index:ident;
             query_fn = $trait_query:ident;
+            $(required_traits_bit = $required_traits_bit:expr;)?
 
             $(struct_bound=$struct_bound:expr;)*
 
@@ -105,6 +110,8 @@ macro_rules! declare_meta_vtable {
             bound(<I as IteratorItemOrDefault<'borr>>::Item: StableAbi),
             bound(I: GetSerializeProxyType<'borr>),
             bound(<I as GetSerializeProxyType<'borr>>::ProxyType: StableAbi),
+            bound(I: GetSabiKeyType),
+            bound(<I as GetSabiKeyType>::KeyType: StableAbi),
             $($(bound=$struct_bound,)*)*
         )]
         pub struct VTable<'borr,$erased_ptr,$interf>{
@@ -385,8 +392,9 @@ macro_rules! declare_meta_vtable {
 
             regular_traits[
                 $(($marker_trait, $marker_trait_query, $marker_trait_path),)*
-                $(($selector, $trait_query, $trait_path),)*
-                (Deserialize, contains_deserialize, serde::Deserialize),
+                $(($selector, $trait_query, $trait_path $(, $required_traits_bit)?),)*
+                // Deserialize keeps the bit it had before the traits after `IoSeek` were added
+                (Deserialize, contains_deserialize, serde::Deserialize, 17),
             ]
         }
     )
@@ -519,9 +527,9 @@ declare_meta_vtable! {
         query_fn = contains_partial_eq;
 
         impl[] VtableFieldValue<PartialEq(std::cmp::PartialEq)>
-        where [T:PartialEq,]
+        where [I::SabiKey: KeyOrPartialEq<T, I>,]
         {
-            partial_eq_impl::<T>
+            <I::SabiKey as KeyOrPartialEq<T, I>>::FN
         }
     ]
     [
@@ -533,9 +541,9 @@ declare_meta_vtable! {
         query_fn = contains_cmp;
 
         impl[] VtableFieldValue<Ord(std::cmp::Ord)>
-        where [T:Ord,]
+        where [I::SabiKey: KeyOrOrd<T, I>,]
         {
-            cmp_ord::<T>
+            <I::SabiKey as KeyOrOrd<T, I>>::FN
         }
     ]
     [
@@ -547,9 +555,9 @@ declare_meta_vtable! {
         query_fn = contains_partial_cmp;
 
         impl[] VtableFieldValue<PartialOrd(std::cmp::PartialOrd)>
-        where [T:PartialOrd,]
+        where [I::SabiKey: KeyOrPartialOrd<T, I>,]
         {
-            partial_cmp_ord::<T>
+            <I::SabiKey as KeyOrPartialOrd<T, I>>::FN
         }
     ]
     [
@@ -561,9 +569,9 @@ declare_meta_vtable! {
         query_fn = contains_hash;
 
         impl[] VtableFieldValue<Hash(std::hash::Hash)>
-        where [T:Hash]
+        where [I::SabiKey: KeyOrHash<T, I>]
         {
-            hash_Hash::<T>
+            <I::SabiKey as KeyOrHash<T, I>>::FN
         }
     ]
     [
@@ -679,6 +687,42 @@ declare_meta_vtable! {
             io_Seek_seek::<T>
         }
     ]
+    [
+        #[sabi(
+            unsafe_change_type=
+            ROption<KeyFns< <I as GetSabiKeyType>::KeyType >>
+        )]
+        #[sabi(accessible_if= <I as MakeRequiredTraits>::MAKE.contains_sabi_key())]
+        erased_key_fns:KeyFns< () >;
+        priv _key_fns;
+        option=ROption,RSome,RNone;
+        field_index=field_index_for__key_fns;
+        query_fn = contains_sabi_key;
+        required_traits_bit = 18;
+
+        impl[] VtableFieldValue<SabiKey(crate::erased_types::SabiKey)>
+        where [
+            T:SabiKey<I>,
+            I:SabiKeyType,
+        ]{
+            MakeKeyFns::<T, I>::NEW
+        }
+    ]
+}
+
+impl<'borr, ErasedPtr, I> VTable_Ref<'borr, ErasedPtr, I>
+where
+    I: InterfaceType,
+{
+    /// Gets the functions that compare values of different types by key,
+    /// returning None if `I` doesn't require the `SabiKey` trait.
+    pub(super) fn key_fns(&self) -> Option<KeyFns<()>> {
+        if <I as MakeRequiredTraits>::MAKE.contains_sabi_key() {
+            self._key_fns().into()
+        } else {
+            None
+        }
+    }
 }
 
 //////////////
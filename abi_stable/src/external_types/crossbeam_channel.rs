@@ -6,6 +6,7 @@
 use std::{
     fmt::{self, Debug},
     marker::PhantomData,
+    task::{Context, Poll},
     time::Duration,
 };
 
@@ -20,14 +21,16 @@ use crate::{
     marker_type::UnsafeIgnoredType,
     pointer_trait::AsPtr,
     prefix_type::WithMetadata,
-    sabi_types::RRef,
-    std_types::{RBox, RDuration, RErr, ROk, ROption, RResult},
+    sabi_types::{RMut, RRef},
+    std_types::{RBox, RContext, RDuration, RErr, ROk, ROption, RPoll, RResult, RStream},
     traits::{ErasedType, IntoReprRust},
 };
 
+mod async_recv;
 mod errors;
 mod extern_fns;
 mod iteration;
+mod wakers;
 
 #[cfg(all(test, not(feature = "test_miri_track_raw")))]
 mod tests;
@@ -36,7 +39,12 @@ use self::errors::{
     RRecvError, RRecvTimeoutError, RSendError, RSendTimeoutError, RTryRecvError, RTrySendError,
 };
 
-pub use self::iteration::{RIntoIter, RIter};
+use self::wakers::{ReceiverEnd, SenderEnd};
+
+pub use self::{
+    async_recv::RRecvFuture,
+    iteration::{RIntoIter, RIter},
+};
 
 ///////////////////////////////////////////////////////////////////////////////
 
@@ -54,7 +62,7 @@ pub use self::iteration::{RIntoIter, RIter};
 ///
 /// ```
 pub fn never<T>() -> RReceiver<T> {
-    crossbeam_channel::never::<T>().into()
+    RReceiver::from_end(ReceiverEnd::never())
 }
 
 /// Creates a channel which can hold up to `capacity` elements in its internal queue.
@@ -88,7 +96,8 @@ pub fn never<T>() -> RReceiver<T> {
 ///
 pub fn bounded<T>(capacity: usize) -> (RSender<T>, RReceiver<T>) {
     let (tx, rx) = crossbeam_channel::bounded::<T>(capacity);
-    (tx.into(), rx.into())
+    let (tx, rx) = wakers::linked(tx, rx);
+    (RSender::from_end(tx), RReceiver::from_end(rx))
 }
 
 /// Creates a channel which can hold an unbounded amount elements in its internal queue.
@@ -119,7 +128,8 @@ pub fn bounded<T>(capacity: usize) -> (RSender<T>, RReceiver<T>) {
 ///
 pub fn unbounded<T>() -> (RSender<T>, RReceiver<T>) {
     let (tx, rx) = crossbeam_channel::unbounded::<T>();
-    (tx.into(), rx.into())
+    let (tx, rx) = wakers::linked(tx, rx);
+    (RSender::from_end(tx), RReceiver::from_end(rx))
 }
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -393,13 +403,19 @@ unsafe impl<T: Send> Sync for RSender<T> {}
 
 unsafe impl<T: Send> Send for RSender<T> {}
 
+impl<T> RSender<T> {
+    fn from_end(end: SenderEnd<T>) -> Self {
+        Self {
+            channel: ErasedSender::from_unerased_value(end),
+            vtable: MakeVTable::<T>::VTABLE,
+        }
+    }
+}
+
 impl_from_rust_repr! {
     impl[T] From<Sender<T>> for RSender<T> {
         fn(this){
-            Self{
-                channel:ErasedSender::from_unerased_value(this),
-                vtable: MakeVTable::<T>::VTABLE,
-            }
+            Self::from_end(SenderEnd::unlinked(this))
         }
     }
 }
@@ -684,6 +700,160 @@ impl<T> RReceiver<T> {
     pub fn iter(&self) -> RIter<'_, T> {
         RIter { channel: self }
     }
+
+    /// Returns a future which receives a value from the channel,
+    /// resolving to an error if the channel is disconnected.
+    ///
+    /// Waiting for a value doesn't block the thread,
+    /// the task is woken up when a value is sent or the channel is disconnected.
+    ///
+    /// Receivers converted from a `crossbeam_channel::Receiver`
+    /// can't be woken up by senders,
+    /// so the future wakes up its task right away when there is no value available,
+    /// polling the channel in a loop until a value is sent or the channel is disconnected,
+    /// construct the channel with [`bounded`] or [`unbounded`] to avoid this.
+    ///
+    /// Receivers from a dynamic library that predates this method
+    /// have the same limitation.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel as mpmc;
+    ///
+    /// # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    /// #     use std::{sync::Arc, task::{Context, Poll, Wake}};
+    /// #     struct Unpark(std::thread::Thread);
+    /// #     impl Wake for Unpark {
+    /// #         fn wake(self: Arc<Self>) { self.0.unpark() }
+    /// #     }
+    /// #     let waker = Arc::new(Unpark(std::thread::current())).into();
+    /// #     let mut cx = Context::from_waker(&waker);
+    /// #     let mut fut = Box::pin(fut);
+    /// #     loop {
+    /// #         match fut.as_mut().poll(&mut cx) {
+    /// #             Poll::Ready(x) => return x,
+    /// #             Poll::Pending => std::thread::park(),
+    /// #         }
+    /// #     }
+    /// # }
+    /// let (tx, rx) = mpmc::unbounded::<u32>();
+    ///
+    /// std::thread::spawn(move || {
+    ///     tx.send(3).unwrap();
+    ///     tx.send(5).unwrap();
+    /// });
+    ///
+    /// block_on(async {
+    ///     assert_eq!(rx.recv_async().await, Ok(3));
+    ///     assert_eq!(rx.recv_async().await, Ok(5));
+    ///     assert!(rx.recv_async().await.is_err());
+    /// });
+    ///
+    /// ```
+    pub fn recv_async(&self) -> RRecvFuture<'_, T> {
+        RRecvFuture {
+            channel: self,
+            key: 0,
+        }
+    }
+
+    /// Converts this receiver into a stream of the values sent through the channel,
+    /// which ends once the channel is disconnected.
+    ///
+    /// This has the same caveat as [`recv_async`](#method.recv_async)
+    /// for receivers converted from a `crossbeam_channel::Receiver`.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::{external_types::crossbeam_channel as mpmc, std_types::RVec};
+    ///
+    /// # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    /// #     use std::{sync::Arc, task::{Context, Poll, Wake}};
+    /// #     struct Unpark(std::thread::Thread);
+    /// #     impl Wake for Unpark {
+    /// #         fn wake(self: Arc<Self>) { self.0.unpark() }
+    /// #     }
+    /// #     let waker = Arc::new(Unpark(std::thread::current())).into();
+    /// #     let mut cx = Context::from_waker(&waker);
+    /// #     let mut fut = Box::pin(fut);
+    /// #     loop {
+    /// #         match fut.as_mut().poll(&mut cx) {
+    /// #             Poll::Ready(x) => return x,
+    /// #             Poll::Pending => std::thread::park(),
+    /// #         }
+    /// #     }
+    /// # }
+    /// let (tx, rx) = mpmc::bounded::<u32>(1);
+    ///
+    /// std::thread::spawn(move || {
+    ///     for i in 0..10 {
+    ///         tx.send(i).unwrap();
+    ///     }
+    /// });
+    ///
+    /// let mut stream = rx.into_stream();
+    ///
+    /// let list = block_on(async {
+    ///     let mut list = RVec::new();
+    ///     while let Some(x) = stream.next().await {
+    ///         list.push(x);
+    ///     }
+    ///     list
+    /// });
+    ///
+    /// assert_eq!(list, (0..10).collect::<RVec<u32>>());
+    ///
+    /// ```
+    pub fn into_stream(self) -> RStream<'static, T>
+    where
+        T: Send + 'static,
+    {
+        let mut stream = async_recv::RecvStream {
+            channel: self,
+            key: 0,
+        };
+        RStream::from_fn(move |cx| stream.poll_next(cx))
+    }
+
+    fn poll_recv(&self, cx: &mut Context<'_>, key: &mut u64) -> Poll<Result<T, RecvError>> {
+        let vtable = self.vtable();
+
+        let poll_recv = match vtable.poll_recv() {
+            Some(x) => x,
+            // The receiver comes from a library that can't register wakers,
+            // so the task is woken up right away to poll the channel again.
+            None => {
+                return match self.try_recv() {
+                    Ok(x) => Poll::Ready(Ok(x)),
+                    Err(TryRecvError::Disconnected) => Poll::Ready(Err(RecvError)),
+                    Err(TryRecvError::Empty) => {
+                        cx.waker().wake_by_ref();
+                        Poll::Pending
+                    }
+                }
+            }
+        };
+
+        let cx = RContext::from_waker(cx.waker());
+        match poll_recv(self.channel.as_rref(), cx, RMut::new(key)) {
+            RPoll::Ready(res) => Poll::Ready(result_from(res)),
+            RPoll::Pending => Poll::Pending,
+        }
+    }
+
+    fn unregister_waker(&self, key: u64) {
+        if key != 0 {
+            let vtable = self.vtable();
+
+            if let Some(unregister_waker) = vtable.unregister_waker() {
+                unregister_waker(self.channel.as_rref(), key)
+            }
+        }
+    }
 }
 
 impl<'a, T> IntoIterator for &'a RReceiver<T> {
@@ -732,13 +902,19 @@ unsafe impl<T: Send> Sync for RReceiver<T> {}
 
 unsafe impl<T: Send> Send for RReceiver<T> {}
 
+impl<T> RReceiver<T> {
+    fn from_end(end: ReceiverEnd<T>) -> Self {
+        Self {
+            channel: ErasedReceiver::from_unerased_value(end),
+            vtable: MakeVTable::<T>::VTABLE,
+        }
+    }
+}
+
 impl_from_rust_repr! {
     impl[T] From<Receiver<T>> for RReceiver<T> {
         fn(this){
-            Self{
-                channel:ErasedReceiver::from_unerased_value(this),
-                vtable:MakeVTable::<T>::VTABLE,
-            }
+            Self::from_end(ReceiverEnd::unlinked(this))
         }
     }
 }
@@ -761,7 +937,7 @@ where
 struct ErasedSender<T>(PhantomData<T>, UnsafeIgnoredType<Sender<T>>);
 
 impl<T> ErasedType<'_> for ErasedSender<T> {
-    type Unerased = Sender<T>;
+    type Unerased = SenderEnd<T>;
 }
 
 #[repr(C)]
@@ -769,7 +945,7 @@ impl<T> ErasedType<'_> for ErasedSender<T> {
 struct ErasedReceiver<T>(PhantomData<T>, UnsafeIgnoredType<Receiver<T>>);
 
 impl<T> ErasedType<'_> for ErasedReceiver<T> {
-    type Unerased = Receiver<T>;
+    type Unerased = ReceiverEnd<T>;
 }
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -805,6 +981,14 @@ struct VTable<T> {
     receiver_len: extern "C" fn(this: RRef<'_, ErasedReceiver<T>>) -> usize,
     #[sabi(last_prefix_field)]
     receiver_capacity: extern "C" fn(this: RRef<'_, ErasedReceiver<T>>) -> ROption<usize>,
+    #[sabi(missing_field(option))]
+    poll_recv: extern "C" fn(
+        this: RRef<'_, ErasedReceiver<T>>,
+        cx: RContext<'_>,
+        key: RMut<'_, u64>,
+    ) -> RPoll<RResult<T, RRecvError>>,
+    #[sabi(missing_field(option))]
+    unregister_waker: extern "C" fn(this: RRef<'_, ErasedReceiver<T>>, key: u64),
 }
 
 struct MakeVTable<'a, T>(&'a T);
@@ -828,6 +1012,8 @@ impl<'a, T: 'a> MakeVTable<'a, T> {
         receiver_is_full: ErasedReceiver::is_full,
         receiver_len: ErasedReceiver::len,
         receiver_capacity: ErasedReceiver::capacity,
+        poll_recv: ErasedReceiver::poll_recv,
+        unregister_waker: ErasedReceiver::unregister_waker,
     };
 
     staticref! {
@@ -0,0 +1,63 @@
+use super::*;
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// A future which receives a value from the channel,
+/// returned by [`RReceiver::recv_async`].
+///
+/// If the channel is disconnected this resolves to an error.
+#[must_use = "futures do nothing unless polled"]
+pub struct RRecvFuture<'a, T> {
+    pub(super) channel: &'a RReceiver<T>,
+    pub(super) key: u64,
+}
+
+impl<'a, T> Future for RRecvFuture<'a, T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.channel.poll_recv(cx, &mut this.key)
+    }
+}
+
+impl<'a, T> Drop for RRecvFuture<'a, T> {
+    fn drop(&mut self) {
+        self.channel.unregister_waker(self.key);
+    }
+}
+
+impl<'a, T> Debug for RRecvFuture<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RRecvFuture{..}")
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The state of the stream returned by `RReceiver::into_stream`.
+pub(super) struct RecvStream<T> {
+    pub(super) channel: RReceiver<T>,
+    pub(super) key: u64,
+}
+
+impl<T> RecvStream<T> {
+    pub(super) fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.channel.poll_recv(cx, &mut self.key) {
+            Poll::Ready(res) => Poll::Ready(res.ok()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T> Drop for RecvStream<T> {
+    fn drop(&mut self) {
+        self.channel.unregister_waker(self.key);
+    }
+}
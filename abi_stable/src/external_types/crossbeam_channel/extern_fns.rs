@@ -47,12 +47,12 @@ macro_rules! shared_fns {
 
 shared_fns! {
     erased=ErasedSender
-    unerased=Sender
+    unerased=SenderEnd
 }
 
 shared_fns! {
     erased=ErasedReceiver
-    unerased=Receiver
+    unerased=ReceiverEnd
 }
 
 #[inline]
@@ -68,13 +68,17 @@ where
 
 impl<T> ErasedSender<T> {
     pub(super) extern "C" fn send(this: RRef<'_, Self>, val: T) -> RResult<(), RSendError<T>> {
-        Self::run(this, |this| this.send(val).piped(rresult_from))
+        Self::run(this, |this| {
+            this.after_send(this.send(val)).piped(rresult_from)
+        })
     }
     pub(super) extern "C" fn try_send(
         this: RRef<'_, Self>,
         val: T,
     ) -> RResult<(), RTrySendError<T>> {
-        Self::run(this, |this| this.try_send(val).piped(rresult_from))
+        Self::run(this, |this| {
+            this.after_send(this.try_send(val)).piped(rresult_from)
+        })
     }
     pub(super) extern "C" fn send_timeout(
         this: RRef<'_, Self>,
@@ -82,7 +86,8 @@ impl<T> ErasedSender<T> {
         timeout: RDuration,
     ) -> RResult<(), RSendTimeoutError<T>> {
         Self::run(this, |this| {
-            this.send_timeout(val, timeout.into()).piped(rresult_from)
+            this.after_send(this.send_timeout(val, timeout.into()))
+                .piped(rresult_from)
         })
     }
 }
@@ -102,4 +107,19 @@ impl<T> ErasedReceiver<T> {
             this.recv_timeout(timeout.into()).piped(rresult_from)
         })
     }
+    pub(super) extern "C" fn poll_recv(
+        this: RRef<'_, Self>,
+        cx: RContext<'_>,
+        mut key: RMut<'_, u64>,
+    ) -> RPoll<RResult<T, RRecvError>> {
+        Self::run(this, |this| {
+            match this.poll_recv(|| cx.waker(), key.get_mut()) {
+                Some(res) => RPoll::Ready(res.piped(rresult_from)),
+                None => RPoll::Pending,
+            }
+        })
+    }
+    pub(super) extern "C" fn unregister_waker(this: RRef<'_, Self>, key: u64) {
+        Self::run(this, |this| this.unregister(key))
+    }
 }
@@ -1,7 +1,11 @@
 use super::*;
 
+use crate::test_utils::block_on;
+
 use crossbeam_utils::thread::scope as scoped_thread;
 
+use std::{future::Future, pin::Pin};
+
 #[test]
 fn test_size_methods() {
     let cap = 5;
@@ -256,3 +260,172 @@ fn into_iter() {
 
     assert_ne!(rx.try_recv().err(), None);
 }
+
+#[test]
+#[cfg(not(miri))]
+fn recv_async() {
+    let (tx, rx) = bounded::<usize>(1);
+
+    scoped_thread(|scope| {
+        scope.spawn(move |_| {
+            for i in 0..100 {
+                tx.send(i).unwrap();
+            }
+        });
+
+        block_on(async {
+            for i in 0..100 {
+                assert_eq!(rx.recv_async().await, Ok(i));
+            }
+            assert_eq!(rx.recv_async().await, Err(RecvError));
+        });
+    })
+    .unwrap();
+}
+
+#[test]
+#[cfg(not(miri))]
+fn recv_async_disconnect() {
+    let (tx, rx) = unbounded::<usize>();
+    let tx2 = tx.clone();
+
+    scoped_thread(|scope| {
+        scope.spawn(move |_| {
+            std::thread::sleep(Duration::from_millis(10));
+            drop(tx);
+            std::thread::sleep(Duration::from_millis(10));
+            drop(tx2);
+        });
+
+        assert_eq!(block_on(rx.recv_async()), Err(RecvError));
+    })
+    .unwrap();
+}
+
+#[test]
+#[cfg(not(miri))]
+fn into_stream() {
+    let (tx, rx) = unbounded::<usize>();
+
+    scoped_thread(|scope| {
+        scope.spawn(move |_| {
+            for i in 0..50 {
+                tx.send(i).unwrap();
+            }
+        });
+
+        let mut stream = rx.into_stream();
+        let list = block_on(async {
+            let mut list = Vec::new();
+            while let Some(x) = stream.next().await {
+                list.push(x);
+            }
+            list
+        });
+        assert_eq!(list, (0..50).collect::<Vec<usize>>());
+    })
+    .unwrap();
+}
+
+#[test]
+#[cfg(not(miri))]
+fn recv_async_unlinked() {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Wake, Waker},
+    };
+
+    struct CountWakes(AtomicUsize);
+
+    impl Wake for CountWakes {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let (tx, rx) = crossbeam_channel::unbounded::<usize>();
+    let rx = RReceiver::from(rx);
+
+    // Unlinked receivers can't be woken up by senders,
+    // so they wake up the task immediately instead of erroring.
+    {
+        let wakes = Arc::new(CountWakes(AtomicUsize::new(0)));
+        let waker = Waker::from(wakes.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut fut = rx.recv_async();
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Pending);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+
+        tx.send(3).unwrap();
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Ready(Ok(3)));
+    }
+
+    scoped_thread(|s| {
+        let tx = tx.clone();
+        s.spawn(move |_| {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            for i in 0..10 {
+                tx.send(i).unwrap();
+            }
+        });
+
+        block_on(async {
+            for i in 0..10 {
+                assert_eq!(rx.recv_async().await, Ok(i));
+            }
+        });
+    })
+    .unwrap();
+
+    drop(tx);
+
+    block_on(async {
+        assert_eq!(rx.recv_async().await, Err(RecvError));
+    });
+}
+
+#[test]
+fn dropped_recv_future_unregisters() {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Wake, Waker},
+    };
+
+    struct CountWakes(AtomicUsize);
+
+    impl Wake for CountWakes {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let (tx, rx) = unbounded::<usize>();
+
+    let wakes = Arc::new(CountWakes(AtomicUsize::new(0)));
+    let waker = Waker::from(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    {
+        let mut fut = rx.recv_async();
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Pending);
+        assert_ne!(fut.key, 0);
+    }
+    tx.send(3).unwrap();
+    assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+
+    {
+        let mut fut = rx.recv_async();
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Ready(Ok(3)));
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Pending);
+        tx.send(5).unwrap();
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Ready(Ok(5)));
+    }
+}
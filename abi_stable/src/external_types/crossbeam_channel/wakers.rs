@@ -0,0 +1,235 @@
+use super::*;
+
+use std::{
+    mem::ManuallyDrop,
+    ops::Deref,
+    sync::{
+        atomic::{self, AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use parking_lot::Mutex;
+
+use crate::std_types::RWaker;
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The wakers of the receivers that are waiting for a value asynchronously,
+/// shared by both ends of a channel.
+pub(super) struct ChannelWakers {
+    /// Whether the senders and receivers of the channel share this,
+    /// which is false for channels converted from crossbeam channels,
+    /// since those can be sent values through crossbeam `Sender`s.
+    linked: bool,
+    /// The amount of `SenderEnd`s, used to wake the receivers when the last one is dropped.
+    senders: AtomicUsize,
+    /// Whether `wakers` is non-empty, to avoid locking in every send.
+    has_wakers: AtomicBool,
+    next_key: AtomicU64,
+    wakers: Mutex<Vec<(u64, RWaker)>>,
+}
+
+impl ChannelWakers {
+    fn new(linked: bool, senders: usize) -> Arc<Self> {
+        Arc::new(Self {
+            linked,
+            senders: AtomicUsize::new(senders),
+            has_wakers: AtomicBool::new(false),
+            next_key: AtomicU64::new(1),
+            wakers: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Registers the waker for the `key` receiver future,
+    /// assigning a key to it if it's `0`.
+    fn register(&self, key: &mut u64, waker: RWaker) {
+        let mut wakers = self.wakers.lock();
+        if *key == 0 {
+            *key = self.next_key.fetch_add(1, Ordering::Relaxed);
+        }
+        match wakers.iter_mut().find(|(k, _)| *k == *key) {
+            Some((_, w)) => *w = waker,
+            None => wakers.push((*key, waker)),
+        }
+        self.has_wakers.store(true, Ordering::SeqCst);
+        drop(wakers);
+
+        // Synchronizes with the fence in `wake_all`,
+        // so that either the receiver sees the sent value,
+        // or the sender sees the registered waker.
+        atomic::fence(Ordering::SeqCst);
+    }
+
+    fn unregister(&self, key: u64) {
+        if key == 0 || !self.has_wakers.load(Ordering::SeqCst) {
+            return;
+        }
+        let mut wakers = self.wakers.lock();
+        wakers.retain(|(k, _)| *k != key);
+        self.has_wakers.store(!wakers.is_empty(), Ordering::SeqCst);
+    }
+
+    fn wake_all(&self) {
+        atomic::fence(Ordering::SeqCst);
+        if !self.has_wakers.load(Ordering::SeqCst) {
+            return;
+        }
+        let wakers = {
+            let mut wakers = self.wakers.lock();
+            self.has_wakers.store(false, Ordering::SeqCst);
+            std::mem::take(&mut *wakers)
+        };
+        for (_, waker) in wakers {
+            waker.wake();
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The sender end of a channel, which wakes the receivers when a value is sent.
+pub(super) struct SenderEnd<T> {
+    sender: ManuallyDrop<Sender<T>>,
+    wakers: Arc<ChannelWakers>,
+}
+
+/// The receiver end of a channel, which can wait for values asynchronously.
+pub(super) struct ReceiverEnd<T> {
+    receiver: Receiver<T>,
+    wakers: Arc<ChannelWakers>,
+}
+
+/// Constructs both ends of a channel, sharing the wakers.
+pub(super) fn linked<T>(
+    sender: Sender<T>,
+    receiver: Receiver<T>,
+) -> (SenderEnd<T>, ReceiverEnd<T>) {
+    let wakers = ChannelWakers::new(true, 1);
+    (
+        SenderEnd {
+            sender: ManuallyDrop::new(sender),
+            wakers: wakers.clone(),
+        },
+        ReceiverEnd { receiver, wakers },
+    )
+}
+
+impl<T> SenderEnd<T> {
+    pub(super) fn unlinked(sender: Sender<T>) -> Self {
+        Self {
+            sender: ManuallyDrop::new(sender),
+            wakers: ChannelWakers::new(false, 1),
+        }
+    }
+
+    /// Wakes the receivers if `res` is a successful send.
+    pub(super) fn after_send<E>(&self, res: Result<(), E>) -> Result<(), E> {
+        if res.is_ok() {
+            self.wakers.wake_all();
+        }
+        res
+    }
+}
+
+impl<T> ReceiverEnd<T> {
+    pub(super) fn unlinked(receiver: Receiver<T>) -> Self {
+        Self {
+            receiver,
+            wakers: ChannelWakers::new(false, 1),
+        }
+    }
+
+    /// A receiver that never receives any value, or gets disconnected.
+    pub(super) fn never() -> Self {
+        Self {
+            receiver: crossbeam_channel::never(),
+            wakers: ChannelWakers::new(true, 0),
+        }
+    }
+
+    /// Attempts to receive a value,
+    /// registering the waker under `key` if there is no value available.
+    pub(super) fn poll_recv(
+        &self,
+        waker: impl FnOnce() -> RWaker,
+        key: &mut u64,
+    ) -> Option<Result<T, RecvError>> {
+        match self.receiver.try_recv() {
+            Ok(x) => return Some(Ok(x)),
+            Err(TryRecvError::Disconnected) => return Some(Err(RecvError)),
+            Err(TryRecvError::Empty) => {}
+        }
+
+        if !self.wakers.linked {
+            // Values can be sent through crossbeam `Sender`s,
+            // which don't wake up this receiver,
+            // so the task is woken up right away to poll the channel again.
+            waker().wake();
+            return None;
+        }
+
+        self.wakers.register(key, waker());
+
+        match self.receiver.try_recv() {
+            Ok(x) => Some(Ok(x)),
+            Err(TryRecvError::Disconnected) => Some(Err(RecvError)),
+            Err(TryRecvError::Empty) => None,
+        }
+        .map(|res| {
+            self.unregister(*key);
+            *key = 0;
+            res
+        })
+    }
+
+    pub(super) fn unregister(&self, key: u64) {
+        self.wakers.unregister(key);
+    }
+}
+
+impl<T> Deref for SenderEnd<T> {
+    type Target = Sender<T>;
+
+    fn deref(&self) -> &Sender<T> {
+        &self.sender
+    }
+}
+
+impl<T> Deref for ReceiverEnd<T> {
+    type Target = Receiver<T>;
+
+    fn deref(&self) -> &Receiver<T> {
+        &self.receiver
+    }
+}
+
+impl<T> Clone for SenderEnd<T> {
+    fn clone(&self) -> Self {
+        self.wakers.senders.fetch_add(1, Ordering::SeqCst);
+        Self {
+            sender: self.sender.clone(),
+            wakers: self.wakers.clone(),
+        }
+    }
+}
+
+impl<T> Clone for ReceiverEnd<T> {
+    fn clone(&self) -> Self {
+        Self {
+            receiver: self.receiver.clone(),
+            wakers: self.wakers.clone(),
+        }
+    }
+}
+
+impl<T> Drop for SenderEnd<T> {
+    fn drop(&mut self) {
+        // Disconnecting the channel before waking the receivers,
+        // so that they observe the disconnection.
+        unsafe { ManuallyDrop::drop(&mut self.sender) }
+        if self.wakers.senders.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.wakers.wake_all();
+        }
+    }
+}
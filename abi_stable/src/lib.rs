@@ -73,6 +73,18 @@ These are default cargo features that enable optional crates :
     `&serde_json::value::RawValue` and `Box<serde_json::value::RawValue>`,
     in `abi_stable::external_types::serde_json` .
 
+These are cargo features, disabled by default, that enable optional crates:
+
+- "futures":
+    Depends on `futures-core`,
+    implementing its `Stream` trait for `abi_stable::std_types::RStream`,
+    and allowing `RStream`s to be constructed from `Stream`s.
+
+- "anyhow":
+    Depends on `anyhow`,
+    implementing conversions between `anyhow::Error` and
+    `abi_stable::std_types::RContextError`.
+
 
 To disable the default features use:
 ```text
@@ -89,6 +101,19 @@ These are crate features to manually enable support for newer language features:
 
 - "rust_1_64": Turns many functions for converting types to slices into const fns.
 
+- "rust_1_65": Enables capturing backtraces in `RBoxError::new_with_backtrace`.
+
+- "rust_1_71": Implements `StableAbi` for `extern "C-unwind" fn()` and `unsafe extern "C-unwind" fn()`.
+
+- "rust_1_83": Converts between the `std::io::ErrorKind` variants stabilized in Rust 1.83
+and the equivalent `RIoErrorKind` constants.
+
+- "rust_1_85": Converts between the `std::io::ErrorKind` variants stabilized in Rust 1.85
+and the equivalent `RIoErrorKind` constants.
+
+- "rust_1_87": Converts between the `std::io::ErrorKind::InvalidFilename` variant
+and the equivalent `RIoErrorKind` constant.
+
 - "rust_latest_stable":
 Enables the "rust_1_*" features for all the stable releases.
 
@@ -334,8 +359,9 @@ pub use crate::{
 pub mod globals {
     use crate::{
         abi_stability::abi_checking::check_layout_compatibility_for_ffi,
+        prefix_type::WithMetadata,
         sabi_types::LateStaticRef,
-        std_types::{RBoxError, RResult},
+        std_types::{time::timeline_now_for_ffi, RBoxError, RDuration, RResult},
         type_layout::TypeLayout,
         utils::leak_value,
     };
@@ -356,6 +382,38 @@ pub mod globals {
         }
     }
 
+    /// Global state added after [`Globals`],
+    /// which can't get new fields because its layout isn't checked
+    /// when it's passed to libraries.
+    ///
+    /// This is passed to libraries through a function that they export
+    /// next to their `LibHeader`,
+    /// so libraries loaded by an older version of abi_stable
+    /// (and loaders of libraries built with an older version)
+    /// each use their own `GlobalsExt` instead.
+    ///
+    /// Fields added after `instant_now` must use the
+    /// `#[sabi(missing_field(option))]` attribute.
+    #[repr(C)]
+    #[derive(StableAbi)]
+    #[sabi(kind(Prefix))]
+    #[sabi(missing_field(panic))]
+    pub struct GlobalsExt {
+        /// Gets the current time in the `RInstant` timeline shared by all libraries.
+        #[sabi(last_prefix_field)]
+        pub instant_now: extern "C" fn() -> RDuration,
+    }
+
+    impl GlobalsExt {
+        pub const NEW: GlobalsExt_Ref = {
+            const S: &WithMetadata<GlobalsExt> = &WithMetadata::new(GlobalsExt {
+                instant_now: timeline_now_for_ffi,
+            });
+
+            GlobalsExt_Ref(S.static_as_prefix())
+        };
+    }
+
     pub(crate) static GLOBALS: LateStaticRef<&Globals> = LateStaticRef::new();
 
     #[inline(never)]
@@ -367,6 +425,18 @@ pub mod globals {
     pub extern "C" fn initialize_globals_with(globs: &'static Globals) {
         GLOBALS.init(|| globs);
     }
+
+    pub(crate) static GLOBALS_EXT: LateStaticRef<GlobalsExt_Ref> = LateStaticRef::new();
+
+    #[inline(never)]
+    pub fn initialized_globals_ext() -> GlobalsExt_Ref {
+        GLOBALS_EXT.init(|| GlobalsExt::NEW)
+    }
+
+    #[inline(never)]
+    pub extern "C" fn initialize_globals_ext_with(globs: GlobalsExt_Ref) {
+        GLOBALS_EXT.init(|| globs);
+    }
 }
 
 #[cfg(all(test, not(feature = "testing")))]
@@ -40,7 +40,7 @@ use libloading::{Library as LibLoadingLibrary, Symbol as LLSymbol};
 
 use crate::{
     abi_stability::stable_abi_trait::StableAbi,
-    globals::{self, Globals},
+    globals::{self, Globals, GlobalsExt_Ref},
     marker_type::ErasedPrefix,
     prefix_type::{PrefixRef, PrefixRefTrait},
     sabi_types::{LateStaticRef, NulStr, VersionNumber, VersionStrings},
@@ -58,18 +58,20 @@ mod library_tests;
 
 mod raw_library;
 mod root_mod_trait;
+pub(crate) mod supervisor;
 
 #[doc(no_inline)]
 pub use self::c_abi_testing::{CAbiTestingFns, C_ABI_TESTING_FNS};
 
 pub use self::{
     errors::{IntoRootModuleResult, LibraryError, RootModuleError},
-    lib_header::{AbiHeader, AbiHeaderRef, LibHeader},
+    lib_header::{AbiHeader, AbiHeaderRef, InitGlobalsExtWith, LibHeader, INIT_GLOBALS_EXT_WITH},
     raw_library::RawLibrary,
     root_mod_trait::{
         abi_header_from_path, abi_header_from_raw_library, lib_header_from_path,
         lib_header_from_raw_library, RootModule, RootModuleConsts,
     },
+    supervisor::{supervised_libraries, LibrarySupervisor, Supervised, SupervisorError},
 };
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -144,6 +146,7 @@ pub type RootModuleResult = RResult<PrefixRef<ErasedPrefix>, RootModuleError>;
 pub struct RootModuleStatics<M> {
     root_mod: LateStaticRef<M>,
     raw_lib: LateStaticRef<&'static RawLibrary>,
+    supervisor: LateStaticRef<&'static LibrarySupervisor>,
 }
 
 impl<M> RootModuleStatics<M> {
@@ -157,6 +160,7 @@ impl<M> RootModuleStatics<M> {
         Self {
             root_mod: LateStaticRef::new(),
             raw_lib: LateStaticRef::new(),
+            supervisor: LateStaticRef::new(),
         }
     }
 }
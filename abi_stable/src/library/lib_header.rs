@@ -325,6 +325,20 @@ struct InitGlobalsWith(pub extern "C" fn(&'static Globals));
 
 const INIT_GLOBALS_WITH: InitGlobalsWith = InitGlobalsWith(crate::globals::initialize_globals_with);
 
+/// The type of the static that `#[export_root_module]` exports next to the [`LibHeader`],
+/// used by loaders to pass their `GlobalsExt` to the library.
+///
+/// Libraries built with versions of abi_stable that predate this type don't export it,
+/// in which case the library uses its own `GlobalsExt`.
+#[doc(hidden)]
+#[repr(C)]
+#[derive(StableAbi, Copy, Clone)]
+pub struct InitGlobalsExtWith(pub extern "C" fn(GlobalsExt_Ref));
+
+#[doc(hidden)]
+pub const INIT_GLOBALS_EXT_WITH: InitGlobalsExtWith =
+    InitGlobalsExtWith(crate::globals::initialize_globals_ext_with);
+
 //////////////////////////////////////////////////////////////////////
 
 /// A handle to the [`AbiHeader`] of a library.
@@ -393,6 +407,9 @@ impl AbiHeader {
 impl AbiHeaderRef {
     /// Gets the LibHeader of a library.
     ///
+    /// This doesn't share the [`RInstant`] timeline of the loader with the library,
+    /// which [`lib_header_from_raw_library`] does.
+    ///
     /// # Errors
     ///
     /// This returns these errors:
@@ -402,6 +419,9 @@ impl AbiHeaderRef {
     ///
     /// - `LibraryError::InvalidCAbi`:
     /// If the C abi used by the library is not compatible.
+    ///
+    /// [`RInstant`]: ../std_types/struct.RInstant.html
+    /// [`lib_header_from_raw_library`]: ./fn.lib_header_from_raw_library.html
     pub fn upgrade(self) -> Result<&'static LibHeader, LibraryError> {
         if !self.is_valid() {
             return Err(LibraryError::InvalidAbiHeader(*self));
@@ -1,7 +1,7 @@
 use crate::library::{
     ROOT_MODULE_LOADER_NAME, ROOT_MODULE_LOADER_NAME_NULSTR, ROOT_MODULE_LOADER_NAME_WITH_NUL,
 };
-use abi_stable_shared::mangled_root_module_loader_name;
+use abi_stable_shared::{mangled_globals_ext_initializer_name, mangled_root_module_loader_name};
 
 #[test]
 fn root_module_loader_name_test() {
@@ -13,3 +13,14 @@ fn root_module_loader_name_test() {
     assert_eq!(ROOT_MODULE_LOADER_NAME_NULSTR.to_str(), name);
     assert_eq!(ROOT_MODULE_LOADER_NAME_NULSTR.to_str_with_nul(), with_nul);
 }
+
+#[test]
+fn globals_ext_initializer_name_test() {
+    let name = mangled_globals_ext_initializer_name();
+
+    assert_eq!(
+        super::PRIV_MANGLED_GLOBALS_EXT_INITIALIZER_NAME_NUL,
+        format!("{}\0", name)
+    );
+    assert_ne!(name, mangled_root_module_loader_name());
+}
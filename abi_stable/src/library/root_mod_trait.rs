@@ -99,6 +99,25 @@ pub trait RootModule: Sized + StableAbi + PrefixRefTrait + 'static {
         Self::root_module_statics().raw_lib.get()
     }
 
+    /// Gets the [`LibrarySupervisor`] of this root module's library,
+    /// which tracks the panics and errors of calls into it.
+    ///
+    /// The supervisor is stored in the statics of `Self`,
+    /// so root modules with the same `Self::NAME` don't share it.
+    ///
+    /// Supervision is advisory,
+    /// it only applies to calls made through the supervisor
+    /// and to the methods of `#[sabi_trait]` trait objects constructed in the library,
+    /// calling the root module directly bypasses it.
+    ///
+    /// [`LibrarySupervisor`]: ./struct.LibrarySupervisor.html
+    fn supervisor() -> LibrarySupervisor {
+        Self::root_module_statics()
+            .supervisor
+            .init(|| leak_value(LibrarySupervisor::new_registered(Self::NAME)))
+            .clone()
+    }
+
     /// Returns the path the library would be loaded from,given a directory(folder).
     fn get_library_path(directory: &Path) -> PathBuf {
         let base_name = Self::BASE_NAME;
@@ -178,6 +197,11 @@ pub trait RootModule: Sized + StableAbi + PrefixRefTrait + 'static {
 
             items.ensure_layout::<Self>()?;
 
+            // Lets the supervisor find the trait objects constructed in the library.
+            if let IsLayoutChecked::Yes(layout) = items.root_mod_consts().layout() {
+                Self::supervisor().set_library_id(layout.get_utypeid());
+            }
+
             // safety: the layout was checked in the code above,
             unsafe {
                 items
@@ -255,7 +279,23 @@ where
 pub unsafe fn lib_header_from_raw_library(
     raw_library: &RawLibrary,
 ) -> Result<&'static LibHeader, LibraryError> {
-    unsafe { abi_header_from_raw_library(raw_library)?.upgrade() }
+    let lib_header = unsafe { abi_header_from_raw_library(raw_library)?.upgrade()? };
+    unsafe { initialize_library_globals_ext(raw_library) };
+    Ok(lib_header)
+}
+
+/// Passes the `GlobalsExt` of this executable/library to the library,
+/// if the library was built with a version of abi_stable that exports its initializer.
+///
+/// # Safety
+///
+/// The library must be an abi_stable library with a compatible `AbiHeader`.
+unsafe fn initialize_library_globals_ext(raw_library: &RawLibrary) {
+    let mangled = PRIV_MANGLED_GLOBALS_EXT_INITIALIZER_NAME_NUL;
+    if let Ok(init) = unsafe { raw_library.get::<&'static InitGlobalsExtWith>(mangled.as_bytes()) }
+    {
+        (init.0)(globals::initialized_globals_ext());
+    }
 }
 
 /// Gets the AbiHeaderRef of a library.
@@ -0,0 +1,524 @@
+//! Tracking of the panics and errors of loaded libraries,
+//! to stop calling into libraries that keep misbehaving.
+
+use std::{
+    error::Error as ErrorTrait,
+    fmt::{self, Display},
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use parking_lot::{const_mutex, Mutex};
+
+use crate::{
+    sabi_types::RPanicPayload,
+    std_types::{RResult, UTypeId},
+};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests;
+
+/// Every `LibrarySupervisor` constructed with [`LibrarySupervisor::for_library`],
+/// or by [`RootModule::supervisor`](crate::library::RootModule::supervisor).
+static REGISTRY: Mutex<Vec<LibrarySupervisor>> = const_mutex(Vec::new());
+
+/// Returns the supervisors of all the libraries that were supervised so far.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::library::{supervised_libraries, LibrarySupervisor};
+///
+/// LibrarySupervisor::for_library("supervised_libraries_example");
+///
+/// assert!(supervised_libraries()
+///     .iter()
+///     .any(|s| s.name() == "supervised_libraries_example"));
+///
+/// ```
+pub fn supervised_libraries() -> Vec<LibrarySupervisor> {
+    REGISTRY.lock().clone()
+}
+
+/// The amount of poisoned supervisors,
+/// used to skip looking up the library of trait objects while no library is poisoned.
+static POISONED_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns an error if the library that `library_id` constructs `UTypeId`s in is poisoned.
+///
+/// `library_id` is only called if any library is poisoned.
+pub(crate) fn check_library_of(
+    library_id: extern "C" fn() -> UTypeId,
+) -> Result<(), SupervisorError> {
+    if POISONED_COUNT.load(Ordering::Acquire) == 0 {
+        return Ok(());
+    }
+
+    let library_id = library_id();
+    let registry = REGISTRY.lock();
+    let poisoned = registry.iter().find(|s| {
+        s.is_poisoned()
+            && s.health
+                .library_id
+                .lock()
+                .map_or(false, |id| id.is_same_executable(&library_id))
+    });
+    match poisoned {
+        Some(supervisor) => supervisor.check(),
+        None => Ok(()),
+    }
+}
+
+/// Tracks the panics and errors of calls into a library,
+/// poisoning the library once it fails too many times.
+///
+/// Once a library is poisoned,
+/// calls through [`LibrarySupervisor::call`] (and [`Supervised`] values)
+/// return a [`SupervisorError::Poisoned`] error instead of running the library's code.
+///
+/// Calling the methods of [`#[sabi_trait]`](macro@crate::sabi_trait) trait objects
+/// that were constructed in a poisoned library panics with a [`SupervisorError::Poisoned`]
+/// payload instead of calling into the library,
+/// which [`LibrarySupervisor::call`] returns as an error.
+/// This only applies to libraries loaded with [`RootModule::load_from`]
+/// whose root module has its layout checked.
+///
+/// Supervision is advisory, it doesn't isolate the library:
+/// other code from the library that's called without going through the supervisor
+/// (eg: calling a function pointer of its root module directly) is not tracked nor stopped,
+/// and a misbehaving library can still corrupt the state of the process.
+///
+/// Panics are only caught if they can unwind into the caller,
+/// which requires the panicking function to either
+/// return the panic as an [`RPanicPayload`] that is then resumed
+/// (eg: methods of [`#[sabi_trait]`](macro@crate::sabi_trait) traits
+/// with the `#[sabi(catch_panic)]` attribute),
+/// or to use the `extern "C-unwind"` ABI.
+///
+/// The supervisor of a [`RootModule`] can be gotten with [`RootModule::supervisor`].
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::library::{LibrarySupervisor, SupervisorError};
+///
+/// let supervisor = LibrarySupervisor::for_library("supervisor_example");
+/// supervisor.set_max_failures(2);
+///
+/// assert_eq!(supervisor.call(|| 3 + 5), Ok(8));
+///
+/// for _ in 0..2 {
+///     let err = supervisor.call(|| -> u32 { panic!("oh no") }).unwrap_err();
+///     assert!(matches!(err, SupervisorError::Panicked { .. }));
+/// }
+///
+/// // The library failed twice, so it doesn't run code from it anymore.
+/// assert!(supervisor.is_poisoned());
+/// assert!(matches!(
+///     supervisor.call(|| 3 + 5),
+///     Err(SupervisorError::Poisoned { .. }),
+/// ));
+///
+/// ```
+///
+/// [`RootModule`]: ./trait.RootModule.html
+/// [`RootModule::supervisor`]: ./trait.RootModule.html#method.supervisor
+/// [`RootModule::load_from`]: ./trait.RootModule.html#method.load_from
+#[derive(Debug, Clone)]
+pub struct LibrarySupervisor {
+    health: Arc<LibraryHealth>,
+}
+
+#[derive(Debug)]
+struct LibraryHealth {
+    name: String,
+    max_failures: AtomicUsize,
+    failures: AtomicUsize,
+    poisoned: AtomicBool,
+    last_panic: Mutex<Option<RPanicPayload>>,
+    /// A `UTypeId` constructed in the supervised library,
+    /// to find out which trait objects were constructed in it.
+    library_id: Mutex<Option<UTypeId>>,
+}
+
+impl LibrarySupervisor {
+    /// The amount of failures after which a library is poisoned by default.
+    pub const DEFAULT_MAX_FAILURES: usize = 3;
+
+    /// Gets the supervisor for the library named `name`,
+    /// registering it if this is the first time it's requested.
+    ///
+    /// Supervisors are keyed by name here,
+    /// so unrelated libraries requested with the same name share their state.
+    /// Use [`RootModule::supervisor`](crate::library::RootModule::supervisor) to get a supervisor
+    /// that's unique to a root module.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::library::LibrarySupervisor;
+    ///
+    /// let supervisor = LibrarySupervisor::for_library("for_library_example");
+    /// supervisor.record_failure();
+    ///
+    /// let same = LibrarySupervisor::for_library("for_library_example");
+    /// assert_eq!(same.failure_count(), 1);
+    ///
+    /// ```
+    pub fn for_library(name: &str) -> Self {
+        let mut registry = REGISTRY.lock();
+        if let Some(supervisor) = registry.iter().find(|s| s.name() == name) {
+            return supervisor.clone();
+        }
+
+        let supervisor = Self::new_unregistered(name);
+        registry.push(supervisor.clone());
+        supervisor
+    }
+
+    /// Constructs and registers a supervisor that doesn't share its state
+    /// with any other supervisor, even ones with the same name.
+    pub(super) fn new_registered(name: &str) -> Self {
+        let supervisor = Self::new_unregistered(name);
+        REGISTRY.lock().push(supervisor.clone());
+        supervisor
+    }
+
+    fn new_unregistered(name: &str) -> Self {
+        Self {
+            health: Arc::new(LibraryHealth {
+                name: name.to_string(),
+                max_failures: AtomicUsize::new(Self::DEFAULT_MAX_FAILURES),
+                failures: AtomicUsize::new(0),
+                poisoned: AtomicBool::new(false),
+                last_panic: const_mutex(None),
+                library_id: const_mutex(None),
+            }),
+        }
+    }
+
+    /// The name of the supervised library.
+    pub fn name(&self) -> &str {
+        &self.health.name
+    }
+
+    /// The amount of failures after which the library is poisoned.
+    pub fn max_failures(&self) -> usize {
+        self.health.max_failures.load(Ordering::Acquire)
+    }
+
+    /// Sets the amount of failures after which the library is poisoned,
+    /// poisoning it immediately if it already failed that many times.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::library::LibrarySupervisor;
+    ///
+    /// let supervisor = LibrarySupervisor::for_library("set_max_failures_example");
+    ///
+    /// supervisor.record_failure();
+    /// assert!(!supervisor.is_poisoned());
+    ///
+    /// supervisor.set_max_failures(1);
+    /// assert!(supervisor.is_poisoned());
+    ///
+    /// ```
+    pub fn set_max_failures(&self, max_failures: usize) {
+        self.health
+            .max_failures
+            .store(max_failures, Ordering::Release);
+        if self.failure_count() >= max_failures {
+            self.set_poisoned(true);
+        }
+    }
+
+    /// The amount of failures recorded since the library was loaded,
+    /// or since the last call to [`reset`](#method.reset).
+    pub fn failure_count(&self) -> usize {
+        self.health.failures.load(Ordering::Acquire)
+    }
+
+    /// Whether the library is poisoned,
+    /// in which case code from it isn't run by this supervisor.
+    pub fn is_poisoned(&self) -> bool {
+        self.health.poisoned.load(Ordering::Acquire)
+    }
+
+    /// The last panic that was caught by this supervisor.
+    pub fn last_panic(&self) -> Option<RPanicPayload> {
+        self.health.last_panic.lock().clone()
+    }
+
+    /// Records that a call into the library failed,
+    /// poisoning the library if it failed too many times.
+    pub fn record_failure(&self) {
+        let failures = self.health.failures.fetch_add(1, Ordering::AcqRel) + 1;
+        if failures >= self.max_failures() {
+            self.set_poisoned(true);
+        }
+    }
+
+    fn set_poisoned(&self, poisoned: bool) {
+        if self.health.poisoned.swap(poisoned, Ordering::AcqRel) != poisoned {
+            if poisoned {
+                POISONED_COUNT.fetch_add(1, Ordering::AcqRel);
+            } else {
+                POISONED_COUNT.fetch_sub(1, Ordering::AcqRel);
+            }
+        }
+    }
+
+    /// Sets the library whose trait objects are gated on this supervisor,
+    /// `library_id` must have been constructed in that library.
+    pub(super) fn set_library_id(&self, library_id: UTypeId) {
+        *self.health.library_id.lock() = Some(library_id);
+    }
+
+    /// Clears the failure count and the poisoned state of the library.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::library::LibrarySupervisor;
+    ///
+    /// let supervisor = LibrarySupervisor::for_library("reset_example");
+    /// supervisor.set_max_failures(1);
+    ///
+    /// supervisor.record_failure();
+    /// assert!(supervisor.is_poisoned());
+    ///
+    /// supervisor.reset();
+    /// assert!(!supervisor.is_poisoned());
+    /// assert_eq!(supervisor.failure_count(), 0);
+    ///
+    /// ```
+    pub fn reset(&self) {
+        self.health.failures.store(0, Ordering::Release);
+        self.set_poisoned(false);
+        *self.health.last_panic.lock() = None;
+    }
+
+    /// Returns an error if the library is poisoned.
+    pub fn check(&self) -> Result<(), SupervisorError> {
+        if self.is_poisoned() {
+            Err(SupervisorError::Poisoned {
+                library: self.health.name.clone(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Calls `f` if the library isn't poisoned,
+    /// catching and recording any panic.
+    ///
+    /// # Errors
+    ///
+    /// This returns a [`SupervisorError::Poisoned`] without calling `f`
+    /// if the library is poisoned,
+    /// and a [`SupervisorError::Panicked`] if `f` panics.
+    ///
+    /// If `f` calls a method of a trait object from a poisoned library,
+    /// this returns the [`SupervisorError::Poisoned`] that it panicked with,
+    /// without recording it as a failure of this library.
+    pub fn call<F, R>(&self, f: F) -> Result<R, SupervisorError>
+    where
+        F: FnOnce() -> R,
+    {
+        self.check()?;
+
+        let res = RPanicPayload::catch_unwind(AssertUnwindSafe(|| {
+            panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+                match payload.downcast::<SupervisorError>() {
+                    Ok(err) => *err,
+                    Err(payload) => panic::resume_unwind(payload),
+                }
+            })
+        }));
+
+        match res {
+            RResult::ROk(res) => res,
+            RResult::RErr(payload) => {
+                *self.health.last_panic.lock() = Some(payload.clone());
+                self.record_failure();
+                Err(SupervisorError::Panicked {
+                    library: self.health.name.clone(),
+                    payload,
+                })
+            }
+        }
+    }
+
+    /// Like [`call`](#method.call),
+    /// but also records a failure when `f` returns an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::library::LibrarySupervisor;
+    ///
+    /// let supervisor = LibrarySupervisor::for_library("call_fallible_example");
+    /// supervisor.set_max_failures(1);
+    ///
+    /// let res = supervisor.call_fallible(|| "100".parse::<u8>());
+    /// assert_eq!(res, Ok(Ok(100)));
+    /// assert!(!supervisor.is_poisoned());
+    ///
+    /// let res = supervisor.call_fallible(|| "1000".parse::<u8>());
+    /// assert!(matches!(res, Ok(Err(_))));
+    /// assert!(supervisor.is_poisoned());
+    ///
+    /// ```
+    pub fn call_fallible<F, T, E>(&self, f: F) -> Result<Result<T, E>, SupervisorError>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        let res = self.call(f)?;
+        if res.is_err() {
+            self.record_failure();
+        }
+        Ok(res)
+    }
+
+    /// Wraps `value` so that it can only be used through this supervisor.
+    pub fn supervise<T>(&self, value: T) -> Supervised<T> {
+        Supervised {
+            value,
+            supervisor: self.clone(),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////
+
+/// A value from a library (eg: a trait object, or a module)
+/// which can only be used through its [`LibrarySupervisor`].
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::{
+///     library::{LibrarySupervisor, SupervisorError},
+///     sabi_trait,
+///     sabi_trait::TD_Opaque,
+///     std_types::RStr,
+/// };
+///
+/// #[sabi_trait]
+/// pub trait Parser {
+///     #[sabi(catch_panic)]
+///     fn parse(&self, s: RStr<'_>) -> u32;
+/// }
+///
+/// impl Parser for () {
+///     fn parse(&self, s: RStr<'_>) -> u32 {
+///         s.parse().unwrap()
+///     }
+/// }
+///
+/// # fn main() {
+/// let supervisor = LibrarySupervisor::for_library("supervised_example");
+/// supervisor.set_max_failures(1);
+///
+/// let parser = supervisor.supervise(Parser_TO::from_value((), TD_Opaque));
+///
+/// assert_eq!(parser.call(|p| p.parse("10".into())), Ok(10));
+///
+/// assert!(matches!(
+///     parser.call(|p| p.parse("what".into())),
+///     Err(SupervisorError::Panicked { .. }),
+/// ));
+///
+/// assert!(matches!(
+///     parser.call(|p| p.parse("10".into())),
+///     Err(SupervisorError::Poisoned { .. }),
+/// ));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Supervised<T> {
+    value: T,
+    supervisor: LibrarySupervisor,
+}
+
+impl<T> Supervised<T> {
+    /// The supervisor of the library this value comes from.
+    pub fn supervisor(&self) -> &LibrarySupervisor {
+        &self.supervisor
+    }
+
+    /// Calls `f` with a reference to the wrapped value,
+    /// with the same semantics as [`LibrarySupervisor::call`].
+    pub fn call<F, R>(&self, f: F) -> Result<R, SupervisorError>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let value = &self.value;
+        self.supervisor.call(move || f(value))
+    }
+
+    /// Calls `f` with a mutable reference to the wrapped value,
+    /// with the same semantics as [`LibrarySupervisor::call`].
+    pub fn call_mut<F, R>(&mut self, f: F) -> Result<R, SupervisorError>
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let value = &mut self.value;
+        self.supervisor.call(move || f(value))
+    }
+
+    /// Calls `f` with a reference to the wrapped value,
+    /// with the same semantics as [`LibrarySupervisor::call_fallible`].
+    pub fn call_fallible<F, U, E>(&self, f: F) -> Result<Result<U, E>, SupervisorError>
+    where
+        F: FnOnce(&T) -> Result<U, E>,
+    {
+        let value = &self.value;
+        self.supervisor.call_fallible(move || f(value))
+    }
+
+    /// Unwraps the value, bypassing the supervisor.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+//////////////////////////////////////////////////////////////////////
+
+/// The errors returned by [`LibrarySupervisor`] when calling into a library.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SupervisorError {
+    /// The library failed too many times, so its code wasn't run.
+    Poisoned {
+        /// The name of the library.
+        library: String,
+    },
+    /// The called code panicked.
+    Panicked {
+        /// The name of the library.
+        library: String,
+        /// The message and location of the panic.
+        payload: RPanicPayload,
+    },
+}
+
+impl Display for SupervisorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Poisoned { library } => write!(
+                f,
+                "the '{}' library is poisoned because it failed too many times",
+                library,
+            ),
+            Self::Panicked { library, payload } => {
+                write!(f, "the '{}' library panicked: {}", library, payload)
+            }
+        }
+    }
+}
+
+impl ErrorTrait for SupervisorError {}
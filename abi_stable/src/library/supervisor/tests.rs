@@ -0,0 +1,139 @@
+use super::*;
+
+use crate::std_types::utypeid::new_utypeid;
+
+#[test]
+fn poisons_after_max_failures() {
+    let supervisor = LibrarySupervisor::for_library("poisons_after_max_failures");
+    assert_eq!(
+        supervisor.max_failures(),
+        LibrarySupervisor::DEFAULT_MAX_FAILURES
+    );
+    supervisor.set_max_failures(2);
+
+    assert_eq!(supervisor.call(|| 10), Ok(10));
+    assert_eq!(supervisor.failure_count(), 0);
+
+    let err = supervisor.call(|| -> u32 { panic!("first") }).unwrap_err();
+    match &err {
+        SupervisorError::Panicked { library, payload } => {
+            assert_eq!(library, "poisons_after_max_failures");
+            assert_eq!(payload.message(), "first");
+        }
+        _ => panic!("expected a panic error: {:?}", err),
+    }
+    assert_eq!(supervisor.failure_count(), 1);
+    assert!(!supervisor.is_poisoned());
+    assert_eq!(supervisor.last_panic().unwrap().message(), "first");
+
+    assert_eq!(
+        supervisor.call_fallible(|| Err::<(), _>("second")),
+        Ok(Err("second"))
+    );
+    assert_eq!(supervisor.failure_count(), 2);
+    assert!(supervisor.is_poisoned());
+
+    let mut ran = false;
+    let err = supervisor.call(|| ran = true).unwrap_err();
+    assert!(!ran);
+    assert_eq!(
+        err,
+        SupervisorError::Poisoned {
+            library: "poisons_after_max_failures".to_string(),
+        }
+    );
+
+    supervisor.reset();
+    assert!(!supervisor.is_poisoned());
+    assert_eq!(supervisor.last_panic(), None);
+    assert_eq!(supervisor.call(|| 3), Ok(3));
+}
+
+#[test]
+fn registry_shares_state() {
+    let first = LibrarySupervisor::for_library("registry_shares_state");
+    let second = LibrarySupervisor::for_library("registry_shares_state");
+    let other = LibrarySupervisor::for_library("registry_shares_state_other");
+
+    first.set_max_failures(1);
+    first.record_failure();
+
+    assert!(second.is_poisoned());
+    assert!(!other.is_poisoned());
+
+    let registered = supervised_libraries()
+        .into_iter()
+        .filter(|s| s.name().starts_with("registry_shares_state"))
+        .count();
+    assert_eq!(registered, 2);
+}
+
+#[test]
+fn supervised_value() {
+    let supervisor = LibrarySupervisor::for_library("supervised_value");
+    supervisor.set_max_failures(1);
+
+    let mut value = supervisor.supervise(vec![3, 5]);
+
+    assert_eq!(value.call_mut(|v| v.push(8)), Ok(()));
+    assert_eq!(value.call(|v| v.len()), Ok(3));
+
+    assert!(value.call(|v| v[10]).is_err());
+    assert!(value.supervisor().is_poisoned());
+    assert!(matches!(
+        value.call(|v| v.len()),
+        Err(SupervisorError::Poisoned { .. })
+    ));
+
+    assert_eq!(value.into_inner(), vec![3, 5, 8]);
+}
+
+#[test]
+#[cfg(not(target_arch = "wasm32"))]
+fn root_module_supervisor_identity() {
+    use crate::{for_examples::Module_Ref, library::RootModule};
+
+    let by_name = LibrarySupervisor::for_library(Module_Ref::NAME);
+    let first = Module_Ref::supervisor();
+    let second = Module_Ref::supervisor();
+
+    assert_eq!(first.name(), Module_Ref::NAME);
+
+    first.set_max_failures(1);
+    first.record_failure();
+
+    assert!(second.is_poisoned());
+    assert!(!by_name.is_poisoned());
+}
+
+extern "C" fn other_library_id() -> UTypeId {
+    UTypeId::new_in_other_library::<()>()
+}
+
+#[test]
+fn gates_trait_objects_of_poisoned_library() {
+    let supervisor = LibrarySupervisor::new_registered("gates_trait_objects");
+    supervisor.set_library_id(UTypeId::new_in_other_library::<u8>());
+    supervisor.set_max_failures(1);
+
+    assert_eq!(check_library_of(other_library_id), Ok(()));
+
+    supervisor.record_failure();
+    let poisoned = SupervisorError::Poisoned {
+        library: "gates_trait_objects".to_string(),
+    };
+    assert_eq!(check_library_of(other_library_id), Err(poisoned.clone()));
+
+    // Trait objects constructed in other libraries are unaffected.
+    assert_eq!(check_library_of(new_utypeid::<()>), Ok(()));
+
+    supervisor.reset();
+    assert_eq!(check_library_of(other_library_id), Ok(()));
+
+    // Calling a trait object of a poisoned library panics with the error,
+    // which is returned without counting as a failure of the calling supervisor.
+    let caller = LibrarySupervisor::for_library("gates_trait_objects_caller");
+    let res = caller.call(|| -> u32 { std::panic::panic_any(poisoned.clone()) });
+    assert_eq!(res, Err(poisoned));
+    assert_eq!(caller.failure_count(), 0);
+}
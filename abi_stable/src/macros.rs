@@ -191,6 +191,18 @@ macro_rules! check_unerased {
 /// `extern_fn_panic_handling!{no_early_return; <code here> }`,
 /// which *might* be cheaper(this has not been tested yet).
 ///
+/// # Catching panics
+///
+/// `extern_fn_panic_handling!{catch_panic; <code here> }`
+/// catches panics instead of aborting,
+/// evaluating to an `RResult<_, `[`RPanicPayload`]`>`
+/// with the value of the code in `ROk`, or the caught panic in `RErr`.
+///
+/// Early returns are allowed inside `catch_panic`,
+/// they return from a closure wrapping the passed code.
+///
+/// [`RPanicPayload`]: ./sabi_types/struct.RPanicPayload.html
+///
 /// # Example
 ///
 /// ```
@@ -239,6 +251,29 @@ macro_rules! check_unerased {
 ///
 /// ```
 ///
+/// # Example, catch_panic
+///
+/// ```
+/// use abi_stable::{
+///     extern_fn_panic_handling,
+///     sabi_types::RPanicPayload,
+///     std_types::RResult,
+/// };
+///
+/// pub extern "C" fn checked_div(l: u32, r: u32) -> RResult<u32, RPanicPayload> {
+///     extern_fn_panic_handling!{catch_panic;
+///         if r == 0 {
+///             panic!("division by zero");
+///         }
+///         l / r
+///     }
+/// }
+///
+/// assert_eq!(checked_div(6, 2), RResult::ROk(3));
+/// assert_eq!(checked_div(6, 0).unwrap_err().message(), "division by zero");
+///
+/// ```
+///
 /// # Returing in `no_early_return`
 ///
 /// Attempting to do any kind of returning from inside of
@@ -279,6 +314,16 @@ macro_rules! extern_fn_panic_handling {
 
         res
     });
+    (catch_panic; $($fn_contents:tt)* ) => ({
+        $crate::extern_fn_panic_handling!{
+            no_early_return;
+            $crate::sabi_types::RPanicPayload::catch_unwind(
+                ::std::panic::AssertUnwindSafe(move||{
+                    $($fn_contents)*
+                })
+            )
+        }
+    });
     ( $($fn_contents:tt)* ) => (
         #[allow(clippy::redundant_closure_call)]
         {
@@ -33,7 +33,8 @@ use core_extensions::type_asserts::AssertEq;
     IoSeek,
     IoRead,
     IoBufRead,
-    Error
+    Error,
+    SabiKey
 ))]
 pub struct AllTraitsImpld;
 
@@ -61,6 +62,7 @@ fn assert_all_traits_impld() {
     let _: AssertEq<<AllTraitsImpld as InterfaceType>::IoRead, Implemented<_>>;
     let _: AssertEq<<AllTraitsImpld as InterfaceType>::IoBufRead, Implemented<_>>;
     let _: AssertEq<<AllTraitsImpld as InterfaceType>::Error, Implemented<_>>;
+    let _: AssertEq<<AllTraitsImpld as InterfaceType>::SabiKey, Implemented<_>>;
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -95,6 +97,7 @@ fn assert_all_traits_unimpld() {
     let _: AssertEq<<NoTraitsImpld<()> as InterfaceType>::IoRead, Unimplemented<_>>;
     let _: AssertEq<<NoTraitsImpld<()> as InterfaceType>::IoBufRead, Unimplemented<_>>;
     let _: AssertEq<<NoTraitsImpld<()> as InterfaceType>::Error, Unimplemented<_>>;
+    let _: AssertEq<<NoTraitsImpld<()> as InterfaceType>::SabiKey, Unimplemented<_>>;
 }
 
 ////////////////////////////////////////////////////////////////////////////////
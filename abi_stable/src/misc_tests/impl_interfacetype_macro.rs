@@ -39,6 +39,7 @@ impl_InterfaceType! {
         type IoRead=True;
         type IoBufRead=True;
         type Error=True;
+        type SabiKey=True;
     }
 }
 
@@ -66,6 +67,7 @@ fn assert_all_traits_impld() {
     let _: AssertEq<<AllTraitsImpld as InterfaceType>::IoRead, Implemented<_>>;
     let _: AssertEq<<AllTraitsImpld as InterfaceType>::IoBufRead, Implemented<_>>;
     let _: AssertEq<<AllTraitsImpld as InterfaceType>::Error, Implemented<_>>;
+    let _: AssertEq<<AllTraitsImpld as InterfaceType>::SabiKey, Implemented<_>>;
 }
 
 #[repr(C)]
@@ -103,6 +105,7 @@ fn assert_all_traits_unimpld() {
     let _: AssertEq<<NoTraitsImpld<()> as InterfaceType>::IoRead, Unimplemented<_>>;
     let _: AssertEq<<NoTraitsImpld<()> as InterfaceType>::IoBufRead, Unimplemented<_>>;
     let _: AssertEq<<NoTraitsImpld<()> as InterfaceType>::Error, Unimplemented<_>>;
+    let _: AssertEq<<NoTraitsImpld<()> as InterfaceType>::SabiKey, Unimplemented<_>>;
 }
 
 #[repr(C)]
@@ -771,6 +771,37 @@ impl<T: OwnedPointer> Drop for DropAllocationMutGuard<'_, T> {
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/// For smart pointers that can be constructed from the value they point to.
+///
+/// This is used by `#[sabi_trait]` trait objects to return `Self` from their methods,
+/// wrapping the returned value in the pointer type the trait object was constructed with.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::{
+///     pointer_trait::PtrFromValue,
+///     std_types::{RArc, RBox},
+/// };
+///
+/// fn wrap<P: PtrFromValue<PtrTarget = u32>>(n: u32) -> P {
+///     P::from_value(n)
+/// }
+///
+/// let boxed: RBox<u32> = wrap(3);
+/// assert_eq!(*boxed, 3);
+///
+/// let arc: RArc<u32> = wrap(5);
+/// assert_eq!(*arc, 5);
+///
+/// ```
+pub trait PtrFromValue: GetPointerKind<Kind = PK_SmartPointer> {
+    /// Constructs this pointer from the value it points to.
+    fn from_value(value: Self::PtrTarget) -> Self;
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
 /// Trait for non-owning pointers that are shared-reference-like.
 ///
 /// # Safety
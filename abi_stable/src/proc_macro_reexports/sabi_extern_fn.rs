@@ -93,6 +93,89 @@ assert_eq!(hello(), vec!["hello".into_c(), "world".into()].into_c(),);
 
 ```
 
+# catch_panic
+
+You can use `#[sabi_extern_fn(catch_panic)]` to catch panics instead of aborting,
+changing the return type of the function from `<return type>` to
+`RResult<<return type>, RPanicPayload>`
+(`RResult<(), RPanicPayload>` if the function doesn't declare a return type).
+
+The [`RPanicPayload`] carries the message and location of the panic
+(the location requires calling [`RPanicPayload::install_location_hook`]),
+and can be re-raised on the caller's side with [`RPanicPayload::resume_unwind`],
+or with `RResult::unwrap_or_resume_unwind`.
+
+Early returns are allowed in functions using this variant of the attribute.
+
+### Example
+
+```rust
+use abi_stable::{
+    sabi_extern_fn,
+    sabi_types::RPanicPayload,
+    std_types::{RResult, RVec},
+};
+
+#[sabi_extern_fn(catch_panic)]
+pub fn nth_element(list: &RVec<u32>, index: usize) -> u32 {
+    list[index]
+}
+
+let list = RVec::from(vec![3, 5, 8]);
+
+assert_eq!(nth_element(&list, 1), RResult::ROk(5));
+
+let err: RPanicPayload = nth_element(&list, 10).unwrap_err();
+assert_eq!(
+    err.message(),
+    "index out of bounds: the len is 3 but the index is 10",
+);
+
+// Re-raising the panic
+let res = std::panic::catch_unwind(|| nth_element(&list, 10).unwrap_or_resume_unwind());
+assert!(res.is_err());
+
+```
+
+# c_unwind
+
+You can use `#[sabi_extern_fn(c_unwind)]` to define an `extern "C-unwind"` function,
+which lets panics unwind into the caller instead of aborting.
+This requires Rust 1.71.0 or later.
+
+Panics can only unwind soundly when the function is called through an
+`extern "C-unwind"` function pointer (or declaration).
+Function pointer types in `#[derive(StableAbi)]` types record their ABI,
+so loading a library fails if the two sides disagree on it.
+
+### Example
+
+```rust
+# #[cfg(feature = "rust_1_71")]
+# fn main() {
+use abi_stable::sabi_extern_fn;
+
+#[sabi_extern_fn(c_unwind)]
+pub fn checked_div(l: u32, r: u32) -> u32 {
+    if r == 0 {
+        panic!("division by zero");
+    }
+    l / r
+}
+
+let func: extern "C-unwind" fn(u32, u32) -> u32 = checked_div;
+
+assert_eq!(func(6, 3), 2);
+assert!(std::panic::catch_unwind(|| func(6, 0)).is_err());
+# }
+# #[cfg(not(feature = "rust_1_71"))]
+# fn main() {}
+```
+
+[`RPanicPayload`]: ./sabi_types/struct.RPanicPayload.html
+[`RPanicPayload::resume_unwind`]: ./sabi_types/struct.RPanicPayload.html#method.resume_unwind
+[`RPanicPayload::install_location_hook`]: ./sabi_types/struct.RPanicPayload.html#method.install_location_hook
+
 
 */
 #[doc(inline)]
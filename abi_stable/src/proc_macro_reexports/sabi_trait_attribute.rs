@@ -26,6 +26,18 @@ it uses the default implementation,
 
 - `Unpin`
 
+- Other `#[sabi_trait]` traits, listed in the
+[`#[sabi(supertrait(...))]`](#sabisupertrait) attribute:
+the trait object can be converted into their trait objects with the generated
+`upcast_to_<supertrait>` methods
+([more details here
+](./docs/sabi_trait_inherent/index.html#upcast_to_supertrait-methods)).
+The trait object implements these supertraits whenever it satisfies
+the same bounds as their trait objects do,
+calling the methods through the vtable of the supertrait.
+The supertraits of these supertraits are not implemented by the trait object,
+they must be listed as supertraits of the annotated trait for it to implement them.
+
 To be able to have more supertraits you must use the `#[sabi(use_dyntrait)]` helper attribute,
 which changes the underlying implementation from [`RObject`] to [`DynTrait`],
 allowing these supertraits: 
@@ -54,6 +66,16 @@ allowing these supertraits:
 
 - `Hash`
 
+- `serde::Serialize`: Written as `Serialize`,
+only usable with the `#[sabi(use_dyntrait)]` attribute,
+serialized through the `SerializeProxyType` implementation of `Trait_Interface`
+([more details here](#serialization)).
+
+- `serde::Deserialize`: Written as `for<'a> Deserialize<'a>`,
+only usable with the `#[sabi(use_dyntrait)]` attribute,
+deserialized through the `DeserializeDyn` implementation of `Trait_Interface`
+([more details here](#serialization)).
+
 
 
 ### Supertrait Extensibility
@@ -79,6 +101,9 @@ A library will not load (through safe means) if methods are added anywhere but t
 Accidentally calling newer methods on trait objects from older versions of a
 library will cause a panic at runtime, unless it has a default implementation
 (within the trait definition that `#[sabi_trait]` can see).
+Whether the trait object has a method can be queried with the generated
+`sabi_has_<method>` methods
+([more details here](./docs/sabi_trait_inherent/index.html#sabi_has_method-methods)).
 
 Panics can only happen if one loads multiple versions of a library,
 where the trait is extended in each version(without using default methods),
@@ -102,6 +127,21 @@ These are the items reexported from the module:
 - [`Trait_CTO`](#trait_cto): 
 A type alias for the trait object which is constructible in constants.
 
+- `Trait_Mock`: 
+A mock implementation of the trait,
+only generated with the [`#[sabi(mock)]`](#sabimock) attribute.
+
+- `Trait_Delegate`: 
+A trait for types that implement the trait by forwarding to another implementor,
+only generated with the [`#[sabi(delegate)]`](#sabidelegate) attribute.
+
+These items are not reexported from the module:
+
+- `Trait_Interface`: 
+The `InterfaceType` of the trait object,
+for which the serialization traits are implemented
+([more details here](#serialization)).
+
 
 ### `Trait_TO` 
 
@@ -159,6 +199,13 @@ allowing one to call these methods(a nonexhaustive list):
 
 - `downcast_as_mut`
 
+- `query`/`query_mut`/`query_into`:
+converts into the trait object of another `#[sabi_trait]` trait,
+registered with the `from_ptr_with_capabilities`/`from_value_with_capabilities`
+constructors
+([more details here
+](./docs/sabi_trait_inherent/index.html#from_ptr_with_capabilities-method)).
+
 To reconstruct `Trait_TO` from its underlying implementation,
 you can use the `Trait_TO::from_sabi` associated function.
 
@@ -217,6 +264,10 @@ These are attributes for the generated trait, applied on the trait(not on method
 Disables the implementation of the trait for the trait object,
 you can still call the inherent versions of those methods on the trait object.
 
+The trait is never implemented for the trait object
+if the trait has methods with `&Self` parameters
+([more details here](#self-in-methods)).
+
 This is useful to reduce compile-time overhead,
 and to allow users to declare a blanket(generic) implementation of the trait.
 
@@ -229,6 +280,330 @@ because the trait object comes from a previous version of the library.
 By using this attribute, defaulted methods will behave the same as 
 non-defaulted methods when they don't exist in the vtable.
 
+###  `#[sabi(catch_panic)]`
+
+Makes the vtable entries of all methods catch panics instead of aborting,
+returning an `RResult<_, `[`RPanicPayload`]`>` with the panic message and location
+(the location requires calling [`RPanicPayload::install_location_hook`]).
+Calling the method on the trait object resumes the panic on the caller's side,
+so the signature of the trait method doesn't change.
+
+This attribute can also be used on individual methods.
+
+Changing whether a method catches panics changes the type of its vtable entry,
+which is a breaking change for the trait object.
+
+Example:
+
+```rust
+use abi_stable::{sabi_trait, sabi_types::RPanicPayload, sabi_trait::TD_Opaque};
+
+#[sabi_trait]
+pub trait Divider {
+    #[sabi(catch_panic)]
+    fn divide(&self, l: u32) -> u32;
+}
+
+impl Divider for u32 {
+    fn divide(&self, l: u32) -> u32 {
+        l / *self
+    }
+}
+
+# fn main() {
+let divider = Divider_TO::from_value(0u32, TD_Opaque);
+
+let err = std::panic::catch_unwind(|| divider.divide(10)).unwrap_err();
+let payload = err.downcast::<RPanicPayload>().unwrap();
+
+assert_eq!(payload.message(), "attempt to divide by zero");
+# }
+```
+
+[`RPanicPayload`]: ./sabi_types/struct.RPanicPayload.html
+[`RPanicPayload::install_location_hook`]: ./sabi_types/struct.RPanicPayload.html#method.install_location_hook
+
+###  `#[sabi(c_unwind)]`
+
+Makes the vtable entries of all methods use the `extern "C-unwind"` ABI,
+letting panics unwind into the caller instead of aborting.
+This requires Rust 1.71.0 or later.
+
+This attribute can also be used on individual methods.
+
+The ABI of each vtable entry is checked when the library is loaded,
+so changing whether a method uses this attribute is a breaking change for the trait object.
+
+Example:
+
+```rust
+# #[cfg(feature = "rust_1_71")]
+# mod example {
+use abi_stable::{sabi_trait, sabi_trait::TD_Opaque};
+
+#[sabi_trait]
+pub trait Divider {
+    #[sabi(c_unwind)]
+    fn divide(&self, l: u32) -> u32;
+}
+
+impl Divider for u32 {
+    fn divide(&self, l: u32) -> u32 {
+        l / *self
+    }
+}
+
+# pub fn main() {
+let divider = Divider_TO::from_value(0u32, TD_Opaque);
+
+assert!(std::panic::catch_unwind(|| divider.divide(10)).is_err());
+# }
+# }
+# fn main() {
+#     #[cfg(feature = "rust_1_71")]
+#     example::main();
+# }
+```
+
+###  `#[sabi(instrument)]`
+
+Implements the trait for [`Instrumented`]`<Trait_TO<..>>`,
+which wraps a trait object, calling its methods through the vtable,
+and reports the name, duration, and outcome of every call to a [`CallHook`]
+registered by whoever wraps the trait object.
+
+The reported outcome is [`CallOutcome::ReturnedErr`] when a method
+whose return type is named `RResult` or `Result` returns an error
+(the return type is detected by its name, so type aliases of results aren't detected),
+and [`CallOutcome::Panicked`] when the method panics,
+which requires the method to use the `#[sabi(catch_panic)]` or `#[sabi(c_unwind)]` attribute.
+
+Methods that return `Self` return an `Instrumented` that reports to the same hook.
+
+This attribute can't be used along with `#[sabi(no_trait_impl)]`,
+nor in traits with `&Self` parameters.
+
+Example:
+
+```rust
+use abi_stable::{
+    sabi_trait,
+    sabi_trait::{Instrumented, MethodCall, SharedCallHook, TD_Opaque},
+    std_types::RArc,
+};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[sabi_trait]
+#[sabi(instrument)]
+pub trait Greeter {
+    fn greet(&self, times: u32) -> u32;
+}
+
+impl Greeter for () {
+    fn greet(&self, times: u32) -> u32 {
+        times
+    }
+}
+
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+# fn main() {
+let hook = SharedCallHook::from_ptr(
+    RArc::new(|call: MethodCall<'_>| {
+        assert_eq!(call.method_name, "greet");
+        CALLS.fetch_add(1, Ordering::Relaxed);
+    }),
+    TD_Opaque,
+);
+
+let greeter = Instrumented::new(Greeter_TO::from_value((), TD_Opaque), hook);
+
+assert_eq!(greeter.greet(3), 3);
+assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+# }
+```
+
+[`Instrumented`]: ./sabi_trait/struct.Instrumented.html
+[`CallHook`]: ./sabi_trait/trait.CallHook.html
+[`CallOutcome::ReturnedErr`]: ./sabi_trait/struct.CallOutcome.html#associatedconstant.ReturnedErr
+[`CallOutcome::Panicked`]: ./sabi_trait/struct.CallOutcome.html#associatedconstant.Panicked
+
+###  `#[sabi(mock)]`
+
+Generates a `Trait_Mock` type that implements the trait,
+to test code that uses the trait object without implementing the trait for every test.
+
+`Trait_Mock` has the generic parameters of the trait,
+followed by the associated types of the trait,
+and is constructed with `Trait_Mock::new()`.
+
+Each `method` of the trait calls the closure set with `Trait_Mock::expect_method`,
+which takes the parameters of the method (other than `self`).
+The closures are `Fn + Send + Sync`, and the mock isn't locked while they run,
+so they can call methods of the mock.
+Unconfigured methods use the default implementation of the method,
+panicking if the method doesn't have one.
+Closures for methods that return borrows of `self` return values that borrow for `'static`.
+
+The mock records the names of the methods that were called,
+which are returned by the `calls` method in call order,
+and counted by the `call_count` method.
+The arguments of the calls to a method are returned by the `call_args` method,
+formatted with `Debug` (or as `_` if their type doesn't implement `Debug`).
+
+The `verify` method panics if any method configured with `expect_method`
+wasn't called.
+
+Clones of a mock share the closures, recorded calls, and expectations,
+so a mock can be inspected after a clone of it is put in a trait object of any pointer type.
+The mock can also be gotten back from trait objects constructed with `TD_CanDowncast`.
+
+The trait can only have `Send`, `Sync`, `Clone`, `Debug`, and `Default` as supertraits,
+which `Trait_Mock` implements.
+
+Example:
+
+```rust
+use abi_stable::{
+    sabi_trait,
+    sabi_trait::TD_CanDowncast,
+    std_types::{RResult, RStr, RString},
+};
+
+#[sabi_trait]
+#[sabi(mock)]
+pub trait Database {
+    fn get(&self, key: RStr<'_>) -> RResult<u32, RString>;
+
+    fn get_or(&self, key: RStr<'_>, default: u32) -> u32 {
+        self.get(key).unwrap_or(default)
+    }
+}
+
+fn total_score(db: &Database_TO<'_, abi_stable::std_types::RBox<()>>) -> u32 {
+    db.get_or("alice".into(), 0) + db.get_or("bob".into(), 0)
+}
+
+# fn main() {
+let mock = Database_Mock::new();
+mock.expect_get(|key| match key.as_str() {
+    "alice" => RResult::ROk(10),
+    _ => RResult::RErr("missing".into()),
+});
+
+let db = Database_TO::from_value(mock.clone(), TD_CanDowncast);
+
+assert_eq!(total_score(&db), 10);
+assert_eq!(mock.calls(), ["get_or", "get", "get_or", "get"]);
+assert_eq!(mock.call_args("get"), [["\"alice\""], ["\"bob\""]]);
+mock.verify();
+
+let downcasted = db.obj.downcast_as::<Database_Mock>().unwrap();
+assert_eq!(downcasted.call_count("get"), 2);
+# }
+```
+
+###  `#[sabi(delegate)]`
+
+Generates a `Trait_Delegate` trait, for writing decorators that wrap an implementor
+of the trait (usually a `Trait_TO` trait object) and only change some of its methods.
+
+`Trait_Delegate` has the generic parameters and associated types of the trait,
+and these items that implementors must define:
+
+- `type Delegate`: The implementor of the trait that the methods forward to.
+
+- `fn sabi_delegate(&self) -> &Self::Delegate`
+
+- `fn sabi_delegate_mut(&mut self) -> &mut Self::Delegate`:
+only declared if the trait has `&mut self` methods.
+
+- `fn sabi_into_delegate(self) -> Self::Delegate`:
+only declared if the trait has `self` methods.
+
+Every method of the trait is also declared in `Trait_Delegate`,
+with a default implementation that calls the method on the delegate,
+other than methods that return `Self`, which must be implemented.
+
+The trait is implemented for all types that implement `Trait_Delegate`,
+so methods added to the trait in minor versions are forwarded to the delegate
+without changing the decorators.
+
+Because both traits have methods of the same names,
+calling the methods of a decorator where `Trait_Delegate` is in scope
+requires the `Trait::method(&decorator)` syntax.
+
+Example:
+
+```rust
+use abi_stable::{
+    sabi_trait,
+    sabi_trait::TD_Opaque,
+    std_types::{RBox, ROption, RStr, RString},
+};
+
+use std::collections::HashMap;
+
+#[sabi_trait]
+#[sabi(delegate)]
+pub trait Cache {
+    fn get(&self, key: RStr<'_>) -> ROption<RString>;
+
+    #[sabi(last_prefix_field)]
+    fn insert(&mut self, key: RStr<'_>, value: RString);
+}
+
+impl Cache for HashMap<String, RString> {
+    fn get(&self, key: RStr<'_>) -> ROption<RString> {
+        HashMap::get(self, key.as_str()).cloned().into()
+    }
+    fn insert(&mut self, key: RStr<'_>, value: RString) {
+        HashMap::insert(self, key.into(), value);
+    }
+}
+
+/// Uppercases the inserted values, leaving `get` unchanged.
+struct Uppercase(Cache_TO<'static, RBox<()>>);
+
+impl Cache_Delegate for Uppercase {
+    type Delegate = Cache_TO<'static, RBox<()>>;
+
+    fn sabi_delegate(&self) -> &Self::Delegate {
+        &self.0
+    }
+    fn sabi_delegate_mut(&mut self) -> &mut Self::Delegate {
+        &mut self.0
+    }
+
+    fn insert(&mut self, key: RStr<'_>, value: RString) {
+        self.0.insert(key, value.to_uppercase().into());
+    }
+}
+
+# fn main() {
+let inner = Cache_TO::from_value(HashMap::new(), TD_Opaque);
+let mut cache = Uppercase(inner);
+
+Cache::insert(&mut cache, "greeting".into(), "hello".into());
+assert_eq!(
+    Cache::get(&cache, "greeting".into()),
+    ROption::RSome(RString::from("HELLO")),
+);
+
+// The decorator can itself be wrapped in a trait object
+let cache = Cache_TO::from_value(cache, TD_Opaque);
+assert_eq!(cache.get("greeting".into()), ROption::RSome(RString::from("HELLO")));
+# }
+```
+
+### `#[sabi(supertrait(...))]`
+
+Lists the supertraits that are `#[sabi_trait]` traits, by the last identifier in their path,
+eg: `#[sabi(supertrait(Reader, Named))]` for `trait Storage<'a>: io::Reader + Named<'a, u8>`.
+
+Any other supertrait that isn't in the list at the [top](#supertraits) is an error.
+
 ### `#[sabi(debug_print_trait)]`
 
 Prints the output generated by the attribute macro,
@@ -247,14 +622,338 @@ The only valid way to refer to associated types in the trait declaration is with
 `Self::AssocType` syntax.
 
 Associated types in the trait object are transformed into type parameters 
-that come after those of the trait.
+that come after those of the trait,
+in the order that they're declared in.
+Because `Trait_TO` is a struct, the associated types are passed positionally
+(`Codec_TO<'a, RBox<()>, RString>`),
+the `Codec_TO<'a, RBox<()>, Output = RString>` syntax isn't available.
+
+Associated types are type parameters of the vtable,
+so they must implement `StableAbi`,
+and they're part of the layout of the trait object,
+checking that both sides of the ffi boundary use the same associated types
+when a dynamic library is loaded.
+
+### Example
+
+```rust
+use abi_stable::{
+    sabi_trait,
+    sabi_trait::TD_Opaque,
+    std_types::{RBox, RStr, RString},
+    StableAbi,
+};
+
+#[sabi_trait]
+pub trait Codec {
+    type Output: StableAbi;
+
+    fn decode(&self, input: RStr<'_>) -> Self::Output;
+}
+
+struct Uppercase;
+
+impl Codec for Uppercase {
+    type Output = RString;
+
+    fn decode(&self, input: RStr<'_>) -> RString {
+        input.as_str().to_uppercase().into()
+    }
+}
+
+fn decode_hello<C>(codec: &C) -> RString
+where
+    C: Codec<Output = RString>,
+{
+    codec.decode("hello".into())
+}
+
+# fn main() {
+let codec: Codec_TO<'_, RBox<()>, RString> = Codec_TO::from_value(Uppercase, TD_Opaque);
+
+assert_eq!(codec.decode("world".into()), "WORLD");
+
+// The trait object implements `Codec<Output = RString>`
+assert_eq!(decode_hello(&codec), "HELLO");
+# }
+```
+
+# Serialization
+
+Traits that use the `#[sabi(use_dyntrait)]` attribute can have 
+`Serialize` and `for<'a> Deserialize<'a>` supertraits,
+which use the same mechanism as [`DynTrait`] to (de)serialize the trait object.
+
+Traits whose trait object is implemented with [`RObject`]
+(the default, without the `#[sabi(use_dyntrait)]` attribute)
+can't have these supertraits, because [`RObject`] doesn't support serialization.
+
+For `Serialize`:
+
+- `Trait_trait::Trait_Interface` must implement 
+[`SerializeProxyType`](crate::erased_types::SerializeProxyType),
+determining the intermediate type that the trait object is serialized as.
+
+- The types that the trait object is constructed from must implement
+[`SerializeType`](crate::erased_types::SerializeType),
+converting the value into the intermediate type.
+
+For `for<'a> Deserialize<'a>`:
+
+- `Trait_trait::Trait_Interface` must implement 
+[`DeserializeDyn<'a, Trait_TO<...>>`](crate::erased_types::DeserializeDyn),
+constructing the trait object from a deserialized intermediate type.
+This would usually call a function exported by a dynamic library.
+
+### Example
+
+```rust
+use abi_stable::{
+    erased_types::{DeserializeDyn, SerializeProxyType, SerializeType},
+    external_types::{RawValueBox, RawValueRef},
+    sabi_trait,
+    sabi_trait::TD_Opaque,
+    std_types::{RBox, RBoxError},
+};
+
+use serde::{Deserialize, Serialize};
+
+#[sabi_trait]
+#[sabi(use_dyntrait)]
+pub trait Counter: Serialize + for<'a> Deserialize<'a> {
+    fn increment(&mut self) -> u32;
+}
+
+pub type CounterBox = Counter_TO<'static, RBox<()>>;
+
+impl SerializeProxyType<'_> for Counter_trait::Counter_Interface {
+    type Proxy = RawValueBox;
+}
+
+impl<'a> DeserializeDyn<'a, CounterBox> for Counter_trait::Counter_Interface {
+    type Proxy = RawValueRef<'a>;
+
+    fn deserialize_dyn(s: RawValueRef<'a>) -> Result<CounterBox, RBoxError> {
+        match serde_json::from_str::<Clicks>(s.get()) {
+            Ok(x) => Ok(CounterBox::from_value(x, TD_Opaque)),
+            Err(e) => Err(RBoxError::new(e)),
+        }
+    }
+}
+
+/////////////
+// everything below could be defined in an implementation crate
+
+#[derive(Serialize, Deserialize)]
+struct Clicks {
+    count: u32,
+}
+
+impl Counter for Clicks {
+    fn increment(&mut self) -> u32 {
+        self.count += 1;
+        self.count
+    }
+}
+
+impl SerializeType<'_> for Clicks {
+    type Interface = Counter_trait::Counter_Interface;
+
+    fn serialize_impl(&self) -> Result<RawValueBox, RBoxError> {
+        match serde_json::value::to_raw_value(self) {
+            Ok(x) => Ok(x.into()),
+            Err(e) => Err(RBoxError::new(e)),
+        }
+    }
+}
+
+# fn main() {
+let mut counter = CounterBox::from_value(Clicks { count: 0 }, TD_Opaque);
+assert_eq!(counter.increment(), 1);
+
+let checkpoint = serde_json::to_string(&counter).unwrap();
+assert_eq!(checkpoint, r#"{"count":1}"#);
+
+let mut restored: CounterBox = serde_json::from_str(&checkpoint).unwrap();
+assert_eq!(restored.increment(), 2);
+# }
+```
+
+# Async methods
+
+`async fn` methods, and methods returning `impl Future<Output = T>`,
+are lowered to methods returning an [`RFuture<'_, T>`](crate::std_types::RFuture)
+that borrows `self`,
+so that the future can be polled by the executor on either side of the ffi boundary.
+
+Implementations of the trait must use the lowered signature
+(`fn method(&self, ...) -> RFuture<'_, T>`),
+constructing the returned future with `RFuture::new(async move { ... })`.
+
+Async methods have these restrictions:
+
+- They must take `&self` or `&mut self`.
+
+- The returned future must be `Send`,
+so `&self` methods require the implementing type to be `Sync`.
+Default implementations of `&self` methods that use `self`
+require the trait to have `Sync` as a supertrait.
+
+- The parameters can't be borrowed by the future,
+unless they have the same lifetime as `self`.
+
+### Example
+
+```rust
+use abi_stable::{
+    sabi_trait,
+    sabi_trait::TD_Opaque,
+    std_types::{RFuture, RString},
+};
+
+#[sabi_trait]
+pub trait Greeter: Sync {
+    async fn greet(&self, name: RString) -> RString;
+
+    async fn greet_twice(&self, name: RString) -> RString {
+        let first = self.greet(name.clone()).await;
+        let second = self.greet(name).await;
+        format!("{} {}", first, second).into()
+    }
+}
+
+struct Polite;
+
+impl Greeter for Polite {
+    fn greet(&self, name: RString) -> RFuture<'_, RString> {
+        RFuture::new(async move { format!("Hello, {}.", name).into() })
+    }
+}
+
+# fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+#     use std::{sync::Arc, task::{Context, Poll, Wake}};
+#     struct Unpark(std::thread::Thread);
+#     impl Wake for Unpark {
+#         fn wake(self: Arc<Self>) { self.0.unpark() }
+#     }
+#     let waker = Arc::new(Unpark(std::thread::current())).into();
+#     let mut cx = Context::from_waker(&waker);
+#     let mut fut = Box::pin(fut);
+#     loop {
+#         match fut.as_mut().poll(&mut cx) {
+#             Poll::Ready(x) => return x,
+#             Poll::Pending => std::thread::park(),
+#         }
+#     }
+# }
+# fn main() {
+let greeter = Greeter_TO::from_value(Polite, TD_Opaque);
+
+let greeting = block_on(greeter.greet_twice("Bob".into()));
+assert_eq!(greeting, "Hello, Bob. Hello, Bob.");
+# }
+```
+
+# `Self` in methods
+
+Methods can return `Self`, and take `&Self` parameters.
+
+A method returning `Self` returns a trait object with the same pointer type
+(and the same vtable) as `self`,
+allocating the returned value in a new pointer with the
+[`PtrFromValue`](crate::pointer_trait::PtrFromValue) trait.
+Because of this:
+
+- The trait object can only be constructed from pointers that implement `PtrFromValue`
+(`RBox`, `RArc`, `RRc`, and `RSmallBox`),
+so `Trait_CTO::from_const` can't be called.
+
+- The trait is only implemented by trait objects that own their pointee,
+the inherent methods returning `Self` require the same.
+
+`&Self` parameters are checked to be constructed from the same type as `self` at runtime,
+which requires both trait objects to be constructed with `TD_CanDowncast`.
+The inherent methods of the trait object with `&Self` parameters return
+`Result<ReturnType, SelfTypeMismatch>`
+(default methods that are missing from the vtable run without the check).
+
+Traits with `&Self` parameters are not implemented for their trait object,
+because the trait methods couldn't report mismatched types,
+so generic code must be written against the inherent methods of the trait object.
+This also means that the `#[sabi(instrument)]` attribute can't be used in those traits.
+
+`Self` can't be used anywhere else in parameters or return types
+(eg: `&mut Self`, `Option<Self>`),
+nor in traits that use the `#[sabi(use_dyntrait)]` attribute.
+
+### Example
+
+```rust
+use abi_stable::{
+    sabi_trait,
+    sabi_trait::TD_CanDowncast,
+    std_types::{RArc, RBox},
+};
+
+#[sabi_trait]
+pub trait Shape {
+    fn area(&self) -> u32;
+
+    fn scaled(&self, factor: u32) -> Self;
+
+    fn merged(&self, other: &Self) -> Self;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+    fn scaled(&self, factor: u32) -> Self {
+        Square(self.0 * factor)
+    }
+    fn merged(&self, other: &Self) -> Self {
+        Square(self.0 + other.0)
+    }
+}
+
+struct Circle(u32);
+
+impl Shape for Circle {
+    fn area(&self) -> u32 {
+        3 * self.0 * self.0
+    }
+    fn scaled(&self, factor: u32) -> Self {
+        Circle(self.0 * factor)
+    }
+    fn merged(&self, other: &Self) -> Self {
+        Circle(self.0 + other.0)
+    }
+}
+
+# fn main() {
+let square: Shape_TO<'_, RArc<()>> = Shape_TO::from_ptr(RArc::new(Square(2)), TD_CanDowncast);
+
+let bigger: Shape_TO<'_, RArc<()>> = square.scaled(3);
+assert_eq!(bigger.area(), 36);
+
+let merged = square.merged(&bigger).unwrap();
+assert_eq!(merged.area(), 64);
+
+let circle = Shape_TO::from_ptr(RArc::new(Circle(1)), TD_CanDowncast);
+assert!(square.merged(&circle).is_err());
+# }
+```
 
 # Object safety
 
 Trait objects generated using this attribute have similar restrictions to built-in trait objects: 
 
 - `Self` can only be used to access associated types 
-    (using the `Self::AssocType` syntax).
+    (using the `Self::AssocType` syntax),
+    as the return type, and in `&Self` parameters
+    ([more details here](#self-in-methods)).
 
 - `self` is a valid method receiver,
     this requires that the pointer that the generated trait object wraps 
@@ -222,6 +222,10 @@ and won't be usable afterwards.
 
 - `Error`: corresponds to `std::error::Error` .
 
+- `SabiKey`:
+    this type will also have to implement [`abi_stable::erased_types::SabiKeyType`],
+    and `PartialEq`/`Ord`/`PartialOrd`/`Hash` compare and hash the keys of the values.
+
 <br>
 Examples:
 
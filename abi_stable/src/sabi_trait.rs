@@ -19,10 +19,18 @@ pub mod reexports {
             marker_type::{
                 NonOwningPhantom, SyncSend, SyncUnsend, UnsafeIgnoredType, UnsyncSend, UnsyncUnsend,
             },
-            pointer_trait::{AsMutPtr, AsPtr, CanTransmuteElement, OwnedPointer, TransmuteElement},
+            pointer_trait::{
+                AsMutPtr, AsPtr, CanTransmuteElement, GetPointerKind, OwnedPointer,
+                PK_SmartPointer, PtrFromValue, TransmuteElement,
+            },
             prefix_type::{PrefixRef, PrefixTypeTrait, WithMetadata},
             sabi_trait::{
-                robject::RObject,
+                capabilities::{
+                    Capabilities, Capability, MakeCapability, QueryTarget, WithCapabilities,
+                },
+                instrument::{instrument_call, instrument_fallible_call, Instrumented},
+                mock::{MockArg, MockArgDebug, MockArgOpaque, MockCalls, MockMethod},
+                robject::{RObject, SelfTypeMismatch},
                 vtable::{GetRObjectVTable, RObjectVtable, RObjectVtable_Ref},
             },
             sabi_types::{MovePtr, RMut, RRef},
@@ -54,6 +62,12 @@ pub mod examples;
 
 pub mod doc_examples;
 
+mod capabilities;
+
+mod instrument;
+
+mod mock;
+
 mod robject;
 
 #[doc(hidden)]
@@ -72,6 +86,15 @@ use std::{
 
 use self::reexports::__sabi_re::*;
 
-pub use self::robject::{RObject, ReborrowBounds, UneraseError};
+pub use self::{
+    capabilities::{
+        Capabilities, Capability, CapabilityList, GetCapabilities, MakeCapability, QueryTarget,
+        WithCapabilities,
+    },
+    instrument::{
+        CallHook, CallHook_CTO, CallHook_TO, CallOutcome, Instrumented, MethodCall, SharedCallHook,
+    },
+    robject::{RObject, ReborrowBounds, SelfTypeMismatch, UneraseError},
+};
 
 use crate::{erased_types::c_functions, marker_type::ErasedObject, sabi_types::MaybeCmp};
@@ -0,0 +1,332 @@
+//! Extra `#[sabi_trait]` vtables ("capabilities") registered on a trait object
+//! when it's constructed, which can then be queried at runtime.
+
+use super::*;
+
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+    abi_stability::abi_checking::check_layout_compatibility,
+    erased_types::InterfaceType,
+    marker_type::ErasedPrefix,
+    pointer_trait::GetPointerKind,
+    sabi_types::LateStaticRef,
+    std_types::{RSlice, UTypeId},
+    type_layout::TypeLayout,
+    type_level::downcasting::GetUTID,
+    utils::leak_value,
+};
+
+/// The vtable of a `#[sabi_trait]` trait that the type inside a trait object implements,
+/// registered when the trait object was constructed.
+///
+/// Capabilities are registered with the
+/// `from_value_with_capabilities`/`from_ptr_with_capabilities` constructors
+/// of `#[sabi_trait]` generated trait objects,
+/// and queried with the
+/// [`RObject::query`](crate::sabi_trait::RObject::query) family of methods.
+#[repr(C)]
+#[derive(StableAbi, Copy, Clone)]
+pub struct Capability {
+    layout: &'static TypeLayout,
+    vtable: PrefixRef<ErasedPrefix>,
+}
+
+impl Capability {
+    /// Constructs a `Capability`.
+    ///
+    /// # Safety
+    ///
+    /// `layout` must be the `QueryTarget::CAPABILITY_LAYOUT` of the trait object
+    /// that `vtable` is the vtable of,
+    /// and `vtable` must have been constructed for the
+    /// same type and pointer type as the trait object it is registered in.
+    #[doc(hidden)]
+    pub const unsafe fn new(layout: &'static TypeLayout, vtable: PrefixRef<ErasedPrefix>) -> Self {
+        Self { layout, vtable }
+    }
+
+    /// The layout of the trait object this is a capability for.
+    pub const fn layout(&self) -> &'static TypeLayout {
+        self.layout
+    }
+}
+
+impl Debug for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Capability")
+            .field("trait_object", &self.layout.full_type())
+            .finish()
+    }
+}
+
+/// Finds the vtable of the capability with the `expected` layout.
+pub(super) fn find_capability(
+    capabilities: RSlice<'static, Capability>,
+    expected: &'static TypeLayout,
+) -> Option<PrefixRef<ErasedPrefix>> {
+    capabilities
+        .iter()
+        .find(|cap| std::ptr::eq(cap.layout, expected))
+        .or_else(|| {
+            capabilities
+                .iter()
+                .find(|cap| is_compatible_capability(expected, cap.layout))
+        })
+        .map(|cap| cap.vtable)
+}
+
+type CapabilityChecks = Mutex<HashMap<(UTypeId, UTypeId), bool>>;
+
+/// The results of checking the layout of a queried trait object
+/// against the layout of a registered capability,
+/// keyed by the type ids of both layouts.
+///
+/// Incompatible layouts are cached too,
+/// so that repeatedly querying an unregistered capability doesn't
+/// redo the layout checks.
+static CAPABILITY_CHECKS: LateStaticRef<&CapabilityChecks> = LateStaticRef::new();
+
+fn capability_checks() -> &'static CapabilityChecks {
+    CAPABILITY_CHECKS.init(|| leak_value(Mutex::new(HashMap::new())))
+}
+
+fn capability_check_key(
+    expected: &'static TypeLayout,
+    found: &'static TypeLayout,
+) -> (UTypeId, UTypeId) {
+    (expected.get_utypeid(), found.get_utypeid())
+}
+
+fn is_compatible_capability(expected: &'static TypeLayout, found: &'static TypeLayout) -> bool {
+    let checks = capability_checks();
+    let key = capability_check_key(expected, found);
+
+    if let Some(&compatible) = checks.lock().unwrap().get(&key) {
+        return compatible;
+    }
+
+    // The lock isn't held while checking the layouts,
+    // in case that the check queries capabilities itself.
+    let compatible = check_layout_compatibility(expected, found).is_ok();
+    checks.lock().unwrap().insert(key, compatible);
+    compatible
+}
+
+/// Gets the cached result of checking `expected` against the `found` capability layout.
+#[cfg(test)]
+pub(super) fn cached_capability_check(
+    expected: &'static TypeLayout,
+    found: &'static TypeLayout,
+) -> Option<bool> {
+    let key = capability_check_key(expected, found);
+    capability_checks().lock().unwrap().get(&key).copied()
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// Implemented by `#[sabi_trait]` generated trait objects (`Trait_TO`),
+/// to construct the [`Capability`] for `_Self`.
+///
+/// The pointer type parameter of the trait object is ignored,
+/// the vtable is always constructed for the `ErasedPtr`/`OrigPtr` pointer types.
+///
+/// This is only implemented for trait objects of `#[sabi_trait]` traits
+/// that use `RObject`, and have neither lifetime parameters nor associated types.
+pub trait MakeCapability<IA, _Self, ErasedPtr, OrigPtr> {
+    /// The capability for `_Self`.
+    const CAPABILITY: Capability;
+}
+
+/// A list of trait objects whose [`Capability`] is registered on a trait object.
+///
+/// This is implemented for tuples of up to 8 trait objects that implement [`MakeCapability`].
+pub trait CapabilityList<IA, _Self, ErasedPtr, OrigPtr> {
+    /// The capabilities for `_Self`.
+    const LIST: RSlice<'static, Capability>;
+}
+
+impl<IA, _Self, ErasedPtr, OrigPtr> CapabilityList<IA, _Self, ErasedPtr, OrigPtr> for () {
+    const LIST: RSlice<'static, Capability> = RSlice::EMPTY;
+}
+
+// A dummy type used to get around a compiler limitation WRT associated constants in traits.
+struct CapabilityListHelper<IA, _Self, ErasedPtr, OrigPtr, Caps>(
+    NonOwningPhantom<(IA, _Self, ErasedPtr, OrigPtr, Caps)>,
+);
+
+macro_rules! impl_capability_list {
+    ( $( ($($cap:ident),*) )* ) => {
+        $(
+            impl<IA, _Self, ErasedPtr, OrigPtr, $($cap,)*>
+                CapabilityListHelper<IA, _Self, ErasedPtr, OrigPtr, ($($cap,)*)>
+            where
+                $($cap: MakeCapability<IA, _Self, ErasedPtr, OrigPtr>,)*
+            {
+                const ARRAY: &'static [Capability] = &[$($cap::CAPABILITY,)*];
+            }
+
+            impl<IA, _Self, ErasedPtr, OrigPtr, $($cap,)*>
+                CapabilityList<IA, _Self, ErasedPtr, OrigPtr>
+            for ($($cap,)*)
+            where
+                $($cap: MakeCapability<IA, _Self, ErasedPtr, OrigPtr>,)*
+            {
+                const LIST: RSlice<'static, Capability> = RSlice::from_slice(
+                    CapabilityListHelper::<IA, _Self, ErasedPtr, OrigPtr, Self>::ARRAY
+                );
+            }
+        )*
+    };
+}
+
+impl_capability_list! {
+    (C0)
+    (C0, C1)
+    (C0, C1, C2)
+    (C0, C1, C2, C3)
+    (C0, C1, C2, C3, C4)
+    (C0, C1, C2, C3, C4, C5)
+    (C0, C1, C2, C3, C4, C5, C6)
+    (C0, C1, C2, C3, C4, C5, C6, C7)
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// Gets the capabilities that are registered in the vtable of `RObject`.
+///
+/// This is implemented by [`TD_CanDowncast`] and [`TD_Opaque`] (with no capabilities),
+/// and by [`WithCapabilities`].
+pub trait GetCapabilities<_Self, ErasedPtr, OrigPtr> {
+    /// The registered capabilities.
+    const CAPABILITIES: RSlice<'static, Capability>;
+}
+
+impl<_Self, ErasedPtr, OrigPtr> GetCapabilities<_Self, ErasedPtr, OrigPtr> for TD_CanDowncast {
+    const CAPABILITIES: RSlice<'static, Capability> = RSlice::EMPTY;
+}
+
+impl<_Self, ErasedPtr, OrigPtr> GetCapabilities<_Self, ErasedPtr, OrigPtr> for TD_Opaque {
+    const CAPABILITIES: RSlice<'static, Capability> = RSlice::EMPTY;
+}
+
+/// A list of trait objects,
+/// passed to the `*_with_capabilities` constructors of `#[sabi_trait]` trait objects
+/// to register the trait objects that can be queried from the constructed one.
+///
+/// `Caps` is a tuple of trait objects,
+/// the pointer type and the lifetime of each trait object are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::{
+///     sabi_trait::{
+///         doc_examples::{Action_TO, Doer_TO},
+///         Capabilities,
+///     },
+///     std_types::RBox,
+///     type_level::downcasting::TD_Opaque,
+///     RRef,
+/// };
+///
+/// let object = Action_TO::from_value_with_capabilities(
+///     10_usize,
+///     TD_Opaque,
+///     Capabilities::<(Doer_TO<'_, RBox<()>>,)>::NEW,
+/// );
+///
+/// let doer = object.obj.query::<Doer_TO<'_, RRef<'_, ()>>>().unwrap();
+/// assert_eq!(doer.value(), 10);
+///
+/// ```
+pub struct Capabilities<Caps>(NonOwningPhantom<Caps>);
+
+impl<Caps> Capabilities<Caps> {
+    /// Constructs this type
+    pub const NEW: Self = Self(NonOwningPhantom::NEW);
+}
+
+impl<Caps> Copy for Capabilities<Caps> {}
+
+impl<Caps> Clone for Capabilities<Caps> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// Wraps a [`TD_CanDowncast`]/[`TD_Opaque`] along with the [`Capabilities`]
+/// registered on a trait object.
+///
+/// This is what the `*_with_capabilities` constructors
+/// of `#[sabi_trait]` trait objects pass to the regular constructors.
+pub struct WithCapabilities<Downcasting, Caps> {
+    /// Whether the trait object can be downcasted.
+    pub downcasting: Downcasting,
+    /// The capabilities registered on the trait object.
+    pub capabilities: Capabilities<Caps>,
+}
+
+impl<Downcasting, Caps> WithCapabilities<Downcasting, Caps> {
+    /// Constructs this type
+    pub const fn new(downcasting: Downcasting, capabilities: Capabilities<Caps>) -> Self {
+        Self {
+            downcasting,
+            capabilities,
+        }
+    }
+}
+
+impl<Downcasting: Copy, Caps> Copy for WithCapabilities<Downcasting, Caps> {}
+
+impl<Downcasting: Copy, Caps> Clone for WithCapabilities<Downcasting, Caps> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, Downcasting, Caps> GetUTID<T> for WithCapabilities<Downcasting, Caps>
+where
+    Downcasting: GetUTID<T>,
+{
+    const UID: extern "C" fn() -> MaybeCmp<UTypeId> = Downcasting::UID;
+}
+
+impl<_Self, ErasedPtr, OrigPtr, Downcasting, Caps> GetCapabilities<_Self, ErasedPtr, OrigPtr>
+    for WithCapabilities<Downcasting, Caps>
+where
+    Caps: CapabilityList<Downcasting, _Self, ErasedPtr, OrigPtr>,
+{
+    const CAPABILITIES: RSlice<'static, Capability> = Caps::LIST;
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// Implemented by `#[sabi_trait]` generated trait objects that can be queried with the
+/// [`RObject::query`](crate::sabi_trait::RObject::query) family of methods.
+///
+/// This is only implemented for trait objects of `#[sabi_trait]` traits
+/// that use `RObject`, and have neither lifetime parameters nor associated types.
+///
+/// # Safety
+///
+/// `CAPABILITY_LAYOUT` must be the same layout that the [`MakeCapability`]
+/// impl of the trait object uses to construct its [`Capability`],
+/// and it must uniquely identify the `VTable` type.
+pub unsafe trait QueryTarget<'lt, P>: Sized
+where
+    P: GetPointerKind,
+{
+    /// The `InterfaceType` of the trait object.
+    type Interface: InterfaceType;
+
+    /// The vtable of the trait object.
+    type VTable;
+
+    /// The layout that identifies the trait object,
+    /// regardless of its pointer type.
+    const CAPABILITY_LAYOUT: &'static TypeLayout;
+
+    /// Constructs the trait object from its underlying `RObject`.
+    fn from_robject(obj: RObject<'lt, P, Self::Interface, Self::VTable>) -> Self;
+}
@@ -0,0 +1,379 @@
+//! Reporting of the method calls of `#[sabi_trait]` trait objects to a host-registered hook,
+//! enabled with the `#[sabi(instrument)]` attribute.
+
+use std::{
+    fmt::{self, Debug, Display},
+    panic::{self, AssertUnwindSafe},
+    time::Instant,
+};
+
+use crate::{
+    sabi_trait,
+    std_types::{RArc, RDuration, RResult, RStr},
+    StableAbi,
+};
+
+/// How a method call of an [`Instrumented`] trait object finished.
+///
+/// Using a struct with associated constants is the
+/// ffi-safe way of doing `#[non_exhaustive]` field-less enums.
+///
+/// Outcomes that this version of `abi_stable` doesn't know about
+/// (eg: ones reported by a library that uses a newer version)
+/// are preserved through their integer value.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct CallOutcome {
+    value: u8,
+}
+
+#[allow(non_upper_case_globals)]
+impl CallOutcome {
+    /// The method returned.
+    pub const Returned: Self = Self { value: 0 };
+
+    /// The method returned an `RErr`/`Err`.
+    ///
+    /// This is only reported for methods whose return type is written
+    /// as a path ending in `RResult` or `Result` in the trait definition,
+    /// since it's detected by the name of the type,
+    /// so methods that return a type alias of a result report `Returned` instead.
+    pub const ReturnedErr: Self = Self { value: 1 };
+
+    /// The method panicked,
+    /// the panic is resumed after the hook is called.
+    ///
+    /// This is only reported for methods whose panics unwind out of the trait object,
+    /// which requires the method to use the `#[sabi(catch_panic)]` or
+    /// `#[sabi(c_unwind)]` attribute
+    /// (panics in other methods abort the process before the hook is called).
+    pub const Panicked: Self = Self { value: 2 };
+}
+
+impl Debug for CallOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match *self {
+            CallOutcome::Returned => "Returned",
+            CallOutcome::ReturnedErr => "ReturnedErr",
+            CallOutcome::Panicked => "Panicked",
+            _ => return f.debug_tuple("CallOutcome").field(&self.value).finish(),
+        };
+        Display::fmt(s, f)
+    }
+}
+
+/// A method call of an [`Instrumented`] trait object,
+/// passed to [`CallHook::on_call`] after the method finishes.
+#[repr(C)]
+#[derive(StableAbi, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MethodCall<'a> {
+    /// The name of the `#[sabi_trait]` trait.
+    pub trait_name: RStr<'a>,
+    /// The name of the called method.
+    pub method_name: RStr<'a>,
+    /// How long the method took to run.
+    pub duration: RDuration,
+    /// How the method finished.
+    pub outcome: CallOutcome,
+}
+
+/// A hook that's notified of every method call of the [`Instrumented`] trait objects using it.
+///
+/// This is implemented for closures that take a [`MethodCall`].
+#[sabi_trait]
+pub trait CallHook: Send + Sync {
+    /// Called after a method of an instrumented trait object returns or panics.
+    #[sabi(last_prefix_field)]
+    fn on_call(&self, call: MethodCall<'_>);
+}
+
+impl<F> CallHook for F
+where
+    F: Fn(MethodCall<'_>) + Send + Sync,
+{
+    fn on_call(&self, call: MethodCall<'_>) {
+        self(call)
+    }
+}
+
+/// The [`CallHook`] trait object that [`Instrumented`] reports calls to,
+/// which can be shared by many instrumented trait objects.
+pub type SharedCallHook = CallHook_TO<'static, RArc<()>>;
+
+impl Clone for CallHook_TO<'static, RArc<()>> {
+    fn clone(&self) -> Self {
+        Self::from_sabi(self.obj.shallow_clone())
+    }
+}
+
+/// A wrapper around a [`#[sabi_trait]`](macro@crate::sabi_trait) trait object,
+/// which reports the name, duration, and outcome of every method call to a [`CallHook`].
+///
+/// This implements a `#[sabi_trait]` trait when the trait has the `#[sabi(instrument)]`
+/// attribute and `T` is the `Trait_TO` trait object of the trait.
+///
+/// Of the supertraits that `#[sabi_trait]` traits can have,
+/// this forwards `Clone`, `Debug`, `Display`, `PartialEq`, `Eq`, `PartialOrd`,
+/// `Ord`, and `Hash` to the wrapped trait object without reporting the calls.
+///
+/// Panics are only reported (as [`CallOutcome::Panicked`])
+/// when they unwind out of the trait object,
+/// which requires the method to use the `#[sabi(catch_panic)]` or
+/// `#[sabi(c_unwind)]` attribute.
+///
+/// Errors are only reported (as [`CallOutcome::ReturnedErr`])
+/// for methods whose return type is named `RResult` or `Result` in the trait definition,
+/// type aliases of results aren't detected.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::{
+///     sabi_trait,
+///     sabi_trait::{CallOutcome, Instrumented, MethodCall, SharedCallHook, TD_Opaque},
+///     std_types::{RArc, RBox, RResult, RStr, RString},
+/// };
+///
+/// use std::sync::{Arc, Mutex};
+///
+/// #[sabi_trait]
+/// #[sabi(instrument)]
+/// pub trait Parser {
+///     #[sabi(last_prefix_field)]
+///     fn parse(&self, input: RStr<'_>) -> RResult<u32, RString>;
+/// }
+///
+/// struct Decimal;
+///
+/// impl Parser for Decimal {
+///     fn parse(&self, input: RStr<'_>) -> RResult<u32, RString> {
+///         input.parse::<u32>().map_err(|e| e.to_string().into()).into()
+///     }
+/// }
+///
+/// # fn main() {
+/// let calls = Arc::new(Mutex::new(Vec::<(String, CallOutcome)>::new()));
+///
+/// let calls_hook = calls.clone();
+/// let hook = SharedCallHook::from_ptr(
+///     RArc::new(move |call: MethodCall<'_>| {
+///         let name = format!("{}::{}", call.trait_name, call.method_name);
+///         calls_hook.lock().unwrap().push((name, call.outcome));
+///     }),
+///     TD_Opaque,
+/// );
+///
+/// let parser = Instrumented::new(Parser_TO::from_value(Decimal, TD_Opaque), hook);
+///
+/// assert_eq!(parser.parse("100".into()), RResult::ROk(100));
+/// assert!(parser.parse("hello".into()).is_err());
+///
+/// assert_eq!(
+///     *calls.lock().unwrap(),
+///     vec![
+///         ("Parser::parse".to_string(), CallOutcome::Returned),
+///         ("Parser::parse".to_string(), CallOutcome::ReturnedErr),
+///     ],
+/// );
+///
+/// // The wrapped trait object can be gotten back
+/// let _: Parser_TO<'static, RBox<()>> = parser.into_inner();
+/// # }
+/// ```
+pub struct Instrumented<T> {
+    inner: T,
+    hook: SharedCallHook,
+}
+
+impl<T> Instrumented<T> {
+    /// Wraps `inner`, reporting the calls to its methods to `hook`.
+    pub fn new(inner: T, hook: SharedCallHook) -> Self {
+        Self { inner, hook }
+    }
+
+    /// Gets a reference to the wrapped trait object.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the wrapped trait object.
+    ///
+    /// Calls through the returned reference aren't reported.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps the trait object.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Gets the hook that the method calls are reported to.
+    pub fn hook(&self) -> &SharedCallHook {
+        &self.hook
+    }
+
+    #[doc(hidden)]
+    pub fn sabi_split_ref(&self) -> (&T, &SharedCallHook) {
+        (&self.inner, &self.hook)
+    }
+
+    #[doc(hidden)]
+    pub fn sabi_split_mut(&mut self) -> (&mut T, &SharedCallHook) {
+        (&mut self.inner, &self.hook)
+    }
+
+    #[doc(hidden)]
+    pub fn sabi_split(self) -> (T, SharedCallHook) {
+        (self.inner, self.hook)
+    }
+}
+
+impl<T> Clone for Instrumented<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone(), self.hook.clone())
+    }
+}
+
+impl<T> Debug for Instrumented<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+
+impl<T> Display for Instrumented<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.inner, f)
+    }
+}
+
+impl<T> PartialEq for Instrumented<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<T> Eq for Instrumented<T> where T: Eq {}
+
+impl<T> PartialOrd for Instrumented<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.inner.partial_cmp(&other.inner)
+    }
+}
+
+impl<T> Ord for Instrumented<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.inner.cmp(&other.inner)
+    }
+}
+
+impl<T> std::hash::Hash for Instrumented<T>
+where
+    T: std::hash::Hash,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.inner.hash(state)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// For the return types of methods that report `CallOutcome::ReturnedErr`.
+#[doc(hidden)]
+pub trait FallibleReturn {
+    fn is_err_return(&self) -> bool;
+}
+
+impl<T, E> FallibleReturn for RResult<T, E> {
+    fn is_err_return(&self) -> bool {
+        self.is_err()
+    }
+}
+
+impl<T, E> FallibleReturn for Result<T, E> {
+    fn is_err_return(&self) -> bool {
+        self.is_err()
+    }
+}
+
+/// Calls `f`, reporting the call to `hook`.
+#[doc(hidden)]
+pub fn instrument_call<R, F>(
+    hook: &SharedCallHook,
+    trait_name: &'static str,
+    method_name: &'static str,
+    f: F,
+) -> R
+where
+    F: FnOnce() -> R,
+{
+    report_call(hook, trait_name, method_name, f, |_| false)
+}
+
+/// Calls `f`, reporting the call to `hook`,
+/// with `CallOutcome::ReturnedErr` if `f` returned an error.
+#[doc(hidden)]
+pub fn instrument_fallible_call<R, F>(
+    hook: &SharedCallHook,
+    trait_name: &'static str,
+    method_name: &'static str,
+    f: F,
+) -> R
+where
+    F: FnOnce() -> R,
+    R: FallibleReturn,
+{
+    report_call(hook, trait_name, method_name, f, R::is_err_return)
+}
+
+fn report_call<R, F>(
+    hook: &SharedCallHook,
+    trait_name: &'static str,
+    method_name: &'static str,
+    f: F,
+    is_err: fn(&R) -> bool,
+) -> R
+where
+    F: FnOnce() -> R,
+{
+    let start = Instant::now();
+    let res = panic::catch_unwind(AssertUnwindSafe(f));
+    let duration = RDuration::from(start.elapsed());
+
+    let outcome = match &res {
+        Ok(ret) if is_err(ret) => CallOutcome::ReturnedErr,
+        Ok(_) => CallOutcome::Returned,
+        Err(_) => CallOutcome::Panicked,
+    };
+
+    hook.on_call(MethodCall {
+        trait_name: RStr::from_str(trait_name),
+        method_name: RStr::from_str(method_name),
+        duration,
+        outcome,
+    });
+
+    match res {
+        Ok(ret) => ret,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
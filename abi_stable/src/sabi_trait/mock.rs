@@ -0,0 +1,177 @@
+//! Items used by the `Trait_Mock` types generated by the `#[sabi(mock)]` attribute.
+
+use std::{
+    fmt::{self, Debug},
+    sync::Arc,
+};
+
+use parking_lot::Mutex;
+
+/// The behavior configured for one method of a `Trait_Mock`,
+/// shared between all the clones of the mock.
+///
+/// `F` is the `dyn Fn(..) -> _` type that the method calls.
+#[doc(hidden)]
+pub struct MockMethod<F: ?Sized> {
+    behavior: Arc<Mutex<Option<Arc<F>>>>,
+}
+
+impl<F: ?Sized> MockMethod<F> {
+    pub fn new() -> Self {
+        Self {
+            behavior: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Sets the behavior of the method,
+    /// expecting `method` to be called before `MockCalls::verify`.
+    pub fn sabi_set(&self, calls: &MockCalls, method: &'static str, behavior: Arc<F>) {
+        *self.behavior.lock() = Some(behavior);
+        calls.expect(method);
+    }
+
+    /// Records the call in `calls`, returning the configured behavior,
+    /// or `None` if the method wasn't configured.
+    ///
+    /// The behavior is cloned out of the lock,
+    /// so that the method can be called again (or concurrently) from inside of it.
+    pub fn sabi_behavior(
+        &self,
+        calls: &MockCalls,
+        method: &'static str,
+        args: Vec<String>,
+    ) -> Option<Arc<F>> {
+        calls.record(method, args);
+        self.behavior.lock().clone()
+    }
+}
+
+impl<F: ?Sized> Default for MockMethod<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: ?Sized> Clone for MockMethod<F> {
+    fn clone(&self) -> Self {
+        Self {
+            behavior: self.behavior.clone(),
+        }
+    }
+}
+
+/// The methods called on a `Trait_Mock` (in call order) along with their arguments,
+/// and the methods that are expected to be called,
+/// shared between all the clones of the mock.
+#[doc(hidden)]
+#[derive(Default, Clone)]
+pub struct MockCalls {
+    calls: Arc<Mutex<Vec<MockCall>>>,
+    expected: Arc<Mutex<Vec<&'static str>>>,
+}
+
+struct MockCall {
+    method: &'static str,
+    args: Vec<String>,
+}
+
+impl MockCalls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&self, method: &'static str, args: Vec<String>) {
+        self.calls.lock().push(MockCall { method, args });
+    }
+
+    fn expect(&self, method: &'static str) {
+        let mut expected = self.expected.lock();
+        if !expected.contains(&method) {
+            expected.push(method);
+        }
+    }
+
+    pub fn calls(&self) -> Vec<&'static str> {
+        self.calls.lock().iter().map(|c| c.method).collect()
+    }
+
+    pub fn call_count(&self, method: &str) -> usize {
+        self.calls
+            .lock()
+            .iter()
+            .filter(|c| c.method == method)
+            .count()
+    }
+
+    pub fn call_args(&self, method: &str) -> Vec<Vec<String>> {
+        self.calls
+            .lock()
+            .iter()
+            .filter(|c| c.method == method)
+            .map(|c| c.args.clone())
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.calls.lock().clear();
+    }
+
+    /// Panics if any of the expected methods wasn't called.
+    pub fn verify(&self, mock_name: &str) {
+        let calls = self.calls.lock();
+        let uncalled = self
+            .expected
+            .lock()
+            .iter()
+            .filter(|&&method| !calls.iter().any(|c| c.method == method))
+            .copied()
+            .collect::<Vec<&str>>();
+
+        if !uncalled.is_empty() {
+            panic!(
+                "these `{}` methods were expected to be called, but weren't: {}",
+                mock_name,
+                uncalled.join(", "),
+            );
+        }
+    }
+}
+
+impl Debug for MockCalls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.calls.lock().iter().map(|c| c.method))
+            .finish()
+    }
+}
+
+/// Formats an argument of a mocked method,
+/// with the Debug representation if it implements `Debug`,
+/// and `_` otherwise.
+///
+/// Used as `(&MockArg(&arg)).sabi_mock_arg()`,
+/// with both `MockArgDebug` and `MockArgOpaque` in scope.
+#[doc(hidden)]
+pub struct MockArg<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait MockArgDebug {
+    fn sabi_mock_arg(&self) -> String;
+}
+
+impl<T: Debug + ?Sized> MockArgDebug for MockArg<'_, T> {
+    fn sabi_mock_arg(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait MockArgOpaque {
+    fn sabi_mock_arg(&self) -> String;
+}
+
+impl<T: ?Sized> MockArgOpaque for &MockArg<'_, T> {
+    fn sabi_mock_arg(&self) -> String {
+        "_".to_string()
+    }
+}
@@ -8,13 +8,17 @@ use core_extensions::SelfOps;
 use crate::{
     abi_stability::PrefixStableAbi,
     erased_types::{c_functions::adapt_std_fmt, InterfaceType, MakeRequiredTraits},
+    library::{supervisor::check_library_of, SupervisorError},
     pointer_trait::{
         AsMutPtr, AsPtr, CanTransmuteElement, GetPointerKind, PK_Reference, PK_SmartPointer,
         PointerKind, TransmuteElement,
     },
-    sabi_trait::vtable::{BaseVtable_Prefix, BaseVtable_Ref},
+    sabi_trait::{
+        capabilities::{find_capability, Capability, QueryTarget},
+        vtable::{BaseVtable_Prefix, BaseVtable_Ref},
+    },
     sabi_types::{MaybeCmp, RMut, RRef},
-    std_types::UTypeId,
+    std_types::{RSlice, UTypeId},
     type_level::{
         impl_enum::{Implemented, Unimplemented},
         trait_marker,
@@ -69,6 +73,20 @@ use crate::{
 ///
 /// `RObject` can only be converted back if the trait object was constructed to allow it.
 ///
+/// # Querying
+///
+/// `RObject<_>` can be converted into the trait object of another `#[sabi_trait]` trait
+/// that was registered as a capability when the trait object was constructed,
+/// using these (fallible) conversion methods:
+///
+/// - [`query`](#method.query): Converts into a trait object that borrows this one.
+///
+/// - [`query_mut`](#method.query_mut):
+/// Converts into a trait object that mutably borrows this one.
+///
+/// - [`query_into`](#method.query_into):
+/// Converts into a trait object with the same pointer.
+///
 ///
 ///
 ///
@@ -216,14 +234,14 @@ where
 
 unsafe impl<'lt, P, I, V> Send for RObject<'lt, P, I, V>
 where
-    P: GetPointerKind,
+    P: Send + GetPointerKind,
     I: InterfaceType<Send = Implemented<trait_marker::Send>>,
 {
 }
 
 unsafe impl<'lt, P, I, V> Sync for RObject<'lt, P, I, V>
 where
-    P: GetPointerKind,
+    P: Sync + GetPointerKind,
     I: InterfaceType<Sync = Implemented<trait_marker::Sync>>,
 {
 }
@@ -271,6 +289,27 @@ where
             _marker: PhantomData,
         }
     }
+
+    /// Constructs an RObject from an already erased pointer and the vtable of another RObject.
+    ///
+    /// This is mostly intended to be called by `#[sabi_trait]` generated trait objects,
+    /// to wrap the `Self` returned by their methods.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a value of the type that `vtable` was constructed for,
+    /// and `P` must be the pointer type that `vtable` was constructed for.
+    ///
+    /// The vtable must not come from a reborrowed `RObject`
+    /// (created using `RObject::reborrow` or `RObject::reborrow_mut`).
+    ///
+    pub unsafe fn from_erased_ptr(ptr: P, vtable: PrefixRef<V>) -> RObject<'lt, P, I, V> {
+        RObject {
+            vtable,
+            ptr: ManuallyDrop::new(ptr),
+            _marker: PhantomData,
+        }
+    }
 }
 
 impl<'borr, 'a, I, V> RObject<'borr, RRef<'a, ()>, I, V> {
@@ -746,6 +785,177 @@ where
     }
 }
 
+impl<'lt, P, I, V> RObject<'lt, P, I, V>
+where
+    P: GetPointerKind,
+{
+    /// Gets the capabilities that were registered when the trait object was constructed.
+    pub fn sabi_capabilities(&self) -> RSlice<'static, Capability> {
+        self.sabi_robject_vtable()._sabi_capabilities()
+    }
+
+    fn sabi_find_capability<'lt2, P2, C>(&self) -> Option<PrefixRef<C::VTable>>
+    where
+        P2: GetPointerKind,
+        C: QueryTarget<'lt2, P2>,
+    {
+        find_capability(self.sabi_capabilities(), C::CAPABILITY_LAYOUT)
+            .map(|vtable| unsafe { vtable.cast::<C::VTable>() })
+    }
+
+    /// Queries whether the trait object was constructed with `C` as a capability,
+    /// returning a borrowing `C` trait object if it was.
+    ///
+    /// Capabilities are registered with the `from_value_with_capabilities`/
+    /// `from_ptr_with_capabilities` constructors of `#[sabi_trait]` trait objects.
+    ///
+    /// The returned trait object can be downcasted if this one can,
+    /// but has no capabilities of its own.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::{
+    ///     sabi_trait::{
+    ///         doc_examples::{Action_TO, Doer_TO},
+    ///         Capabilities,
+    ///     },
+    ///     std_types::RBox,
+    ///     type_level::downcasting::TD_Opaque,
+    ///     RRef,
+    /// };
+    ///
+    /// let object = Action_TO::from_value_with_capabilities(
+    ///     3_usize,
+    ///     TD_Opaque,
+    ///     Capabilities::<(Doer_TO<'_, RBox<()>>,)>::NEW,
+    /// );
+    ///
+    /// // `object.obj` is an RObject
+    /// let doer = object.obj.query::<Doer_TO<'_, RRef<'_, ()>>>().unwrap();
+    /// assert_eq!(doer.do_it(2), 5);
+    ///
+    /// // Trait objects constructed without capabilities can't be queried.
+    /// let object = Action_TO::from_value(3_usize, TD_Opaque);
+    /// assert!(object.obj.query::<Doer_TO<'_, RRef<'_, ()>>>().is_none());
+    ///
+    /// ```
+    pub fn query<'re, C>(&'re self) -> Option<C>
+    where
+        P: AsPtr<PtrTarget = ()>,
+        C: QueryTarget<'lt, RRef<'re, ()>>,
+        PrivStruct: ReborrowBounds<
+            <C::Interface as InterfaceType>::Send,
+            <C::Interface as InterfaceType>::Sync,
+        >,
+    {
+        let vtable = self.sabi_find_capability::<RRef<'re, ()>, C>()?;
+        Some(C::from_robject(RObject {
+            vtable,
+            ptr: ManuallyDrop::new(self.ptr.as_rref()),
+            _marker: PhantomData,
+        }))
+    }
+
+    /// Queries whether the trait object was constructed with `C` as a capability,
+    /// returning a mutably borrowing `C` trait object if it was.
+    ///
+    /// The returned trait object can be downcasted if this one can,
+    /// but has no capabilities of its own.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::{
+    ///     sabi_trait::{
+    ///         doc_examples::{Action_TO, Doer_TO},
+    ///         Capabilities,
+    ///     },
+    ///     std_types::RBox,
+    ///     type_level::downcasting::TD_Opaque,
+    ///     RMut,
+    /// };
+    ///
+    /// let mut object = Action_TO::from_value_with_capabilities(
+    ///     3_usize,
+    ///     TD_Opaque,
+    ///     Capabilities::<(Doer_TO<'_, RBox<()>>,)>::NEW,
+    /// );
+    ///
+    /// {
+    ///     // `object.obj` is an RObject
+    ///     let mut doer = object.obj.query_mut::<Doer_TO<'_, RMut<'_, ()>>>().unwrap();
+    ///     doer.add_into(5);
+    /// }
+    /// assert_eq!(object.get(), 8);
+    ///
+    /// ```
+    pub fn query_mut<'re, C>(&'re mut self) -> Option<C>
+    where
+        P: AsMutPtr<PtrTarget = ()>,
+        C: QueryTarget<'lt, RMut<'re, ()>>,
+        PrivStruct: ReborrowBounds<
+            <C::Interface as InterfaceType>::Send,
+            <C::Interface as InterfaceType>::Sync,
+        >,
+    {
+        let vtable = self.sabi_find_capability::<RMut<'re, ()>, C>()?;
+        Some(C::from_robject(RObject {
+            vtable,
+            ptr: ManuallyDrop::new(self.ptr.as_rmut()),
+            _marker: PhantomData,
+        }))
+    }
+
+    /// Queries whether the trait object was constructed with `C` as a capability,
+    /// converting this into a `C` trait object with the same pointer if it was.
+    ///
+    /// The returned trait object can be downcasted if this one can,
+    /// but has no capabilities of its own.
+    ///
+    /// # Errors
+    ///
+    /// This returns `self` back if the trait object was not constructed with
+    /// `C` as a capability.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::{
+    ///     sabi_trait::{
+    ///         doc_examples::{Action_TO, Doer_TO},
+    ///         Capabilities,
+    ///     },
+    ///     std_types::RBox,
+    ///     type_level::downcasting::TD_Opaque,
+    /// };
+    ///
+    /// let object = Action_TO::from_value_with_capabilities(
+    ///     3_usize,
+    ///     TD_Opaque,
+    ///     Capabilities::<(Doer_TO<'_, RBox<()>>,)>::NEW,
+    /// );
+    ///
+    /// // `object.obj` is an RObject
+    /// let doer: Doer_TO<'_, RBox<()>> = object.obj.query_into().ok().unwrap();
+    /// assert_eq!(doer.value(), 3);
+    ///
+    /// ```
+    pub fn query_into<C>(self) -> Result<C, Self>
+    where
+        C: QueryTarget<'lt, P>,
+    {
+        match self.sabi_find_capability::<P, C>() {
+            Some(vtable) => Ok(C::from_robject(RObject {
+                vtable,
+                ptr: self.sabi_into_erased_ptr(),
+                _marker: PhantomData,
+            })),
+            None => Err(self),
+        }
+    }
+}
+
 impl<'lt, P, I, V> RObject<'lt, P, I, V>
 where
     P: GetPointerKind,
@@ -762,6 +972,84 @@ where
         unsafe { BaseVtable_Ref(self.vtable.cast::<BaseVtable_Prefix<(), P, I>>())._sabi_vtable() }
     }
 
+    /// Returns an error if the dynamic library that constructed this trait object
+    /// was poisoned by its [`LibrarySupervisor`].
+    ///
+    /// This always returns `Ok` for trait objects whose library isn't supervised,
+    /// and for the trait objects constructed by older versions of `abi_stable`.
+    ///
+    /// [`LibrarySupervisor`]: ../library/struct.LibrarySupervisor.html
+    pub fn sabi_check_library(&self) -> Result<(), SupervisorError> {
+        match self.sabi_robject_vtable()._sabi_library_id() {
+            Some(library_id) => check_library_of(library_id),
+            None => Ok(()),
+        }
+    }
+
+    /// Panics with a `SupervisorError::Poisoned` payload if
+    /// [`sabi_check_library`](#method.sabi_check_library) returns an error.
+    ///
+    /// This is called by `#[sabi_trait]` generated trait objects before calling a method,
+    /// so that code from poisoned libraries doesn't run.
+    #[doc(hidden)]
+    #[inline]
+    pub fn sabi_assert_library_not_poisoned(&self) {
+        if let Err(e) = self.sabi_check_library() {
+            std::panic::panic_any(e)
+        }
+    }
+
+    /// Checks that `other` was constructed from the same type as `self`.
+    ///
+    /// This is mostly intended to be called by `#[sabi_trait]` generated trait objects,
+    /// to check the `&Self` parameters of their methods.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the trait objects were constructed from different types,
+    /// or if either of them was constructed with `TD_Opaque`.
+    pub fn sabi_check_same_type<P2, I2, V2>(
+        &self,
+        other: &RObject<'_, P2, I2, V2>,
+    ) -> Result<(), SelfTypeMismatch>
+    where
+        P2: GetPointerKind,
+    {
+        let expected_typeid = self.sabi_robject_vtable()._sabi_type_id()();
+        let found_typeid = other.sabi_robject_vtable()._sabi_type_id()();
+        // `MaybeCmp::Nothing` compares unequal to everything, including itself.
+        if expected_typeid == found_typeid {
+            Ok(())
+        } else {
+            Err(SelfTypeMismatch {
+                expected_typeid,
+                found_typeid,
+            })
+        }
+    }
+
+    /// Replaces the vtable of this `RObject`, along with the `InterfaceType` it implements.
+    ///
+    /// This is mostly intended to be called by `#[sabi_trait]` generated trait objects,
+    /// to upcast them into the trait object of a supertrait.
+    ///
+    /// # Safety
+    ///
+    /// `vtable` must have been constructed for the same type and pointer type
+    /// as the current vtable,
+    /// and must have `RObjectVtable_Ref` as its first declared field.
+    ///
+    pub unsafe fn sabi_replace_vtable<I2, V2>(
+        self,
+        vtable: PrefixRef<V2>,
+    ) -> RObject<'lt, P, I2, V2> {
+        RObject {
+            vtable,
+            ptr: self.sabi_into_erased_ptr(),
+            _marker: PhantomData,
+        }
+    }
+
     #[inline]
     fn sabi_into_erased_ptr(self) -> ManuallyDrop<P> {
         let __this = ManuallyDrop::new(self);
@@ -822,6 +1110,40 @@ impl<'lt, I, V> RObject<'lt, crate::std_types::RArc<()>, I, V> {
     }
 }
 
+impl<'lt, I, V> RObject<'lt, crate::std_types::RRc<()>, I, V> {
+    /// Does a shallow clone of the object, just incrementing the reference counter
+    ///
+    /// Because the reference count of `RRc` isn't atomic,
+    /// `RObject`s wrapping an `RRc` are neither `Send` nor `Sync`,
+    /// and can't be constructed for traits that require `Send` or `Sync`.
+    ///
+    /// ```compile_fail
+    /// use abi_stable::{sabi_trait, sabi_trait::TD_Opaque, std_types::RRc};
+    ///
+    /// #[sabi_trait]
+    /// pub trait Getter: Send + Sync {
+    ///     fn get(&self) -> u32;
+    /// }
+    ///
+    /// impl Getter for u32 {
+    ///     fn get(&self) -> u32 {
+    ///         *self
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// let _: Getter_TO<'static, RRc<()>> = Getter_TO::from_ptr(RRc::new(3u32), TD_Opaque);
+    /// # }
+    /// ```
+    pub fn shallow_clone(&self) -> Self {
+        Self {
+            vtable: self.vtable,
+            ptr: self.ptr.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
 impl<P, I, V> Drop for RObject<'_, P, I, V>
 where
     P: GetPointerKind,
@@ -888,3 +1210,52 @@ impl<D> fmt::Display for UneraseError<D> {
 impl<D> ::std::error::Error for UneraseError<D> {}
 
 //////////////////////////////////////////////////////////////////
+
+/// Error for a `&Self` argument of a `#[sabi_trait]` trait object method
+/// that was constructed from a different type than `self`.
+///
+/// Both trait objects must be constructed with `TD_CanDowncast`
+/// for the check to succeed.
+#[derive(Copy, Clone)]
+pub struct SelfTypeMismatch {
+    expected_typeid: MaybeCmp<UTypeId>,
+    found_typeid: MaybeCmp<UTypeId>,
+}
+
+impl SelfTypeMismatch {
+    /// The type id of the type that `self` was constructed from.
+    pub const fn expected_typeid(&self) -> MaybeCmp<UTypeId> {
+        self.expected_typeid
+    }
+
+    /// The type id of the type that the `&Self` argument was constructed from.
+    pub const fn found_typeid(&self) -> MaybeCmp<UTypeId> {
+        self.found_typeid
+    }
+}
+
+impl fmt::Debug for SelfTypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SelfTypeMismatch")
+            .field("expected_typeid", &self.expected_typeid)
+            .field("found_typeid", &self.found_typeid)
+            .finish()
+    }
+}
+
+impl fmt::Display for SelfTypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let (MaybeCmp::Just(_), MaybeCmp::Just(_)) = (self.expected_typeid, self.found_typeid) {
+            f.write_str("the `&Self` argument was constructed from a different type than `self`")
+        } else {
+            f.write_str(
+                "can't check that the `&Self` argument was constructed from the same type \
+                 as `self`, both trait objects must be constructed with `TD_CanDowncast`",
+            )
+        }
+    }
+}
+
+impl ::std::error::Error for SelfTypeMismatch {}
+
+//////////////////////////////////////////////////////////////////
@@ -276,73 +276,150 @@ pub mod only_debug {
     }
 }
 
-// pub mod only_serialize{
-//     use super::*;
-
-//     #[sabi_trait]
-//     #[sabi(use_dyntrait)]
-//     pub trait Trait:Serialize{
-//         fn method(&self){}
-//     }
-
-//     #[derive(::serde::Serialize)]
-//     pub struct Struct;
-
-//     impl Trait for Struct{}
-
-//     impl SerializeType for Struct {
-//         fn serialize_impl<'a>(&'a self) -> Result<RCow<'a, str>, RBoxError>{
-//             Ok(RCow::from("Struct"))
-//         }
-//     }
-
-//     fn assert_bound<T>(_:&T)
-//     where
-//         T:serde::Serialize
-//     {}
-
-//     fn test_constructible(){
-//         let object=Trait_TO::from_value(Struct,TD_CanDowncast);
-//         object.method();
-//         assert_bound(&object);
-//     }
-// }
-
-// pub mod only_deserialize_a{
-//     use super::*;
-//     use serde::Deserialize;
-
-//     #[sabi_trait]
-//     #[sabi(use_dyntrait)]
-//     //#[sabi(debug_print_trait)]
-//     pub trait Trait: for<'a> Deserialize<'a> {
-//         fn method(&self){}
-//     }
-
-//     impl<'a> DeserializeOwnedInterface<'a> for Trait_Interface{
-//         type Deserialized=Trait_Backend<'a,RBox<()>>;
-
-//         fn deserialize_impl(s: RStr<'_>) -> Result<Self::Deserialized, RBoxError>{
-//             Ok(DynTrait::from_value(Struct,Trait_Interface::NEW))
-//         }
-//     }
-
-//     #[derive(Deserialize)]
-//     pub struct Struct;
-
-//     impl Trait for Struct{}
-
-//     fn assert_bound<T>(_:&T)
-//     where
-//         T:for<'a>Deserialize<'a>
-//     {}
-
-//     fn test_constructible(){
-//         let object=Trait_TO::from_value(Struct,TD_CanDowncast);
-//         object.method();
-//         assert_bound(&object);
-//     }
-// }
+pub mod only_serialize {
+    use super::*;
+
+    use crate::{
+        erased_types::{SerializeProxyType, SerializeType},
+        std_types::{RBoxError, RString},
+    };
+
+    use serde::Serialize;
+
+    #[sabi_trait]
+    #[sabi(use_dyntrait)]
+    pub trait Trait: Serialize {
+        fn method(&self) {}
+    }
+
+    impl SerializeProxyType<'_> for Trait_trait::Trait_Interface {
+        type Proxy = RString;
+    }
+
+    #[test]
+    fn test_impls() {
+        type GI = GetImpls<Trait_TO<'static, RBox<()>>>;
+        assert!(!GI::IMPLS_SEND);
+        assert!(!GI::IMPLS_SYNC);
+        assert!(!GI::IMPLS_UNPIN);
+        assert!(!GI::IMPLS_CLONE);
+        assert!(!GI::IMPLS_DISPLAY);
+        assert!(!GI::IMPLS_DEBUG);
+        assert!(GI::IMPLS_SERIALIZE);
+        assert!(!GI::IMPLS_EQ);
+        assert!(!GI::IMPLS_PARTIAL_EQ);
+        assert!(!GI::IMPLS_ORD);
+        assert!(!GI::IMPLS_PARTIAL_ORD);
+        assert!(!GI::IMPLS_HASH);
+        assert!(!GI::IMPLS_DESERIALIZE);
+        assert!(!GI::IMPLS_ITERATOR);
+        assert!(!GI::IMPLS_DOUBLE_ENDED_ITERATOR);
+        assert!(!GI::IMPLS_FMT_WRITE);
+        assert!(!GI::IMPLS_IO_WRITE);
+        assert!(!GI::IMPLS_IO_SEEK);
+        assert!(!GI::IMPLS_IO_READ);
+        assert!(!GI::IMPLS_IO_BUF_READ);
+        assert!(!GI::IMPLS_ERROR);
+    }
+
+    #[derive(Serialize)]
+    pub struct Struct;
+
+    impl Trait for Struct {}
+
+    impl SerializeType<'_> for Struct {
+        type Interface = Trait_trait::Trait_Interface;
+
+        fn serialize_impl(&self) -> Result<RString, RBoxError> {
+            Ok(RString::from("Struct"))
+        }
+    }
+
+    fn assert_bound<T>(_: &T)
+    where
+        T: Trait + Serialize,
+    {
+    }
+
+    #[test]
+    fn test_constructible() {
+        let object = Trait_TO::from_value(Struct, TD_CanDowncast);
+        object.method();
+        assert_bound(&object);
+        assert_eq!(serde_json::to_string(&object).unwrap(), r#""Struct""#);
+    }
+}
+
+pub mod only_deserialize {
+    use super::*;
+
+    use crate::{erased_types::DeserializeDyn, std_types::RBoxError};
+
+    use serde::Deserialize;
+
+    #[sabi_trait]
+    #[sabi(use_dyntrait)]
+    pub trait Trait: for<'a> Deserialize<'a> {
+        fn method(&self) -> u32;
+    }
+
+    impl<'a> DeserializeDyn<'a, Trait_TO<'static, RBox<()>>> for Trait_trait::Trait_Interface {
+        type Proxy = u32;
+
+        fn deserialize_dyn(s: u32) -> Result<Trait_TO<'static, RBox<()>>, RBoxError> {
+            Ok(Trait_TO::from_value(Struct(s), TD_CanDowncast))
+        }
+    }
+
+    #[test]
+    fn test_impls() {
+        type GI = GetImpls<Trait_TO<'static, RBox<()>>>;
+        assert!(!GI::IMPLS_SEND);
+        assert!(!GI::IMPLS_SYNC);
+        assert!(!GI::IMPLS_UNPIN);
+        assert!(!GI::IMPLS_CLONE);
+        assert!(!GI::IMPLS_DISPLAY);
+        assert!(!GI::IMPLS_DEBUG);
+        assert!(!GI::IMPLS_SERIALIZE);
+        assert!(!GI::IMPLS_EQ);
+        assert!(!GI::IMPLS_PARTIAL_EQ);
+        assert!(!GI::IMPLS_ORD);
+        assert!(!GI::IMPLS_PARTIAL_ORD);
+        assert!(!GI::IMPLS_HASH);
+        assert!(GI::IMPLS_DESERIALIZE);
+        assert!(!GI::IMPLS_ITERATOR);
+        assert!(!GI::IMPLS_DOUBLE_ENDED_ITERATOR);
+        assert!(!GI::IMPLS_FMT_WRITE);
+        assert!(!GI::IMPLS_IO_WRITE);
+        assert!(!GI::IMPLS_IO_SEEK);
+        assert!(!GI::IMPLS_IO_READ);
+        assert!(!GI::IMPLS_IO_BUF_READ);
+        assert!(!GI::IMPLS_ERROR);
+    }
+
+    #[derive(Deserialize)]
+    pub struct Struct(u32);
+
+    impl Trait for Struct {
+        fn method(&self) -> u32 {
+            self.0
+        }
+    }
+
+    fn assert_bound<T>(_: &T)
+    where
+        T: Trait + for<'a> Deserialize<'a>,
+    {
+    }
+
+    #[test]
+    fn test_constructible() {
+        let object = serde_json::from_str::<Trait_TO<'static, RBox<()>>>("3").unwrap();
+        assert_eq!(object.method(), 3);
+        assert_bound(&object);
+        assert!(serde_json::from_str::<Trait_TO<'static, RBox<()>>>(r#""3""#).is_err());
+    }
+}
 
 pub mod only_partial_eq {
     use super::*;
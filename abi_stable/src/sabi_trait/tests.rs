@@ -333,3 +333,1307 @@ fn docs_are_included_test() {
         has_docs::TOKENS
     );
 }
+
+/*////////////////////////////////////////////////////////////////////////////////
+Test that #[sabi(catch_panic)] propagates panics across the vtable.
+*/////////////////////////////////////////////////////////////////////////////////
+
+mod catch_panic {
+    use super::*;
+
+    #[sabi_trait]
+    #[sabi(catch_panic)]
+    pub trait AllMethods {
+        fn by_ref(&self, x: u32) -> u32;
+        fn by_mut(&mut self) -> &mut u32;
+        fn borrowing(&self) -> RStr<'_>;
+        fn by_val(self) -> u32;
+    }
+
+    #[sabi_trait]
+    pub trait OneMethod {
+        #[sabi(catch_panic)]
+        fn catches(&self);
+        fn aborts(&self) -> u32;
+    }
+
+    impl AllMethods for u32 {
+        fn by_ref(&self, x: u32) -> u32 {
+            *self / x
+        }
+        fn by_mut(&mut self) -> &mut u32 {
+            assert_ne!(*self, 0, "by_mut");
+            self
+        }
+        fn borrowing(&self) -> RStr<'_> {
+            assert_ne!(*self, 0, "borrowing");
+            RStr::from_str("hello")
+        }
+        fn by_val(self) -> u32 {
+            assert_ne!(self, 0, "by_val");
+            self * 2
+        }
+    }
+
+    impl OneMethod for u32 {
+        fn catches(&self) {
+            panic!("catches {}", self)
+        }
+        fn aborts(&self) -> u32 {
+            *self
+        }
+    }
+}
+
+#[test]
+fn catch_panic_methods() {
+    use self::catch_panic::{AllMethods_TO, OneMethod_TO};
+    use crate::sabi_types::RPanicPayload;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    RPanicPayload::install_location_hook();
+
+    fn payload_message<F: FnOnce() -> R, R>(f: F) -> String {
+        let payload = catch_unwind(AssertUnwindSafe(f)).map(drop).unwrap_err();
+        let payload = payload.downcast::<RPanicPayload>().unwrap();
+        assert_eq!(payload.location().unwrap().file(), file!());
+        payload.message().to_string()
+    }
+
+    {
+        let mut obj = AllMethods_TO::from_value(10u32, TD_Opaque);
+        assert_eq!(obj.by_ref(2), 5);
+        *obj.by_mut() += 1;
+        assert_eq!(obj.borrowing().as_str(), "hello");
+        assert_eq!(obj.by_ref(1), 11);
+        assert_eq!(obj.by_val(), 22);
+    }
+    {
+        let mut obj = AllMethods_TO::from_value(0u32, TD_Opaque);
+        assert_eq!(
+            payload_message(|| obj.by_ref(0)),
+            "attempt to divide by zero"
+        );
+        assert!(payload_message(|| obj.borrowing()).contains("borrowing"));
+        assert!(payload_message(|| obj.by_mut().to_string()).contains("by_mut"));
+        assert!(payload_message(|| obj.by_val()).contains("by_val"));
+    }
+    {
+        let obj = OneMethod_TO::from_value(3u32, TD_Opaque);
+        assert_eq!(payload_message(|| obj.catches()), "catches 3");
+        assert_eq!(obj.aborts(), 3);
+    }
+}
+
+/*////////////////////////////////////////////////////////////////////////////////
+Test that #[sabi(c_unwind)] lets panics unwind through the vtable.
+*/////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "rust_1_71")]
+mod c_unwind {
+    use super::*;
+
+    #[sabi_trait]
+    #[sabi(c_unwind)]
+    pub trait Unwinds {
+        fn by_ref(&self) -> RStr<'_>;
+        fn by_mut(&mut self) -> &mut u32;
+        fn by_val(self) -> u32;
+        #[sabi(catch_panic)]
+        fn caught(&self) -> u32;
+    }
+
+    impl Unwinds for u32 {
+        fn by_ref(&self) -> RStr<'_> {
+            assert_ne!(*self, 0, "by_ref");
+            RStr::from_str("hello")
+        }
+        fn by_mut(&mut self) -> &mut u32 {
+            assert_ne!(*self, 0, "by_mut");
+            self
+        }
+        fn by_val(self) -> u32 {
+            assert_ne!(self, 0, "by_val");
+            self + 1
+        }
+        fn caught(&self) -> u32 {
+            assert_ne!(*self, 0, "caught");
+            *self
+        }
+    }
+}
+
+#[cfg(feature = "rust_1_71")]
+#[test]
+fn c_unwind_methods() {
+    use self::c_unwind::Unwinds_TO;
+    use crate::sabi_types::RPanicPayload;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    fn panic_message<F: FnOnce() -> R, R>(f: F) -> String {
+        let payload = catch_unwind(AssertUnwindSafe(f)).map(drop).unwrap_err();
+        if let Some(payload) = payload.downcast_ref::<RPanicPayload>() {
+            payload.message().to_string()
+        } else {
+            RPanicPayload::from_any(payload).message().to_string()
+        }
+    }
+
+    {
+        let mut obj = Unwinds_TO::from_value(3u32, TD_Opaque);
+        assert_eq!(obj.by_ref().as_str(), "hello");
+        *obj.by_mut() += 1;
+        assert_eq!(obj.caught(), 4);
+        assert_eq!(obj.by_val(), 5);
+    }
+    {
+        let mut obj = Unwinds_TO::from_value(0u32, TD_Opaque);
+        assert!(panic_message(|| obj.by_ref()).contains("by_ref"));
+        assert!(panic_message(|| obj.by_mut().to_string()).contains("by_mut"));
+        assert!(panic_message(|| obj.caught()).contains("caught"));
+        assert!(panic_message(|| obj.by_val()).contains("by_val"));
+    }
+}
+
+/*////////////////////////////////////////////////////////////////////////////////
+Test that async methods are lowered to methods returning `RFuture`.
+*/////////////////////////////////////////////////////////////////////////////////
+
+mod async_methods {
+    use super::*;
+
+    use crate::std_types::{RFuture, RString};
+
+    #[sabi_trait]
+    pub trait Service: Sync {
+        async fn double(&self, x: u32) -> u32;
+        async fn increment(&mut self);
+        fn name(&self) -> impl Future<Output = RString>;
+        async fn doubled_plus_one(&self, x: u32) -> u32 {
+            self.double(x).await + 1
+        }
+    }
+
+    pub struct Counter(pub u32);
+
+    impl Service for Counter {
+        fn double(&self, x: u32) -> RFuture<'_, u32> {
+            RFuture::new(async move { self.0 * x * 2 })
+        }
+        fn increment(&mut self) -> RFuture<'_, ()> {
+            RFuture::new(async move { self.0 += 1 })
+        }
+        fn name(&self) -> RFuture<'_, RString> {
+            RFuture::new(async move { format!("counter {}", self.0).into() })
+        }
+    }
+}
+
+#[test]
+fn async_methods() {
+    use self::async_methods::{Counter, Service, Service_TO};
+    use crate::test_utils::block_on;
+
+    let mut obj = Service_TO::from_value(Counter(3), TD_Opaque);
+
+    assert_eq!(block_on(obj.double(5)), 30);
+    block_on(obj.increment());
+    assert_eq!(block_on(obj.name()), "counter 4");
+    assert_eq!(block_on(obj.doubled_plus_one(1)), 9);
+
+    let total = block_on(async {
+        obj.increment().await;
+        obj.double(1).await + Service::double(&Counter(1), 1).await
+    });
+    assert_eq!(total, 12);
+}
+
+/*////////////////////////////////////////////////////////////////////////////////
+Test upcasting trait objects into the trait objects of `#[sabi_trait]` supertraits.
+*/////////////////////////////////////////////////////////////////////////////////
+
+mod upcasting {
+    use super::*;
+
+    use crate::std_types::RString;
+
+    #[sabi_trait]
+    pub trait Reader: Debug {
+        fn read(&self) -> u32;
+    }
+
+    #[sabi_trait]
+    pub trait Named<'a, T>: Send + Sync {
+        fn name(&self, prefix: RStr<'a>, suffix: T) -> RString;
+    }
+
+    #[sabi_trait]
+    #[sabi(supertrait(Reader, Named))]
+    pub trait Storage<'a>: Reader + Named<'a, u8> {
+        fn write(&mut self, value: u32);
+    }
+
+    #[sabi_trait]
+    #[sabi(supertrait(Reader))]
+    pub trait Opaque: Reader + Send + Sync + 'static {}
+
+    #[derive(Debug, PartialEq)]
+    pub struct Cell(pub u32);
+
+    impl Reader for Cell {
+        fn read(&self) -> u32 {
+            self.0
+        }
+    }
+
+    impl<'a> Named<'a, u8> for Cell {
+        fn name(&self, prefix: RStr<'a>, suffix: u8) -> RString {
+            format!("{}{}{}", prefix, self.0, suffix).into()
+        }
+    }
+
+    impl<'a> Storage<'a> for Cell {
+        fn write(&mut self, value: u32) {
+            self.0 = value;
+        }
+    }
+
+    impl Opaque for Cell {}
+}
+
+#[test]
+fn upcasting() {
+    use self::upcasting::*;
+
+    let mut storage = Storage_TO::from_value(Cell(3), TD_CanDowncast);
+    storage.write(5);
+
+    {
+        let reader = storage.sabi_reborrow().upcast_to_Reader();
+        assert_eq!(reader.read(), 5);
+        assert_eq!(format!("{:?}", reader), "Cell(5)");
+        assert_eq!(reader.obj.downcast_as::<Cell>().unwrap(), &Cell(5));
+    }
+    {
+        let named: Named_TO<'_, '_, RRef<'_, ()>, u8> = storage.sabi_reborrow().upcast_to_Named();
+        assert_eq!(named.name("cell ".into(), 8), "cell 58");
+    }
+
+    let reader: Reader_TO<'_, RBox<()>> = storage.upcast_to_Reader();
+    assert_eq!(reader.read(), 5);
+    assert_eq!(
+        reader.obj.downcast_into::<Cell>().unwrap(),
+        RBox::new(Cell(5))
+    );
+
+    let opaque = Opaque_TO::from_ptr(crate::std_types::RArc::new(Cell(8)), TD_Opaque);
+    let reader = opaque.upcast_to_Reader();
+    assert_eq!(reader.read(), 8);
+    assert_eq!(format!("{:?}", reader), "Cell(8)");
+    assert!(reader.obj.downcast_as::<Cell>().is_err());
+}
+
+/*////////////////////////////////////////////////////////////////////////////////
+Test that trait objects implement their `#[sabi_trait]` supertraits.
+*/////////////////////////////////////////////////////////////////////////////////
+
+mod supertrait_impls {
+    use super::*;
+
+    use crate::std_types::RString;
+
+    pub mod counter {
+        use super::*;
+
+        // Not imported by the module of the subtrait, to test that the methods of `Counter`
+        // can refer to it when it's implemented for the trait objects of subtraits.
+        pub type Count = u32;
+
+        #[sabi_trait]
+        pub trait Counter {
+            fn count(&self) -> Count;
+
+            fn label(&self) -> RStr<'_>;
+
+            fn add(&mut self, n: Count) -> Count;
+
+            fn into_count(self) -> Count;
+
+            fn scaled(&self, factor: Count) -> Self;
+
+            #[sabi(last_prefix_field)]
+            fn double(&mut self) -> Count {
+                let count = self.count();
+                self.add(count)
+            }
+        }
+    }
+
+    pub use self::counter::{Counter, Counter_trait};
+
+    #[sabi_trait]
+    pub trait Named<'a, T>: Send + Sync {
+        fn name(&self, prefix: RStr<'a>, suffix: T) -> RString;
+    }
+
+    #[sabi_trait]
+    #[sabi(supertrait(Counter, Named))]
+    pub trait Register<'a>: Counter + Named<'a, u8> + Send + Sync {
+        fn reset(&mut self);
+    }
+
+    pub struct Cell(pub u32);
+
+    impl Counter for Cell {
+        fn count(&self) -> u32 {
+            self.0
+        }
+        fn label(&self) -> RStr<'_> {
+            "cell".into()
+        }
+        fn add(&mut self, n: u32) -> u32 {
+            self.0 += n;
+            self.0
+        }
+        fn into_count(self) -> u32 {
+            self.0
+        }
+        fn scaled(&self, factor: u32) -> Self {
+            Cell(self.0 * factor)
+        }
+    }
+
+    impl<'a> Named<'a, u8> for Cell {
+        fn name(&self, prefix: RStr<'a>, suffix: u8) -> RString {
+            format!("{}{}{}", prefix, self.0, suffix).into()
+        }
+    }
+
+    impl<'a> Register<'a> for Cell {
+        fn reset(&mut self) {
+            self.0 = 0;
+        }
+    }
+}
+
+#[test]
+fn supertrait_impls() {
+    use self::supertrait_impls::*;
+
+    use crate::std_types::RString;
+
+    fn add_twice<C: Counter>(counter: &mut C, n: u32) -> u32 {
+        counter.add(n);
+        counter.add(n)
+    }
+
+    fn label<C: Counter>(counter: &C) -> &str {
+        counter.label().as_str()
+    }
+
+    fn name<'a, N: Named<'a, u8>>(named: &N, prefix: &'a str) -> RString {
+        named.name(prefix.into(), 9)
+    }
+
+    fn reset<'a, R: Register<'a>>(register: &mut R) -> u32 {
+        register.reset();
+        register.count()
+    }
+
+    let mut register = Register_TO::from_value(Cell(3), TD_Opaque);
+
+    assert_eq!(add_twice(&mut register, 2), 7);
+    assert_eq!(Counter::count(&register), 7);
+    assert_eq!(Counter::double(&mut register), 14);
+    assert_eq!(label(&register), "cell");
+    assert_eq!(name(&register, "cell "), "cell 149");
+    assert_eq!(reset(&mut register), 0);
+
+    register.add(5);
+    let scaled = Counter::scaled(&register, 3);
+    assert_eq!(Counter::count(&scaled), 15);
+    assert_eq!(name(&scaled, "scaled "), "scaled 159");
+    assert_eq!(Counter::into_count(scaled), 15);
+}
+
+mod capabilities {
+    use super::*;
+
+    use crate::std_types::RString;
+
+    #[sabi_trait]
+    pub trait Plugin: Debug {
+        fn name(&self) -> RString;
+    }
+
+    #[sabi_trait]
+    pub trait Configurable: Send + Sync {
+        fn configure(&mut self, value: u32);
+    }
+
+    #[sabi_trait]
+    pub trait HasMetrics<T> {
+        fn metric(&self) -> T;
+    }
+
+    #[sabi_trait]
+    pub trait Reloadable: Debug + 'static {
+        fn reload(&mut self) -> u32;
+    }
+
+    #[sabi_trait]
+    #[sabi(supertrait(Reloadable))]
+    pub trait Counter: Reloadable + 'static {
+        fn count(&self) -> u32;
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Service(pub u32);
+
+    impl Plugin for Service {
+        fn name(&self) -> RString {
+            format!("service {}", self.0).into()
+        }
+    }
+
+    impl Configurable for Service {
+        fn configure(&mut self, value: u32) {
+            self.0 = value;
+        }
+    }
+
+    impl HasMetrics<u8> for Service {
+        fn metric(&self) -> u8 {
+            self.0 as u8
+        }
+    }
+
+    impl HasMetrics<u16> for Service {
+        fn metric(&self) -> u16 {
+            self.0 as u16 + 1000
+        }
+    }
+
+    impl Reloadable for Service {
+        fn reload(&mut self) -> u32 {
+            self.0 += 1;
+            self.0
+        }
+    }
+
+    impl Counter for Service {
+        fn count(&self) -> u32 {
+            self.0
+        }
+    }
+}
+
+#[test]
+fn capabilities() {
+    use self::capabilities::*;
+
+    use crate::sabi_trait::{
+        capabilities::{cached_capability_check, QueryTarget},
+        Capabilities,
+    };
+
+    type Caps = (
+        Configurable_TO<'static, RBox<()>>,
+        HasMetrics_TO<'static, RBox<()>, u8>,
+        Counter_TO<RBox<()>>,
+    );
+
+    let mut plugin = Plugin_TO::from_value_with_capabilities(
+        Service(3),
+        TD_CanDowncast,
+        Capabilities::<Caps>::NEW,
+    );
+    assert_eq!(plugin.obj.sabi_capabilities().len(), 3);
+
+    {
+        let metrics = plugin.obj.query::<HasMetrics_TO<'_, RRef<'_, ()>, u8>>();
+        assert_eq!(metrics.unwrap().metric(), 3);
+
+        let metrics = plugin.obj.query::<HasMetrics_TO<'_, RRef<'_, ()>, u16>>();
+        assert!(metrics.is_none());
+
+        // the failed layout check is cached
+        assert_eq!(
+            cached_capability_check(
+                <HasMetrics_TO<'_, RRef<'_, ()>, u16>>::CAPABILITY_LAYOUT,
+                <HasMetrics_TO<'_, RRef<'_, ()>, u8>>::CAPABILITY_LAYOUT,
+            ),
+            Some(false),
+        );
+
+        let reloadable = plugin.obj.query::<Reloadable_TO<RRef<'_, ()>>>();
+        assert!(reloadable.is_none());
+    }
+    {
+        let mut configurable = plugin
+            .obj
+            .query_mut::<Configurable_TO<'_, RMut<'_, ()>>>()
+            .unwrap();
+        configurable.configure(5);
+        assert_eq!(
+            configurable.obj.downcast_as::<Service>().unwrap(),
+            &Service(5)
+        );
+        assert_eq!(configurable.obj.sabi_capabilities().len(), 0);
+    }
+    assert_eq!(plugin.name(), "service 5");
+    {
+        let mut counter = plugin.obj.query_mut::<Counter_TO<RMut<'_, ()>>>().unwrap();
+        assert_eq!(counter.count(), 5);
+        let mut reloadable = counter.sabi_reborrow_mut().upcast_to_Reloadable();
+        assert_eq!(format!("{:?}", reloadable), "Service(5)");
+        assert_eq!(reloadable.reload(), 6);
+    }
+
+    let plugin = match plugin.obj.query_into::<HasMetrics_TO<'_, RBox<()>, u16>>() {
+        Ok(_) => panic!("`HasMetrics<u16>` was not registered"),
+        Err(obj) => Plugin_TO::from_sabi(obj),
+    };
+
+    let counter: Counter_TO<RBox<()>> = plugin.obj.query_into().ok().unwrap();
+    assert_eq!(counter.count(), 6);
+    assert_eq!(
+        counter.obj.downcast_into::<Service>().unwrap(),
+        RBox::new(Service(6))
+    );
+
+    let plugin = Plugin_TO::from_ptr_with_capabilities(
+        &Service(8),
+        TD_Opaque,
+        Capabilities::<(HasMetrics_TO<'_, RRef<'_, ()>, u16>,)>::NEW,
+    );
+    let metrics: HasMetrics_TO<'_, RRef<'_, ()>, u16> = plugin.obj.query().unwrap();
+    assert_eq!(metrics.metric(), 1008);
+    assert!(metrics.obj.downcast_as::<Service>().is_err());
+
+    let plugin = Plugin_TO::from_value(Service(13), TD_CanDowncast);
+    assert_eq!(plugin.obj.sabi_capabilities().len(), 0);
+    assert!(plugin
+        .obj
+        .query::<HasMetrics_TO<'_, RRef<'_, ()>, u8>>()
+        .is_none());
+}
+
+mod self_type {
+    use super::*;
+
+    #[sabi_trait]
+    pub trait Shape: Debug {
+        fn area(&self) -> u32;
+
+        fn scaled(&self, factor: u32) -> Self;
+
+        fn into_doubled(self) -> Self;
+
+        #[sabi(catch_panic)]
+        fn merged(&self, other: &Self) -> Self;
+
+        fn same_area<'a>(&'a self, other: &'a Self) -> bool {
+            self.area() == other.area()
+        }
+    }
+
+    #[sabi_trait]
+    #[sabi(supertrait(Shape))]
+    pub trait Polygon: Shape {
+        fn sides(&self) -> u32;
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Square(pub u32);
+
+    #[derive(Debug, PartialEq)]
+    pub struct Rect(pub u32, pub u32);
+
+    impl Shape for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+        fn scaled(&self, factor: u32) -> Self {
+            Square(self.0 * factor)
+        }
+        fn into_doubled(self) -> Self {
+            self.scaled(2)
+        }
+        fn merged(&self, other: &Self) -> Self {
+            assert_ne!(other.0, 0, "merged");
+            Square(self.0 + other.0)
+        }
+    }
+
+    impl Polygon for Square {
+        fn sides(&self) -> u32 {
+            4
+        }
+    }
+
+    impl Shape for Rect {
+        fn area(&self) -> u32 {
+            self.0 * self.1
+        }
+        fn scaled(&self, factor: u32) -> Self {
+            Rect(self.0 * factor, self.1 * factor)
+        }
+        fn into_doubled(self) -> Self {
+            self.scaled(2)
+        }
+        fn merged(&self, other: &Self) -> Self {
+            Rect(self.0 + other.0, self.1 + other.1)
+        }
+    }
+}
+
+#[test]
+fn self_type() {
+    use self::self_type::*;
+
+    use crate::{sabi_types::RPanicPayload, std_types::RArc};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let square = Shape_TO::from_value(Square(2), TD_CanDowncast);
+    let scaled: Shape_TO<'_, RBox<()>> = square.scaled(3);
+    assert_eq!(scaled.area(), 36);
+    assert_eq!(
+        scaled.obj.downcast_into::<Square>().unwrap(),
+        RBox::new(Square(6))
+    );
+    assert_eq!(format!("{:?}", square.into_doubled()), "Square(4)");
+
+    let arc = Shape_TO::from_ptr(RArc::new(Rect(2, 3)), TD_CanDowncast);
+    let scaled: Shape_TO<'_, RArc<()>> = arc.scaled(2);
+    assert_eq!(scaled.obj.downcast_as::<Rect>().unwrap(), &Rect(4, 6));
+
+    {
+        let left = Shape_TO::from_value(Square(2), TD_CanDowncast);
+        let right = Shape_TO::from_value(Square(3), TD_CanDowncast);
+        let rect = Shape_TO::from_value(Rect(4, 1), TD_CanDowncast);
+        let opaque = Shape_TO::from_value(Square(4), TD_Opaque);
+
+        let merged = left.merged(&right).unwrap();
+        assert_eq!(merged.obj.downcast_as::<Square>().unwrap(), &Square(5));
+
+        let err = left.merged(&rect).unwrap_err();
+        assert_eq!(
+            err.expected_typeid(),
+            left.obj.sabi_robject_vtable()._sabi_type_id()()
+        );
+        assert_eq!(
+            err.found_typeid(),
+            rect.obj.sabi_robject_vtable()._sabi_type_id()()
+        );
+        assert!(left.merged(&opaque).is_err());
+        assert!(opaque.merged(&left).is_err());
+
+        assert!(!left.same_area(&right).unwrap());
+        let other_rect = Shape_TO::from_value(Rect(1, 4), TD_CanDowncast);
+        assert!(rect.same_area(&other_rect).unwrap());
+        assert!(rect.same_area(&opaque).is_err());
+    }
+
+    {
+        let left = Shape_TO::from_value(Square(2), TD_CanDowncast);
+        let zero = Shape_TO::from_value(Square(0), TD_CanDowncast);
+        let err = catch_unwind(AssertUnwindSafe(|| left.merged(&zero))).unwrap_err();
+        let payload = err.downcast_ref::<RPanicPayload>().unwrap();
+        assert!(payload.message().to_string().contains("merged"));
+    }
+
+    let polygon = Polygon_TO::from_value(Square(3), TD_CanDowncast);
+    assert_eq!(polygon.sides(), 4);
+    let shape = polygon.upcast_to_Shape().scaled(2);
+    assert_eq!(shape.area(), 36);
+}
+
+mod assoc_types {
+    use super::*;
+
+    use crate::std_types::{RSlice, RString, RVec};
+
+    #[sabi_trait]
+    pub trait Codec {
+        type Output: StableAbi + std::fmt::Debug;
+
+        fn decode(&self, input: RStr<'_>) -> Self::Output;
+
+        fn decode_all(&self, inputs: RSlice<'_, RStr<'_>>) -> RVec<Self::Output> {
+            inputs.iter().map(|x| self.decode(*x)).collect()
+        }
+    }
+
+    pub struct Uppercase;
+
+    pub struct Length;
+
+    impl Codec for Uppercase {
+        type Output = RString;
+
+        fn decode(&self, input: RStr<'_>) -> RString {
+            input.as_str().to_uppercase().into()
+        }
+    }
+
+    impl Codec for Length {
+        type Output = u32;
+
+        fn decode(&self, input: RStr<'_>) -> u32 {
+            input.len() as u32
+        }
+    }
+
+    pub fn decode_debug<C: Codec>(codec: &C, input: &str) -> String {
+        format!("{:?}", codec.decode(input.into()))
+    }
+}
+
+#[test]
+fn assoc_types() {
+    use self::assoc_types::*;
+
+    use crate::std_types::{RSlice, RString, RVec};
+
+    let upper: Codec_TO<'_, RBox<()>, RString> = Codec_TO::from_value(Uppercase, TD_Opaque);
+    assert_eq!(upper.decode("hello".into()), "HELLO");
+    assert_eq!(
+        upper.decode_all(RSlice::from_slice(&["a".into(), "b".into()])),
+        RVec::from(vec![RString::from("A"), RString::from("B")])
+    );
+    assert_eq!(decode_debug(&upper, "hi"), "\"HI\"");
+
+    let length: Codec_TO<'_, RBox<()>, u32> = Codec_TO::from_value(Length, TD_Opaque);
+    assert_eq!(length.decode("hello".into()), 5);
+    assert_eq!(decode_debug(&length, "hi"), "2");
+
+    let codecs: Vec<Codec_TO<'_, RBox<()>, u32>> = vec![
+        Codec_TO::from_value(Length, TD_Opaque),
+        Codec_TO::from_ptr(RBox::new(Length), TD_Opaque),
+    ];
+    for codec in &codecs {
+        assert_eq!(codec.decode("four".into()), 4);
+    }
+}
+
+mod method_probes {
+    use super::*;
+
+    pub mod v1 {
+        use super::*;
+
+        #[sabi_trait]
+        pub trait Plugin {
+            fn name(&self) -> u32;
+
+            #[sabi(last_prefix_field)]
+            fn version(&self) -> u32 {
+                1
+            }
+        }
+
+        impl Plugin for () {
+            fn name(&self) -> u32 {
+                10
+            }
+        }
+    }
+
+    pub mod v2 {
+        use super::*;
+
+        #[sabi_trait]
+        pub trait Plugin {
+            fn name(&self) -> u32;
+
+            #[sabi(last_prefix_field)]
+            fn version(&self) -> u32 {
+                1
+            }
+
+            fn reload(&self) -> u32;
+
+            fn unload(&self) -> u32 {
+                3
+            }
+        }
+
+        impl Plugin for () {
+            fn name(&self) -> u32 {
+                20
+            }
+            fn reload(&self) -> u32 {
+                2
+            }
+            fn unload(&self) -> u32 {
+                4
+            }
+        }
+    }
+}
+
+#[test]
+fn method_probes() {
+    use self::method_probes::{v1, v2};
+
+    let current = v2::Plugin_TO::from_value((), TD_Opaque);
+    assert!(current.sabi_has_name());
+    assert!(current.sabi_has_version());
+    assert!(current.sabi_has_reload());
+    assert!(current.sabi_has_unload());
+    assert_eq!(current.reload(), 2);
+    assert_eq!(current.unload(), 4);
+
+    // Simulates a trait object constructed in a library compiled against
+    // an older version of the trait.
+    let old: v2::Plugin_TO<'_, RBox<()>> =
+        unsafe { mem::transmute(v1::Plugin_TO::from_value((), TD_Opaque)) };
+
+    assert!(old.sabi_has_name());
+    assert!(old.sabi_has_version());
+    assert!(!old.sabi_has_reload());
+    assert!(!old.sabi_has_unload());
+
+    assert_eq!(old.name(), 10);
+    assert_eq!(old.version(), 1);
+    assert_eq!(old.unload(), 3);
+    must_panic(|| old.reload()).unwrap();
+}
+
+mod instrument {
+    use super::*;
+
+    use crate::std_types::{RResult, RString};
+
+    #[sabi_trait]
+    #[sabi(instrument)]
+    pub trait Counter: Debug {
+        fn get(&self) -> u32;
+
+        fn add(&mut self, n: u32);
+
+        #[sabi(catch_panic)]
+        fn checked_div(&self, n: u32) -> RResult<u32, RString>;
+
+        fn doubled(&self) -> Self;
+
+        fn into_value(self) -> u32;
+    }
+
+    #[derive(Debug)]
+    pub struct Count(pub u32);
+
+    impl Counter for Count {
+        fn get(&self) -> u32 {
+            self.0
+        }
+        fn add(&mut self, n: u32) {
+            self.0 += n;
+        }
+        fn checked_div(&self, n: u32) -> RResult<u32, RString> {
+            assert_ne!(n, 0, "division by zero");
+            if self.0 % n == 0 {
+                RResult::ROk(self.0 / n)
+            } else {
+                RResult::RErr(RString::from("inexact division"))
+            }
+        }
+        fn doubled(&self) -> Self {
+            Count(self.0 * 2)
+        }
+        fn into_value(self) -> u32 {
+            self.0
+        }
+    }
+
+    pub fn sum_of<C: Counter>(counters: &[C]) -> u32 {
+        counters.iter().map(|c| c.get()).sum()
+    }
+}
+
+#[test]
+fn instrument() {
+    use self::instrument::{sum_of, Count, Counter, Counter_TO};
+
+    use crate::{
+        sabi_trait::{CallOutcome, Instrumented, MethodCall, SharedCallHook},
+        std_types::{RArc, RResult},
+    };
+
+    use std::sync::{Arc, Mutex};
+
+    let calls = Arc::new(Mutex::new(Vec::<(String, CallOutcome)>::new()));
+    let take_calls = || mem::take(&mut *calls.lock().unwrap());
+
+    let hook = {
+        let calls = calls.clone();
+        SharedCallHook::from_ptr(
+            RArc::new(move |call: MethodCall<'_>| {
+                assert_eq!(call.trait_name.as_str(), "Counter");
+                calls
+                    .lock()
+                    .unwrap()
+                    .push((call.method_name.to_string(), call.outcome));
+            }),
+            TD_Opaque,
+        )
+    };
+    let wrap = |n: u32| {
+        Instrumented::new(
+            Counter_TO::from_value(Count(n), TD_CanDowncast),
+            hook.clone(),
+        )
+    };
+
+    let mut counter = wrap(12);
+    assert_eq!(format!("{:?}", counter), "Count(12)");
+
+    assert_eq!(counter.get(), 12);
+    counter.add(3);
+    assert_eq!(counter.checked_div(5), RResult::ROk(3));
+    assert!(counter.checked_div(2).is_err());
+    must_panic(|| counter.checked_div(0)).unwrap();
+    assert_eq!(
+        take_calls(),
+        vec![
+            ("get".to_string(), CallOutcome::Returned),
+            ("add".to_string(), CallOutcome::Returned),
+            ("checked_div".to_string(), CallOutcome::Returned),
+            ("checked_div".to_string(), CallOutcome::ReturnedErr),
+            ("checked_div".to_string(), CallOutcome::Panicked),
+        ],
+    );
+
+    // Methods returning `Self` return trait objects that are instrumented with the same hook.
+    let doubled = counter.doubled();
+    assert_eq!(doubled.get(), 30);
+    assert_eq!(sum_of(&[doubled, wrap(5)]), 35);
+    assert_eq!(counter.into_value(), 15);
+    assert_eq!(
+        take_calls(),
+        vec![
+            ("doubled".to_string(), CallOutcome::Returned),
+            ("get".to_string(), CallOutcome::Returned),
+            ("get".to_string(), CallOutcome::Returned),
+            ("get".to_string(), CallOutcome::Returned),
+            ("into_value".to_string(), CallOutcome::Returned),
+        ],
+    );
+
+    // Calls through the wrapped trait object aren't reported.
+    let mut counter = wrap(1);
+    counter.inner_mut().add(1);
+    assert_eq!(counter.into_inner().get(), 2);
+    assert_eq!(take_calls(), vec![]);
+
+    assert_eq!(format!("{:?}", CallOutcome::Returned), "Returned");
+    assert_eq!(format!("{:?}", CallOutcome::ReturnedErr), "ReturnedErr");
+    assert_eq!(format!("{:?}", CallOutcome::Panicked), "Panicked");
+}
+
+mod mock {
+    use super::*;
+
+    use crate::std_types::{RResult, RString};
+
+    #[sabi_trait]
+    #[sabi(mock)]
+    pub trait Store: Debug + Send + Sync {
+        fn get(&self, key: RStr<'_>) -> RResult<u32, RString>;
+
+        fn name(&self) -> RStr<'_>;
+
+        fn get_or_zero(&self, key: RStr<'_>) -> u32 {
+            self.get(key).unwrap_or(0)
+        }
+    }
+
+    #[sabi_trait]
+    #[sabi(mock)]
+    pub trait Counter {
+        type Item: StableAbi;
+
+        fn add(&mut self, n: u32) -> Self::Item;
+
+        fn merged(&self, other: &Self) -> Self;
+
+        fn into_total(self) -> u32;
+    }
+
+    #[repr(C)]
+    #[derive(StableAbi)]
+    pub struct NotDebug(pub u32);
+
+    #[sabi_trait]
+    #[sabi(mock)]
+    pub trait Visitor {
+        fn visit(&self, value: NotDebug, n: u32);
+    }
+
+    pub fn get_both<S: Store>(store: &S) -> (u32, u32) {
+        (store.get_or_zero("a".into()), store.get_or_zero("b".into()))
+    }
+}
+
+#[test]
+fn mock() {
+    use self::mock::{get_both, Counter, Counter_Mock, Store, Store_Mock, Store_TO};
+
+    use crate::std_types::{RArc, RResult, RString};
+
+    let store = Store_Mock::new();
+    store
+        .expect_get(|key| match key.as_str() {
+            "a" => RResult::ROk(3),
+            _ => RResult::RErr(RString::from("not found")),
+        })
+        .expect_name(|| "fake".into());
+
+    // Mocks are constructible with any pointer type,
+    // sharing the configuration and recorded calls with the original.
+    let boxed = Store_TO::from_value(store.clone(), TD_CanDowncast);
+    let arced = Store_TO::from_ptr(RArc::new(store.clone()), TD_Opaque);
+    let borrowed = Store_TO::from_ptr(&store, TD_Opaque);
+
+    assert_eq!(get_both(&boxed), (3, 0));
+    assert_eq!(arced.name(), "fake");
+    assert!(borrowed.get("c".into()).is_err());
+    assert_eq!(
+        store.calls(),
+        vec!["get_or_zero", "get", "get_or_zero", "get", "name", "get"],
+    );
+    assert_eq!(store.call_count("get"), 3);
+
+    store.clear_calls();
+    store.expect_get_or_zero(|_| 100);
+    assert_eq!(get_both(&boxed), (100, 100));
+    assert_eq!(store.calls(), vec!["get_or_zero", "get_or_zero"]);
+
+    let downcasted = boxed.obj.downcast_as::<Store_Mock>().unwrap();
+    assert_eq!(downcasted.call_count("get_or_zero"), 2);
+    assert_eq!(
+        format!("{:?}", downcasted),
+        r#"Store_Mock { calls: ["get_or_zero", "get_or_zero"] }"#,
+    );
+
+    // Methods that weren't configured and have no default implementation panic.
+    must_panic(|| {
+        let _ = Store_Mock::new().name();
+    })
+    .unwrap();
+
+    let mut counter = Counter_Mock::<RString>::new();
+    let total = std::sync::atomic::AtomicU32::new(0);
+    counter
+        .expect_add(move |n| {
+            let total = total.fetch_add(n, std::sync::atomic::Ordering::SeqCst) + n;
+            RString::from(total.to_string())
+        })
+        .expect_merged(|other| {
+            assert_eq!(other.call_count("add"), 0);
+            Counter_Mock::new()
+        })
+        .expect_into_total(|| 7);
+
+    assert_eq!(counter.add(2), "2");
+    assert_eq!(counter.add(3), "5");
+    let merged = counter.merged(&Counter_Mock::new());
+    assert_eq!(merged.calls(), Vec::<&str>::new());
+    assert_eq!(counter.clone().into_total(), 7);
+    assert_eq!(counter.calls(), vec!["add", "add", "merged", "into_total"]);
+    assert_eq!(counter.call_args("add"), vec![vec!["2"], vec!["3"]]);
+    counter.verify();
+}
+
+#[test]
+fn mock_args_and_verify() {
+    use self::mock::{NotDebug, Store, Store_Mock, Visitor, Visitor_Mock};
+
+    use crate::std_types::RResult;
+
+    let store = Store_Mock::new();
+    store.expect_get(|key| RResult::ROk(key.len() as u32));
+    store.expect_name(|| "fake".into());
+
+    assert_eq!(store.get_or_zero("hello".into()), 5);
+    assert_eq!(
+        store.call_args("get_or_zero"),
+        vec![vec![r#""hello""#.to_string()]],
+    );
+    assert_eq!(store.call_args("get"), vec![vec![r#""hello""#.to_string()]]);
+
+    // `name` was expected, but wasn't called.
+    let err = must_panic(|| store.verify()).unwrap();
+    let msg = err.downcast_ref::<String>().unwrap();
+    assert!(msg.contains("name") && !msg.contains("get"), "{}", msg);
+
+    assert_eq!(store.name(), "fake");
+    store.verify();
+
+    // The closures don't hold the lock of the mock while running,
+    // so they can call the mock reentrantly.
+    let inner = store.clone();
+    store.expect_get(move |key| match key.as_str() {
+        "" => RResult::ROk(0),
+        _ => RResult::ROk(1 + inner.get(key[1..].into()).unwrap()),
+    });
+    assert_eq!(store.get("abc".into()), RResult::ROk(3));
+
+    // Arguments that don't implement Debug are recorded as `_`
+    let visitor = Visitor_Mock::new();
+    visitor.expect_visit(|_, _| {});
+    visitor.visit(NotDebug(3), 5);
+    assert_eq!(visitor.call_args("visit"), vec![vec!["_", "5"]]);
+}
+
+mod delegate {
+    use super::*;
+
+    use crate::std_types::{ROption, RString, RVec};
+
+    use std::collections::BTreeMap;
+
+    #[sabi_trait]
+    #[sabi(delegate)]
+    pub trait Storage {
+        fn get(&self, key: RStr<'_>) -> ROption<u32>;
+
+        fn set(&mut self, key: RStr<'_>, value: u32);
+
+        fn into_keys(self) -> RVec<RString>;
+    }
+
+    #[derive(Default)]
+    pub struct MapStorage(pub BTreeMap<String, u32>);
+
+    impl Storage for MapStorage {
+        fn get(&self, key: RStr<'_>) -> ROption<u32> {
+            self.0.get(key.as_str()).copied().into()
+        }
+        fn set(&mut self, key: RStr<'_>, value: u32) {
+            self.0.insert(key.into(), value);
+        }
+        fn into_keys(self) -> RVec<RString> {
+            self.0.into_keys().map(RString::from).collect()
+        }
+    }
+
+    /// Only overrides the method whose behavior it changes.
+    pub struct ReadOnly<S> {
+        pub inner: S,
+        pub rejected: u32,
+    }
+
+    impl<S: Storage> Storage_Delegate for ReadOnly<S> {
+        type Delegate = S;
+
+        fn sabi_delegate(&self) -> &S {
+            &self.inner
+        }
+        fn sabi_delegate_mut(&mut self) -> &mut S {
+            &mut self.inner
+        }
+        fn sabi_into_delegate(self) -> S {
+            self.inner
+        }
+
+        fn set(&mut self, _key: RStr<'_>, _value: u32) {
+            self.rejected += 1;
+        }
+    }
+
+    #[sabi_trait]
+    #[sabi(delegate)]
+    pub trait Shape {
+        type Unit: StableAbi;
+
+        fn area(&self) -> Self::Unit;
+
+        fn same_area(&self, other: &Self) -> bool;
+
+        fn doubled(&self) -> Self;
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Square(pub u32);
+
+    impl Shape for Square {
+        type Unit = u32;
+
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+        fn same_area(&self, other: &Self) -> bool {
+            self.area() == other.area()
+        }
+        fn doubled(&self) -> Self {
+            Square(self.0 * 2)
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Named {
+        pub name: &'static str,
+        pub shape: Square,
+    }
+
+    impl Shape_Delegate for Named {
+        type Unit = u32;
+        type Delegate = Square;
+
+        fn sabi_delegate(&self) -> &Square {
+            &self.shape
+        }
+
+        fn doubled(&self) -> Self {
+            Named {
+                name: self.name,
+                shape: self.shape.doubled(),
+            }
+        }
+    }
+}
+
+#[test]
+fn delegate() {
+    use self::delegate::{MapStorage, Named, ReadOnly, Shape, Square, Storage, Storage_TO};
+
+    use crate::std_types::{ROption, RString};
+
+    let mut map = MapStorage::default();
+    map.set("a".into(), 3);
+
+    // Decorating a trait object
+    let mut storage = ReadOnly {
+        inner: Storage_TO::from_value(map, TD_Opaque),
+        rejected: 0,
+    };
+    storage.set("a".into(), 5);
+    storage.set("b".into(), 8);
+    assert_eq!(storage.rejected, 2);
+    assert_eq!(storage.get("a".into()), ROption::RSome(3));
+    assert_eq!(storage.get("b".into()), ROption::RNone);
+
+    // The decorator is itself usable as a trait object
+    let storage = Storage_TO::from_value(storage, TD_Opaque);
+    assert_eq!(storage.get("a".into()), ROption::RSome(3));
+    assert_eq!(storage.into_keys(), vec![RString::from("a")]);
+
+    let named = Named {
+        name: "foo",
+        shape: Square(3),
+    };
+    assert_eq!(named.area(), 9);
+    assert!(named.same_area(&Named {
+        name: "bar",
+        shape: Square(3),
+    }));
+    assert_eq!(
+        named.doubled(),
+        Named {
+            name: "foo",
+            shape: Square(6),
+        },
+    );
+}
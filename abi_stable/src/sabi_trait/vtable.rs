@@ -3,7 +3,7 @@ use super::*;
 use crate::{
     erased_types::{FormattingMode, InterfaceType, MakeRequiredTraits},
     marker_type::NonOwningPhantom,
-    std_types::{RResult, RString, UTypeId},
+    std_types::{utypeid::new_utypeid, RResult, RSlice, RString, UTypeId},
     type_level::{
         downcasting::GetUTID,
         impl_enum::{Implemented, Unimplemented},
@@ -65,7 +65,7 @@ where
     I::Clone: InitCloneField<_Self, ErasedPtr, OrigPtr>,
     I::Debug: InitDebugField<_Self, ErasedPtr, OrigPtr>,
     I::Display: InitDisplayField<_Self, ErasedPtr, OrigPtr>,
-    IA: GetUTID<_Self>,
+    IA: GetUTID<_Self> + GetCapabilities<_Self, ErasedPtr, OrigPtr>,
 {
     const VTABLE_VAL: RObjectVtable<_Self, ErasedPtr, I> = RObjectVtable {
         _sabi_tys: NonOwningPhantom::NEW,
@@ -74,6 +74,8 @@ where
         _sabi_clone: <I::Clone as InitCloneField<_Self, ErasedPtr, OrigPtr>>::VALUE,
         _sabi_debug: <I::Debug as InitDebugField<_Self, ErasedPtr, OrigPtr>>::VALUE,
         _sabi_display: <I::Display as InitDisplayField<_Self, ErasedPtr, OrigPtr>>::VALUE,
+        _sabi_library_id: Some(new_utypeid::<()>),
+        _sabi_capabilities: <IA as GetCapabilities<_Self, ErasedPtr, OrigPtr>>::CAPABILITIES,
     };
 }
 
@@ -121,6 +123,10 @@ pub struct RObjectVtable<_Self, ErasedPtr, I> {
             &mut RString,
         ) -> RResult<(), ()>,
     >,
+    /// Constructs a `UTypeId` in the dynamic library/executable that created this vtable,
+    /// used to find out which library a trait object comes from.
+    pub _sabi_library_id: Option<extern "C" fn() -> UTypeId>,
+    pub _sabi_capabilities: RSlice<'static, Capability>,
 }
 
 /// The common prefix of all `#[trait_object]` derived vtables,
@@ -7,6 +7,7 @@ mod late_static_ref;
 mod maybe_cmp;
 mod move_ptr;
 mod nul_str;
+mod panic_payload;
 mod rmut;
 mod rref;
 pub mod rsmallbox;
@@ -21,6 +22,7 @@ pub use self::{
     maybe_cmp::MaybeCmp,
     move_ptr::MovePtr,
     nul_str::{NulStr, NulStrError},
+    panic_payload::{RPanicLocation, RPanicPayload},
     rmut::RMut,
     rref::RRef,
     rsmallbox::RSmallBox,
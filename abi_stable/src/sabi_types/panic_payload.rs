@@ -0,0 +1,381 @@
+//! Contains the [`RPanicPayload`] type,
+//! used to propagate panics across the ffi boundary.
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests;
+
+use std::{
+    any::Any,
+    cell::Cell,
+    error::Error as ErrorTrait,
+    fmt::{self, Display},
+    panic::{self, Location, UnwindSafe},
+    sync::Once,
+};
+
+use crate::std_types::{RNone, ROption, RResult, RSome, RStr, RString};
+
+/// The message and location of a panic,
+/// caught so that it can be passed across the ffi boundary.
+///
+/// This is the error type returned by functions declared with
+/// [`#[sabi_extern_fn(catch_panic)]`](macro@crate::sabi_extern_fn),
+/// and by vtable entries of methods declared with the
+/// `#[sabi(catch_panic)]` attribute in [`#[sabi_trait]`](macro@crate::sabi_trait).
+///
+/// # Location
+///
+/// The location of the panic is recorded by a panic hook,
+/// chained with the previously installed one,
+/// which is only installed by calling [`RPanicPayload::install_location_hook`].
+/// If the hook isn't installed,
+/// or some other code replaces the panic hook afterwards,
+/// the location won't be available.
+///
+/// Since every dynamic library can have its own copy of the standard library,
+/// the hook must be installed in the library where the panic is caught.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::{sabi_extern_fn, sabi_types::RPanicPayload, std_types::RResult};
+///
+/// #[sabi_extern_fn(catch_panic)]
+/// fn divide(l: u32, r: u32) -> u32 {
+///     if r == 0 {
+///         panic!("attempted to divide {} by zero", l);
+///     }
+///     l / r
+/// }
+///
+/// RPanicPayload::install_location_hook();
+///
+/// assert_eq!(divide(12, 4), RResult::ROk(3));
+///
+/// let payload: RPanicPayload = divide(7, 0).unwrap_err();
+/// assert_eq!(payload.message(), "attempted to divide 7 by zero");
+/// assert_eq!(payload.location().unwrap().file(), file!());
+///
+/// // Re-raising the panic on the caller side.
+/// let res = std::panic::catch_unwind(|| divide(7, 0).unwrap_or_resume_unwind());
+/// let payload = res.unwrap_err().downcast::<RPanicPayload>().unwrap();
+/// assert_eq!(payload.message(), "attempted to divide 7 by zero");
+///
+/// ```
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StableAbi)]
+pub struct RPanicPayload {
+    message: RString,
+    location: ROption<RPanicLocation>,
+}
+
+/// The source code location where a panic happened.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::sabi_types::RPanicLocation;
+///
+/// let loc = RPanicLocation::new("src/lib.rs", 10, 5);
+///
+/// assert_eq!(loc.file(), "src/lib.rs");
+/// assert_eq!(loc.line(), 10);
+/// assert_eq!(loc.column(), 5);
+/// assert_eq!(loc.to_string(), "src/lib.rs:10:5");
+///
+/// ```
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StableAbi)]
+pub struct RPanicLocation {
+    file: RString,
+    line: u32,
+    column: u32,
+}
+
+thread_local! {
+    // How many `RPanicPayload::catch_unwind` calls are running in this thread.
+    static CATCH_DEPTH: Cell<usize> = const { Cell::new(0) };
+
+    // The location of the last panic in this thread,
+    // only recorded inside of `RPanicPayload::catch_unwind`.
+    static LAST_LOCATION: Cell<Option<RPanicLocation>> = const { Cell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+impl RPanicLocation {
+    /// Constructs an `RPanicLocation`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::sabi_types::RPanicLocation;
+    ///
+    /// let loc = RPanicLocation::new("src/main.rs", 3, 14);
+    ///
+    /// assert_eq!(loc.file(), "src/main.rs");
+    ///
+    /// ```
+    pub fn new<S>(file: S, line: u32, column: u32) -> Self
+    where
+        S: Into<RString>,
+    {
+        Self {
+            file: file.into(),
+            line,
+            column,
+        }
+    }
+
+    /// Constructs an `RPanicLocation` from a `std::panic::Location`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::sabi_types::RPanicLocation;
+    ///
+    /// let std_loc = std::panic::Location::caller();
+    /// let loc = RPanicLocation::from_location(std_loc);
+    ///
+    /// assert_eq!(loc.file(), std_loc.file());
+    /// assert_eq!(loc.line(), std_loc.line());
+    /// assert_eq!(loc.column(), std_loc.column());
+    ///
+    /// ```
+    pub fn from_location(location: &Location<'_>) -> Self {
+        Self::new(location.file(), location.line(), location.column())
+    }
+
+    /// The path of the file where the panic happened.
+    pub fn file(&self) -> RStr<'_> {
+        self.file.as_rstr()
+    }
+
+    /// The line where the panic happened.
+    pub const fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// The column where the panic happened.
+    pub const fn column(&self) -> u32 {
+        self.column
+    }
+}
+
+impl Display for RPanicLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+impl RPanicPayload {
+    /// Constructs an `RPanicPayload`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::sabi_types::{RPanicLocation, RPanicPayload};
+    ///
+    /// let payload = RPanicPayload::new("oh no", Some(RPanicLocation::new("foo.rs", 1, 2)));
+    ///
+    /// assert_eq!(payload.message(), "oh no");
+    /// assert_eq!(payload.location().unwrap().line(), 1);
+    /// assert_eq!(payload.to_string(), "oh no, at foo.rs:1:2");
+    ///
+    /// ```
+    pub fn new<S>(message: S, location: Option<RPanicLocation>) -> Self
+    where
+        S: Into<RString>,
+    {
+        Self {
+            message: message.into(),
+            location: location.into(),
+        }
+    }
+
+    /// Constructs an `RPanicPayload` from the payload of a panic,
+    /// as returned by `std::panic::catch_unwind`.
+    ///
+    /// The message is taken from `&str` and `String` payloads,
+    /// and `RPanicPayload` payloads (from [`RPanicPayload::resume_unwind`])
+    /// are returned unchanged.
+    ///
+    /// The returned value has no location unless the payload was an `RPanicPayload`,
+    /// use [`RPanicPayload::catch_unwind`] to also capture the location of the panic.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::sabi_types::RPanicPayload;
+    ///
+    /// let payload = std::panic::catch_unwind(|| panic!("{}", 100)).unwrap_err();
+    ///
+    /// assert_eq!(RPanicPayload::from_any(payload).message(), "100");
+    ///
+    /// ```
+    pub fn from_any(payload: Box<dyn Any + Send>) -> Self {
+        let payload = match payload.downcast::<Self>() {
+            Ok(this) => return *this,
+            Err(payload) => payload,
+        };
+
+        let message = if let Some(s) = payload.downcast_ref::<&'static str>() {
+            RString::from(*s)
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            RString::from(&**s)
+        } else {
+            RString::from("Box<dyn Any>")
+        };
+
+        Self::new(message, None)
+    }
+
+    /// Installs the panic hook that records the location of panics
+    /// caught by [`catch_unwind`](#method.catch_unwind),
+    /// calling the previously installed panic hook from it.
+    ///
+    /// Calling this more than once only installs the hook the first time.
+    ///
+    /// # Panics
+    ///
+    /// This panics if called while the current thread is panicking.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::sabi_types::RPanicPayload;
+    ///
+    /// RPanicPayload::install_location_hook();
+    ///
+    /// let payload = RPanicPayload::catch_unwind(|| panic!("hello")).unwrap_err();
+    /// assert_eq!(payload.location().unwrap().file(), file!());
+    ///
+    /// ```
+    pub fn install_location_hook() {
+        INSTALL_HOOK.call_once(|| {
+            let prev_hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                if CATCH_DEPTH.with(|d| d.get() != 0) {
+                    let location = info.location().map(RPanicLocation::from_location);
+                    LAST_LOCATION.with(|l| l.set(location));
+                }
+                prev_hook(info);
+            }));
+        });
+    }
+
+    /// Calls `f`, catching any panic that happens inside of it.
+    ///
+    /// The returned `RPanicPayload` includes the location of the panic
+    /// if [`install_location_hook`](#method.install_location_hook) was called
+    /// (read the [type-level docs](#location) for when it's unavailable).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::{sabi_types::RPanicPayload, std_types::RResult};
+    ///
+    /// RPanicPayload::install_location_hook();
+    ///
+    /// assert_eq!(RPanicPayload::catch_unwind(|| 3 + 5), RResult::ROk(8));
+    ///
+    /// let line = line!() + 1;
+    /// let payload = RPanicPayload::catch_unwind(|| panic!("hello")).unwrap_err();
+    ///
+    /// assert_eq!(payload.message(), "hello");
+    /// assert_eq!(payload.location().unwrap().line(), line);
+    ///
+    /// ```
+    pub fn catch_unwind<F, R>(f: F) -> RResult<R, Self>
+    where
+        F: FnOnce() -> R + UnwindSafe,
+    {
+        CATCH_DEPTH.with(|d| d.set(d.get() + 1));
+        let res = panic::catch_unwind(f);
+        CATCH_DEPTH.with(|d| d.set(d.get() - 1));
+
+        match res {
+            Ok(x) => RResult::ROk(x),
+            Err(payload) => {
+                let location = LAST_LOCATION.with(Cell::take);
+                let mut this = Self::from_any(payload);
+                if let (RNone, Some(location)) = (&this.location, location) {
+                    this.location = RSome(location);
+                }
+                RResult::RErr(this)
+            }
+        }
+    }
+
+    /// The panic message.
+    ///
+    /// This is `"Box<dyn Any>"` if the panic payload was neither a `&str` nor a `String`.
+    pub fn message(&self) -> RStr<'_> {
+        self.message.as_rstr()
+    }
+
+    /// Where the panic happened, if it was recorded.
+    pub fn location(&self) -> Option<&RPanicLocation> {
+        self.location.as_ref().into_option()
+    }
+
+    /// Resumes unwinding with this `RPanicPayload` as the panic payload.
+    ///
+    /// This does not invoke the panic hook,
+    /// the panic message was already printed when the panic happened.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::sabi_types::RPanicPayload;
+    ///
+    /// let res = std::panic::catch_unwind(|| {
+    ///     RPanicPayload::new("hello", None).resume_unwind()
+    /// });
+    ///
+    /// let payload = res.unwrap_err().downcast::<RPanicPayload>().unwrap();
+    /// assert_eq!(payload.message(), "hello");
+    ///
+    /// ```
+    pub fn resume_unwind(self) -> ! {
+        panic::resume_unwind(Box::new(self))
+    }
+}
+
+impl Display for RPanicPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.message, f)?;
+        if let RSome(location) = &self.location {
+            write!(f, ", at {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+impl ErrorTrait for RPanicPayload {}
+
+impl<T> RResult<T, RPanicPayload> {
+    /// Unwraps the `ROk` value,
+    /// resuming the panic with [`RPanicPayload::resume_unwind`] if this is an `RErr`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::{sabi_types::RPanicPayload, std_types::RResult};
+    ///
+    /// assert_eq!(RResult::<_, RPanicPayload>::ROk(10).unwrap_or_resume_unwind(), 10);
+    ///
+    /// let res = std::panic::catch_unwind(|| {
+    ///     RPanicPayload::catch_unwind(|| panic!("oops")).unwrap_or_resume_unwind()
+    /// });
+    /// let payload = res.unwrap_err().downcast::<RPanicPayload>().unwrap();
+    /// assert_eq!(payload.message(), "oops");
+    ///
+    /// ```
+    pub fn unwrap_or_resume_unwind(self) -> T {
+        match self {
+            RResult::ROk(x) => x,
+            RResult::RErr(e) => e.resume_unwind(),
+        }
+    }
+}
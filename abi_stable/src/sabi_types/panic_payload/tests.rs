@@ -0,0 +1,77 @@
+use super::*;
+
+use crate::std_types::RResult::{RErr, ROk};
+
+#[test]
+fn from_any_message() {
+    let payload = panic::catch_unwind(|| panic!("hello")).unwrap_err();
+    assert_eq!(RPanicPayload::from_any(payload).message(), "hello");
+
+    let payload = panic::catch_unwind(|| panic!("{} {}", 3, 5)).unwrap_err();
+    assert_eq!(RPanicPayload::from_any(payload).message(), "3 5");
+
+    let payload = panic::catch_unwind(|| panic::panic_any(100u32)).unwrap_err();
+    let payload = RPanicPayload::from_any(payload);
+    assert_eq!(payload.message(), "Box<dyn Any>");
+    assert_eq!(payload.location(), None);
+
+    let original = RPanicPayload::new("foo", Some(RPanicLocation::new("bar.rs", 3, 5)));
+    let payload = panic::catch_unwind(|| original.clone().resume_unwind()).unwrap_err();
+    assert_eq!(RPanicPayload::from_any(payload), original);
+}
+
+#[test]
+fn catch_unwind_location() {
+    RPanicPayload::install_location_hook();
+
+    assert_eq!(RPanicPayload::catch_unwind(|| 10), ROk(10));
+
+    let line = line!() + 1;
+    let payload = RPanicPayload::catch_unwind(|| panic!("what")).unwrap_err();
+    assert_eq!(payload.message(), "what");
+
+    let location = payload.location().unwrap();
+    assert_eq!(location.file(), file!());
+    assert_eq!(location.line(), line);
+
+    assert_eq!(
+        payload.to_string(),
+        format!("what, at {}:{}:{}", file!(), line, location.column())
+    );
+}
+
+#[test]
+fn nested_catch_unwind() {
+    RPanicPayload::install_location_hook();
+
+    let inner_line = line!() + 3;
+    let res = RPanicPayload::catch_unwind(|| {
+        let inner = RPanicPayload::catch_unwind(|| -> u32 {
+            panic!("inner");
+        });
+        assert_eq!(
+            inner.as_ref().unwrap_err().location().unwrap().line(),
+            inner_line
+        );
+
+        // the location of a resumed panic is the original one.
+        inner.unwrap_or_resume_unwind()
+    });
+
+    match res {
+        RErr(e) => {
+            assert_eq!(e.message(), "inner");
+            assert_eq!(e.location().unwrap().line(), inner_line);
+        }
+        ROk(x) => panic!("expected a panic, found {}", x),
+    }
+}
+
+#[test]
+fn location_outside_catch_unwind() {
+    RPanicPayload::install_location_hook();
+
+    // panics outside of `RPanicPayload::catch_unwind` don't record their location
+    let _ = panic::catch_unwind(|| panic!("outside"));
+    LAST_LOCATION.with(|l| assert_eq!(l.take(), None));
+}
@@ -3,7 +3,7 @@
 use crate::{
     pointer_trait::{
         AsMutPtr, AsPtr, CallReferentDrop, CanTransmuteElement, Deallocate, GetPointerKind,
-        OwnedPointer, PK_SmartPointer,
+        OwnedPointer, PK_SmartPointer, PtrFromValue,
     },
     sabi_types::MovePtr,
     std_types::RBox,
@@ -467,6 +467,16 @@ unsafe impl<T, O, Inline> CanTransmuteElement<O> for RSmallBox<T, Inline> {
     }
 }
 
+impl<T, Inline> PtrFromValue for RSmallBox<T, Inline>
+where
+    Inline: InlineStorage,
+{
+    #[inline]
+    fn from_value(value: T) -> Self {
+        RSmallBox::new(value)
+    }
+}
+
 unsafe impl<T: Send, Inline> Send for RSmallBox<T, Inline> {}
 unsafe impl<T: Sync, Inline> Sync for RSmallBox<T, Inline> {}
 
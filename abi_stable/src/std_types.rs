@@ -5,18 +5,30 @@
 //! the [external_types module](../external_types/index.html)
 
 pub(crate) mod arc;
+pub(crate) mod arc_slice;
 pub(crate) mod boxed;
+pub(crate) mod boxed_slice;
+pub(crate) mod boxed_str;
+pub mod closures;
 pub(crate) mod cmp_ordering;
+pub(crate) mod context_error;
 pub mod cow;
+#[cfg(any(unix, windows))]
+pub(crate) mod file;
+pub mod future;
+pub(crate) mod io_objects;
 pub mod map;
+pub(crate) mod net;
 pub(crate) mod option;
 pub(crate) mod range;
+pub(crate) mod rc;
 pub(crate) mod result;
 pub(crate) mod slice_mut;
 pub(crate) mod slices;
 pub(crate) mod std_error;
 pub(crate) mod std_io;
 pub(crate) mod str;
+pub(crate) mod stream;
 pub mod string;
 pub(crate) mod time;
 pub(crate) mod tuple;
@@ -37,20 +49,34 @@ pub mod sync {}
 #[doc(inline)]
 pub use self::{
     arc::RArc,
+    arc_slice::{RArcSlice, RArcStr},
     boxed::RBox,
+    boxed_slice::RBoxedSlice,
+    boxed_str::RBoxedStr,
+    closures::{RFn, RFnMut, RFnOnce},
     cmp_ordering::RCmpOrdering,
+    context_error::{RContextError, RResultExt},
     cow::{RCow, RCowSlice, RCowStr, RCowVal},
+    future::{RContext, RFuture, RPoll, RWaker},
+    io_objects::{RBoxBufRead, RBoxRead, RBoxReadSeek, RBoxWrite},
     map::RHashMap,
+    net::{RIpAddr, RIpv4Addr, RIpv6Addr, RSocketAddr, RSocketAddrV4, RSocketAddrV6},
     option::{RNone, ROption, RSome},
+    rc::{RRc, RRcWeak},
     result::{RErr, ROk, RResult},
     slice_mut::RSliceMut,
     slices::RSlice,
     std_error::{RBoxError, RBoxError_, SendRBoxError, UnsyncRBoxError},
     std_io::{RIoError, RIoErrorKind, RSeekFrom},
     str::RStr,
+    stream::{RNext, RStream},
     string::RString,
-    time::RDuration,
+    time::{RDuration, RInstant, RSystemTime},
     tuple::{Tuple1, Tuple2, Tuple3, Tuple4},
     utypeid::UTypeId,
     vec::RVec,
 };
+
+#[cfg(any(unix, windows))]
+#[doc(inline)]
+pub use self::file::RFile;
@@ -8,7 +8,7 @@ use crate::{
     abi_stability::StableAbi,
     marker_type::ErasedPrefix,
     pointer_trait::{
-        AsPtr, CallReferentDrop, CanTransmuteElement, GetPointerKind, PK_SmartPointer,
+        AsPtr, CallReferentDrop, CanTransmuteElement, GetPointerKind, PK_SmartPointer, PtrFromValue,
     },
     prefix_type::{PrefixRef, WithMetadata},
     std_types::{
@@ -111,6 +111,13 @@ mod private {
         }
     }
 
+    impl<T> PtrFromValue for RArc<T> {
+        #[inline]
+        fn from_value(value: T) -> Self {
+            RArc::new(value)
+        }
+    }
+
     impl<T> RArc<T> {
         #[inline(always)]
         pub(super) const fn data(&self) -> *const T {
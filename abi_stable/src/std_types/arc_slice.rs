@@ -0,0 +1,448 @@
+//! Contains shared, immutable, ffi-safe slices and strings backed by `RArc`.
+
+use std::{
+    borrow::Borrow,
+    fmt::{self, Display, Formatter},
+    iter::FromIterator,
+    ops::Deref,
+    sync::Arc,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::std_types::{RArc, RBoxedSlice, RBoxedStr, RSlice, RStr, RString, RVec};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+/// An immutable, reference counted, ffi-safe slice.
+///
+/// This is an [`RArc`] of an [`RBoxedSlice<T>`],
+/// cloning it only increments the reference count.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RArcSlice;
+///
+/// let slice: RArcSlice<u32> = vec![3, 5, 8].into();
+/// let clone = slice.clone();
+///
+/// assert!(std::ptr::eq(slice.as_slice(), clone.as_slice()));
+/// assert_eq!(&*clone, &[3, 5, 8]);
+///
+/// ```
+#[repr(transparent)]
+#[derive(StableAbi)]
+pub struct RArcSlice<T> {
+    inner: RArc<RBoxedSlice<T>>,
+}
+
+impl<T> RArcSlice<T> {
+    /// Constructs an `RArcSlice` from an `RBoxedSlice`, without copying the elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArcSlice, RBoxedSlice};
+    ///
+    /// let slice = RArcSlice::new(RBoxedSlice::from(vec!['a', 'b']));
+    /// assert_eq!(&*slice, &['a', 'b']);
+    ///
+    /// ```
+    pub fn new(slice: RBoxedSlice<T>) -> Self {
+        Self {
+            inner: RArc::new(slice),
+        }
+    }
+
+    /// Creates a `&[T]` with access to all the elements of the `RArcSlice<T>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcSlice;
+    ///
+    /// let slice = RArcSlice::from(vec![0, 1, 2, 3]);
+    /// assert_eq!(slice.as_slice(), &[0, 1, 2, 3]);
+    ///
+    /// ```
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.inner.as_slice()
+    }
+
+    /// Creates an `RSlice<'_, T>` with access to all the elements of the `RArcSlice<T>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArcSlice, RSlice};
+    ///
+    /// let slice = RArcSlice::from(vec![0, 1, 2, 3]);
+    /// assert_eq!(slice.as_rslice(), RSlice::from_slice(&[0, 1, 2, 3]));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_rslice(&self) -> RSlice<'_, T> {
+        self.as_slice().into()
+    }
+
+    /// Gets the number of `RArcSlice`s that point to the same elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcSlice;
+    ///
+    /// let slice = RArcSlice::from(vec![0, 1]);
+    /// assert_eq!(RArcSlice::strong_count(&slice), 1);
+    ///
+    /// let clone = slice.clone();
+    /// assert_eq!(RArcSlice::strong_count(&slice), 2);
+    ///
+    /// ```
+    pub fn strong_count(this: &Self) -> usize {
+        RArc::strong_count(&this.inner)
+    }
+
+    /// Attempts to unwrap this `RArcSlice<T>` into an `RBoxedSlice<T>`,
+    /// returns `Err(self)` if there are other `RArcSlice`s pointing to the same elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArcSlice, RBoxedSlice};
+    ///
+    /// let slice = RArcSlice::from(vec![0, 1]);
+    /// let clone = slice.clone();
+    ///
+    /// let slice = RArcSlice::try_unwrap(slice).unwrap_err();
+    /// drop(clone);
+    ///
+    /// assert_eq!(RArcSlice::try_unwrap(slice).ok(), Some(RBoxedSlice::from(vec![0, 1])));
+    ///
+    /// ```
+    pub fn try_unwrap(this: Self) -> Result<RBoxedSlice<T>, Self> {
+        RArc::try_unwrap(this.inner).map_err(|inner| Self { inner })
+    }
+}
+
+impl<T> Clone for RArcSlice<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Default for RArcSlice<T> {
+    fn default() -> Self {
+        Self::new(RBoxedSlice::default())
+    }
+}
+
+impl<T> Deref for RArcSlice<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T> AsRef<[T]> for RArcSlice<T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> Borrow<[T]> for RArcSlice<T> {
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+shared_impls! {
+    mod = arc_slice_impls
+    new_type = RArcSlice[][T],
+    original_type = Arc,
+}
+
+impl<T> From<RBoxedSlice<T>> for RArcSlice<T> {
+    fn from(this: RBoxedSlice<T>) -> Self {
+        Self::new(this)
+    }
+}
+
+impl<T> From<Box<[T]>> for RArcSlice<T> {
+    fn from(this: Box<[T]>) -> Self {
+        Self::new(this.into())
+    }
+}
+
+impl<T> From<Vec<T>> for RArcSlice<T> {
+    fn from(this: Vec<T>) -> Self {
+        Self::new(this.into())
+    }
+}
+
+impl<T> From<RVec<T>> for RArcSlice<T> {
+    fn from(this: RVec<T>) -> Self {
+        Self::new(this.into())
+    }
+}
+
+impl<T> From<&[T]> for RArcSlice<T>
+where
+    T: Clone,
+{
+    fn from(this: &[T]) -> Self {
+        Self::new(this.into())
+    }
+}
+
+impl<T> From<Arc<[T]>> for RArcSlice<T>
+where
+    T: Clone,
+{
+    fn from(this: Arc<[T]>) -> Self {
+        Self::from(&*this)
+    }
+}
+
+impl<T> FromIterator<T> for RArcSlice<T> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RArcSlice<T> {
+    type Item = &'a T;
+
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for RArcSlice<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        RBoxedSlice::<T>::deserialize(deserializer).map(Self::new)
+    }
+}
+
+impl<T> Serialize for RArcSlice<T>
+where
+    T: Serialize,
+{
+    fn serialize<Z>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+    where
+        Z: Serializer,
+    {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An immutable, reference counted, ffi-safe string.
+///
+/// This is an [`RArc`] of an [`RBoxedStr`],
+/// cloning it only increments the reference count.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RArcStr;
+///
+/// let name = RArcStr::from("plugin_0");
+/// let clone = name.clone();
+///
+/// assert!(std::ptr::eq(name.as_str(), clone.as_str()));
+/// assert_eq!(&*clone, "plugin_0");
+///
+/// ```
+#[repr(transparent)]
+#[derive(StableAbi)]
+pub struct RArcStr {
+    inner: RArc<RBoxedStr>,
+}
+
+impl RArcStr {
+    /// Constructs an `RArcStr` from an `RBoxedStr`, without copying the string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArcStr, RBoxedStr};
+    ///
+    /// let str = RArcStr::new(RBoxedStr::from("hello"));
+    /// assert_eq!(&*str, "hello");
+    ///
+    /// ```
+    pub fn new(str: RBoxedStr) -> Self {
+        Self {
+            inner: RArc::new(str),
+        }
+    }
+
+    /// Creates a `&str` with access to all the characters of the `RArcStr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcStr;
+    ///
+    /// assert_eq!(RArcStr::from("hello").as_str(), "hello");
+    ///
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self.inner.as_str()
+    }
+
+    /// Creates an `RStr<'_>` with access to all the characters of the `RArcStr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArcStr, RStr};
+    ///
+    /// assert_eq!(RArcStr::from("hello").as_rstr(), RStr::from("hello"));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_rstr(&self) -> RStr<'_> {
+        self.as_str().into()
+    }
+
+    /// Gets the number of `RArcStr`s that point to the same string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcStr;
+    ///
+    /// let str = RArcStr::from("hello");
+    /// assert_eq!(RArcStr::strong_count(&str), 1);
+    ///
+    /// let clone = str.clone();
+    /// assert_eq!(RArcStr::strong_count(&str), 2);
+    ///
+    /// ```
+    pub fn strong_count(this: &Self) -> usize {
+        RArc::strong_count(&this.inner)
+    }
+}
+
+impl Clone for RArcStr {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Default for RArcStr {
+    fn default() -> Self {
+        Self::new(RBoxedStr::default())
+    }
+}
+
+impl Deref for RArcStr {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for RArcStr {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl Borrow<str> for RArcStr {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl Display for RArcStr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+shared_impls! {
+    mod = arc_str_impls
+    new_type = RArcStr[][],
+    original_type = str,
+}
+
+impl From<RBoxedStr> for RArcStr {
+    fn from(this: RBoxedStr) -> Self {
+        Self::new(this)
+    }
+}
+
+impl From<&str> for RArcStr {
+    fn from(this: &str) -> Self {
+        Self::new(this.into())
+    }
+}
+
+impl<'a> From<RStr<'a>> for RArcStr {
+    fn from(this: RStr<'a>) -> Self {
+        Self::new(this.into())
+    }
+}
+
+impl From<String> for RArcStr {
+    fn from(this: String) -> Self {
+        Self::new(this.into())
+    }
+}
+
+impl From<RString> for RArcStr {
+    fn from(this: RString) -> Self {
+        Self::new(this.into())
+    }
+}
+
+impl From<Arc<str>> for RArcStr {
+    fn from(this: Arc<str>) -> Self {
+        Self::from(&*this)
+    }
+}
+
+impl<'de> Deserialize<'de> for RArcStr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        RBoxedStr::deserialize(deserializer).map(Self::new)
+    }
+}
+
+impl Serialize for RArcStr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
+}
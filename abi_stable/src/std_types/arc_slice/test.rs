@@ -0,0 +1,56 @@
+use super::*;
+
+#[test]
+fn slice_clone_shares() {
+    let slice = RArcSlice::from(vec![3, 5, 8]);
+    let clone = slice.clone();
+
+    assert!(std::ptr::eq(slice.as_slice(), clone.as_slice()));
+    assert_eq!(RArcSlice::strong_count(&slice), 2);
+
+    let slice = RArcSlice::try_unwrap(slice).unwrap_err();
+    drop(clone);
+    assert_eq!(
+        RArcSlice::try_unwrap(slice).ok(),
+        Some(RBoxedSlice::from(vec![3, 5, 8]))
+    );
+}
+
+#[test]
+fn slice_conversions() {
+    let from_arc = RArcSlice::from(Arc::<[u8]>::from(&[1, 2][..]));
+    assert_eq!(from_arc.as_rslice(), RSlice::from_slice(&[1, 2]));
+
+    let collected = (0..4).collect::<RArcSlice<u32>>();
+    assert_eq!(&*collected, &[0, 1, 2, 3]);
+    assert_eq!((&collected).into_iter().sum::<u32>(), 6);
+
+    assert!(RArcSlice::<u32>::default().is_empty());
+}
+
+#[test]
+fn str_clone_shares() {
+    let str = RArcStr::from("hello");
+    let clone = str.clone();
+
+    assert!(std::ptr::eq(str.as_str(), clone.as_str()));
+    assert_eq!(RArcStr::strong_count(&str), 2);
+    assert_eq!(clone.as_rstr(), RStr::from("hello"));
+    assert_eq!(RArcStr::from(Arc::<str>::from("hello")), str);
+}
+
+#[test]
+fn serde() {
+    let slice = RArcSlice::from(vec!["a".to_string(), "b".to_string()]);
+    let json = serde_json::to_string(&slice).unwrap();
+    assert_eq!(json, r#"["a","b"]"#);
+    let deserialized: RArcSlice<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, slice);
+
+    let str = RArcStr::from("world");
+    let json = serde_json::to_string(&str).unwrap();
+    assert_eq!(json, r#""world""#);
+    let deserialized: RArcStr = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, str);
+    assert_eq!(format!("{}", deserialized), "world");
+}
@@ -22,7 +22,7 @@ use crate::{
     marker_type::NonOwningPhantom,
     pointer_trait::{
         AsMutPtr, AsPtr, CallReferentDrop, CanTransmuteElement, Deallocate, GetPointerKind,
-        OwnedPointer, PK_SmartPointer,
+        OwnedPointer, PK_SmartPointer, PtrFromValue,
     },
     prefix_type::WithMetadata,
     sabi_types::MovePtr,
@@ -195,6 +195,13 @@ unsafe impl<T, O> CanTransmuteElement<O> for RBox<T> {
     }
 }
 
+impl<T> PtrFromValue for RBox<T> {
+    #[inline]
+    fn from_value(value: T) -> Self {
+        RBox::new(value)
+    }
+}
+
 impl<T> RBox<T> {
     /// Converts this `RBox<T>` into a `Box<T>`
     ///
@@ -0,0 +1,561 @@
+//! Contains the ffi-safe equivalent of `Box<[T]>`.
+
+use std::{
+    borrow::{Borrow, BorrowMut},
+    iter::FromIterator,
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+    slice,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[allow(unused_imports)]
+use core_extensions::SelfOps;
+
+use crate::{
+    marker_type::NonOwningPhantom,
+    pointer_trait::CallReferentDrop,
+    prefix_type::WithMetadata,
+    std_types::{
+        utypeid::{new_utypeid, UTypeId},
+        vec::IntoIter,
+        RSlice, RSliceMut, RVec,
+    },
+};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+mod private {
+    use super::*;
+
+    /// Ffi-safe equivalent of `Box<[T]>`.
+    ///
+    /// Unlike [`RVec<T>`](crate::std_types::RVec),
+    /// this doesn't store a capacity and can't grow,
+    /// which makes it a better fit for owned buffers that are never resized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::{
+    ///     sabi_extern_fn,
+    ///     std_types::{RBoxedSlice, RVec},
+    /// };
+    ///
+    /// #[sabi_extern_fn]
+    /// fn squares(up_to: u32) -> RBoxedSlice<u32> {
+    ///     (0..up_to).map(|x| x * x).collect()
+    /// }
+    ///
+    /// assert_eq!(&*squares(5), &[0, 1, 4, 9, 16][..]);
+    ///
+    /// let from_vec = RBoxedSlice::from(RVec::from(vec![3, 5, 8]));
+    /// assert_eq!(from_vec.len(), 3);
+    ///
+    /// ```
+    ///
+    #[repr(C)]
+    #[derive(StableAbi)]
+    pub struct RBoxedSlice<T> {
+        data: NonNull<T>,
+        length: usize,
+        vtable: BoxedSliceVtable_Ref<T>,
+        _marker: PhantomData<T>,
+    }
+
+    impl<T> RBoxedSlice<T> {
+        /// Converts a `Box<[T]>` to an `RBoxedSlice<T>`, reusing its heap allocation.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use abi_stable::std_types::RBoxedSlice;
+        ///
+        /// let boxed: Box<[u8]> = vec![3, 5, 8].into_boxed_slice();
+        /// let rboxed = RBoxedSlice::from_box(boxed);
+        ///
+        /// assert_eq!(rboxed.as_slice(), &[3, 5, 8]);
+        ///
+        /// ```
+        pub fn from_box(this: Box<[T]>) -> Self {
+            let length = this.len();
+            let data = Box::into_raw(this) as *mut T;
+            Self {
+                data: unsafe { NonNull::new_unchecked(data) },
+                length,
+                vtable: VTableGetter::<T>::LIB_VTABLE,
+                _marker: PhantomData,
+            }
+        }
+
+        /// The amount of elements in the slice.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use abi_stable::std_types::RBoxedSlice;
+        ///
+        /// assert_eq!(RBoxedSlice::<u8>::default().len(), 0);
+        /// assert_eq!(RBoxedSlice::from(vec![3, 5, 8]).len(), 3);
+        ///
+        /// ```
+        #[inline(always)]
+        pub const fn len(&self) -> usize {
+            self.length
+        }
+
+        /// Whether the slice is empty.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use abi_stable::std_types::RBoxedSlice;
+        ///
+        /// assert!(RBoxedSlice::<u8>::default().is_empty());
+        /// assert!(!RBoxedSlice::from(vec![3, 5, 8]).is_empty());
+        ///
+        /// ```
+        #[inline(always)]
+        pub const fn is_empty(&self) -> bool {
+            self.length == 0
+        }
+
+        /// Gets a raw pointer to the start of the slice.
+        #[inline(always)]
+        pub const fn as_ptr(&self) -> *const T {
+            self.data.as_ptr()
+        }
+
+        /// Gets a mutable raw pointer to the start of the slice.
+        #[inline(always)]
+        pub fn as_mut_ptr(&mut self) -> *mut T {
+            self.data.as_ptr()
+        }
+
+        #[inline(always)]
+        pub(super) const fn vtable(&self) -> BoxedSliceVtable_Ref<T> {
+            self.vtable
+        }
+
+        #[allow(dead_code)]
+        #[cfg(test)]
+        pub(super) fn set_vtable_for_testing(&mut self) {
+            self.vtable = VTableGetter::<T>::LIB_VTABLE_FOR_TESTING;
+        }
+    }
+}
+
+pub use self::private::RBoxedSlice;
+
+impl<T> RBoxedSlice<T> {
+    /// Creates a `&[T]` with access to all the elements of the `RBoxedSlice<T>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxedSlice;
+    ///
+    /// let slice = RBoxedSlice::from(vec![0, 1, 2, 3]);
+    /// assert_eq!(slice.as_slice(), &[0, 1, 2, 3]);
+    ///
+    /// ```
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len()) }
+    }
+
+    /// Creates a `&mut [T]` with access to all the elements of the `RBoxedSlice<T>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxedSlice;
+    ///
+    /// let mut slice = RBoxedSlice::from(vec![0, 1, 2, 3]);
+    /// slice.as_mut_slice()[1] = 10;
+    /// assert_eq!(slice.as_slice(), &[0, 10, 2, 3]);
+    ///
+    /// ```
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let len = self.len();
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), len) }
+    }
+
+    /// Creates an `RSlice<'_, T>` with access to all the elements of the `RBoxedSlice<T>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBoxedSlice, RSlice};
+    ///
+    /// let slice = RBoxedSlice::from(vec![0, 1, 2, 3]);
+    /// assert_eq!(slice.as_rslice(), RSlice::from_slice(&[0, 1, 2, 3]));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_rslice(&self) -> RSlice<'_, T> {
+        self.as_slice().into()
+    }
+
+    /// Creates an `RSliceMut<'_, T>` with access to all the elements of the `RBoxedSlice<T>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBoxedSlice, RSliceMut};
+    ///
+    /// let mut slice = RBoxedSlice::from(vec![0, 1, 2, 3]);
+    /// assert_eq!(
+    ///     slice.as_mut_rslice(),
+    ///     RSliceMut::from_mut_slice(&mut [0, 1, 2, 3]),
+    /// );
+    ///
+    /// ```
+    #[inline]
+    pub fn as_mut_rslice(&mut self) -> RSliceMut<'_, T> {
+        self.as_mut_slice().into()
+    }
+
+    /// Converts this `RBoxedSlice<T>` into a `Box<[T]>`
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created
+    /// the `RBoxedSlice<T>`,
+    /// it will allocate a new `Box<[T]>` and move the elements into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxedSlice;
+    ///
+    /// let slice = RBoxedSlice::from(vec![3, 5, 8]);
+    /// let boxed: Box<[u32]> = slice.into_box();
+    /// assert_eq!(&*boxed, &[3, 5, 8]);
+    ///
+    /// ```
+    pub fn into_box(self) -> Box<[T]> {
+        let mut this = ManuallyDrop::new(self);
+
+        unsafe {
+            let len = this.len();
+            let this_vtable = this.vtable();
+            let other_vtable = VTableGetter::<T>::LIB_VTABLE;
+            if ::std::ptr::eq(this_vtable.0.to_raw_ptr(), other_vtable.0.to_raw_ptr())
+                || this_vtable.type_id()() == other_vtable.type_id()()
+            {
+                Box::from_raw(ptr::slice_from_raw_parts_mut(this.as_mut_ptr(), len))
+            } else {
+                let mut ret = Vec::<T>::with_capacity(len);
+                ptr::copy_nonoverlapping(this.as_ptr(), ret.as_mut_ptr(), len);
+                ret.set_len(len);
+                // Just deallocating the slice, without dropping the elements
+                (this_vtable.destructor())(this.as_mut_ptr() as *mut (), len, CallReferentDrop::No);
+                ret.into_boxed_slice()
+            }
+        }
+    }
+
+    /// Converts this `RBoxedSlice<T>` into a `Vec<T>`.
+    ///
+    /// # Allocation
+    ///
+    /// This allocates with the same conditions as [`into_box`](#method.into_box).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxedSlice;
+    ///
+    /// let slice = RBoxedSlice::from(vec![3, 5, 8]);
+    /// assert_eq!(slice.into_vec(), vec![3, 5, 8]);
+    ///
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_box().into_vec()
+    }
+
+    /// Converts this `RBoxedSlice<T>` into an `RVec<T>`.
+    ///
+    /// # Allocation
+    ///
+    /// This allocates with the same conditions as [`into_box`](#method.into_box).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBoxedSlice, RVec};
+    ///
+    /// let slice = RBoxedSlice::from(vec![3, 5, 8]);
+    /// assert_eq!(slice.into_rvec(), RVec::from(vec![3, 5, 8]));
+    ///
+    /// ```
+    pub fn into_rvec(self) -> RVec<T> {
+        self.into_vec().into()
+    }
+}
+
+/////////////////////////////////////////////////////////////////
+
+impl<T> Default for RBoxedSlice<T> {
+    fn default() -> Self {
+        Vec::new().into()
+    }
+}
+
+impl<T> Clone for RBoxedSlice<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        self.as_slice().to_vec().into()
+    }
+}
+
+impl<T> Deref for RBoxedSlice<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for RBoxedSlice<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
+
+impl<T> AsRef<[T]> for RBoxedSlice<T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> AsMut<[T]> for RBoxedSlice<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> Borrow<[T]> for RBoxedSlice<T> {
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> BorrowMut<[T]> for RBoxedSlice<T> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+shared_impls! {
+    mod = boxed_slice_impls
+    new_type = RBoxedSlice[][T],
+    original_type = Box,
+}
+
+/////////////////////////////////////////////////////////////////
+
+impl_from_rust_repr! {
+    impl[T] From<Box<[T]>> for RBoxedSlice<T> {
+        fn(this){
+            RBoxedSlice::from_box(this)
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl[T] Into<Box<[T]>> for RBoxedSlice<T> {
+        fn(this){
+            this.into_box()
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for RBoxedSlice<T> {
+    fn from(this: Vec<T>) -> Self {
+        this.into_boxed_slice().into()
+    }
+}
+
+/// Shrinks the allocation of the `RVec<T>` to fit its length.
+impl<T> From<RVec<T>> for RBoxedSlice<T> {
+    fn from(this: RVec<T>) -> Self {
+        this.into_vec().into()
+    }
+}
+
+impl<T> From<RBoxedSlice<T>> for RVec<T> {
+    fn from(this: RBoxedSlice<T>) -> Self {
+        this.into_rvec()
+    }
+}
+
+impl<T> From<&[T]> for RBoxedSlice<T>
+where
+    T: Clone,
+{
+    fn from(this: &[T]) -> Self {
+        this.to_vec().into()
+    }
+}
+
+impl<'a, T> From<RSlice<'a, T>> for RBoxedSlice<T>
+where
+    T: Clone,
+{
+    fn from(this: RSlice<'a, T>) -> Self {
+        this.as_slice().into()
+    }
+}
+
+/////////////////////////////////////////////////////////////////
+
+impl<T> IntoIterator for RBoxedSlice<T> {
+    type Item = T;
+
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        self.into_rvec().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RBoxedSlice<T> {
+    type Item = &'a T;
+
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut RBoxedSlice<T> {
+    type Item = &'a mut T;
+
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for RBoxedSlice<T> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        iter.into_iter().collect::<Vec<T>>().into()
+    }
+}
+
+/////////////////////////////////////////////////////////////////
+
+impl<'de, T> Deserialize<'de> for RBoxedSlice<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <Vec<T>>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+impl<T> Serialize for RBoxedSlice<T>
+where
+    T: Serialize,
+{
+    fn serialize<Z>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+    where
+        Z: Serializer,
+    {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+/////////////////////////////////////////////////////////////////
+
+unsafe impl<T: Send> Send for RBoxedSlice<T> {}
+unsafe impl<T: Sync> Sync for RBoxedSlice<T> {}
+impl<T> Unpin for RBoxedSlice<T> {}
+
+impl<T> Drop for RBoxedSlice<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let len = self.len();
+            let dstr = self.vtable().destructor();
+            dstr(self.as_mut_ptr() as *mut (), len, CallReferentDrop::Yes);
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////
+
+#[derive(StableAbi)]
+#[repr(C)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+pub(crate) struct BoxedSliceVtable<T> {
+    type_id: extern "C" fn() -> UTypeId,
+    #[sabi(last_prefix_field)]
+    destructor: unsafe extern "C" fn(*mut (), usize, CallReferentDrop),
+    _marker: NonOwningPhantom<T>,
+}
+
+struct VTableGetter<'a, T>(&'a T);
+
+impl<'a, T: 'a> VTableGetter<'a, T> {
+    const DEFAULT_VTABLE: BoxedSliceVtable<T> = BoxedSliceVtable {
+        type_id: new_utypeid::<RBoxedSlice<()>>,
+        destructor: destroy_boxed_slice::<T>,
+        _marker: NonOwningPhantom::NEW,
+    };
+
+    staticref! {
+        const WM_DEFAULT: WithMetadata<BoxedSliceVtable<T>> =
+            WithMetadata::new(Self::DEFAULT_VTABLE);
+    }
+
+    // The VTABLE for this type in this executable/library
+    const LIB_VTABLE: BoxedSliceVtable_Ref<T> = BoxedSliceVtable_Ref(Self::WM_DEFAULT.as_prefix());
+
+    #[cfg(test)]
+    staticref! {
+        const WM_FOR_TESTING: WithMetadata<BoxedSliceVtable<T>> =
+            WithMetadata::new(
+                BoxedSliceVtable {
+                    type_id: new_utypeid::<RBoxedSlice<i32>>,
+                    ..Self::DEFAULT_VTABLE
+                },
+            )
+    }
+
+    #[allow(dead_code)]
+    #[cfg(test)]
+    const LIB_VTABLE_FOR_TESTING: BoxedSliceVtable_Ref<T> =
+        BoxedSliceVtable_Ref(Self::WM_FOR_TESTING.as_prefix());
+}
+
+unsafe extern "C" fn destroy_boxed_slice<T>(ptr: *mut (), len: usize, call_drop: CallReferentDrop) {
+    extern_fn_panic_handling! {no_early_return;
+        let ptr = ptr as *mut T;
+        if let CallReferentDrop::Yes = call_drop {
+            unsafe { drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len))); }
+        } else {
+            let ptr = ptr as *mut ManuallyDrop<T>;
+            unsafe { drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len))); }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use super::*;
+
+use std::{cell::Cell, rc::Rc};
+
+fn _covariant_boxed_slice<'a: 'b, 'b, T>(slice: RBoxedSlice<&'a T>) -> RBoxedSlice<&'b T> {
+    slice
+}
+
+#[test]
+fn to_from_box() {
+    let orig: Box<[u32]> = vec![3, 5, 8, 13].into_boxed_slice();
+    let addr = orig.as_ptr();
+
+    let rboxed = RBoxedSlice::from(orig);
+    assert_eq!(rboxed.as_ptr(), addr);
+    assert_eq!(rboxed.len(), 4);
+
+    let back = rboxed.into_box();
+    assert_eq!(back.as_ptr(), addr);
+    assert_eq!(&*back, &[3, 5, 8, 13]);
+}
+
+#[test]
+fn into_box_from_other_library() {
+    let count = Rc::new(Cell::new(0));
+
+    let mut rboxed: RBoxedSlice<DropCounter> = (0..3)
+        .map(|_| DropCounter(count.clone()))
+        .collect::<RBoxedSlice<_>>();
+    let addr = rboxed.as_ptr();
+
+    rboxed.set_vtable_for_testing();
+
+    let back = rboxed.into_box();
+    assert_ne!(back.as_ptr(), addr);
+    assert_eq!(back.len(), 3);
+    assert_eq!(count.get(), 0);
+
+    drop(back);
+    assert_eq!(count.get(), 3);
+}
+
+#[test]
+fn from_rvec_shrinks() {
+    let mut vec = RVec::with_capacity(100);
+    vec.extend_from_slice(&[1u8, 2, 3]);
+
+    let rboxed = RBoxedSlice::from(vec);
+    assert_eq!(rboxed.as_slice(), &[1, 2, 3]);
+
+    let back = rboxed.into_rvec();
+    assert_eq!(back.capacity(), 3);
+}
+
+#[test]
+fn empty_and_zst() {
+    let empty = RBoxedSlice::<String>::default();
+    assert!(empty.is_empty());
+    assert_eq!(empty.as_slice(), &[] as &[String]);
+
+    let zsts = RBoxedSlice::from(vec![(); 10]);
+    assert_eq!(zsts.len(), 10);
+    assert_eq!(zsts.into_vec(), vec![(); 10]);
+}
+
+#[test]
+fn drops_elements() {
+    let count = Rc::new(Cell::new(0));
+
+    let rboxed: RBoxedSlice<DropCounter> = vec![DropCounter(count.clone()); 5].into();
+    let cloned = rboxed.clone();
+    assert_eq!(count.get(), 0);
+
+    drop(rboxed);
+    assert_eq!(count.get(), 5);
+    drop(cloned);
+    assert_eq!(count.get(), 10);
+}
+
+#[test]
+fn mutation_and_iteration() {
+    let mut rboxed = RBoxedSlice::from(vec![1, 2, 3]);
+    for x in &mut rboxed {
+        *x *= 10;
+    }
+    rboxed[0] += 1;
+    assert_eq!(rboxed.iter().copied().collect::<Vec<_>>(), vec![11, 20, 30]);
+    assert_eq!(rboxed.into_iter().collect::<Vec<_>>(), vec![11, 20, 30]);
+}
+
+#[test]
+fn serde() {
+    let rboxed = RBoxedSlice::from(vec![3, 5, 8]);
+    let json = serde_json::to_string(&rboxed).unwrap();
+    assert_eq!(json, "[3,5,8]");
+
+    let deserialized: RBoxedSlice<u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, rboxed);
+}
+
+#[derive(Clone)]
+struct DropCounter(Rc<Cell<u32>>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
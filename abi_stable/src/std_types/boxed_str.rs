@@ -0,0 +1,326 @@
+//! Contains the ffi-safe equivalent of `Box<str>`.
+
+use std::{
+    borrow::{Borrow, Cow},
+    fmt::{self, Display, Formatter},
+    iter::FromIterator,
+    ops::Deref,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::std_types::{RBoxedSlice, RStr, RString};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+/// Ffi-safe equivalent of `Box<str>`.
+///
+/// Unlike [`RString`](crate::std_types::RString),
+/// this doesn't store a capacity and can't grow.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::{
+///     sabi_extern_fn,
+///     std_types::{RBoxedStr, RStr},
+/// };
+///
+/// #[sabi_extern_fn]
+/// fn shout(phrase: RStr<'_>) -> RBoxedStr {
+///     phrase.to_uppercase().into()
+/// }
+///
+/// assert_eq!(&*shout("hello".into()), "HELLO");
+///
+/// ```
+///
+#[derive(Clone, Default)]
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RBoxedStr {
+    inner: RBoxedSlice<u8>,
+}
+
+impl RBoxedStr {
+    /// Creates a `&str` with access to all the characters of the `RBoxedStr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxedStr;
+    ///
+    /// let str = "What is that.";
+    /// assert_eq!(RBoxedStr::from(str).as_str(), str);
+    ///
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { std::str::from_utf8_unchecked(self.inner.as_slice()) }
+    }
+
+    /// Creates an `RStr<'_>` with access to all the characters of the `RBoxedStr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBoxedStr, RStr};
+    ///
+    /// let str = "What is that.";
+    /// assert_eq!(RBoxedStr::from(str).as_rstr(), RStr::from(str));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_rstr(&self) -> RStr<'_> {
+        self.as_str().into()
+    }
+
+    /// Returns the length (in bytes) of the `RBoxedStr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxedStr;
+    ///
+    /// assert_eq!(RBoxedStr::from("").len(), 0);
+    /// assert_eq!(RBoxedStr::from("a").len(), 1);
+    /// assert_eq!(RBoxedStr::from("Regular").len(), 7);
+    ///
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Queries whether the `RBoxedStr` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxedStr;
+    ///
+    /// assert_eq!(RBoxedStr::from("").is_empty(), true);
+    /// assert_eq!(RBoxedStr::from("a").is_empty(), false);
+    ///
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Converts this `RBoxedStr` into its bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxedStr;
+    ///
+    /// let bytes = RBoxedStr::from("hello").into_bytes();
+    /// assert_eq!(&*bytes, b"hello");
+    ///
+    /// ```
+    pub fn into_bytes(self) -> RBoxedSlice<u8> {
+        self.inner
+    }
+
+    /// Converts this `RBoxedStr` into a `Box<str>`.
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created
+    /// the `RBoxedStr`,
+    /// it will allocate a new `Box<str>` and copy the string into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxedStr;
+    ///
+    /// let boxed: Box<str> = RBoxedStr::from("world").into_box();
+    /// assert_eq!(&*boxed, "world");
+    ///
+    /// ```
+    pub fn into_box(self) -> Box<str> {
+        unsafe { String::from_utf8_unchecked(self.inner.into_vec()).into_boxed_str() }
+    }
+
+    /// Converts this `RBoxedStr` into a `String`.
+    ///
+    /// # Allocation
+    ///
+    /// This allocates with the same conditions as [`into_box`](#method.into_box).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxedStr;
+    ///
+    /// assert_eq!(RBoxedStr::from("world").into_string(), "world".to_string());
+    ///
+    /// ```
+    pub fn into_string(self) -> String {
+        self.into_box().into_string()
+    }
+
+    /// Converts this `RBoxedStr` into an `RString`.
+    ///
+    /// # Allocation
+    ///
+    /// This allocates with the same conditions as [`into_box`](#method.into_box).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBoxedStr, RString};
+    ///
+    /// assert_eq!(RBoxedStr::from("world").into_rstring(), RString::from("world"));
+    ///
+    /// ```
+    pub fn into_rstring(self) -> RString {
+        self.into_string().into()
+    }
+}
+
+////////////////////
+
+impl_from_rust_repr! {
+    impl From<Box<str>> for RBoxedStr {
+        fn(this){
+            RBoxedStr {
+                inner: this.into_boxed_bytes().into(),
+            }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<Box<str>> for RBoxedStr {
+        fn(this){
+            this.into_box()
+        }
+    }
+}
+
+impl From<&str> for RBoxedStr {
+    fn from(this: &str) -> Self {
+        Box::<str>::from(this).into()
+    }
+}
+
+impl<'a> From<RStr<'a>> for RBoxedStr {
+    fn from(this: RStr<'a>) -> Self {
+        this.as_str().into()
+    }
+}
+
+impl From<String> for RBoxedStr {
+    fn from(this: String) -> Self {
+        this.into_boxed_str().into()
+    }
+}
+
+/// Shrinks the allocation of the `RString` to fit its length.
+impl From<RString> for RBoxedStr {
+    fn from(this: RString) -> Self {
+        this.into_string().into()
+    }
+}
+
+impl From<RBoxedStr> for RString {
+    fn from(this: RBoxedStr) -> Self {
+        this.into_rstring()
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for RBoxedStr {
+    fn from(this: Cow<'a, str>) -> Self {
+        this.into_owned().into()
+    }
+}
+
+impl FromStr for RBoxedStr {
+    type Err = <String as FromStr>::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.into())
+    }
+}
+
+impl FromIterator<char> for RBoxedStr {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = char>,
+    {
+        iter.into_iter().collect::<String>().into()
+    }
+}
+
+impl<'a> FromIterator<&'a str> for RBoxedStr {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        iter.into_iter().collect::<String>().into()
+    }
+}
+
+////////////////////
+
+impl Borrow<str> for RBoxedStr {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl AsRef<str> for RBoxedStr {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl AsRef<[u8]> for RBoxedStr {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Deref for RBoxedStr {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl Display for RBoxedStr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+shared_impls! {
+    mod = boxed_str_impls
+    new_type = RBoxedStr[][],
+    original_type = str,
+}
+
+impl<'de> Deserialize<'de> for RBoxedStr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(From::from)
+    }
+}
+
+impl Serialize for RBoxedStr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
+}
@@ -0,0 +1,44 @@
+use super::*;
+
+#[test]
+fn to_from_box() {
+    let orig: Box<str> = "hello world".into();
+    let addr = orig.as_ptr();
+
+    let rboxed = RBoxedStr::from(orig);
+    assert_eq!(rboxed.as_ptr(), addr);
+    assert_eq!(rboxed.len(), 11);
+
+    let back = rboxed.into_box();
+    assert_eq!(back.as_ptr(), addr);
+    assert_eq!(&*back, "hello world");
+}
+
+#[test]
+fn conversions() {
+    let mut rstring = RString::with_capacity(64);
+    rstring.push_str("foo");
+
+    let rboxed = RBoxedStr::from(rstring);
+    assert_eq!(rboxed.as_rstr(), RStr::from("foo"));
+    assert_eq!(rboxed.clone().into_rstring().capacity(), 3);
+    assert_eq!(rboxed.clone().into_string(), "foo");
+    assert_eq!(&*rboxed.into_bytes(), b"foo");
+
+    assert_eq!(&*"bar".parse::<RBoxedStr>().unwrap(), "bar");
+    assert_eq!(&*"baz".chars().collect::<RBoxedStr>(), "baz");
+    assert_eq!(&*RBoxedStr::default(), "");
+}
+
+#[test]
+fn fmt_and_serde() {
+    let rboxed = RBoxedStr::from("what\"");
+    assert_eq!(format!("{}", rboxed), "what\"");
+    assert_eq!(format!("{:?}", rboxed), format!("{:?}", "what\""));
+
+    let json = serde_json::to_string(&rboxed).unwrap();
+    assert_eq!(json, r#""what\"""#);
+
+    let deserialized: RBoxedStr = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, rboxed);
+}
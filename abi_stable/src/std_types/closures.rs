@@ -0,0 +1,621 @@
+//! Contains ffi-safe equivalents of `Box<dyn Fn*>` and `&dyn Fn*`.
+//!
+//! The arguments of the closures are passed as a tuple type parameter,
+//! which is `()` for closures that take no arguments,
+//! and one of [`Tuple1`], [`Tuple2`], [`Tuple3`], or [`Tuple4`] otherwise.
+//!
+//! [`Tuple1`]: crate::std_types::Tuple1
+//! [`Tuple2`]: crate::std_types::Tuple2
+//! [`Tuple3`]: crate::std_types::Tuple3
+//! [`Tuple4`]: crate::std_types::Tuple4
+
+use std::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+};
+
+use crate::{
+    marker_type::UnsyncUnsend,
+    pointer_trait::{AsMutPtr, AsPtr, TransmuteElement},
+    prefix_type::WithMetadata,
+    sabi_types::{RMut, RRef},
+    std_types::{RBox, Tuple1, Tuple2, Tuple3, Tuple4},
+};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests;
+
+/// Ffi-safe equivalent of `Box<dyn Fn(..) -> R + 'a>`,
+/// and of `&'a dyn Fn(..) -> R` when `P` is `RRef<'a, ()>`.
+///
+/// `A` is the tuple of arguments the closure takes,
+/// which is `()` for closures that take no arguments,
+/// and one of the `Tuple*` types otherwise.
+///
+/// This type is neither `Send` nor `Sync`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::{
+///     sabi_extern_fn,
+///     std_types::{RFn, RVec, Tuple1},
+/// };
+///
+/// #[sabi_extern_fn]
+/// fn map_all(list: RVec<u32>, mapper: RFn<'_, Tuple1<u32>, u32>) -> RVec<u32> {
+///     list.into_iter().map(|x| mapper.call(x)).collect()
+/// }
+///
+/// let offset = 10;
+/// let mapped = map_all(vec![3, 5, 8].into(), RFn::new(|x| x + offset));
+/// assert_eq!(mapped, vec![13, 15, 18]);
+///
+/// // Borrowing the closure instead of boxing it.
+/// let closure = |x: u32| x * 2;
+/// let borrowed: RFn<'_, Tuple1<u32>, u32, _> = RFn::from_ref(&closure);
+/// assert_eq!(borrowed.call(21), 42);
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RFn<'a, A, R, P = RBox<()>> {
+    ptr: P,
+    vtable: FnVtable_Ref<A, R>,
+    _marker: PhantomData<(&'a (), UnsyncUnsend)>,
+}
+
+/// Ffi-safe equivalent of `Box<dyn FnMut(..) -> R + 'a>`,
+/// and of `&'a mut dyn FnMut(..) -> R` when `P` is `RMut<'a, ()>`.
+///
+/// `A` is the tuple of arguments the closure takes,
+/// which is `()` for closures that take no arguments,
+/// and one of the `Tuple*` types otherwise.
+///
+/// This type is neither `Send` nor `Sync`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::{
+///     sabi_extern_fn,
+///     std_types::{RFnMut, Tuple2},
+/// };
+///
+/// #[sabi_extern_fn]
+/// fn visit_pairs(mut visitor: RFnMut<'_, Tuple2<u32, char>, ()>) {
+///     visitor.call(0, 'a');
+///     visitor.call(1, 'b');
+/// }
+///
+/// let mut visited = Vec::new();
+/// visit_pairs(RFnMut::new(|num, ch| visited.push((num, ch))));
+/// assert_eq!(visited, vec![(0, 'a'), (1, 'b')]);
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RFnMut<'a, A, R, P = RBox<()>> {
+    ptr: P,
+    vtable: FnMutVtable_Ref<A, R>,
+    _marker: PhantomData<(&'a (), UnsyncUnsend)>,
+}
+
+/// Ffi-safe equivalent of `Box<dyn FnOnce(..) -> R + 'a>`.
+///
+/// `A` is the tuple of arguments the closure takes,
+/// which is `()` for closures that take no arguments,
+/// and one of the `Tuple*` types otherwise.
+///
+/// This type is neither `Send` nor `Sync`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RFnOnce, RString};
+///
+/// let greeting = RString::from("hello");
+///
+/// let consumer: RFnOnce<'_, (), RString> = RFnOnce::new(move || greeting);
+///
+/// assert_eq!(consumer.call(), "hello");
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RFnOnce<'a, A, R> {
+    ptr: RBox<()>,
+    vtable: FnOnceVtable_Ref<A, R>,
+    _marker: PhantomData<(&'a (), UnsyncUnsend)>,
+}
+
+impl<'a, A, R> RFn<'a, A, R> {
+    /// Constructs an `RFn` from a closure, boxing it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RFn, Tuple2};
+    ///
+    /// let adder: RFn<'_, Tuple2<u32, u32>, u32> = RFn::new(|l, r| l + r);
+    ///
+    /// assert_eq!(adder.call(3, 5), 8);
+    ///
+    /// ```
+    pub fn new<F>(closure: F) -> Self
+    where
+        F: CallWith<A, R> + 'a,
+    {
+        unsafe {
+            Self {
+                ptr: RBox::new(closure).transmute_element::<()>(),
+                vtable: VTableGetter::<F, A, R>::FN_VTABLE,
+                _marker: PhantomData,
+            }
+        }
+    }
+}
+
+impl<'a, A, R> RFn<'a, A, R, RRef<'a, ()>> {
+    /// Constructs an `RFn` from a reference to a closure.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::{
+    ///     sabi_types::RRef,
+    ///     std_types::{RFn, Tuple1},
+    /// };
+    ///
+    /// let closure = |x: u8| x.count_ones();
+    /// let rfn: RFn<'_, Tuple1<u8>, u32, RRef<'_, ()>> = RFn::from_ref(&closure);
+    ///
+    /// assert_eq!(rfn.call(0b1011), 3);
+    ///
+    /// ```
+    pub fn from_ref<F>(closure: &'a F) -> Self
+    where
+        F: CallWith<A, R>,
+    {
+        unsafe {
+            Self {
+                ptr: RRef::new(closure).transmute::<()>(),
+                vtable: VTableGetter::<F, A, R>::FN_VTABLE,
+                _marker: PhantomData,
+            }
+        }
+    }
+}
+
+impl<'a, A, R, P> RFn<'a, A, R, P>
+where
+    P: AsPtr<PtrTarget = ()>,
+{
+    /// Calls the closure with a tuple of arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RFn, Tuple2};
+    ///
+    /// let adder: RFn<'_, Tuple2<u32, u32>, u32> = RFn::new(|l, r| l + r);
+    ///
+    /// assert_eq!(adder.call_with(Tuple2(3, 5)), 8);
+    ///
+    /// ```
+    pub fn call_with(&self, args: A) -> R {
+        unsafe { self.vtable.call()(self.ptr.as_rref(), args) }
+    }
+
+    /// Constructs an `RFn` that borrows the closure of this one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RFn, Tuple1};
+    ///
+    /// let boxed: RFn<'_, Tuple1<u32>, u32> = RFn::new(|x| x + 1);
+    ///
+    /// let borrowed = boxed.reborrow();
+    /// assert_eq!(borrowed.call(1), 2);
+    ///
+    /// ```
+    pub fn reborrow(&self) -> RFn<'_, A, R, RRef<'_, ()>> {
+        RFn {
+            ptr: self.ptr.as_rref(),
+            vtable: self.vtable,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, A, R> RFnMut<'a, A, R> {
+    /// Constructs an `RFnMut` from a closure, boxing it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RFnMut;
+    ///
+    /// let mut count = 0;
+    /// let mut counter: RFnMut<'_, (), u32> = RFnMut::new(move || {
+    ///     count += 1;
+    ///     count
+    /// });
+    ///
+    /// assert_eq!(counter.call(), 1);
+    /// assert_eq!(counter.call(), 2);
+    ///
+    /// ```
+    pub fn new<F>(closure: F) -> Self
+    where
+        F: CallMutWith<A, R> + 'a,
+    {
+        unsafe {
+            Self {
+                ptr: RBox::new(closure).transmute_element::<()>(),
+                vtable: VTableGetter::<F, A, R>::FN_MUT_VTABLE,
+                _marker: PhantomData,
+            }
+        }
+    }
+}
+
+impl<'a, A, R> RFnMut<'a, A, R, RMut<'a, ()>> {
+    /// Constructs an `RFnMut` from a mutable reference to a closure.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::{
+    ///     sabi_types::RMut,
+    ///     std_types::{RFnMut, Tuple1},
+    /// };
+    ///
+    /// let mut list = Vec::new();
+    /// let mut closure = |x: u32| list.push(x);
+    ///
+    /// let mut rfn: RFnMut<'_, Tuple1<u32>, (), RMut<'_, ()>> = RFnMut::from_mut(&mut closure);
+    /// rfn.call(3);
+    /// rfn.call(5);
+    ///
+    /// assert_eq!(list, vec![3, 5]);
+    ///
+    /// ```
+    pub fn from_mut<F>(closure: &'a mut F) -> Self
+    where
+        F: CallMutWith<A, R>,
+    {
+        unsafe {
+            Self {
+                ptr: RMut::new(closure).transmute::<()>(),
+                vtable: VTableGetter::<F, A, R>::FN_MUT_VTABLE,
+                _marker: PhantomData,
+            }
+        }
+    }
+}
+
+impl<'a, A, R, P> RFnMut<'a, A, R, P>
+where
+    P: AsMutPtr<PtrTarget = ()>,
+{
+    /// Calls the closure with a tuple of arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RFnMut, Tuple1};
+    ///
+    /// let mut total = 0;
+    /// let mut adder: RFnMut<'_, Tuple1<u32>, u32> = RFnMut::new(|x| {
+    ///     total += x;
+    ///     total
+    /// });
+    ///
+    /// assert_eq!(adder.call_with(Tuple1(3)), 3);
+    /// assert_eq!(adder.call_with(Tuple1(5)), 8);
+    ///
+    /// ```
+    pub fn call_with(&mut self, args: A) -> R {
+        unsafe { self.vtable.call_mut()(self.ptr.as_rmut(), args) }
+    }
+
+    /// Constructs an `RFnMut` that mutably borrows the closure of this one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RFnMut;
+    ///
+    /// let mut count = 0;
+    /// let mut counter: RFnMut<'_, (), u32> = RFnMut::new(move || {
+    ///     count += 1;
+    ///     count
+    /// });
+    ///
+    /// assert_eq!(counter.reborrow_mut().call(), 1);
+    /// assert_eq!(counter.call(), 2);
+    ///
+    /// ```
+    pub fn reborrow_mut(&mut self) -> RFnMut<'_, A, R, RMut<'_, ()>> {
+        RFnMut {
+            ptr: self.ptr.as_rmut(),
+            vtable: self.vtable,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, A, R> RFnOnce<'a, A, R> {
+    /// Constructs an `RFnOnce` from a closure, boxing it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RFnOnce, RVec, Tuple1};
+    ///
+    /// let list = RVec::from(vec![3, 5]);
+    /// let pusher: RFnOnce<'_, Tuple1<u32>, RVec<u32>> = RFnOnce::new(move |x| {
+    ///     let mut list = list;
+    ///     list.push(x);
+    ///     list
+    /// });
+    ///
+    /// assert_eq!(pusher.call(8), vec![3, 5, 8]);
+    ///
+    /// ```
+    pub fn new<F>(closure: F) -> Self
+    where
+        F: CallOnceWith<A, R> + 'a,
+    {
+        unsafe {
+            Self {
+                ptr: RBox::new(closure).transmute_element::<()>(),
+                vtable: VTableGetter::<F, A, R>::FN_ONCE_VTABLE,
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    /// Calls the closure with a tuple of arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RFnOnce, RString, Tuple1};
+    ///
+    /// let suffix = RString::from("world");
+    /// let concat: RFnOnce<'_, Tuple1<&str>, RString> =
+    ///     RFnOnce::new(move |prefix| format!("{}{}", prefix, suffix).into());
+    ///
+    /// assert_eq!(concat.call_with(Tuple1("hello ")), "hello world");
+    ///
+    /// ```
+    pub fn call_with(self, args: A) -> R {
+        unsafe { self.vtable.call_once()(self.ptr, args) }
+    }
+}
+
+macro_rules! impl_call_methods {
+    (
+        $(
+            [$($param:ident: $arg:ident),*] $args_ty:ty, ($($args_ctor:tt)*);
+        )*
+    ) => {
+        $(
+            impl<'a, $($arg,)* R, P> RFn<'a, $args_ty, R, P>
+            where
+                P: AsPtr<PtrTarget = ()>,
+            {
+                /// Calls the closure.
+                #[inline]
+                pub fn call(&self, $($param: $arg),*) -> R {
+                    self.call_with($($args_ctor)*($($param),*))
+                }
+            }
+
+            impl<'a, $($arg,)* R, P> RFnMut<'a, $args_ty, R, P>
+            where
+                P: AsMutPtr<PtrTarget = ()>,
+            {
+                /// Calls the closure.
+                #[inline]
+                pub fn call(&mut self, $($param: $arg),*) -> R {
+                    self.call_with($($args_ctor)*($($param),*))
+                }
+            }
+
+            impl<'a, $($arg,)* R> RFnOnce<'a, $args_ty, R> {
+                /// Calls the closure.
+                #[inline]
+                pub fn call(self, $($param: $arg),*) -> R {
+                    self.call_with($($args_ctor)*($($param),*))
+                }
+            }
+
+            impl<F, $($arg,)* R> CallWith<$args_ty, R> for F
+            where
+                F: Fn($($arg),*) -> R,
+            {
+                #[inline]
+                fn call_with(&self, $($args_ctor)*($($param),*): $args_ty) -> R {
+                    self($($param),*)
+                }
+            }
+
+            impl<F, $($arg,)* R> CallMutWith<$args_ty, R> for F
+            where
+                F: FnMut($($arg),*) -> R,
+            {
+                #[inline]
+                fn call_mut_with(&mut self, $($args_ctor)*($($param),*): $args_ty) -> R {
+                    self($($param),*)
+                }
+            }
+
+            impl<F, $($arg,)* R> CallOnceWith<$args_ty, R> for F
+            where
+                F: FnOnce($($arg),*) -> R,
+            {
+                #[inline]
+                fn call_once_with(self, $($args_ctor)*($($param),*): $args_ty) -> R {
+                    self($($param),*)
+                }
+            }
+        )*
+    };
+}
+
+impl_call_methods! {
+    [] (), ();
+    [a0: A0] Tuple1<A0>, (Tuple1);
+    [a0: A0, a1: A1] Tuple2<A0, A1>, (Tuple2);
+    [a0: A0, a1: A1, a2: A2] Tuple3<A0, A1, A2>, (Tuple3);
+    [a0: A0, a1: A1, a2: A2, a3: A3] Tuple4<A0, A1, A2, A3>, (Tuple4);
+}
+
+impl<'a, A, R, P> Debug for RFn<'a, A, R, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RFn{..}")
+    }
+}
+
+impl<'a, A, R, P> Debug for RFnMut<'a, A, R, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RFnMut{..}")
+    }
+}
+
+impl<'a, A, R> Debug for RFnOnce<'a, A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RFnOnce{..}")
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// Implemented by all closures that can be wrapped in an [`RFn`],
+/// calling the closure with the `A` tuple of arguments.
+pub trait CallWith<A, R> {
+    /// Calls the closure with a tuple of arguments.
+    fn call_with(&self, args: A) -> R;
+}
+
+/// Implemented by all closures that can be wrapped in an [`RFnMut`],
+/// calling the closure with the `A` tuple of arguments.
+pub trait CallMutWith<A, R> {
+    /// Calls the closure with a tuple of arguments.
+    fn call_mut_with(&mut self, args: A) -> R;
+}
+
+/// Implemented by all closures that can be wrapped in an [`RFnOnce`],
+/// calling the closure with the `A` tuple of arguments.
+pub trait CallOnceWith<A, R> {
+    /// Calls the closure with a tuple of arguments.
+    fn call_once_with(self, args: A) -> R;
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+#[derive(StableAbi)]
+#[repr(C)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+pub(crate) struct FnVtable<A, R> {
+    #[sabi(last_prefix_field)]
+    call: unsafe extern "C" fn(RRef<'_, ()>, A) -> R,
+}
+
+#[derive(StableAbi)]
+#[repr(C)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+pub(crate) struct FnMutVtable<A, R> {
+    #[sabi(last_prefix_field)]
+    call_mut: unsafe extern "C" fn(RMut<'_, ()>, A) -> R,
+}
+
+#[derive(StableAbi)]
+#[repr(C)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+pub(crate) struct FnOnceVtable<A, R> {
+    #[sabi(last_prefix_field)]
+    call_once: unsafe extern "C" fn(RBox<()>, A) -> R,
+}
+
+struct VTableGetter<'a, F, A, R>(&'a F, PhantomData<extern "C" fn(A) -> R>);
+
+impl<'a, F: 'a, A, R> VTableGetter<'a, F, A, R>
+where
+    F: CallWith<A, R>,
+{
+    staticref! {
+        const WM_FN: WithMetadata<FnVtable<A, R>> = WithMetadata::new(
+            FnVtable {
+                call: call_fn::<F, A, R>,
+            }
+        )
+    }
+
+    const FN_VTABLE: FnVtable_Ref<A, R> = FnVtable_Ref(Self::WM_FN.as_prefix());
+}
+
+impl<'a, F: 'a, A, R> VTableGetter<'a, F, A, R>
+where
+    F: CallMutWith<A, R>,
+{
+    staticref! {
+        const WM_FN_MUT: WithMetadata<FnMutVtable<A, R>> = WithMetadata::new(
+            FnMutVtable {
+                call_mut: call_fn_mut::<F, A, R>,
+            }
+        )
+    }
+
+    const FN_MUT_VTABLE: FnMutVtable_Ref<A, R> = FnMutVtable_Ref(Self::WM_FN_MUT.as_prefix());
+}
+
+impl<'a, F: 'a, A, R> VTableGetter<'a, F, A, R>
+where
+    F: CallOnceWith<A, R>,
+{
+    staticref! {
+        const WM_FN_ONCE: WithMetadata<FnOnceVtable<A, R>> = WithMetadata::new(
+            FnOnceVtable {
+                call_once: call_fn_once::<F, A, R>,
+            }
+        )
+    }
+
+    const FN_ONCE_VTABLE: FnOnceVtable_Ref<A, R> = FnOnceVtable_Ref(Self::WM_FN_ONCE.as_prefix());
+}
+
+unsafe extern "C" fn call_fn<F, A, R>(this: RRef<'_, ()>, args: A) -> R
+where
+    F: CallWith<A, R>,
+{
+    extern_fn_panic_handling! {no_early_return;
+        unsafe { this.transmute_into_ref::<F>().call_with(args) }
+    }
+}
+
+unsafe extern "C" fn call_fn_mut<F, A, R>(this: RMut<'_, ()>, args: A) -> R
+where
+    F: CallMutWith<A, R>,
+{
+    extern_fn_panic_handling! {no_early_return;
+        unsafe { this.transmute_into_mut::<F>().call_mut_with(args) }
+    }
+}
+
+unsafe extern "C" fn call_fn_once<F, A, R>(this: RBox<()>, args: A) -> R
+where
+    F: CallOnceWith<A, R>,
+{
+    extern_fn_panic_handling! {no_early_return;
+        let this = unsafe { this.transmute_element::<F>() };
+        RBox::into_inner(this).call_once_with(args)
+    }
+}
@@ -0,0 +1,115 @@
+use super::*;
+
+use std::{cell::Cell, rc::Rc};
+
+use crate::std_types::{RString, RVec};
+
+#[test]
+fn fn_arities() {
+    let f0: RFn<'_, (), u32> = RFn::new(|| 3);
+    let f1: RFn<'_, Tuple1<u32>, u32> = RFn::new(|a| a + 1);
+    let f2: RFn<'_, Tuple2<u32, u32>, u32> = RFn::new(|a, b| a + b);
+    let f3: RFn<'_, Tuple3<u32, u32, u32>, u32> = RFn::new(|a, b, c| a + b + c);
+    let f4: RFn<'_, Tuple4<u32, u32, u32, u32>, u32> = RFn::new(|a, b, c, d| a + b + c + d);
+
+    assert_eq!(f0.call(), 3);
+    assert_eq!(f1.call(3), 4);
+    assert_eq!(f2.call(3, 5), 8);
+    assert_eq!(f3.call(3, 5, 8), 16);
+    assert_eq!(f4.call(3, 5, 8, 13), 29);
+    assert_eq!(f4.call_with(Tuple4(1, 1, 1, 1)), 4);
+}
+
+#[test]
+fn fn_borrowed() {
+    let prefix = RString::from("hello ");
+    let closure = |name: &str| -> RString { format!("{}{}", prefix, name).into() };
+
+    let borrowed: RFn<'_, Tuple1<&str>, RString, RRef<'_, ()>> = RFn::from_ref(&closure);
+    assert_eq!(borrowed.call("world"), "hello world");
+    assert_eq!(borrowed.reborrow().call("there"), "hello there");
+
+    let boxed: RFn<'_, Tuple1<&str>, RString> = RFn::new(closure);
+    assert_eq!(boxed.reborrow().call("you"), "hello you");
+}
+
+#[test]
+fn fn_mut_state() {
+    let mut list = RVec::new();
+    {
+        let mut pusher: RFnMut<'_, Tuple1<u32>, usize> = RFnMut::new(|x| {
+            list.push(x);
+            list.len()
+        });
+        assert_eq!(pusher.call(3), 1);
+        assert_eq!(pusher.reborrow_mut().call(5), 2);
+        assert_eq!(pusher.call_with(Tuple1(8)), 3);
+    }
+    assert_eq!(list, vec![3, 5, 8]);
+
+    let mut count = 0;
+    let mut closure = || count += 1;
+    {
+        let mut borrowed: RFnMut<'_, (), (), RMut<'_, ()>> = RFnMut::from_mut(&mut closure);
+        borrowed.call();
+        borrowed.call();
+    }
+    assert_eq!(count, 2);
+}
+
+#[test]
+fn fn_once_moves() {
+    let dropped = Rc::new(Cell::new(0));
+
+    let guard = DropCounter(dropped.clone());
+    let once: RFnOnce<'_, Tuple1<u32>, u32> = RFnOnce::new(move |x| {
+        let guard = guard;
+        x + guard.0.get()
+    });
+    assert_eq!(dropped.get(), 0);
+    assert_eq!(once.call(10), 10);
+    assert_eq!(dropped.get(), 1);
+
+    // Dropping an uncalled closure drops its captures.
+    let guard = DropCounter(dropped.clone());
+    let once: RFnOnce<'_, (), ()> = RFnOnce::new(move || drop(guard));
+    drop(once);
+    assert_eq!(dropped.get(), 2);
+}
+
+#[test]
+fn boxed_captures_are_dropped() {
+    let dropped = Rc::new(Cell::new(0));
+
+    let guard = DropCounter(dropped.clone());
+    let f: RFn<'_, (), u32> = RFn::new(move || guard.0.get());
+    assert_eq!(f.call(), 0);
+    drop(f);
+    assert_eq!(dropped.get(), 1);
+
+    let guard = DropCounter(dropped.clone());
+    let f: RFnMut<'_, (), u32> = RFnMut::new(move || guard.0.get());
+    drop(f);
+    assert_eq!(dropped.get(), 2);
+}
+
+#[test]
+fn layout_checking() {
+    use crate::{abi_stability::abi_checking::check_layout_compatibility, StableAbi};
+
+    let lay_u32 = <RFn<'_, Tuple1<u32>, u32> as StableAbi>::LAYOUT;
+    let lay_u64 = <RFn<'_, Tuple1<u64>, u32> as StableAbi>::LAYOUT;
+    let lay_ret = <RFn<'_, Tuple1<u32>, u64> as StableAbi>::LAYOUT;
+
+    assert!(check_layout_compatibility(lay_u32, lay_u32).is_ok());
+    assert!(check_layout_compatibility(lay_u32, lay_u64).is_err());
+    assert!(check_layout_compatibility(lay_u32, lay_ret).is_err());
+}
+
+struct DropCounter(Rc<Cell<u32>>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
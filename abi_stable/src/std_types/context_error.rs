@@ -0,0 +1,473 @@
+//! Contains an ffi-safe error type with a stack of context messages,
+//! and an extension trait to add context to `RResult`s and `ROption`s.
+
+use std::{
+    error::Error as ErrorTrait,
+    fmt::{self, Debug, Display},
+};
+
+use crate::std_types::{RBoxError, RErr, RNone, ROk, ROption, RResult, RSome, RString, RVec};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests;
+
+/// An ffi-safe error with a stack of context messages describing
+/// what was being done when the error happened,
+/// similar to `anyhow::Error`.
+///
+/// Context is added with the [`RResultExt`] extension trait,
+/// or the [`context`](#method.context) method.
+///
+/// # Formatting
+///
+/// This renders like `anyhow::Error` does:
+///
+/// - `{}`: prints the outermost context message,
+///   or the error if there is no context.
+///
+/// - `{:#}`: prints every context message, then the error and its sources,
+///   separated by `": "`.
+///
+/// - `{:?}`: prints the outermost message,
+///   followed by a numbered list of the remaining messages under "Caused by:".
+///
+/// - `{:#?}`: prints the struct-like representation of the error.
+///
+/// # Conversions
+///
+/// Every `std::error::Error + Send + Sync + 'static` type can be converted into an
+/// `RContextError` with `From`.
+/// Because of this, `RContextError` does not implement `std::error::Error`,
+/// use [`into_rboxerror`](#method.into_rboxerror) to get an error that does.
+///
+/// With the "anyhow" feature enabled, this can be converted into an `anyhow::Error`
+/// with `From`, and constructed from one with [`from_anyhow`](#method.from_anyhow).
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::{
+///     sabi_extern_fn,
+///     std_types::{RContextError, RResult, RResultExt, RStr},
+/// };
+///
+/// #[sabi_extern_fn]
+/// pub fn parse_port(text: RStr<'_>) -> RResult<u16, RContextError> {
+///     let port = text.as_str().parse::<u16>().map_err(RContextError::from);
+///     RResult::from(port).with_context(|| format!("parsing port {:?}", text.as_str()))
+/// }
+///
+/// fn load_config(port: RStr<'_>) -> RResult<u16, RContextError> {
+///     parse_port(port).context("loading config")
+/// }
+///
+/// assert_eq!(load_config("8080".into()).unwrap(), 8080);
+///
+/// let err = load_config("80800".into()).unwrap_err();
+///
+/// assert_eq!(err.to_string(), "loading config");
+/// assert_eq!(
+///     format!("{:#}", err),
+///     "loading config: parsing port \"80800\": number too large to fit in target type",
+/// );
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RContextError {
+    error: RBoxError,
+    /// The context messages, from the innermost to the outermost.
+    context: RVec<RString>,
+}
+
+impl RContextError {
+    /// Constructs an `RContextError` from an error, without any context.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RContextError;
+    ///
+    /// let err = RContextError::new(std::fmt::Error);
+    ///
+    /// assert_eq!(err.to_string(), std::fmt::Error.to_string());
+    /// ```
+    pub fn new<E>(error: E) -> Self
+    where
+        E: ErrorTrait + Send + Sync + 'static,
+    {
+        // Going through `from_box` so that `RBoxError`s aren't boxed again.
+        Self::from_rboxerror(RBoxError::from_box(Box::new(error)))
+    }
+
+    /// Constructs an `RContextError` from an `RBoxError`, without any context.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBoxError, RContextError};
+    ///
+    /// let err = RContextError::from_rboxerror(RBoxError::new(std::fmt::Error));
+    ///
+    /// assert!(err.contexts().is_empty());
+    /// ```
+    pub fn from_rboxerror(error: RBoxError) -> Self {
+        Self {
+            error,
+            context: RVec::new(),
+        }
+    }
+
+    /// Constructs an `RContextError` from a message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RContextError;
+    ///
+    /// let err = RContextError::msg("the plugin isn't initialized");
+    ///
+    /// assert_eq!(err.to_string(), "the plugin isn't initialized");
+    /// ```
+    pub fn msg<M>(message: M) -> Self
+    where
+        M: Display + Debug,
+    {
+        Self::from_rboxerror(RBoxError::from_fmt(&message))
+    }
+
+    /// Wraps this error with a context message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RContextError;
+    ///
+    /// let err = RContextError::msg("file not found")
+    ///     .context("reading the manifest")
+    ///     .context("loading the plugin");
+    ///
+    /// assert_eq!(err.to_string(), "loading the plugin");
+    /// assert_eq!(
+    ///     format!("{:#}", err),
+    ///     "loading the plugin: reading the manifest: file not found",
+    /// );
+    /// ```
+    pub fn context<C>(mut self, context: C) -> Self
+    where
+        C: Display,
+    {
+        self.context.push(context.to_string().into());
+        self
+    }
+
+    /// Gets the context messages, from the innermost to the outermost.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RContextError;
+    ///
+    /// let err = RContextError::msg("oh no").context("foo").context("bar");
+    ///
+    /// assert_eq!(err.contexts(), ["foo", "bar"]);
+    /// ```
+    pub fn contexts(&self) -> &[RString] {
+        &self.context
+    }
+
+    /// Gets the error that the context messages were added to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RContextError;
+    ///
+    /// let err = RContextError::new(std::fmt::Error).context("formatting");
+    ///
+    /// assert!(err.root_cause().downcast_ref::<std::fmt::Error>().is_some());
+    /// ```
+    pub fn root_cause(&self) -> &RBoxError {
+        &self.error
+    }
+
+    /// Converts this into the error that the context messages were added to,
+    /// discarding the context messages.
+    pub fn into_root_cause(self) -> RBoxError {
+        self.error
+    }
+
+    /// Converts this into an `RBoxError` that formats the same as this error,
+    /// and whose `source` is the root cause (if there is any context).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RContextError;
+    ///
+    /// let err = RContextError::new(std::fmt::Error).context("formatting");
+    /// let boxed = err.into_rboxerror();
+    ///
+    /// assert_eq!(boxed.to_string(), "formatting");
+    /// assert_eq!(format!("{:#}", boxed), "formatting: an error occurred when formatting an argument");
+    /// ```
+    pub fn into_rboxerror(self) -> RBoxError {
+        if self.context.is_empty() {
+            self.error
+        } else {
+            RBoxError::new(ContextChain(self))
+        }
+    }
+
+    /// Constructs an `RContextError` from an `anyhow::Error`,
+    /// converting the context messages of the `anyhow::Error`.
+    ///
+    /// If the `anyhow::Error` was converted from an `RContextError`,
+    /// this returns the original root cause and context messages.
+    ///
+    /// Otherwise, the root cause of the `anyhow::Error` is converted with
+    /// [`RBoxError::from_fmt`], which preserves its messages but not its type,
+    /// and every other error in its chain becomes a context message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RContextError;
+    ///
+    /// let err = anyhow::anyhow!("file not found").context("reading the manifest");
+    /// let err = RContextError::from_anyhow(err);
+    ///
+    /// assert_eq!(err.contexts(), ["reading the manifest"]);
+    /// assert_eq!(err.root_cause().to_string(), "file not found");
+    ///
+    /// let err = anyhow::Error::from(err);
+    /// assert_eq!(format!("{:#}", err), "reading the manifest: file not found");
+    /// ```
+    #[cfg(feature = "anyhow")]
+    #[cfg_attr(feature = "docsrs", doc(cfg(feature = "anyhow")))]
+    pub fn from_anyhow(error: anyhow::Error) -> Self {
+        let mut context = error
+            .chain()
+            .map(|e| RString::from(e.to_string()))
+            .collect::<RVec<RString>>();
+
+        let root_depth = context.len() - 1;
+        let (error, depth) = match error.chain().position(|e| e.is::<RBoxError>()) {
+            Some(depth) => match error.downcast::<RBoxError>() {
+                Ok(root) => (root, depth),
+                Err(error) => (RBoxError::from_fmt(error.root_cause()), root_depth),
+            },
+            None => (RBoxError::from_fmt(error.root_cause()), root_depth),
+        };
+
+        context.truncate(depth);
+        context.reverse();
+        Self { error, context }
+    }
+}
+
+impl<E> From<E> for RContextError
+where
+    E: ErrorTrait + Send + Sync + 'static,
+{
+    fn from(error: E) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "anyhow")]
+impl From<RContextError> for anyhow::Error {
+    fn from(this: RContextError) -> Self {
+        this.context
+            .into_iter()
+            .fold(anyhow::Error::new(this.error), |error, context| {
+                error.context(context)
+            })
+    }
+}
+
+impl Display for RContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() {
+            return match self.context.last() {
+                Some(context) => Display::fmt(context, f),
+                None => Display::fmt(&self.error, f),
+            };
+        }
+
+        for context in self.context.iter().rev() {
+            write!(f, "{}: ", context)?;
+        }
+        write!(f, "{}", self.error)?;
+
+        let mut source = self.error.source_error();
+        while let Some(error) = source {
+            write!(f, ": {}", error)?;
+            source = error.source_error();
+        }
+        Ok(())
+    }
+}
+
+impl Debug for RContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return f
+                .debug_struct("RContextError")
+                .field("context", &self.context)
+                .field("error", &self.error)
+                .finish();
+        }
+
+        let contexts = self.context.iter().map(|c| c.to_string()).rev();
+        let sources = std::iter::successors(self.error.source_error(), |e| e.source_error())
+            .map(|e| e.to_string());
+        let mut causes = contexts
+            .chain(std::iter::once(self.error.to_string()))
+            .chain(sources);
+
+        if let Some(first) = causes.next() {
+            f.write_str(&first)?;
+        }
+
+        let mut causes = causes.enumerate().peekable();
+        if causes.peek().is_some() {
+            f.write_str("\n\nCaused by:")?;
+        }
+        for (i, cause) in causes {
+            write!(f, "\n    {}: {}", i, cause)?;
+        }
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// The error that an `RContextError` with context is converted into by
+/// `RContextError::into_rboxerror`.
+struct ContextChain(RContextError);
+
+impl Display for ContextChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Debug for ContextChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl ErrorTrait for ContextChain {
+    fn source(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+        Some(&self.0.error)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Extension trait for adding context to the error of an `RResult`,
+/// or to the absence of a value in an `ROption`,
+/// converting them into an `RResult<T, RContextError>`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RContextError, RNone, ROption, RResult, RResultExt, RSome};
+///
+/// fn plugin_name(name: ROption<&str>) -> RResult<&str, RContextError> {
+///     name.context("the plugin has no name")
+/// }
+///
+/// assert_eq!(plugin_name(RSome("foo")).unwrap(), "foo");
+/// assert_eq!(
+///     plugin_name(RNone).unwrap_err().to_string(),
+///     "the plugin has no name",
+/// );
+///
+/// ```
+pub trait RResultExt<T> {
+    /// Wraps the error with a context message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RContextError, RErr, RResult, RResultExt};
+    ///
+    /// let res: RResult<u32, std::fmt::Error> = RErr(std::fmt::Error);
+    ///
+    /// let err = res.context("writing the report").unwrap_err();
+    /// assert_eq!(err.to_string(), "writing the report");
+    /// ```
+    fn context<C>(self, context: C) -> RResult<T, RContextError>
+    where
+        C: Display;
+
+    /// Wraps the error with a context message,
+    /// which is only constructed if there is an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RContextError, RErr, RResult, RResultExt};
+    ///
+    /// let res: RResult<u32, RContextError> = RErr(RContextError::msg("timed out"));
+    ///
+    /// let err = res.with_context(|| format!("connecting to {}", "localhost")).unwrap_err();
+    /// assert_eq!(format!("{:#}", err), "connecting to localhost: timed out");
+    /// ```
+    fn with_context<C, F>(self, f: F) -> RResult<T, RContextError>
+    where
+        C: Display,
+        F: FnOnce() -> C;
+}
+
+impl<T, E> RResultExt<T> for RResult<T, E>
+where
+    E: Into<RContextError>,
+{
+    fn context<C>(self, context: C) -> RResult<T, RContextError>
+    where
+        C: Display,
+    {
+        match self {
+            ROk(x) => ROk(x),
+            RErr(e) => RErr(e.into().context(context)),
+        }
+    }
+
+    fn with_context<C, F>(self, f: F) -> RResult<T, RContextError>
+    where
+        C: Display,
+        F: FnOnce() -> C,
+    {
+        match self {
+            ROk(x) => ROk(x),
+            RErr(e) => RErr(e.into().context(f())),
+        }
+    }
+}
+
+impl<T> RResultExt<T> for ROption<T> {
+    fn context<C>(self, context: C) -> RResult<T, RContextError>
+    where
+        C: Display,
+    {
+        match self {
+            RSome(x) => ROk(x),
+            RNone => RErr(RContextError::msg(context.to_string())),
+        }
+    }
+
+    fn with_context<C, F>(self, f: F) -> RResult<T, RContextError>
+    where
+        C: Display,
+        F: FnOnce() -> C,
+    {
+        match self {
+            RSome(x) => ROk(x),
+            RNone => RErr(RContextError::msg(f().to_string())),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use super::*;
+
+use std::num::ParseIntError;
+
+use crate::test_utils::Stringy;
+
+#[derive(Debug)]
+struct WithSource(ParseIntError);
+
+impl Display for WithSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid number")
+    }
+}
+
+impl ErrorTrait for WithSource {
+    fn source(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn parse_error() -> ParseIntError {
+    "".parse::<u32>().unwrap_err()
+}
+
+#[test]
+fn formatting() {
+    let parse_msg = parse_error().to_string();
+
+    let err = RContextError::new(WithSource(parse_error()));
+    assert_eq!(err.to_string(), "invalid number");
+    assert_eq!(
+        format!("{:#}", err),
+        format!("invalid number: {}", parse_msg)
+    );
+    assert_eq!(
+        format!("{:?}", err),
+        format!("invalid number\n\nCaused by:\n    0: {}", parse_msg),
+    );
+
+    let err = err
+        .context("parsing the count")
+        .context("loading the config");
+    assert_eq!(err.to_string(), "loading the config");
+    assert_eq!(
+        format!("{:#}", err),
+        format!(
+            "loading the config: parsing the count: invalid number: {}",
+            parse_msg
+        ),
+    );
+    assert_eq!(
+        format!("{:?}", err),
+        format!(
+            "loading the config\n\n\
+             Caused by:\n    \
+             0: parsing the count\n    \
+             1: invalid number\n    \
+             2: {}",
+            parse_msg
+        ),
+    );
+    assert!(format!("{:#?}", err).starts_with("RContextError {"));
+}
+
+#[test]
+fn root_cause() {
+    let err = RContextError::from(Stringy::new("hello")).context("foo");
+
+    assert_eq!(err.contexts(), ["foo"]);
+    assert_eq!(
+        err.root_cause().downcast_ref::<Stringy>().unwrap().str,
+        "hello"
+    );
+
+    let root = err.into_root_cause();
+    assert_eq!(root.downcast::<Stringy>().unwrap().str, "hello");
+}
+
+#[test]
+fn rboxerror_is_not_reboxed() {
+    let rbox = RBoxError::new(Stringy::new("hello"));
+    let address = rbox.heap_address();
+
+    let err = RContextError::from(rbox);
+    assert_eq!(err.root_cause().heap_address(), address);
+    assert_eq!(err.into_rboxerror().heap_address(), address);
+}
+
+#[test]
+fn into_rboxerror() {
+    let err = RContextError::msg("oh no").context("foo").context("bar");
+    let rbox = err.into_rboxerror();
+
+    assert_eq!(rbox.to_string(), "bar");
+    assert_eq!(format!("{:#}", rbox), "bar: foo: oh no");
+    assert_eq!(rbox.source_error().unwrap().to_string(), "oh no");
+}
+
+#[test]
+fn result_ext() {
+    let ok: RResult<u32, ParseIntError> = ROk(3);
+    assert_eq!(ok.context("unused").unwrap(), 3);
+
+    let ok: RResult<u32, ParseIntError> = ROk(5);
+    let res = ok.with_context(|| -> &str { panic!("the context isn't lazy") });
+    assert_eq!(res.unwrap(), 5);
+
+    let err: RResult<u32, ParseIntError> = RErr(parse_error());
+    let err = err.with_context(|| format!("parsing {}", "foo"));
+    let err = err.context("bar").unwrap_err();
+    assert_eq!(err.contexts(), ["parsing foo", "bar"]);
+    assert!(err.root_cause().downcast_ref::<ParseIntError>().is_some());
+
+    let none: ROption<u32> = RNone;
+    let err = none.context("missing value").unwrap_err();
+    assert_eq!(err.to_string(), "missing value");
+    assert!(err.contexts().is_empty());
+
+    assert_eq!(RSome(8).with_context(|| "unused").unwrap(), 8);
+}
+
+#[test]
+#[cfg(feature = "anyhow")]
+fn anyhow_conversions() {
+    let err = RContextError::new(Stringy::new("hello"))
+        .context("foo")
+        .context("bar");
+
+    let anyhow_err = anyhow::Error::from(err);
+    assert_eq!(format!("{:#}", anyhow_err), "bar: foo: hello");
+
+    let err = RContextError::from_anyhow(anyhow_err);
+    assert_eq!(err.contexts(), ["foo", "bar"]);
+    assert_eq!(
+        err.root_cause().downcast_ref::<Stringy>().unwrap().str,
+        "hello"
+    );
+
+    let anyhow_err = anyhow::Error::new(WithSource(parse_error())).context("baz");
+    let err = RContextError::from_anyhow(anyhow_err);
+    assert_eq!(err.contexts(), ["invalid number", "baz"]);
+    assert_eq!(err.root_cause().to_string(), parse_error().to_string());
+    assert_eq!(
+        format!("{:#}", err),
+        format!("baz: invalid number: {}", parse_error())
+    );
+}
+
+#[test]
+fn layout_checking() {
+    use crate::{abi_stability::abi_checking::check_layout_compatibility, StableAbi};
+
+    let layout = <RContextError as StableAbi>::LAYOUT;
+    assert!(check_layout_compatibility(layout, layout).is_ok());
+}
@@ -0,0 +1,250 @@
+//! Contains an ffi-safe wrapper for `std::fs::File`.
+
+use std::{
+    fmt::{self, Debug},
+    fs::File,
+    io::{self, Read, Seek, SeekFrom, Write},
+    mem::ManuallyDrop,
+};
+
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd as RawHandle};
+
+#[cfg(windows)]
+use std::os::windows::io::{AsRawHandle, FromRawHandle, IntoRawHandle, RawHandle};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests;
+
+/// Ffi-safe wrapper for a `std::fs::File`,
+/// which owns the file descriptor (or handle on Windows) of an open file.
+///
+/// This allows passing the ownership of an open file between dynamic libraries,
+/// closing it when the `RFile` is dropped.
+///
+/// This type is only available on unix and Windows.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RFile;
+///
+/// use std::{
+///     fs::File,
+///     io::{Read, Write},
+/// };
+///
+/// # let path = std::env::temp_dir().join("abi_stable_rfile_example.txt");
+/// let mut file = RFile::from(File::create(&path).unwrap());
+/// file.write_all(b"hello").unwrap();
+///
+/// let mut file: File = RFile::from(File::open(&path).unwrap()).into_file();
+///
+/// let mut text = String::new();
+/// file.read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "hello");
+///
+/// # drop(file);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RFile {
+    handle: RawHandle,
+}
+
+#[cfg(windows)]
+unsafe impl Send for RFile {}
+
+#[cfg(windows)]
+unsafe impl Sync for RFile {}
+
+impl RFile {
+    /// Constructs an `RFile`, taking ownership of the file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RFile;
+    ///
+    /// # let path = std::env::temp_dir().join("abi_stable_rfile_new.txt");
+    /// let file = RFile::new(std::fs::File::create(&path).unwrap());
+    ///
+    /// # drop(file);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn new(file: File) -> Self {
+        Self {
+            handle: into_raw(file),
+        }
+    }
+
+    /// Converts this back into a `std::fs::File`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RFile;
+    ///
+    /// use std::fs::File;
+    ///
+    /// # let path = std::env::temp_dir().join("abi_stable_rfile_into_file.txt");
+    /// let file: File = RFile::new(File::create(&path).unwrap()).into_file();
+    ///
+    /// # drop(file);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn into_file(self) -> File {
+        let this = ManuallyDrop::new(self);
+        unsafe { from_raw(this.handle) }
+    }
+
+    /// Gets the raw file descriptor (or handle on Windows) of the file,
+    /// without transferring ownership.
+    pub fn as_raw(&self) -> RawHandle {
+        self.handle
+    }
+
+    /// Calls `f` with a temporary `File` that borrows the file descriptor.
+    fn with_file<R>(&self, f: impl FnOnce(&mut File) -> R) -> R {
+        // The `File` is never dropped, so the file descriptor isn't closed here.
+        let mut file = ManuallyDrop::new(unsafe { from_raw(self.handle) });
+        f(&mut file)
+    }
+
+    /// Queries metadata about the file.
+    pub fn metadata(&self) -> io::Result<std::fs::Metadata> {
+        self.with_file(|f| f.metadata())
+    }
+
+    /// Attempts to sync all OS-internal data and metadata of the file to disk.
+    pub fn sync_all(&self) -> io::Result<()> {
+        self.with_file(|f| f.sync_all())
+    }
+
+    /// Truncates or extends the file to `size` bytes.
+    pub fn set_len(&self, size: u64) -> io::Result<()> {
+        self.with_file(|f| f.set_len(size))
+    }
+
+    /// Creates a new `RFile` that shares the same underlying file handle.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        self.with_file(|f| f.try_clone()).map(Self::new)
+    }
+}
+
+impl From<File> for RFile {
+    fn from(file: File) -> Self {
+        Self::new(file)
+    }
+}
+
+impl From<RFile> for File {
+    fn from(file: RFile) -> Self {
+        file.into_file()
+    }
+}
+
+impl Drop for RFile {
+    fn drop(&mut self) {
+        drop(unsafe { from_raw(self.handle) });
+    }
+}
+
+impl Debug for RFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with_file(|file| Debug::fmt(file, f))
+    }
+}
+
+impl Read for RFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.with_file(|f| f.read(buf))
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.with_file(|f| f.read_exact(buf))
+    }
+}
+
+impl Write for RFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.with_file(|f| f.write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.with_file(|f| f.flush())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.with_file(|f| f.write_all(buf))
+    }
+}
+
+impl Seek for RFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.with_file(|f| f.seek(pos))
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for RFile {
+    fn as_raw_fd(&self) -> RawHandle {
+        self.handle
+    }
+}
+
+#[cfg(unix)]
+impl IntoRawFd for RFile {
+    fn into_raw_fd(self) -> RawHandle {
+        ManuallyDrop::new(self).handle
+    }
+}
+
+#[cfg(unix)]
+impl FromRawFd for RFile {
+    unsafe fn from_raw_fd(handle: RawHandle) -> Self {
+        Self { handle }
+    }
+}
+
+#[cfg(windows)]
+impl AsRawHandle for RFile {
+    fn as_raw_handle(&self) -> RawHandle {
+        self.handle
+    }
+}
+
+#[cfg(windows)]
+impl IntoRawHandle for RFile {
+    fn into_raw_handle(self) -> RawHandle {
+        ManuallyDrop::new(self).handle
+    }
+}
+
+#[cfg(windows)]
+impl FromRawHandle for RFile {
+    unsafe fn from_raw_handle(handle: RawHandle) -> Self {
+        Self { handle }
+    }
+}
+
+#[cfg(unix)]
+fn into_raw(file: File) -> RawHandle {
+    file.into_raw_fd()
+}
+
+#[cfg(windows)]
+fn into_raw(file: File) -> RawHandle {
+    file.into_raw_handle()
+}
+
+#[cfg(unix)]
+unsafe fn from_raw(handle: RawHandle) -> File {
+    unsafe { File::from_raw_fd(handle) }
+}
+
+#[cfg(windows)]
+unsafe fn from_raw(handle: RawHandle) -> File {
+    unsafe { File::from_raw_handle(handle) }
+}
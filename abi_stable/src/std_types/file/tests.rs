@@ -0,0 +1,80 @@
+use super::*;
+
+struct TempPath(std::path::PathBuf);
+
+impl TempPath {
+    fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("abi_stable_rfile_{}_{}", name, std::process::id()));
+        Self(path)
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn read_write_seek() {
+    let path = TempPath::new("read_write_seek");
+
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path.0)
+        .unwrap();
+
+    let mut file = RFile::new(file);
+    file.write_all(b"hello world").unwrap();
+    file.flush().unwrap();
+    assert_eq!(file.metadata().unwrap().len(), 11);
+
+    file.seek(SeekFrom::Start(6)).unwrap();
+    let mut text = String::new();
+    file.read_to_string(&mut text).unwrap();
+    assert_eq!(text, "world");
+
+    // The clone shares the cursor with the original.
+    let mut clone = file.try_clone().unwrap();
+    clone.seek(SeekFrom::Start(0)).unwrap();
+    let mut buffer = [0; 5];
+    file.read_exact(&mut buffer).unwrap();
+    assert_eq!(&buffer, b"hello");
+
+    file.set_len(5).unwrap();
+    let mut std_file = file.into_file();
+    std_file.seek(SeekFrom::Start(0)).unwrap();
+    text.clear();
+    std_file.read_to_string(&mut text).unwrap();
+    assert_eq!(text, "hello");
+}
+
+#[test]
+#[cfg(unix)]
+fn raw_fd_roundtrip() {
+    let path = TempPath::new("raw_fd_roundtrip");
+
+    let file = RFile::new(std::fs::File::create(&path.0).unwrap());
+    let fd = file.as_raw_fd();
+    assert_eq!(file.as_raw(), fd);
+
+    let raw = file.into_raw_fd();
+    assert_eq!(raw, fd);
+
+    let mut file = unsafe { RFile::from_raw_fd(raw) };
+    file.write_all(b"foo").unwrap();
+    drop(file);
+
+    assert_eq!(std::fs::read(&path.0).unwrap(), b"foo");
+}
+
+#[test]
+fn is_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<RFile>();
+}
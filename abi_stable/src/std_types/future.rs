@@ -0,0 +1,510 @@
+//! Contains ffi-safe equivalents of `Pin<Box<dyn Future>>`, `Waker`, `Context`, and `Poll`.
+//!
+//! These allow a dynamic library to return futures that are driven by
+//! the executor of the program that loaded it (or vice versa),
+//! since the waker of the executor is passed through an ffi-safe vtable.
+
+use std::{
+    fmt::{self, Debug},
+    future::Future,
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    pin::Pin,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use crate::{
+    marker_type::{UnsyncSend, UnsyncUnsend},
+    pointer_trait::{AsMutPtr, AsPtr, TransmuteElement},
+    prefix_type::WithMetadata,
+    sabi_types::{RMut, RRef},
+    std_types::RBox,
+};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests;
+
+/// Ffi-safe equivalent of `std::task::Poll`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RPoll;
+///
+/// use std::task::Poll;
+///
+/// assert_eq!(RPoll::from(Poll::Ready(3)), RPoll::Ready(3));
+/// assert_eq!(Poll::<u32>::from(RPoll::Pending), Poll::Pending);
+///
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[repr(u8)]
+#[derive(StableAbi)]
+pub enum RPoll<T> {
+    /// The future finished, with this value.
+    Ready(T),
+    /// The future isn't finished yet.
+    Pending,
+}
+
+impl<T> RPoll<T> {
+    /// Whether this is a `Ready`.
+    pub const fn is_ready(&self) -> bool {
+        matches!(self, RPoll::Ready { .. })
+    }
+
+    /// Whether this is a `Pending`.
+    pub const fn is_pending(&self) -> bool {
+        matches!(self, RPoll::Pending)
+    }
+
+    /// Converts this to a `std::task::Poll`.
+    pub fn into_poll(self) -> Poll<T> {
+        match self {
+            RPoll::Ready(x) => Poll::Ready(x),
+            RPoll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T> From<Poll<T>> for RPoll<T> {
+    fn from(poll: Poll<T>) -> Self {
+        match poll {
+            Poll::Ready(x) => RPoll::Ready(x),
+            Poll::Pending => RPoll::Pending,
+        }
+    }
+}
+
+impl<T> From<RPoll<T>> for Poll<T> {
+    fn from(poll: RPoll<T>) -> Self {
+        poll.into_poll()
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `Pin<Box<dyn Future<Output = T> + Send + 'a>>`.
+///
+/// `RFuture` implements `Future`, so it can be awaited and driven by any executor,
+/// regardless of which side of the ffi boundary constructed it.
+///
+/// This type is `Send`, but not `Sync`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::{
+///     sabi_extern_fn,
+///     std_types::{RFuture, RString},
+/// };
+///
+/// #[sabi_extern_fn]
+/// fn greet(name: RString) -> RFuture<'static, RString> {
+///     RFuture::new(async move { format!("hello {}", name).into() })
+/// }
+///
+/// # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+/// #     use std::{sync::Arc, task::{Context, Poll, Wake}};
+/// #     struct Unpark(std::thread::Thread);
+/// #     impl Wake for Unpark {
+/// #         fn wake(self: Arc<Self>) { self.0.unpark() }
+/// #     }
+/// #     let waker = Arc::new(Unpark(std::thread::current())).into();
+/// #     let mut cx = Context::from_waker(&waker);
+/// #     let mut fut = Box::pin(fut);
+/// #     loop {
+/// #         match fut.as_mut().poll(&mut cx) {
+/// #             Poll::Ready(x) => return x,
+/// #             Poll::Pending => std::thread::park(),
+/// #         }
+/// #     }
+/// # }
+/// let greeting = block_on(async { greet("world".into()).await });
+///
+/// assert_eq!(greeting, "hello world");
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RFuture<'a, T> {
+    ptr: RBox<()>,
+    vtable: FutureVtable_Ref<T>,
+    _marker: PhantomData<(&'a (), UnsyncSend)>,
+}
+
+impl<'a, T> RFuture<'a, T> {
+    /// Constructs an `RFuture` from a future, boxing it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RFuture;
+    ///
+    /// let fut: RFuture<'_, u32> = RFuture::new(async { 3 + 5 });
+    ///
+    /// ```
+    pub fn new<F>(future: F) -> Self
+    where
+        F: Future<Output = T> + Send + 'a,
+    {
+        unsafe {
+            Self {
+                ptr: RBox::new(future).transmute_element::<()>(),
+                vtable: VTableGetter::<F>::FUTURE_VTABLE,
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    /// Polls the future with an `RContext`.
+    ///
+    /// This is what the `Future` impl of `RFuture` uses,
+    /// after converting the `Context` to an `RContext`.
+    pub fn poll_with(&mut self, cx: RContext<'_>) -> RPoll<T> {
+        // The future is never moved out of the box, so it's pinned.
+        unsafe { self.vtable.poll()(self.ptr.as_rmut(), cx) }
+    }
+}
+
+impl<'a, T> Future for RFuture<'a, T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let waker = cx.waker();
+        self.get_mut()
+            .poll_with(RContext::from_waker(waker))
+            .into_poll()
+    }
+}
+
+impl<'a, T> Debug for RFuture<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RFuture{..}")
+    }
+}
+
+#[derive(StableAbi)]
+#[repr(C)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+pub(crate) struct FutureVtable<T> {
+    #[sabi(last_prefix_field)]
+    poll: unsafe extern "C" fn(RMut<'_, ()>, RContext<'_>) -> RPoll<T>,
+}
+
+struct VTableGetter<F>(PhantomData<F>);
+
+impl<F> VTableGetter<F>
+where
+    F: Future,
+{
+    staticref! {
+        const WM_FUTURE: WithMetadata<FutureVtable<F::Output>> = WithMetadata::new(
+            FutureVtable {
+                poll: poll_future::<F>,
+            }
+        )
+    }
+
+    const FUTURE_VTABLE: FutureVtable_Ref<F::Output> =
+        FutureVtable_Ref(Self::WM_FUTURE.as_prefix());
+}
+
+unsafe extern "C" fn poll_future<F>(this: RMut<'_, ()>, cx: RContext<'_>) -> RPoll<F::Output>
+where
+    F: Future,
+{
+    extern_fn_panic_handling! {no_early_return;
+        let future = unsafe { Pin::new_unchecked(this.transmute_into_mut::<F>()) };
+        cx.with_context(|cx| future.poll(cx).into())
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `std::task::Waker`.
+///
+/// An `RWaker` can be converted to and from a `Waker` with
+/// [`RWaker::from_waker`] and [`RWaker::into_waker`],
+/// waking the `RWaker` wakes the task of the `Waker` it was constructed from.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RWaker;
+///
+/// use std::{
+///     sync::{
+///         atomic::{AtomicUsize, Ordering},
+///         Arc,
+///     },
+///     task::{Wake, Waker},
+/// };
+///
+/// struct Counter(AtomicUsize);
+///
+/// impl Wake for Counter {
+///     fn wake(self: Arc<Self>) {
+///         self.0.fetch_add(1, Ordering::SeqCst);
+///     }
+/// }
+///
+/// let counter = Arc::new(Counter(AtomicUsize::new(0)));
+/// let waker = Waker::from(counter.clone());
+///
+/// let rwaker = RWaker::from_waker(&waker);
+/// rwaker.wake_by_ref();
+/// rwaker.clone().wake();
+///
+/// // Going through a `Waker` again.
+/// rwaker.into_waker().wake();
+///
+/// assert_eq!(counter.0.load(Ordering::SeqCst), 3);
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RWaker {
+    ptr: RBox<()>,
+    vtable: WakerVtable_Ref,
+}
+
+/// Ffi-safe equivalent of `&mut std::task::Context<'_>`,
+/// passed to the poll function of [`RFuture`].
+///
+/// This borrows the waker of the `Context`,
+/// use [`RContext::waker`] to get an owned [`RWaker`].
+///
+/// This type is neither `Send` nor `Sync`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RContext, RPoll};
+///
+/// use std::{
+///     future::Future,
+///     pin::Pin,
+///     sync::Arc,
+///     task::{Poll, Wake, Waker},
+/// };
+///
+/// struct Noop;
+///
+/// impl Wake for Noop {
+///     fn wake(self: Arc<Self>) {}
+/// }
+///
+/// let waker = Waker::from(Arc::new(Noop));
+/// let cx = RContext::from_waker(&waker);
+///
+/// let poll = cx.with_context(|cx| Pin::new(&mut std::future::ready(10)).poll(cx));
+/// assert_eq!(poll, Poll::Ready(10));
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RContext<'a> {
+    waker: RRef<'a, ()>,
+    vtable: WakerVtable_Ref,
+    _marker: PhantomData<UnsyncUnsend>,
+}
+
+unsafe impl Send for RWaker {}
+unsafe impl Sync for RWaker {}
+
+impl RWaker {
+    /// Constructs an `RWaker` from a clone of `waker`.
+    pub fn from_waker(waker: &Waker) -> Self {
+        unsafe {
+            Self {
+                ptr: RBox::new(waker.clone()).transmute_element::<()>(),
+                vtable: WAKER_VTABLE,
+            }
+        }
+    }
+
+    /// Converts this `RWaker` into a `Waker`.
+    pub fn into_waker(self) -> Waker {
+        unsafe { Waker::from_raw(owned_raw_waker(self)) }
+    }
+
+    /// Wakes up the task associated with this waker.
+    pub fn wake(self) {
+        let this = ManuallyDrop::new(self);
+        unsafe {
+            let ptr = std::ptr::read(&this.ptr);
+            this.vtable.wake()(ptr)
+        }
+    }
+
+    /// Wakes up the task associated with this waker, without consuming it.
+    pub fn wake_by_ref(&self) {
+        unsafe { self.vtable.wake_by_ref()(self.ptr.as_rref()) }
+    }
+}
+
+impl Clone for RWaker {
+    fn clone(&self) -> Self {
+        unsafe { self.vtable.clone_waker()(self.ptr.as_rref()) }
+    }
+}
+
+impl Debug for RWaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RWaker{..}")
+    }
+}
+
+impl From<Waker> for RWaker {
+    fn from(waker: Waker) -> Self {
+        unsafe {
+            Self {
+                ptr: RBox::new(waker).transmute_element::<()>(),
+                vtable: WAKER_VTABLE,
+            }
+        }
+    }
+}
+
+impl From<RWaker> for Waker {
+    fn from(waker: RWaker) -> Self {
+        waker.into_waker()
+    }
+}
+
+impl<'a> RContext<'a> {
+    /// Constructs an `RContext` that borrows `waker`.
+    pub fn from_waker(waker: &'a Waker) -> Self {
+        Self {
+            waker: unsafe { RRef::new(waker).transmute::<()>() },
+            vtable: WAKER_VTABLE,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gets a clone of the waker of this context.
+    pub fn waker(&self) -> RWaker {
+        unsafe { self.vtable.clone_waker()(self.waker) }
+    }
+
+    /// Calls `f` with a `Context` whose waker wakes the waker of this `RContext`.
+    pub fn with_context<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Context<'_>) -> R,
+    {
+        // The `Waker` only borrows `self`, which is why it must not be dropped.
+        let waker = ManuallyDrop::new(unsafe {
+            Waker::from_raw(RawWaker::new(
+                self as *const Self as *const (),
+                &BORROWED_RAW_WAKER_VTABLE,
+            ))
+        });
+        f(&mut Context::from_waker(&waker))
+    }
+}
+
+impl<'a> Debug for RContext<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RContext{..}")
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+// The vtable for `RBox<Waker>`s and `RRef<Waker>`s erased into `()`.
+#[derive(StableAbi)]
+#[repr(C)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+pub(crate) struct WakerVtable {
+    clone_waker: unsafe extern "C" fn(RRef<'_, ()>) -> RWaker,
+    wake: unsafe extern "C" fn(RBox<()>),
+    #[sabi(last_prefix_field)]
+    wake_by_ref: unsafe extern "C" fn(RRef<'_, ()>),
+}
+
+// The vtable for wakers in this executable/library
+const WAKER_VTABLE: WakerVtable_Ref = {
+    const S: &WithMetadata<WakerVtable> = &WithMetadata::new(WakerVtable {
+        clone_waker,
+        wake,
+        wake_by_ref,
+    });
+
+    WakerVtable_Ref(S.static_as_prefix())
+};
+
+unsafe extern "C" fn clone_waker(this: RRef<'_, ()>) -> RWaker {
+    extern_fn_panic_handling! {no_early_return;
+        RWaker::from_waker(unsafe { this.transmute_into_ref::<Waker>() })
+    }
+}
+
+unsafe extern "C" fn wake(this: RBox<()>) {
+    extern_fn_panic_handling! {no_early_return;
+        RBox::into_inner(unsafe { this.transmute_element::<Waker>() }).wake();
+    }
+}
+
+unsafe extern "C" fn wake_by_ref(this: RRef<'_, ()>) {
+    extern_fn_panic_handling! {no_early_return;
+        unsafe { this.transmute_into_ref::<Waker>() }.wake_by_ref();
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+// `RawWaker`s whose data pointer is a `Box<RWaker>`.
+
+fn owned_raw_waker(waker: RWaker) -> RawWaker {
+    RawWaker::new(
+        Box::into_raw(Box::new(waker)) as *const (),
+        &OWNED_RAW_WAKER_VTABLE,
+    )
+}
+
+static OWNED_RAW_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(
+    owned_raw_clone,
+    owned_raw_wake,
+    owned_raw_wake_by_ref,
+    owned_raw_drop,
+);
+
+unsafe fn owned_raw_clone(data: *const ()) -> RawWaker {
+    owned_raw_waker(unsafe { (*(data as *const RWaker)).clone() })
+}
+
+unsafe fn owned_raw_wake(data: *const ()) {
+    unsafe { Box::from_raw(data as *mut RWaker) }.wake()
+}
+
+unsafe fn owned_raw_wake_by_ref(data: *const ()) {
+    unsafe { (*(data as *const RWaker)).wake_by_ref() }
+}
+
+unsafe fn owned_raw_drop(data: *const ()) {
+    drop(unsafe { Box::from_raw(data as *mut RWaker) })
+}
+
+// `RawWaker`s whose data pointer is an `RContext`,
+// only used in `RContext::with_context`, where the `Waker` is never dropped.
+
+static BORROWED_RAW_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(
+    borrowed_raw_clone,
+    borrowed_raw_wake_by_ref,
+    borrowed_raw_wake_by_ref,
+    borrowed_raw_drop,
+);
+
+unsafe fn borrowed_raw_clone(data: *const ()) -> RawWaker {
+    owned_raw_waker(unsafe { (*(data as *const RContext<'_>)).waker() })
+}
+
+unsafe fn borrowed_raw_wake_by_ref(data: *const ()) {
+    let cx = unsafe { &*(data as *const RContext<'_>) };
+    unsafe { cx.vtable.wake_by_ref()(cx.waker) }
+}
+
+unsafe fn borrowed_raw_drop(_: *const ()) {}
@@ -0,0 +1,149 @@
+use super::*;
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::Wake,
+    thread,
+};
+
+use crate::{
+    std_types::{RArc, RString},
+    test_utils::block_on,
+};
+
+struct ThreadWaker {
+    thread: thread::Thread,
+    wakes: AtomicUsize,
+}
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wakes.fetch_add(1, Ordering::SeqCst);
+        self.thread.unpark();
+    }
+}
+
+fn thread_waker() -> Arc<ThreadWaker> {
+    Arc::new(ThreadWaker {
+        thread: thread::current(),
+        wakes: AtomicUsize::new(0),
+    })
+}
+
+/// A future that returns `Pending` `remaining` times,
+/// waking the task from another thread each time.
+struct YieldTimes {
+    remaining: u32,
+}
+
+impl Future for YieldTimes {
+    type Output = u32;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+        if self.remaining == 0 {
+            Poll::Ready(100)
+        } else {
+            self.remaining -= 1;
+            let waker = cx.waker().clone();
+            thread::spawn(move || waker.wake());
+            Poll::Pending
+        }
+    }
+}
+
+#[test]
+fn ready_future() {
+    let fut: RFuture<'_, RString> = RFuture::new(async { RString::from("hello") });
+    assert_eq!(block_on(fut), "hello");
+}
+
+#[test]
+fn pending_future() {
+    let fut = RFuture::new(YieldTimes { remaining: 3 });
+    assert_eq!(block_on(fut), 100);
+
+    let nested = RFuture::new(async {
+        let a = RFuture::new(YieldTimes { remaining: 2 }).await;
+        let b = RFuture::new(async { 5 }).await;
+        a + b
+    });
+    assert_eq!(block_on(nested), 105);
+}
+
+#[test]
+fn poll_with_rcontext() {
+    let state = thread_waker();
+    let waker = Waker::from(state.clone());
+
+    let mut fut = RFuture::new(YieldTimes { remaining: 1 });
+
+    assert_eq!(fut.poll_with(RContext::from_waker(&waker)), RPoll::Pending);
+    while state.wakes.load(Ordering::SeqCst) == 0 {
+        thread::park();
+    }
+    assert_eq!(
+        fut.poll_with(RContext::from_waker(&waker)),
+        RPoll::Ready(100)
+    );
+}
+
+#[test]
+fn waker_conversions() {
+    let state = thread_waker();
+    let waker = Waker::from(state.clone());
+    let wakes = || state.wakes.load(Ordering::SeqCst);
+
+    let rwaker = RWaker::from_waker(&waker);
+    rwaker.wake_by_ref();
+    assert_eq!(wakes(), 1);
+
+    rwaker.clone().wake();
+    assert_eq!(wakes(), 2);
+
+    let std_waker = rwaker.clone().into_waker();
+    std_waker.wake_by_ref();
+    std_waker.clone().wake();
+    assert_eq!(wakes(), 4);
+
+    let cx = RContext::from_waker(&waker);
+    cx.waker().wake();
+    cx.with_context(|cx| {
+        cx.waker().wake_by_ref();
+        cx.waker().clone().wake();
+    });
+    assert_eq!(wakes(), 7);
+
+    drop((rwaker, std_waker));
+    // Only the `waker` and `state` variables hold references.
+    assert_eq!(Arc::strong_count(&state), 2);
+}
+
+#[test]
+fn drops_future() {
+    let arc = RArc::new(0);
+    let fut = RFuture::new({
+        let arc = arc.clone();
+        async move { *arc }
+    });
+    assert_eq!(RArc::strong_count(&arc), 2);
+    drop(fut);
+    assert_eq!(RArc::strong_count(&arc), 1);
+}
+
+#[test]
+fn layout_checking() {
+    use crate::{abi_stability::abi_checking::check_layout_compatibility, StableAbi};
+
+    let lay_u32 = <RFuture<'_, u32> as StableAbi>::LAYOUT;
+    let lay_u64 = <RFuture<'_, u64> as StableAbi>::LAYOUT;
+
+    assert!(check_layout_compatibility(lay_u32, lay_u32).is_ok());
+    assert!(check_layout_compatibility(lay_u32, lay_u64).is_err());
+}
@@ -0,0 +1,252 @@
+//! Contains ffi-safe trait objects for the `std::io` traits.
+
+use std::{
+    fmt::{self, Debug},
+    io::{self, BufRead, Read, Seek, SeekFrom, Write},
+};
+
+use crate::{
+    erased_types::{
+        interfaces::{
+            IoBufReadInterface, IoReadInterface, IoReadSeekInterface, IoWriteSendInterface,
+        },
+        DynTrait,
+    },
+    std_types::RBox,
+};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests;
+
+macro_rules! declare_io_object {
+    (
+        $(#[$attr:meta])*
+        struct $name:ident = $interface:ident;
+        bounds($($bounds:tt)*)
+        example($($example:tt)*)
+    ) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        #[derive(StableAbi)]
+        pub struct $name<'a> {
+            inner: DynTrait<'a, RBox<()>, $interface>,
+        }
+
+        impl<'a> $name<'a> {
+            #[doc = concat!("Constructs a `", stringify!($name), "`, boxing `value`.")]
+            ///
+            /// # Example
+            ///
+            /// ```
+            $($example)*
+            /// ```
+            pub fn new<T>(value: T) -> Self
+            where
+                T: $($bounds)* + Send + 'a,
+            {
+                Self {
+                    inner: DynTrait::from_borrowing_value(value),
+                }
+            }
+
+            /// Converts this into the `DynTrait` it wraps.
+            pub fn into_dyn_trait(self) -> DynTrait<'a, RBox<()>, $interface> {
+                self.inner
+            }
+        }
+
+        impl<'a> From<DynTrait<'a, RBox<()>, $interface>> for $name<'a> {
+            fn from(inner: DynTrait<'a, RBox<()>, $interface>) -> Self {
+                Self { inner }
+            }
+        }
+
+        impl<'a> Debug for $name<'a> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(concat!(stringify!($name), "{..}"))
+            }
+        }
+    };
+}
+
+macro_rules! impl_read {
+    ($($name:ident),*) => {$(
+        impl<'a> Read for $name<'a> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.inner.read(buf)
+            }
+
+            fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+                self.inner.read_exact(buf)
+            }
+        }
+    )*};
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+declare_io_object! {
+    /// Ffi-safe equivalent of `Box<dyn std::io::Read + Send + 'a>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBoxRead, RString};
+    ///
+    /// use std::io::Read;
+    ///
+    /// fn read_all(mut reader: RBoxRead<'_>) -> RString {
+    ///     let mut buffer = String::new();
+    ///     reader.read_to_string(&mut buffer).unwrap();
+    ///     buffer.into()
+    /// }
+    ///
+    /// assert_eq!(read_all(RBoxRead::new(&b"hello"[..])), "hello");
+    ///
+    /// ```
+    struct RBoxRead = IoReadInterface;
+    bounds(Read)
+    example(
+        /// use abi_stable::std_types::RBoxRead;
+        ///
+        /// use std::io::Read;
+        ///
+        /// let mut reader = RBoxRead::new(&[3, 5, 8][..]);
+        ///
+        /// let mut buffer = [0; 2];
+        /// reader.read_exact(&mut buffer).unwrap();
+        /// assert_eq!(buffer, [3, 5]);
+        ///
+    )
+}
+
+impl_read! {RBoxRead}
+
+///////////////////////////////////////////////////////////////////////////////
+
+declare_io_object! {
+    /// Ffi-safe equivalent of `Box<dyn std::io::Read + std::io::Seek + Send + 'a>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxReadSeek;
+    ///
+    /// use std::io::{Cursor, Read, Seek, SeekFrom};
+    ///
+    /// let mut reader = RBoxReadSeek::new(Cursor::new(vec![3, 5, 8, 13]));
+    ///
+    /// reader.seek(SeekFrom::End(-2)).unwrap();
+    ///
+    /// let mut buffer = Vec::new();
+    /// reader.read_to_end(&mut buffer).unwrap();
+    /// assert_eq!(buffer, [8, 13]);
+    ///
+    /// ```
+    struct RBoxReadSeek = IoReadSeekInterface;
+    bounds(Read + Seek)
+    example(
+        /// use abi_stable::std_types::RBoxReadSeek;
+        ///
+        /// use std::io::Cursor;
+        ///
+        /// let reader = RBoxReadSeek::new(Cursor::new(vec![3, 5, 8, 13]));
+        ///
+    )
+}
+
+impl_read! {RBoxReadSeek}
+
+impl<'a> Seek for RBoxReadSeek<'a> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+declare_io_object! {
+    /// Ffi-safe equivalent of `Box<dyn std::io::BufRead + Send + 'a>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxBufRead;
+    ///
+    /// use std::io::BufRead;
+    ///
+    /// let reader = RBoxBufRead::new(&b"foo\nbar\nbaz"[..]);
+    ///
+    /// let lines = reader.lines().collect::<Result<Vec<String>, _>>().unwrap();
+    /// assert_eq!(lines, ["foo", "bar", "baz"]);
+    ///
+    /// ```
+    struct RBoxBufRead = IoBufReadInterface;
+    bounds(BufRead)
+    example(
+        /// use abi_stable::std_types::RBoxBufRead;
+        ///
+        /// use std::io::{BufReader, Cursor};
+        ///
+        /// let reader = RBoxBufRead::new(BufReader::new(Cursor::new(vec![3, 5, 8])));
+        ///
+    )
+}
+
+impl_read! {RBoxBufRead}
+
+impl<'a> BufRead for RBoxBufRead<'a> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+declare_io_object! {
+    /// Ffi-safe equivalent of `Box<dyn std::io::Write + Send + 'a>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxWrite;
+    ///
+    /// use std::io::Write;
+    ///
+    /// fn greet(mut writer: RBoxWrite<'_>) {
+    ///     writeln!(writer, "Hello, world!").unwrap();
+    /// }
+    ///
+    /// let mut buffer = Vec::<u8>::new();
+    /// greet(RBoxWrite::new(&mut buffer));
+    ///
+    /// assert_eq!(buffer, b"Hello, world!\n");
+    ///
+    /// ```
+    struct RBoxWrite = IoWriteSendInterface;
+    bounds(Write)
+    example(
+        /// use abi_stable::std_types::RBoxWrite;
+        ///
+        /// let writer = RBoxWrite::new(std::io::sink());
+        ///
+    )
+}
+
+impl<'a> Write for RBoxWrite<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.inner.write_all(buf)
+    }
+}
@@ -0,0 +1,93 @@
+use super::*;
+
+use std::io::Cursor;
+
+use crate::std_types::RVec;
+
+#[test]
+fn read() {
+    let mut reader = RBoxRead::new(&b"hello world"[..]);
+
+    let mut buffer = [0; 6];
+    reader.read_exact(&mut buffer).unwrap();
+    assert_eq!(&buffer, b"hello ");
+
+    let mut text = String::new();
+    reader.read_to_string(&mut text).unwrap();
+    assert_eq!(text, "world");
+
+    let mut buffer = [0; 1];
+    assert_eq!(
+        reader.read_exact(&mut buffer).unwrap_err().kind(),
+        io::ErrorKind::UnexpectedEof,
+    );
+}
+
+#[test]
+fn read_seek() {
+    let mut reader = RBoxReadSeek::new(Cursor::new(vec![0u8, 1, 2, 3, 4, 5]));
+
+    assert_eq!(reader.seek(SeekFrom::Start(4)).unwrap(), 4);
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer).unwrap();
+    assert_eq!(buffer, [4, 5]);
+
+    assert_eq!(reader.seek(SeekFrom::Current(-3)).unwrap(), 3);
+    let mut buffer = [0; 2];
+    reader.read_exact(&mut buffer).unwrap();
+    assert_eq!(buffer, [3, 4]);
+}
+
+#[test]
+fn buf_read() {
+    let mut reader = RBoxBufRead::new(&b"foo\nbar\nbaz"[..]);
+
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line, "foo\n");
+
+    assert_eq!(reader.fill_buf().unwrap(), b"bar\nbaz");
+    reader.consume(4);
+
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "baz");
+}
+
+#[test]
+fn write() {
+    let mut buffer = RVec::<u8>::new();
+    {
+        let mut writer = RBoxWrite::new(&mut buffer);
+        write!(writer, "{}-{}", 3, 5).unwrap();
+        writer.write_all(b"!").unwrap();
+        writer.flush().unwrap();
+    }
+    assert_eq!(&buffer[..], b"3-5!");
+}
+
+#[test]
+fn dyn_trait_conversions() {
+    let writer = RBoxWrite::new(Vec::<u8>::new());
+    let mut writer = RBoxWrite::from(writer.into_dyn_trait());
+    writer.write_all(b"hello").unwrap();
+
+    let reader = RBoxRead::new(&b"hi"[..]);
+    let mut reader = RBoxRead::from(reader.into_dyn_trait());
+    let mut text = String::new();
+    reader.read_to_string(&mut text).unwrap();
+    assert_eq!(text, "hi");
+}
+
+#[test]
+fn layout_checking() {
+    use crate::{abi_stability::abi_checking::check_layout_compatibility, StableAbi};
+
+    let read = <RBoxRead<'_> as StableAbi>::LAYOUT;
+    let read_seek = <RBoxReadSeek<'_> as StableAbi>::LAYOUT;
+    let write = <RBoxWrite<'_> as StableAbi>::LAYOUT;
+
+    assert!(check_layout_compatibility(read, read).is_ok());
+    assert!(check_layout_compatibility(read, read_seek).is_err());
+    assert!(check_layout_compatibility(read, write).is_err());
+}
@@ -0,0 +1,698 @@
+//! Contains ffi-safe equivalents of the address types in `std::net`.
+
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    net::{AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+/// Implements `Debug`, `Display`, `FromStr` and serde traits by delegating to the
+/// `std::net` equivalent, so that the textual and serialized representations are the same.
+///
+/// Note that the serde impls of `std::net::SocketAddrV6` don't preserve
+/// its `flowinfo` and `scope_id`.
+macro_rules! delegate_to_std {
+    ($($new_type:ident => $std_type:ident,)*) => {
+        $(
+            impl Debug for $new_type {
+                fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                    Debug::fmt(&$std_type::from(*self), f)
+                }
+            }
+
+            impl Display for $new_type {
+                fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                    Display::fmt(&$std_type::from(*self), f)
+                }
+            }
+
+            impl FromStr for $new_type {
+                type Err = AddrParseError;
+
+                fn from_str(s: &str) -> Result<Self, AddrParseError> {
+                    s.parse::<$std_type>().map(Self::from)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $new_type {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    $std_type::deserialize(deserializer).map(Self::from)
+                }
+            }
+
+            impl Serialize for $new_type {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    $std_type::from(*self).serialize(serializer)
+                }
+            }
+        )*
+    };
+}
+
+delegate_to_std! {
+    RIpv4Addr => Ipv4Addr,
+    RIpv6Addr => Ipv6Addr,
+    RIpAddr => IpAddr,
+    RSocketAddrV4 => SocketAddrV4,
+    RSocketAddrV6 => SocketAddrV6,
+    RSocketAddr => SocketAddr,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `std::net::Ipv4Addr`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RIpv4Addr;
+///
+/// use std::net::Ipv4Addr;
+///
+/// let addr: RIpv4Addr = "192.168.0.1".parse().unwrap();
+///
+/// assert_eq!(addr, RIpv4Addr::new(192, 168, 0, 1));
+/// assert_eq!(addr.octets(), [192, 168, 0, 1]);
+/// assert_eq!(Ipv4Addr::from(addr), Ipv4Addr::new(192, 168, 0, 1));
+/// assert_eq!(addr.to_string(), "192.168.0.1");
+///
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, StableAbi)]
+#[repr(C)]
+pub struct RIpv4Addr {
+    octets: [u8; 4],
+}
+
+impl RIpv4Addr {
+    /// The address of the loopback interface, `127.0.0.1`.
+    pub const LOCALHOST: Self = Self::new(127, 0, 0, 1);
+
+    /// The unspecified address, `0.0.0.0`.
+    pub const UNSPECIFIED: Self = Self::new(0, 0, 0, 0);
+
+    /// The broadcast address, `255.255.255.255`.
+    pub const BROADCAST: Self = Self::new(255, 255, 255, 255);
+
+    /// Constructs an `RIpv4Addr` from its four octets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIpv4Addr;
+    ///
+    /// assert_eq!(RIpv4Addr::new(127, 0, 0, 1), RIpv4Addr::LOCALHOST);
+    ///
+    /// ```
+    pub const fn new(a: u8, b: u8, c: u8, d: u8) -> Self {
+        Self {
+            octets: [a, b, c, d],
+        }
+    }
+
+    /// Returns the four octets of this address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIpv4Addr;
+    ///
+    /// assert_eq!(RIpv4Addr::new(10, 0, 3, 4).octets(), [10, 0, 3, 4]);
+    ///
+    /// ```
+    pub const fn octets(&self) -> [u8; 4] {
+        self.octets
+    }
+}
+
+impl From<[u8; 4]> for RIpv4Addr {
+    fn from(octets: [u8; 4]) -> Self {
+        Self { octets }
+    }
+}
+
+impl From<RIpv4Addr> for [u8; 4] {
+    fn from(addr: RIpv4Addr) -> Self {
+        addr.octets
+    }
+}
+
+impl_from_rust_repr! {
+    impl From<Ipv4Addr> for RIpv4Addr {
+        fn(this){
+            RIpv4Addr { octets: this.octets() }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<Ipv4Addr> for RIpv4Addr {
+        fn(this){
+            Ipv4Addr::from(this.octets)
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `std::net::Ipv6Addr`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RIpv6Addr;
+///
+/// use std::net::Ipv6Addr;
+///
+/// let addr: RIpv6Addr = "2001:db8::1".parse().unwrap();
+///
+/// assert_eq!(addr, RIpv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+/// assert_eq!(addr.segments(), [0x2001, 0xdb8, 0, 0, 0, 0, 0, 1]);
+/// assert_eq!(Ipv6Addr::from(addr), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+/// assert_eq!(addr.to_string(), "2001:db8::1");
+///
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, StableAbi)]
+#[repr(C)]
+pub struct RIpv6Addr {
+    octets: [u8; 16],
+}
+
+impl RIpv6Addr {
+    /// The address of the loopback interface, `::1`.
+    pub const LOCALHOST: Self = Self::new(0, 0, 0, 0, 0, 0, 0, 1);
+
+    /// The unspecified address, `::`.
+    pub const UNSPECIFIED: Self = Self::new(0, 0, 0, 0, 0, 0, 0, 0);
+
+    /// Constructs an `RIpv6Addr` from its eight 16-bit segments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIpv6Addr;
+    ///
+    /// assert_eq!(RIpv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1), RIpv6Addr::LOCALHOST);
+    ///
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(a: u16, b: u16, c: u16, d: u16, e: u16, f: u16, g: u16, h: u16) -> Self {
+        let [a0, a1] = a.to_be_bytes();
+        let [b0, b1] = b.to_be_bytes();
+        let [c0, c1] = c.to_be_bytes();
+        let [d0, d1] = d.to_be_bytes();
+        let [e0, e1] = e.to_be_bytes();
+        let [f0, f1] = f.to_be_bytes();
+        let [g0, g1] = g.to_be_bytes();
+        let [h0, h1] = h.to_be_bytes();
+        Self {
+            octets: [
+                a0, a1, b0, b1, c0, c1, d0, d1, e0, e1, f0, f1, g0, g1, h0, h1,
+            ],
+        }
+    }
+
+    /// Returns the sixteen octets of this address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIpv6Addr;
+    ///
+    /// assert_eq!(
+    ///     RIpv6Addr::new(0xff01, 0, 0, 0, 0, 0, 0, 2).octets(),
+    ///     [0xff, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2],
+    /// );
+    ///
+    /// ```
+    pub const fn octets(&self) -> [u8; 16] {
+        self.octets
+    }
+
+    /// Returns the eight 16-bit segments of this address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIpv6Addr;
+    ///
+    /// assert_eq!(
+    ///     RIpv6Addr::new(0xff01, 0, 0, 0, 0, 0, 0, 2).segments(),
+    ///     [0xff01, 0, 0, 0, 0, 0, 0, 2],
+    /// );
+    ///
+    /// ```
+    pub const fn segments(&self) -> [u16; 8] {
+        let o = &self.octets;
+        [
+            u16::from_be_bytes([o[0], o[1]]),
+            u16::from_be_bytes([o[2], o[3]]),
+            u16::from_be_bytes([o[4], o[5]]),
+            u16::from_be_bytes([o[6], o[7]]),
+            u16::from_be_bytes([o[8], o[9]]),
+            u16::from_be_bytes([o[10], o[11]]),
+            u16::from_be_bytes([o[12], o[13]]),
+            u16::from_be_bytes([o[14], o[15]]),
+        ]
+    }
+}
+
+impl From<[u8; 16]> for RIpv6Addr {
+    fn from(octets: [u8; 16]) -> Self {
+        Self { octets }
+    }
+}
+
+impl From<RIpv6Addr> for [u8; 16] {
+    fn from(addr: RIpv6Addr) -> Self {
+        addr.octets
+    }
+}
+
+impl_from_rust_repr! {
+    impl From<Ipv6Addr> for RIpv6Addr {
+        fn(this){
+            RIpv6Addr { octets: this.octets() }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<Ipv6Addr> for RIpv6Addr {
+        fn(this){
+            Ipv6Addr::from(this.octets)
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `std::net::IpAddr`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RIpAddr, RIpv4Addr, RIpv6Addr};
+///
+/// use std::net::{IpAddr, Ipv6Addr};
+///
+/// let v4: RIpAddr = "10.0.0.1".parse().unwrap();
+/// assert_eq!(v4, RIpAddr::V4(RIpv4Addr::new(10, 0, 0, 1)));
+/// assert!(v4.is_ipv4());
+///
+/// let v6 = RIpAddr::from(RIpv6Addr::LOCALHOST);
+/// assert_eq!(IpAddr::from(v6), IpAddr::V6(Ipv6Addr::LOCALHOST));
+/// assert_eq!(v6.to_string(), "::1");
+///
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[repr(u8)]
+#[derive(StableAbi)]
+pub enum RIpAddr {
+    /// An IPv4 address.
+    V4(RIpv4Addr),
+    /// An IPv6 address.
+    V6(RIpv6Addr),
+}
+
+impl RIpAddr {
+    /// Queries whether this is an IPv4 address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIpAddr, RIpv4Addr, RIpv6Addr};
+    ///
+    /// assert!(RIpAddr::V4(RIpv4Addr::LOCALHOST).is_ipv4());
+    /// assert!(!RIpAddr::V6(RIpv6Addr::LOCALHOST).is_ipv4());
+    ///
+    /// ```
+    pub const fn is_ipv4(&self) -> bool {
+        matches!(self, RIpAddr::V4 { .. })
+    }
+
+    /// Queries whether this is an IPv6 address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIpAddr, RIpv4Addr, RIpv6Addr};
+    ///
+    /// assert!(RIpAddr::V6(RIpv6Addr::LOCALHOST).is_ipv6());
+    /// assert!(!RIpAddr::V4(RIpv4Addr::LOCALHOST).is_ipv6());
+    ///
+    /// ```
+    pub const fn is_ipv6(&self) -> bool {
+        matches!(self, RIpAddr::V6 { .. })
+    }
+}
+
+impl From<RIpv4Addr> for RIpAddr {
+    fn from(addr: RIpv4Addr) -> Self {
+        RIpAddr::V4(addr)
+    }
+}
+
+impl From<RIpv6Addr> for RIpAddr {
+    fn from(addr: RIpv6Addr) -> Self {
+        RIpAddr::V6(addr)
+    }
+}
+
+impl_from_rust_repr! {
+    impl From<IpAddr> for RIpAddr {
+        fn(this){
+            match this {
+                IpAddr::V4(addr) => RIpAddr::V4(addr.into()),
+                IpAddr::V6(addr) => RIpAddr::V6(addr.into()),
+            }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<IpAddr> for RIpAddr {
+        fn(this){
+            match this {
+                RIpAddr::V4(addr) => IpAddr::V4(addr.into()),
+                RIpAddr::V6(addr) => IpAddr::V6(addr.into()),
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `std::net::SocketAddrV4`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RIpv4Addr, RSocketAddrV4};
+///
+/// let addr: RSocketAddrV4 = "127.0.0.1:8080".parse().unwrap();
+///
+/// assert_eq!(addr, RSocketAddrV4::new(RIpv4Addr::LOCALHOST, 8080));
+/// assert_eq!(addr.ip(), RIpv4Addr::LOCALHOST);
+/// assert_eq!(addr.port(), 8080);
+///
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, StableAbi)]
+#[repr(C)]
+pub struct RSocketAddrV4 {
+    ip: RIpv4Addr,
+    port: u16,
+}
+
+impl RSocketAddrV4 {
+    /// Constructs an `RSocketAddrV4` from an ip address and a port.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIpv4Addr, RSocketAddrV4};
+    ///
+    /// let addr = RSocketAddrV4::new(RIpv4Addr::new(10, 0, 0, 1), 80);
+    /// assert_eq!(addr.to_string(), "10.0.0.1:80");
+    ///
+    /// ```
+    pub const fn new(ip: RIpv4Addr, port: u16) -> Self {
+        Self { ip, port }
+    }
+
+    /// Gets the ip address.
+    pub const fn ip(&self) -> RIpv4Addr {
+        self.ip
+    }
+
+    /// Gets the port.
+    pub const fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Replaces the ip address.
+    pub fn set_ip(&mut self, ip: RIpv4Addr) {
+        self.ip = ip;
+    }
+
+    /// Replaces the port.
+    pub fn set_port(&mut self, port: u16) {
+        self.port = port;
+    }
+}
+
+impl_from_rust_repr! {
+    impl From<SocketAddrV4> for RSocketAddrV4 {
+        fn(this){
+            RSocketAddrV4::new((*this.ip()).into(), this.port())
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<SocketAddrV4> for RSocketAddrV4 {
+        fn(this){
+            SocketAddrV4::new(this.ip.into(), this.port)
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `std::net::SocketAddrV6`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RIpv6Addr, RSocketAddrV6};
+///
+/// let addr: RSocketAddrV6 = "[::1]:8080".parse().unwrap();
+///
+/// assert_eq!(addr, RSocketAddrV6::new(RIpv6Addr::LOCALHOST, 8080, 0, 0));
+/// assert_eq!(addr.ip(), RIpv6Addr::LOCALHOST);
+/// assert_eq!(addr.port(), 8080);
+///
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, StableAbi)]
+#[repr(C)]
+pub struct RSocketAddrV6 {
+    ip: RIpv6Addr,
+    port: u16,
+    flowinfo: u32,
+    scope_id: u32,
+}
+
+impl RSocketAddrV6 {
+    /// Constructs an `RSocketAddrV6` from an ip address, a port,
+    /// and the `flowinfo` and `scope_id` fields.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIpv6Addr, RSocketAddrV6};
+    ///
+    /// let addr = RSocketAddrV6::new(RIpv6Addr::LOCALHOST, 80, 0, 3);
+    /// assert_eq!(addr.to_string(), "[::1%3]:80");
+    ///
+    /// ```
+    pub const fn new(ip: RIpv6Addr, port: u16, flowinfo: u32, scope_id: u32) -> Self {
+        Self {
+            ip,
+            port,
+            flowinfo,
+            scope_id,
+        }
+    }
+
+    /// Gets the ip address.
+    pub const fn ip(&self) -> RIpv6Addr {
+        self.ip
+    }
+
+    /// Gets the port.
+    pub const fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Gets the flow information associated with this address.
+    pub const fn flowinfo(&self) -> u32 {
+        self.flowinfo
+    }
+
+    /// Gets the scope ID associated with this address.
+    pub const fn scope_id(&self) -> u32 {
+        self.scope_id
+    }
+
+    /// Replaces the ip address.
+    pub fn set_ip(&mut self, ip: RIpv6Addr) {
+        self.ip = ip;
+    }
+
+    /// Replaces the port.
+    pub fn set_port(&mut self, port: u16) {
+        self.port = port;
+    }
+
+    /// Replaces the flow information.
+    pub fn set_flowinfo(&mut self, flowinfo: u32) {
+        self.flowinfo = flowinfo;
+    }
+
+    /// Replaces the scope ID.
+    pub fn set_scope_id(&mut self, scope_id: u32) {
+        self.scope_id = scope_id;
+    }
+}
+
+impl_from_rust_repr! {
+    impl From<SocketAddrV6> for RSocketAddrV6 {
+        fn(this){
+            RSocketAddrV6::new(
+                (*this.ip()).into(),
+                this.port(),
+                this.flowinfo(),
+                this.scope_id(),
+            )
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<SocketAddrV6> for RSocketAddrV6 {
+        fn(this){
+            SocketAddrV6::new(this.ip.into(), this.port, this.flowinfo, this.scope_id)
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `std::net::SocketAddr`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RIpAddr, RIpv4Addr, RSocketAddr};
+///
+/// use std::net::SocketAddr;
+///
+/// let addr = RSocketAddr::new(RIpv4Addr::new(10, 0, 0, 1).into(), 443);
+///
+/// assert_eq!(addr.ip(), RIpAddr::V4(RIpv4Addr::new(10, 0, 0, 1)));
+/// assert_eq!(addr.port(), 443);
+/// assert_eq!(SocketAddr::from(addr), "10.0.0.1:443".parse().unwrap());
+/// assert_eq!("[::1]:443".parse::<RSocketAddr>().unwrap().to_string(), "[::1]:443");
+///
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[repr(u8)]
+#[derive(StableAbi)]
+pub enum RSocketAddr {
+    /// An IPv4 socket address.
+    V4(RSocketAddrV4),
+    /// An IPv6 socket address.
+    V6(RSocketAddrV6),
+}
+
+impl RSocketAddr {
+    /// Constructs an `RSocketAddr` from an ip address and a port.
+    ///
+    /// IPv6 addresses get a `flowinfo` and `scope_id` of 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIpv6Addr, RSocketAddr, RSocketAddrV6};
+    ///
+    /// assert_eq!(
+    ///     RSocketAddr::new(RIpv6Addr::LOCALHOST.into(), 80),
+    ///     RSocketAddr::V6(RSocketAddrV6::new(RIpv6Addr::LOCALHOST, 80, 0, 0)),
+    /// );
+    ///
+    /// ```
+    pub const fn new(ip: RIpAddr, port: u16) -> Self {
+        match ip {
+            RIpAddr::V4(ip) => RSocketAddr::V4(RSocketAddrV4::new(ip, port)),
+            RIpAddr::V6(ip) => RSocketAddr::V6(RSocketAddrV6::new(ip, port, 0, 0)),
+        }
+    }
+
+    /// Gets the ip address.
+    pub const fn ip(&self) -> RIpAddr {
+        match self {
+            RSocketAddr::V4(addr) => RIpAddr::V4(addr.ip),
+            RSocketAddr::V6(addr) => RIpAddr::V6(addr.ip),
+        }
+    }
+
+    /// Gets the port.
+    pub const fn port(&self) -> u16 {
+        match self {
+            RSocketAddr::V4(addr) => addr.port,
+            RSocketAddr::V6(addr) => addr.port,
+        }
+    }
+
+    /// Replaces the port.
+    pub fn set_port(&mut self, port: u16) {
+        match self {
+            RSocketAddr::V4(addr) => addr.port = port,
+            RSocketAddr::V6(addr) => addr.port = port,
+        }
+    }
+
+    /// Queries whether this is an IPv4 socket address.
+    pub const fn is_ipv4(&self) -> bool {
+        matches!(self, RSocketAddr::V4 { .. })
+    }
+
+    /// Queries whether this is an IPv6 socket address.
+    pub const fn is_ipv6(&self) -> bool {
+        matches!(self, RSocketAddr::V6 { .. })
+    }
+}
+
+impl From<RSocketAddrV4> for RSocketAddr {
+    fn from(addr: RSocketAddrV4) -> Self {
+        RSocketAddr::V4(addr)
+    }
+}
+
+impl From<RSocketAddrV6> for RSocketAddr {
+    fn from(addr: RSocketAddrV6) -> Self {
+        RSocketAddr::V6(addr)
+    }
+}
+
+impl_from_rust_repr! {
+    impl From<SocketAddr> for RSocketAddr {
+        fn(this){
+            match this {
+                SocketAddr::V4(addr) => RSocketAddr::V4(addr.into()),
+                SocketAddr::V6(addr) => RSocketAddr::V6(addr.into()),
+            }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<SocketAddr> for RSocketAddr {
+        fn(this){
+            match this {
+                RSocketAddr::V4(addr) => SocketAddr::V4(addr.into()),
+                RSocketAddr::V6(addr) => SocketAddr::V6(addr.into()),
+            }
+        }
+    }
+}
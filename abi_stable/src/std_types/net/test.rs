@@ -0,0 +1,145 @@
+use super::*;
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+const ADDRS: &[&str] = &[
+    "0.0.0.0:0",
+    "127.0.0.1:80",
+    "192.168.100.200:65535",
+    "255.255.255.255:1",
+    "[::]:0",
+    "[::1]:80",
+    "[2001:db8::ff00:42:8329]:443",
+    "[fe80::1%7]:8080",
+    "[::ffff:10.0.0.1]:22",
+];
+
+#[test]
+fn socket_addr_roundtrip() {
+    for &s in ADDRS {
+        let std_addr: SocketAddr = s.parse().unwrap();
+        let addr = RSocketAddr::from(std_addr);
+
+        assert_eq!(SocketAddr::from(addr), std_addr);
+        assert_eq!(s.parse::<RSocketAddr>().unwrap(), addr);
+        assert_eq!(addr.to_string(), std_addr.to_string());
+        assert_eq!(format!("{:?}", addr), format!("{:?}", std_addr));
+        assert_eq!(addr.port(), std_addr.port());
+        assert_eq!(IpAddr::from(addr.ip()), std_addr.ip());
+        assert_eq!(addr.is_ipv4(), std_addr.is_ipv4());
+
+        match (addr, std_addr) {
+            (RSocketAddr::V4(addr), SocketAddr::V4(std_addr)) => {
+                assert_eq!(SocketAddrV4::from(addr), std_addr);
+                assert_eq!(Ipv4Addr::from(addr.ip()), *std_addr.ip());
+            }
+            (RSocketAddr::V6(addr), SocketAddr::V6(std_addr)) => {
+                assert_eq!(SocketAddrV6::from(addr), std_addr);
+                assert_eq!(Ipv6Addr::from(addr.ip()), *std_addr.ip());
+                assert_eq!(addr.scope_id(), std_addr.scope_id());
+                assert_eq!(addr.flowinfo(), std_addr.flowinfo());
+            }
+            _ => panic!("mismatched variants for {}", s),
+        }
+    }
+}
+
+#[test]
+fn ipv6_segments() {
+    let std_addr = Ipv6Addr::new(0x2001, 0xdb8, 1, 2, 3, 4, 0xff00, 0xabcd);
+    let addr = RIpv6Addr::new(0x2001, 0xdb8, 1, 2, 3, 4, 0xff00, 0xabcd);
+
+    assert_eq!(RIpv6Addr::from(std_addr), addr);
+    assert_eq!(addr.segments(), std_addr.segments());
+    assert_eq!(addr.octets(), std_addr.octets());
+    assert_eq!(Ipv6Addr::from(RIpv6Addr::LOCALHOST), Ipv6Addr::LOCALHOST);
+    assert_eq!(
+        Ipv6Addr::from(RIpv6Addr::UNSPECIFIED),
+        Ipv6Addr::UNSPECIFIED
+    );
+    assert_eq!(Ipv4Addr::from(RIpv4Addr::LOCALHOST), Ipv4Addr::LOCALHOST);
+    assert_eq!(Ipv4Addr::from(RIpv4Addr::BROADCAST), Ipv4Addr::BROADCAST);
+}
+
+#[test]
+fn ordering_and_hashing_match_std() {
+    let mut addrs = ADDRS
+        .iter()
+        .map(|s| s.parse::<SocketAddr>().unwrap())
+        .collect::<Vec<_>>();
+    addrs.push("127.0.0.1:79".parse().unwrap());
+    addrs.push("[fe80::1%6]:8080".parse().unwrap());
+
+    for &l in &addrs {
+        for &r in &addrs {
+            let (rl, rr) = (RSocketAddr::from(l), RSocketAddr::from(r));
+            assert_eq!(rl.cmp(&rr), l.cmp(&r), "{} {}", l, r);
+            assert_eq!(rl.ip().cmp(&rr.ip()), l.ip().cmp(&r.ip()), "{} {}", l, r);
+            assert_eq!(rl == rr, hash_of(&rl) == hash_of(&rr));
+        }
+    }
+}
+
+#[test]
+fn parse_errors() {
+    assert!("256.0.0.1".parse::<RIpv4Addr>().is_err());
+    assert!("::1".parse::<RIpv4Addr>().is_err());
+    assert!("1.2.3.4".parse::<RIpv6Addr>().is_err());
+    assert!("1.2.3.4".parse::<RSocketAddr>().is_err());
+    assert!("[::1]:80".parse::<RSocketAddrV4>().is_err());
+    assert!("127.0.0.1:80".parse::<RSocketAddrV6>().is_err());
+}
+
+#[test]
+fn setters() {
+    let mut addr = RSocketAddr::new(RIpv4Addr::LOCALHOST.into(), 80);
+    addr.set_port(8080);
+    assert_eq!(addr.to_string(), "127.0.0.1:8080");
+
+    let mut addr = RSocketAddrV6::new(RIpv6Addr::UNSPECIFIED, 0, 0, 0);
+    addr.set_ip(RIpv6Addr::LOCALHOST);
+    addr.set_port(1);
+    addr.set_flowinfo(2);
+    addr.set_scope_id(3);
+    assert_eq!(
+        SocketAddrV6::from(addr),
+        SocketAddrV6::new(Ipv6Addr::LOCALHOST, 1, 2, 3)
+    );
+}
+
+#[test]
+fn serde_matches_std() {
+    for &s in ADDRS {
+        let std_addr: SocketAddr = s.parse().unwrap();
+        let addr = RSocketAddr::from(std_addr);
+
+        let json = serde_json::to_string(&addr).unwrap();
+        assert_eq!(json, serde_json::to_string(&std_addr).unwrap());
+        // std's serde impls don't preserve the `flowinfo` and `scope_id` of IPv6 addresses
+        let std_roundtrip: SocketAddr = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RSocketAddr>(&json).unwrap(),
+            RSocketAddr::from(std_roundtrip),
+        );
+
+        let bytes = bincode::serialize(&addr).unwrap();
+        assert_eq!(bytes, bincode::serialize(&std_addr).unwrap());
+        let std_roundtrip: SocketAddr = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(
+            bincode::deserialize::<RSocketAddr>(&bytes).unwrap(),
+            RSocketAddr::from(std_roundtrip),
+        );
+
+        let ip = addr.ip();
+        let json = serde_json::to_string(&ip).unwrap();
+        assert_eq!(json, serde_json::to_string(&std_addr.ip()).unwrap());
+        assert_eq!(serde_json::from_str::<RIpAddr>(&json).unwrap(), ip);
+    }
+}
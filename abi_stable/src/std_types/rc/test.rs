@@ -0,0 +1,158 @@
+use super::*;
+
+use std::cell::Cell;
+
+fn _covariant_rc<'a: 'b, 'b, T>(rc: RRc<&'a T>) -> RRc<&'b T> {
+    rc
+}
+
+fn refaddr<T>(ref_: &T) -> usize {
+    ref_ as *const T as usize
+}
+
+#[test]
+fn to_from_rc() {
+    let orig_a = Rc::new(1000);
+    let a_addr = (&*orig_a) as *const _ as usize;
+    let mut reprc_a = orig_a.clone().piped(RRc::from);
+
+    assert_eq!(a_addr, refaddr(&*reprc_a));
+
+    assert_eq!(a_addr, reprc_a.clone().piped(|a| refaddr(&*a)));
+    assert_eq!(
+        a_addr,
+        reprc_a.clone().piped(RRc::into_rc).piped(|a| refaddr(&*a))
+    );
+
+    reprc_a.set_vtable_for_testing();
+
+    assert_eq!(a_addr, refaddr(&*reprc_a));
+    assert_eq!(Rc::strong_count(&orig_a), 2);
+
+    let back_to_a = reprc_a.piped(RRc::into_rc);
+    assert_eq!(Rc::strong_count(&orig_a), 1);
+    assert_ne!(a_addr, refaddr(&*back_to_a));
+    drop(back_to_a);
+
+    assert_eq!(Rc::strong_count(&orig_a), 1);
+}
+
+#[allow(clippy::unit_cmp)]
+#[test]
+fn default() {
+    assert_eq!(*RRc::<String>::default(), "");
+    assert_eq!(*RRc::<()>::default(), ());
+    assert_eq!(*RRc::<u32>::default(), 0);
+    assert_eq!(*RRc::<bool>::default(), false);
+}
+
+#[test]
+fn get_mut() {
+    let mut conv = Rc::new(200).piped(RRc::from);
+
+    {
+        let _conv_clone = conv.clone();
+        assert_eq!(RRc::get_mut(&mut conv), None);
+    }
+    {
+        let _weak = RRc::downgrade(&conv);
+        assert_eq!(RRc::get_mut(&mut conv), None);
+    }
+    assert_eq!(RRc::get_mut(&mut conv), Some(&mut 200));
+}
+
+#[test]
+fn make_mut() {
+    let count = Cell::new(1);
+    let dod = DecrementOnDrop(&count);
+
+    let mut rc = RRc::new(ValueAndDod {
+        value: 'a',
+        _dod: dod.clone(),
+    });
+
+    {
+        assert_eq!(dod.count(), 2);
+        let rc_clone = rc.clone();
+
+        let mutref = RRc::make_mut(&mut rc);
+        assert_eq!(dod.count(), 3);
+        mutref.value = 'c';
+
+        assert_eq!(rc_clone.value, 'a');
+    }
+    assert_eq!(dod.count(), 2);
+    assert_eq!(rc.value, 'c');
+}
+
+#[test]
+fn weak_upgrade_and_counts() {
+    let count = Cell::new(1);
+    let dod = DecrementOnDrop(&count);
+
+    let rc = RRc::new(dod.clone());
+    let weak = RRc::downgrade(&rc);
+    let weak_clone = weak.clone();
+
+    assert_eq!(RRc::strong_count(&rc), 1);
+    assert_eq!(RRc::weak_count(&rc), 2);
+    assert_eq!(weak.strong_count(), 1);
+
+    {
+        let upgraded = weak.upgrade().unwrap();
+        assert!(std::ptr::eq(&*upgraded, &*rc));
+        assert_eq!(RRc::strong_count(&rc), 2);
+    }
+
+    drop(weak_clone);
+    assert_eq!(RRc::weak_count(&rc), 1);
+
+    assert_eq!(dod.count(), 2);
+    drop(rc);
+    assert_eq!(dod.count(), 1);
+
+    assert_eq!(weak.strong_count(), 0);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn weak_to_from_std() {
+    let rc = Rc::new(33);
+    let weak = RRcWeak::from(Rc::downgrade(&rc));
+    assert_eq!(Rc::weak_count(&rc), 1);
+
+    let std_weak = weak.into_weak().ok().unwrap();
+    assert_eq!(Rc::weak_count(&rc), 1);
+    assert_eq!(std_weak.upgrade(), Some(rc));
+}
+
+/////////////////////////////////////////
+
+#[derive(Clone)]
+struct ValueAndDod<'a, T> {
+    value: T,
+    _dod: DecrementOnDrop<'a>,
+}
+
+/////////////////////////////////////////
+
+struct DecrementOnDrop<'a>(&'a Cell<u32>);
+
+impl<'a> DecrementOnDrop<'a> {
+    fn count(&self) -> u32 {
+        self.0.get()
+    }
+}
+
+impl<'a> Clone for DecrementOnDrop<'a> {
+    fn clone(&self) -> Self {
+        self.0.set(self.0.get() + 1);
+        DecrementOnDrop(self.0)
+    }
+}
+
+impl<'a> Drop for DecrementOnDrop<'a> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() - 1);
+    }
+}
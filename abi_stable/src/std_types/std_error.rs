@@ -4,7 +4,7 @@ use std::{
     error::Error as ErrorTrait,
     fmt::{self, Debug, Display},
     marker::PhantomData,
-    mem,
+    mem, ptr,
 };
 
 #[allow(unused_imports)]
@@ -180,6 +180,48 @@ mod test;
 ///
 /// ```
 ///
+/// ### Source chains
+///
+/// The chain of errors returned by [`std::error::Error::source`]
+/// is preserved when an error is converted to an `RBoxError_`,
+/// each link being an [`UnsyncRBoxError`] that can be downcast
+/// within the dynamic library/executable that created it.
+///
+/// ```
+/// use std::{
+///     error::Error as ErrorTrait,
+///     fmt::{self, Display},
+///     num::ParseIntError,
+/// };
+///
+/// use abi_stable::std_types::RBoxError;
+///
+/// #[derive(Debug)]
+/// struct ConfigError(ParseIntError);
+///
+/// impl Display for ConfigError {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         f.write_str("invalid configuration")
+///     }
+/// }
+///
+/// impl ErrorTrait for ConfigError {
+///     fn source(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+///         Some(&self.0)
+///     }
+/// }
+///
+/// let err = RBoxError::new(ConfigError("a".parse::<u32>().unwrap_err()));
+///
+/// let source = err.source().unwrap();
+/// assert_eq!(source.to_string(), "invalid digit found in string");
+/// assert!(source.source().is_none());
+///
+/// let source = err.source_error().unwrap();
+/// assert!(source.downcast_ref::<ParseIntError>().is_some());
+///
+/// ```
+///
 ///
 ///
 #[repr(C)]
@@ -215,7 +257,29 @@ impl RBoxError_<SyncSend> {
     where
         T: ErrorTrait + Send + Sync + 'static,
     {
-        Self::new_inner(value)
+        Self::new_inner(value, None)
+    }
+
+    /// Constructs a `Send + Sync` `RBoxError_` from an error,
+    /// capturing a backtrace that can be gotten with
+    /// [`backtrace`](#method.backtrace).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxError;
+    ///
+    /// let err = RBoxError::new_with_backtrace(std::fmt::Error);
+    ///
+    /// if let Some(backtrace) = err.backtrace() {
+    ///     println!("{}", backtrace);
+    /// }
+    /// ```
+    pub fn new_with_backtrace<T>(value: T) -> Self
+    where
+        T: ErrorTrait + Send + Sync + 'static,
+    {
+        Self::new_inner(value, capture_backtrace())
     }
 }
 
@@ -235,7 +299,29 @@ impl RBoxError_<UnsyncSend> {
     where
         T: ErrorTrait + Send + 'static,
     {
-        Self::new_inner(value)
+        Self::new_inner(value, None)
+    }
+
+    /// Constructs a `Send + !Sync` `RBoxError_` from an error,
+    /// capturing a backtrace that can be gotten with
+    /// [`backtrace`](#method.backtrace).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::SendRBoxError;
+    ///
+    /// let err = SendRBoxError::new_with_backtrace(std::fmt::Error);
+    ///
+    /// if let Some(backtrace) = err.backtrace() {
+    ///     println!("{}", backtrace);
+    /// }
+    /// ```
+    pub fn new_with_backtrace<T>(value: T) -> Self
+    where
+        T: ErrorTrait + Send + 'static,
+    {
+        Self::new_inner(value, capture_backtrace())
     }
 }
 
@@ -255,7 +341,29 @@ impl RBoxError_<UnsyncUnsend> {
     where
         T: ErrorTrait + 'static,
     {
-        Self::new_inner(value)
+        Self::new_inner(value, None)
+    }
+
+    /// Constructs a `!Send + !Sync` `RBoxError_` from an error,
+    /// capturing a backtrace that can be gotten with
+    /// [`backtrace`](#method.backtrace).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::UnsyncRBoxError;
+    ///
+    /// let err = UnsyncRBoxError::new_with_backtrace(std::fmt::Error);
+    ///
+    /// if let Some(backtrace) = err.backtrace() {
+    ///     println!("{}", backtrace);
+    /// }
+    /// ```
+    pub fn new_with_backtrace<T>(value: T) -> Self
+    where
+        T: ErrorTrait + 'static,
+    {
+        Self::new_inner(value, capture_backtrace())
     }
 }
 
@@ -320,11 +428,67 @@ impl<M> RBoxError_<M> {
         unsafe { Self::new_with_vtable(value, MakeRErrorVTable::LIB_VTABLE_DEBUG_DISPLAY) }
     }
 
-    fn new_inner<T>(value: T) -> Self
+    /// Constructs an error from a raw OS error code,
+    /// which [`raw_os_error`](#method.raw_os_error) reads through the vtable,
+    /// so that it can be read in any dynamic library.
+    pub(crate) fn from_raw_os_error(code: i32) -> Self {
+        let chained = ErrorWithChain {
+            error: OsError { code },
+            source: None,
+            backtrace: None,
+        };
+        unsafe { Self::new_with_vtable(chained, MakeRErrorVTable::LIB_VTABLE_OS_ERROR) }
+    }
+
+    /// Like `from_raw_os_error`,
+    /// but as though the error was constructed in another dynamic library.
+    #[cfg(test)]
+    pub(crate) fn from_raw_os_error_in_other_library(code: i32) -> Self {
+        let chained = ErrorWithChain {
+            error: OsError { code },
+            source: None,
+            backtrace: None,
+        };
+        unsafe {
+            Self::new_with_vtable(chained, MakeRErrorVTable::LIB_VTABLE_OTHER_LIBRARY_OS_ERROR)
+        }
+    }
+
+    fn new_inner<T>(value: T, backtrace: Option<String>) -> Self
     where
         T: ErrorTrait + 'static,
     {
-        unsafe { Self::new_with_vtable(value, MakeRErrorVTable::<T>::LIB_VTABLE) }
+        Self::new_chained(value, |x| x, backtrace)
+    }
+
+    /// Constructs an `RBoxError_` that includes the source chain of `as_error(&value)`.
+    fn new_chained<T>(
+        value: T,
+        as_error: fn(&T) -> &(dyn ErrorTrait + 'static),
+        backtrace: Option<String>,
+    ) -> Self
+    where
+        T: Debug + Display + 'static,
+    {
+        let mut chained = RBox::new(ErrorWithChain {
+            error: value,
+            source: None,
+            backtrace,
+        });
+
+        let ptr: *mut ErrorWithChain<T> = chained.as_mut_ptr();
+        unsafe {
+            let root: *const (dyn ErrorTrait + 'static) = as_error(&*ptr::addr_of!((*ptr).error));
+            (*ptr).source = SourceLink::chain_of(root);
+        }
+
+        Self {
+            value: unsafe {
+                mem::transmute::<RBox<ErrorWithChain<T>>, RBox<ErasedObject>>(chained)
+            },
+            vtable: MakeRErrorVTable::<ErrorWithChain<T>>::LIB_VTABLE,
+            _sync_send: PhantomData,
+        }
     }
 
     unsafe fn new_with_vtable<T>(value: T, vtable: RErrorVTable_Ref) -> Self {
@@ -349,20 +513,125 @@ impl<M> RBoxError_<M> {
     // This isn't strictly required anymore because abi_stable doesn't
     // unload libraries right now.
     ///
+    /// The source chain and backtrace are preserved,
+    /// with every link in the chain converted to a formatted error.
+    ///
     pub fn to_formatted_error<N>(&self) -> RBoxError_<N> {
-        if let Some(dd) = self.as_debug_display() {
-            RBoxError_::from_debug_display(DebugDisplay {
+        if let Some(code) = self.raw_os_error() {
+            return RBoxError_::from_raw_os_error(code);
+        }
+
+        let error = match self.as_debug_display() {
+            Some(dd) => DebugDisplay {
                 debug: dd.debug.into(),
                 display: dd.display.into(),
-            })
+            },
+            None => DebugDisplay {
+                debug: format!("{:#?}", self),
+                display: format!("{:#}", self),
+            },
+        };
+
+        let source = self.source_error().map(|e| e.to_formatted_error());
+        let backtrace = self.backtrace().map(|bt| bt.to_string());
+
+        if source.is_none() && backtrace.is_none() {
+            RBoxError_::from_debug_display(error)
         } else {
-            RBoxError_::from_fmt(self)
+            let chained = ErrorWithChain {
+                error,
+                source,
+                backtrace,
+            };
+            unsafe {
+                RBoxError_::new_with_vtable(
+                    chained,
+                    MakeRErrorVTable::LIB_VTABLE_CHAINED_DEBUG_DISPLAY,
+                )
+            }
         }
     }
 
     fn as_debug_display(&self) -> Option<DebugDisplayRef<'_>> {
         unsafe { self.vtable.as_debug_display()(self.value.as_rref()).into_option() }
     }
+
+    /// Gets the error that caused this one,
+    /// the next link in the chain that `std::error::Error::source` walks.
+    ///
+    /// This returns `None` for errors without a source,
+    /// and for errors constructed by a dynamic library that uses
+    /// an older version of `abi_stable`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::{error::Error as ErrorTrait, fmt};
+    ///
+    /// use abi_stable::std_types::RBoxError;
+    ///
+    /// #[derive(Debug)]
+    /// struct WriteError(fmt::Error);
+    ///
+    /// impl fmt::Display for WriteError {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         f.write_str("could not write the report")
+    ///     }
+    /// }
+    ///
+    /// impl ErrorTrait for WriteError {
+    ///     fn source(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+    ///         Some(&self.0)
+    ///     }
+    /// }
+    ///
+    /// let err = RBoxError::new(WriteError(fmt::Error));
+    ///
+    /// let source = err.source_error().unwrap();
+    /// assert_eq!(source.to_string(), fmt::Error.to_string());
+    /// assert!(source.downcast_ref::<fmt::Error>().is_some());
+    ///
+    /// assert!(source.source_error().is_none());
+    ///
+    /// ```
+    pub fn source_error(&self) -> Option<&UnsyncRBoxError> {
+        let function = self.vtable.source()?;
+        unsafe { function(self.value.as_rref()) }
+            .into_option()
+            .map(RRef::get)
+    }
+
+    /// Gets the backtrace captured when this error was constructed, rendered as text.
+    ///
+    /// Backtraces are only captured by the `new_with_backtrace` constructors,
+    /// with the `"rust_1_65"` feature enabled,
+    /// and when the `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE`
+    /// environment variables enable them
+    /// (as described in the docs for `std::backtrace::Backtrace::capture`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxError;
+    ///
+    /// let err = RBoxError::new_with_backtrace(std::fmt::Error);
+    ///
+    /// if let Some(backtrace) = err.backtrace() {
+    ///     println!("{}", backtrace);
+    /// }
+    ///
+    /// ```
+    pub fn backtrace(&self) -> Option<RStr<'_>> {
+        let function = self.vtable.backtrace()?;
+        unsafe { function(self.value.as_rref()) }.into_option()
+    }
+
+    /// Gets the raw OS error code,
+    /// if this was constructed with `from_raw_os_error`.
+    pub(crate) fn raw_os_error(&self) -> Option<i32> {
+        let function = self.vtable.raw_os_error()?;
+        unsafe { function(self.value.as_rref()) }.into_option()
+    }
 }
 
 impl<M> RBoxError_<M> {
@@ -371,6 +640,10 @@ impl<M> RBoxError_<M> {
         self.vtable.type_id()()
     }
 
+    /// Whether this wraps a `T`.
+    ///
+    /// Every error type other than `DebugDisplay` is stored in an `ErrorWithChain<T>`,
+    /// whose vtable uses the type id of `T`.
     fn is_type<T: 'static>(&self) -> bool {
         let self_id = self.vtable.type_id()();
         let other_id = UTypeId::new::<T>();
@@ -463,7 +736,12 @@ impl RBoxError_<SyncSend> {
     }
 }
 
-impl<M> ErrorTrait for RBoxError_<M> {}
+impl<M> ErrorTrait for RBoxError_<M> {
+    fn source(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+        self.source_error()
+            .map(|e| e as &(dyn ErrorTrait + 'static))
+    }
+}
 
 impl<M> Display for RBoxError_<M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -520,12 +798,7 @@ macro_rules! from_impls {
             pub fn from_box(this: $boxdyn) -> Self {
                 match this.downcast::<Self>() {
                     Ok(e) => *e,
-                    Err(e) => unsafe {
-                        Self::new_with_vtable::<$boxdyn>(
-                            e,
-                            MakeBoxedRErrorVTable::<$boxdyn>::LIB_VTABLE,
-                        )
-                    },
+                    Err(e) => Self::new_chained::<$boxdyn>(e, |e| &**e, None),
                 }
             }
 
@@ -548,8 +821,11 @@ macro_rules! from_impls {
             pub fn into_box(self) -> $boxdyn {
                 if self.is_type::<$boxdyn>() {
                     unsafe {
-                        let box_ = mem::transmute::<RBox<ErasedObject>, RBox<$boxdyn>>(self.value);
-                        RBox::into_inner(box_)
+                        let box_ = mem::transmute::<
+                            RBox<ErasedObject>,
+                            RBox<ErrorWithChain<$boxdyn>>,
+                        >(self.value);
+                        RBox::into_inner(box_).error
                     }
                 } else {
                     Box::new(self)
@@ -582,11 +858,16 @@ macro_rules! from_impls {
             {
                 match (self.is_type::<T>(), self.is_type::<$boxdyn>()) {
                     (true, _) => unsafe {
-                        Ok(mem::transmute::<RBox<ErasedObject>, RBox<T>>(self.value))
+                        let x = mem::transmute::<RBox<ErasedObject>, RBox<ErrorWithChain<T>>>(
+                            self.value,
+                        );
+                        Ok(RBox::new(RBox::into_inner(x).error))
                     },
                     (false, true) if self.downcast_ref::<T>().is_some() => unsafe {
-                        let x = mem::transmute::<RBox<ErasedObject>, RBox<$boxdyn>>(self.value);
-                        let x = RBox::into_inner(x);
+                        let x = mem::transmute::<RBox<ErasedObject>, RBox<ErrorWithChain<$boxdyn>>>(
+                            self.value,
+                        );
+                        let x = RBox::into_inner(x).error;
                         Ok(RBox::from_box(x.downcast::<T>().unwrap()))
                     },
                     (false, _) => Err(self),
@@ -617,13 +898,17 @@ macro_rules! from_impls {
             where
                 T: ErrorTrait + 'static,
             {
-                match (self.is_type::<T>(), self.is_type::<$boxdyn>()) {
-                    (true, _) => unsafe { Some(&*(self.value.as_ptr() as *const T)) },
-                    (false, true) => unsafe {
-                        let ref_box = &*(self.value.as_ptr() as *const $boxdyn);
-                        (&**ref_box).downcast_ref::<T>()
-                    },
-                    (false, false) => None,
+                let ptr = self.value.as_ptr();
+                if self.is_type::<T>() {
+                    unsafe { Some(&(*(ptr as *const ErrorWithChain<T>)).error) }
+                } else if self.is_type::<$boxdyn>() {
+                    let ref_box = unsafe { &(*(ptr as *const ErrorWithChain<$boxdyn>)).error };
+                    (&**ref_box).downcast_ref::<T>()
+                } else if self.is_type::<SourceLink>() {
+                    let link = unsafe { &(*(ptr as *const ErrorWithChain<SourceLink>)).error };
+                    link.get()?.downcast_ref::<T>()
+                } else {
+                    None
                 }
             }
 
@@ -651,13 +936,14 @@ macro_rules! from_impls {
             where
                 T: ErrorTrait + 'static,
             {
-                match (self.is_type::<T>(), self.is_type::<$boxdyn>()) {
-                    (true, _) => unsafe { Some(&mut *(self.value.as_mut_ptr() as *mut T)) },
-                    (false, true) => unsafe {
-                        let mut_box = &mut *(self.value.as_mut_ptr() as *mut $boxdyn);
-                        (&mut **mut_box).downcast_mut::<T>()
-                    },
-                    (false, false) => None,
+                let ptr = self.value.as_mut_ptr();
+                if self.is_type::<T>() {
+                    unsafe { Some(&mut (*(ptr as *mut ErrorWithChain<T>)).error) }
+                } else if self.is_type::<$boxdyn>() {
+                    let mut_box = unsafe { &mut (*(ptr as *mut ErrorWithChain<$boxdyn>)).error };
+                    (&mut **mut_box).downcast_mut::<T>()
+                } else {
+                    None
                 }
             }
         }
@@ -702,21 +988,30 @@ struct RErrorVTable {
 
     #[sabi(last_prefix_field)]
     type_id: extern "C" fn() -> UTypeId,
+
+    source: unsafe extern "C" fn(RRef<'_, ErasedObject>) -> ROption<RRef<'_, UnsyncRBoxError>>,
+
+    backtrace: unsafe extern "C" fn(RRef<'_, ErasedObject>) -> ROption<RStr<'_>>,
+
+    raw_os_error: unsafe extern "C" fn(RRef<'_, ErasedObject>) -> ROption<i32>,
 }
 
 ///////////////////
 
 struct MakeRErrorVTable<T>(T);
 
-impl<T> MakeRErrorVTable<T>
+impl<T> MakeRErrorVTable<ErrorWithChain<T>>
 where
-    T: ErrorTrait + 'static,
+    T: Debug + Display + 'static,
 {
     const VALUE: RErrorVTable = RErrorVTable {
-        debug: debug_impl::<T>,
-        display: display_impl::<T>,
+        debug: debug_impl::<ErrorWithChain<T>>,
+        display: display_impl::<ErrorWithChain<T>>,
         as_debug_display: not_as_debug_display,
         type_id: new_utypeid::<T>,
+        source: chained_source::<T>,
+        backtrace: chained_backtrace::<T>,
+        raw_os_error: no_raw_os_error,
     };
 
     const VALUE_MD: &'static WithMetadata<RErrorVTable> = &WithMetadata::new(Self::VALUE);
@@ -731,31 +1026,166 @@ impl MakeRErrorVTable<DebugDisplay> {
             display: display_impl::<DebugDisplay>,
             as_debug_display,
             type_id: new_utypeid::<DebugDisplay>,
+            source: no_source,
+            backtrace: no_backtrace,
+            raw_os_error: no_raw_os_error,
         })
     };
 
     const LIB_VTABLE_DEBUG_DISPLAY: RErrorVTable_Ref =
         { RErrorVTable_Ref(Self::WM_DEBUG_DISPLAY.static_as_prefix()) };
+
+    const WM_CHAINED_DEBUG_DISPLAY: &'static WithMetadata<RErrorVTable> = {
+        &WithMetadata::new(RErrorVTable {
+            debug: debug_impl::<ErrorWithChain<DebugDisplay>>,
+            display: display_impl::<ErrorWithChain<DebugDisplay>>,
+            as_debug_display: chained_as_debug_display,
+            type_id: new_utypeid::<DebugDisplay>,
+            source: chained_source::<DebugDisplay>,
+            backtrace: chained_backtrace::<DebugDisplay>,
+            raw_os_error: no_raw_os_error,
+        })
+    };
+
+    const LIB_VTABLE_CHAINED_DEBUG_DISPLAY: RErrorVTable_Ref =
+        { RErrorVTable_Ref(Self::WM_CHAINED_DEBUG_DISPLAY.static_as_prefix()) };
 }
 
-///////////////////
+impl MakeRErrorVTable<OsError> {
+    const OS_ERROR_VALUE: RErrorVTable = RErrorVTable {
+        raw_os_error: os_error_code,
+        ..MakeRErrorVTable::<ErrorWithChain<OsError>>::VALUE
+    };
 
-struct MakeBoxedRErrorVTable<T>(T);
+    const WM_OS_ERROR: &'static WithMetadata<RErrorVTable> =
+        &WithMetadata::new(Self::OS_ERROR_VALUE);
 
-impl<T> MakeBoxedRErrorVTable<Box<T>>
-where
-    T: ?Sized + ErrorTrait + 'static,
-{
-    const VALUE: RErrorVTable = RErrorVTable {
-        debug: debug_impl::<Box<T>>,
-        display: display_impl::<Box<T>>,
-        as_debug_display: not_as_debug_display,
-        type_id: new_utypeid::<Box<T>>,
+    const LIB_VTABLE_OS_ERROR: RErrorVTable_Ref =
+        { RErrorVTable_Ref(Self::WM_OS_ERROR.static_as_prefix()) };
+
+    #[cfg(test)]
+    const WM_OTHER_LIBRARY_OS_ERROR: &'static WithMetadata<RErrorVTable> = {
+        &WithMetadata::new(RErrorVTable {
+            type_id: other_library_utypeid::<OsError>,
+            ..Self::OS_ERROR_VALUE
+        })
     };
 
-    const WM_VTABLE: &'static WithMetadata<RErrorVTable> = &WithMetadata::new(Self::VALUE);
+    #[cfg(test)]
+    const LIB_VTABLE_OTHER_LIBRARY_OS_ERROR: RErrorVTable_Ref =
+        { RErrorVTable_Ref(Self::WM_OTHER_LIBRARY_OS_ERROR.static_as_prefix()) };
+}
 
-    const LIB_VTABLE: RErrorVTable_Ref = RErrorVTable_Ref(Self::WM_VTABLE.static_as_prefix());
+#[cfg(test)]
+extern "C" fn other_library_utypeid<T: 'static>() -> UTypeId {
+    UTypeId::new_in_other_library::<T>()
+}
+
+////////////////////////////////////////////////////////////////////////
+
+/// What `RBoxError_`s point to, an error along with the chain of its sources,
+/// and the backtrace captured when it was constructed.
+struct ErrorWithChain<T> {
+    error: T,
+    source: Option<UnsyncRBoxError>,
+    backtrace: Option<String>,
+}
+
+impl<T: Display> Display for ErrorWithChain<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl<T: Debug> Debug for ErrorWithChain<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.error, f)
+    }
+}
+
+#[cfg(feature = "rust_1_65")]
+fn capture_backtrace() -> Option<String> {
+    use std::backtrace::{Backtrace, BacktraceStatus};
+
+    let backtrace = Backtrace::capture();
+    match backtrace.status() {
+        BacktraceStatus::Captured => Some(backtrace.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(not(feature = "rust_1_65"))]
+fn capture_backtrace() -> Option<String> {
+    None
+}
+
+////////////////////////////////////////////////////////////////////////
+
+/// A link in the source chain of an error,
+/// the error reached by calling `source` `depth` times on `root`.
+///
+/// This walks the chain from `root` every time it's used,
+/// so that mutating the root error (through `downcast_mut`)
+/// doesn't leave it pointing to a dropped error.
+struct SourceLink {
+    root: *const (dyn ErrorTrait + 'static),
+    depth: usize,
+}
+
+impl SourceLink {
+    /// Constructs the links for the source chain of `root`.
+    ///
+    /// # Safety
+    ///
+    /// `root` must point to an error that outlives the returned links.
+    unsafe fn chain_of(root: *const (dyn ErrorTrait + 'static)) -> Option<UnsyncRBoxError> {
+        let mut len = 0;
+        let mut current = unsafe { (*root).source() };
+        while let Some(error) = current {
+            len += 1;
+            current = error.source();
+        }
+
+        (1..=len).rev().fold(None, |source, depth| {
+            let link = ErrorWithChain {
+                error: SourceLink { root, depth },
+                source,
+                backtrace: None,
+            };
+            unsafe {
+                Some(UnsyncRBoxError::new_with_vtable(
+                    link,
+                    MakeRErrorVTable::<ErrorWithChain<SourceLink>>::LIB_VTABLE,
+                ))
+            }
+        })
+    }
+
+    fn get(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+        let mut current = unsafe { &*self.root };
+        for _ in 0..self.depth {
+            current = current.source()?;
+        }
+        Some(current)
+    }
+}
+
+impl Display for SourceLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(error) => Display::fmt(error, f),
+            None => f.write_str("<unavailable error source>"),
+        }
+    }
+}
+
+impl Debug for SourceLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(error) => Debug::fmt(error, f),
+            None => f.write_str("<unavailable error source>"),
+        }
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////
@@ -783,6 +1213,25 @@ impl ErrorTrait for DebugDisplay {}
 
 ////////////////////////////////////////////////////////////////////////
 
+/// An error from the OS, stored as its raw error code.
+struct OsError {
+    code: i32,
+}
+
+impl Display for OsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&std::io::Error::from_raw_os_error(self.code), f)
+    }
+}
+
+impl Debug for OsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&std::io::Error::from_raw_os_error(self.code), f)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
 #[repr(C)]
 #[derive(Debug, StableAbi, PartialEq)]
 struct DebugDisplayRef<'a> {
@@ -804,8 +1253,55 @@ unsafe extern "C" fn as_debug_display(
     }
 }
 
+unsafe extern "C" fn chained_as_debug_display(
+    this: RRef<'_, ErasedObject>,
+) -> ROption<DebugDisplayRef<'_>> {
+    extern_fn_panic_handling! {
+        let this = unsafe{ this.transmute_into_ref::<ErrorWithChain<DebugDisplay>>() };
+        ROption::RSome(DebugDisplayRef{
+            debug: this.error.debug.as_str().into(),
+            display: this.error.display.as_str().into(),
+        })
+    }
+}
+
 unsafe extern "C" fn not_as_debug_display(
     _: RRef<'_, ErasedObject>,
 ) -> ROption<DebugDisplayRef<'_>> {
     ROption::RNone
 }
+
+unsafe extern "C" fn chained_source<T: 'static>(
+    this: RRef<'_, ErasedObject>,
+) -> ROption<RRef<'_, UnsyncRBoxError>> {
+    extern_fn_panic_handling! {
+        let this = unsafe{ this.transmute_into_ref::<ErrorWithChain<T>>() };
+        this.source.as_ref().map(RRef::new).into()
+    }
+}
+
+unsafe extern "C" fn no_source(_: RRef<'_, ErasedObject>) -> ROption<RRef<'_, UnsyncRBoxError>> {
+    ROption::RNone
+}
+
+unsafe extern "C" fn chained_backtrace<T: 'static>(
+    this: RRef<'_, ErasedObject>,
+) -> ROption<RStr<'_>> {
+    extern_fn_panic_handling! {
+        let this = unsafe{ this.transmute_into_ref::<ErrorWithChain<T>>() };
+        this.backtrace.as_deref().map(RStr::from).into()
+    }
+}
+
+unsafe extern "C" fn no_backtrace(_: RRef<'_, ErasedObject>) -> ROption<RStr<'_>> {
+    ROption::RNone
+}
+
+unsafe extern "C" fn os_error_code(this: RRef<'_, ErasedObject>) -> ROption<i32> {
+    let this = unsafe { this.transmute_into_ref::<ErrorWithChain<OsError>>() };
+    ROption::RSome(this.error.code)
+}
+
+unsafe extern "C" fn no_raw_os_error(_: RRef<'_, ErasedObject>) -> ROption<i32> {
+    ROption::RNone
+}
@@ -177,3 +177,143 @@ fn from_fmt_or_debug() {
         assert_eq!(format!("{:#?}", str_err), format!("{}", rerr));
     }
 }
+
+/// An error with an optional source, used to build source chains.
+#[derive(Debug)]
+struct Chained {
+    name: &'static str,
+    source: Option<Box<dyn ErrorTrait + Send + Sync + 'static>>,
+}
+
+impl Chained {
+    fn chain(names: &[&'static str]) -> Self {
+        let mut iter = names.iter().rev();
+        let mut err = Chained {
+            name: iter.next().unwrap(),
+            source: None,
+        };
+        for name in iter {
+            err = Chained {
+                name,
+                source: Some(Box::new(err)),
+            };
+        }
+        err
+    }
+}
+
+impl Display for Chained {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+impl ErrorTrait for Chained {
+    fn source(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+        self.source.as_ref().map(|x| &**x as _)
+    }
+}
+
+fn source_names(err: &dyn ErrorTrait) -> Vec<String> {
+    let mut list = Vec::new();
+    let mut current = err.source();
+    while let Some(source) = current {
+        list.push(source.to_string());
+        current = source.source();
+    }
+    list
+}
+
+#[test]
+fn source_chain() {
+    let names = ["foo", "bar", "baz", "qux"];
+    let expected = &names[1..];
+
+    let err = RBoxError::new(Chained::chain(&names));
+    assert_eq!(err.to_string(), "foo");
+    assert_eq!(source_names(&err), expected);
+
+    {
+        let boxed: Box<dyn ErrorTrait + Send + Sync> = Box::new(Chained::chain(&names));
+        let err = RBoxError::from_box(boxed);
+        assert_eq!(source_names(&err), expected);
+        assert_eq!(source_names(&*err.into_box()), expected);
+    }
+
+    let formatted: RBoxError = err.to_formatted_error();
+    assert_eq!(source_names(&formatted), expected);
+    assert!(formatted
+        .source_error()
+        .unwrap()
+        .downcast_ref::<Chained>()
+        .is_none());
+
+    assert_eq!(source_names(&*err.into_box()), expected);
+
+    assert!(RBoxError::new(Stringy::new("hello")).source().is_none());
+    assert!(RBoxError::from_fmt(&Stringy::new("hello"))
+        .source()
+        .is_none());
+}
+
+#[test]
+fn downcast_source_links() {
+    let err = RBoxError::new(Chained::chain(&["foo", "bar", "baz"]));
+
+    let bar = err.source_error().unwrap();
+    assert_eq!(bar.downcast_ref::<Chained>().unwrap().name, "bar");
+    assert!(bar.downcast_ref::<Stringy>().is_none());
+
+    let baz = bar.source_error().unwrap();
+    assert_eq!(baz.downcast_ref::<Chained>().unwrap().name, "baz");
+    check_formatting_equivalence(baz, baz.downcast_ref::<Chained>().unwrap());
+
+    assert!(baz.source_error().is_none());
+}
+
+#[test]
+fn mutated_source_chain() {
+    let mut err = RBoxError::new(Chained::chain(&["foo", "bar", "baz"]));
+
+    err.downcast_mut::<Chained>().unwrap().source = None;
+
+    let bar = err.source_error().unwrap();
+    assert_eq!(bar.to_string(), "<unavailable error source>");
+    assert!(bar.downcast_ref::<Chained>().is_none());
+
+    let err = err.downcast::<Chained>().unwrap();
+    assert_eq!(err.name, "foo");
+}
+
+#[test]
+fn type_id_of_wrapped_error() {
+    let err = RBoxError::new(Stringy::new("hello"));
+    assert_eq!(err.type_id(), UTypeId::new::<Stringy>());
+
+    let boxed: Box<dyn ErrorTrait + Send + Sync> = Box::new(Stringy::new("hello"));
+    let err = RBoxError::from_box(boxed);
+    assert_eq!(
+        err.type_id(),
+        UTypeId::new::<Box<dyn ErrorTrait + Send + Sync>>()
+    );
+    assert!(err.downcast_ref::<Stringy>().is_some());
+}
+
+#[test]
+fn backtrace() {
+    // only `new_with_backtrace` captures backtraces
+    assert!(RBoxError::new(Stringy::new("hello")).backtrace().is_none());
+
+    let err = RBoxError::new_with_backtrace(Stringy::new("hello"));
+
+    #[cfg(not(feature = "rust_1_65"))]
+    assert!(err.backtrace().is_none());
+
+    assert!(err.downcast_ref::<Stringy>().is_some());
+
+    let formatted: RBoxError = err.to_formatted_error();
+    assert_eq!(
+        formatted.backtrace().map(|x| x.to_string()),
+        err.backtrace().map(|x| x.to_string()),
+    );
+}
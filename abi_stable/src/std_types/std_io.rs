@@ -21,6 +21,11 @@ use crate::{
 ///
 /// Using a struct with associated constants is the
 /// ffi-safe way of doing `#[non_exhaustive]` field-less enums.
+///
+/// Kinds that this version of `abi_stable` doesn't know about
+/// (eg: ones created by a library that uses a newer version)
+/// are preserved through their integer value,
+/// and converted to `std::io::ErrorKind::Other`.
 #[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 #[repr(C)]
 #[derive(StableAbi)]
@@ -31,6 +36,7 @@ pub struct RIoErrorKind {
 macro_rules! impl_error_kind {
     (
         $(
+            $(#[cfg($cfg:meta)])?
             $variant: ident, discriminant = $value: expr , message = $as_str_msg: expr ;
         )*
     ) => (
@@ -51,7 +57,8 @@ macro_rules! impl_error_kind {
                     $(
                         RIoErrorKind::$variant=> stringify!($variant),
                     )*
-                    _ => "Other",
+                    RIoErrorKind::Other => "Other",
+                    _ => return f.debug_tuple("RIoErrorKind").field(&self.value).finish(),
                 };
                 Display::fmt(s, f)
             }
@@ -62,6 +69,7 @@ macro_rules! impl_error_kind {
                 fn(this){
                     match this {
                         $(
+                            $(#[cfg($cfg)])?
                             ErrorKind::$variant=> RIoErrorKind::$variant,
                         )*
                         _ => RIoErrorKind::Other,
@@ -75,6 +83,7 @@ macro_rules! impl_error_kind {
                 fn(this){
                     match this {
                         $(
+                            $(#[cfg($cfg)])?
                             RIoErrorKind::$variant=> ErrorKind::$variant,
                         )*
                         _ => ErrorKind::Other,
@@ -114,6 +123,81 @@ impl_error_kind! {
     WriteZero, discriminant = 15 , message = "write zero" ;
     Interrupted, discriminant = 16 , message = "operation interrupted" ;
     UnexpectedEof, discriminant = 17 , message = "unexpected end of file" ;
+    Unsupported, discriminant = 18 , message = "unsupported" ;
+    OutOfMemory, discriminant = 19 , message = "out of memory" ;
+    #[cfg(feature = "rust_1_83")]
+    HostUnreachable, discriminant = 20 , message = "host unreachable" ;
+    #[cfg(feature = "rust_1_83")]
+    NetworkUnreachable, discriminant = 21 , message = "network unreachable" ;
+    #[cfg(feature = "rust_1_83")]
+    NetworkDown, discriminant = 22 , message = "network down" ;
+    #[cfg(feature = "rust_1_83")]
+    NotADirectory, discriminant = 23 , message = "not a directory" ;
+    #[cfg(feature = "rust_1_83")]
+    IsADirectory, discriminant = 24 , message = "is a directory" ;
+    #[cfg(feature = "rust_1_83")]
+    DirectoryNotEmpty, discriminant = 25 , message = "directory not empty" ;
+    #[cfg(feature = "rust_1_83")]
+    ReadOnlyFilesystem, discriminant = 26 , message = "read-only filesystem or storage medium" ;
+    #[cfg(feature = "rust_1_83")]
+    StaleNetworkFileHandle, discriminant = 27 , message = "stale network file handle" ;
+    #[cfg(feature = "rust_1_83")]
+    StorageFull, discriminant = 28 , message = "no storage space" ;
+    #[cfg(feature = "rust_1_83")]
+    NotSeekable, discriminant = 29 , message = "seek on unseekable file" ;
+    #[cfg(feature = "rust_1_83")]
+    FileTooLarge, discriminant = 30 , message = "file too large" ;
+    #[cfg(feature = "rust_1_83")]
+    ResourceBusy, discriminant = 31 , message = "resource busy" ;
+    #[cfg(feature = "rust_1_83")]
+    ExecutableFileBusy, discriminant = 32 , message = "executable file busy" ;
+    #[cfg(feature = "rust_1_83")]
+    Deadlock, discriminant = 33 , message = "deadlock" ;
+    #[cfg(feature = "rust_1_83")]
+    TooManyLinks, discriminant = 34 , message = "too many links" ;
+    #[cfg(feature = "rust_1_83")]
+    ArgumentListTooLong, discriminant = 35 , message = "argument list too long" ;
+    #[cfg(feature = "rust_1_85")]
+    QuotaExceeded, discriminant = 36 , message = "quota exceeded" ;
+    #[cfg(feature = "rust_1_85")]
+    CrossesDevices, discriminant = 37 , message = "cross-device link or rename" ;
+    #[cfg(feature = "rust_1_87")]
+    InvalidFilename, discriminant = 38 , message = "invalid filename" ;
+}
+
+impl RIoErrorKind {
+    /// Constructs an `RIoErrorKind` from its integer representation.
+    ///
+    /// This is useful to preserve error kinds that
+    /// this version of `abi_stable` doesn't have a constant for.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIoErrorKind;
+    ///
+    /// let kind = RIoErrorKind::NotFound;
+    ///
+    /// assert_eq!(RIoErrorKind::from_value(kind.value()), kind);
+    ///
+    /// ```
+    pub const fn from_value(value: u8) -> Self {
+        Self { value }
+    }
+
+    /// Gets the integer representation of this `RIoErrorKind`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIoErrorKind;
+    ///
+    /// assert_eq!(RIoErrorKind::Other.value(), 0);
+    ///
+    /// ```
+    pub const fn value(self) -> u8 {
+        self.value
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -161,6 +245,10 @@ impl_into_rust_repr! {
 
 /// Ffi safe equivalent to `std::io::Error`.
 ///
+/// Converting an `std::io::Error` into an `RIoError` and back preserves
+/// the raw OS error code (see [`RIoError::raw_os_error`])
+/// and the custom error payload (see [`RIoError::into_inner`]).
+///
 /// # Example
 ///
 /// Defining an extern function to write a slice into a writer twice.
@@ -195,13 +283,21 @@ pub struct RIoError {
     error: ROption<RBoxError>,
 }
 
+// `RIoError`s constructed from a raw OS error code store it in an `RBoxError`
+// whose vtable returns the code, so that the layout of `RIoError` is unchanged.
+fn is_os_error(error: &RBoxError) -> bool {
+    error.raw_os_error().is_some()
+}
+
 impl_from_rust_repr! {
     impl From<ioError> for RIoError {
         fn(this){
-            RIoError{
-                kind: this.kind().into(),
-                error: this.into_inner().map(RBoxError::from_box).into_c()
-            }
+            let kind = this.kind().into();
+            let error = match this.raw_os_error() {
+                Some(code) => RSome(RBoxError::from_raw_os_error(code)),
+                None => this.into_inner().map(RBoxError::from_box).into_c(),
+            };
+            RIoError{ kind, error }
         }
     }
 }
@@ -210,6 +306,9 @@ impl_into_rust_repr! {
     impl Into<ioError> for RIoError {
         fn(this){
             let kind = this.kind().into_::<ErrorKind>();
+            if let Some(code) = this.raw_os_error() {
+                return ioError::from_raw_os_error(code);
+            }
             match this.into_inner() {
                 Some(e) => ioError::new(kind, RBoxError::into_box(e)),
                 None => ioError::from(kind),
@@ -333,6 +432,63 @@ impl RIoError {
         }
     }
 
+    /// Constructs an `RIoError` from a raw OS error code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIoError;
+    ///
+    /// let err = RIoError::from_raw_os_error(2);
+    ///
+    /// assert_eq!(err.raw_os_error(), Some(2));
+    /// assert_eq!(std::io::Error::from(err).raw_os_error(), Some(2));
+    /// ```
+    pub fn from_raw_os_error(code: i32) -> Self {
+        Self {
+            kind: ioError::from_raw_os_error(code).kind().into_c(),
+            error: RSome(RBoxError::from_raw_os_error(code)),
+        }
+    }
+
+    /// Constructs an `RIoError` from the last error that occurred in the OS,
+    /// equivalent to `std::io::Error::last_os_error`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIoError;
+    ///
+    /// let err = RIoError::last_os_error();
+    ///
+    /// assert!(err.raw_os_error().is_some());
+    /// ```
+    pub fn last_os_error() -> Self {
+        ioError::last_os_error().into_c()
+    }
+
+    /// Gets the raw OS error code,
+    /// returning `None` if this wasn't constructed from an OS error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIoError;
+    /// use std::io::{Error, ErrorKind};
+    ///
+    /// let err = RIoError::from(Error::from_raw_os_error(13));
+    /// assert_eq!(err.raw_os_error(), Some(13));
+    ///
+    /// let err = RIoError::from_kind(ErrorKind::AlreadyExists);
+    /// assert_eq!(err.raw_os_error(), None);
+    /// ```
+    pub fn raw_os_error(&self) -> Option<i32> {
+        self.error
+            .as_ref()
+            .into_rust()
+            .and_then(RBoxError::raw_os_error)
+    }
+
     /// Retrieves the kind of io error.
     ///
     /// # Example
@@ -350,7 +506,8 @@ impl RIoError {
     }
 
     /// Gets the internal error,
-    /// returning `None` if this was constructed with `RIoError::from_kind`.
+    /// returning `None` if this was constructed with `RIoError::from_kind`,
+    /// or from a raw OS error.
     ///
     /// # Example
     ///
@@ -371,11 +528,15 @@ impl RIoError {
     ///
     /// ```
     pub fn get_ref(&self) -> Option<&RBoxError> {
-        self.error.as_ref().into_rust()
+        match &self.error {
+            RSome(e) if !is_os_error(e) => Some(e),
+            _ => None,
+        }
     }
 
     /// Gets the internal error,
-    /// returning `None` if this was constructed with `RIoError::from_kind`.
+    /// returning `None` if this was constructed with `RIoError::from_kind`,
+    /// or from a raw OS error.
     ///
     /// # Example
     ///
@@ -395,11 +556,15 @@ impl RIoError {
     ///
     /// ```
     pub fn get_mut(&mut self) -> Option<&mut RBoxError> {
-        self.error.as_mut().into_rust()
+        match &mut self.error {
+            RSome(e) if !is_os_error(e) => Some(e),
+            _ => None,
+        }
     }
 
     /// Converts this into the internal error,
-    /// returning `None` if this was constructed with `RIoError::from_kind`.
+    /// returning `None` if this was constructed with `RIoError::from_kind`,
+    /// or from a raw OS error.
     ///
     /// # Example
     ///
@@ -419,7 +584,10 @@ impl RIoError {
     ///
     /// ```
     pub fn into_inner(self) -> Option<RBoxError> {
-        self.error.into_rust()
+        match self.error {
+            RSome(e) if !is_os_error(&e) => Some(e),
+            _ => None,
+        }
     }
 }
 
@@ -460,6 +628,35 @@ mod error_kind_tests {
             assert_eq!(to.into_::<ErrorKind>(), from);
         }
     }
+
+    #[test]
+    fn newer_kinds() {
+        for (from, to) in [
+            (ErrorKind::Unsupported, RIoErrorKind::Unsupported),
+            (ErrorKind::OutOfMemory, RIoErrorKind::OutOfMemory),
+            #[cfg(feature = "rust_1_83")]
+            (ErrorKind::StorageFull, RIoErrorKind::StorageFull),
+            #[cfg(feature = "rust_1_83")]
+            (ErrorKind::NotADirectory, RIoErrorKind::NotADirectory),
+            #[cfg(feature = "rust_1_85")]
+            (ErrorKind::CrossesDevices, RIoErrorKind::CrossesDevices),
+        ] {
+            assert_eq!(RIoErrorKind::from(from), to);
+            assert_eq!(to.into_::<ErrorKind>(), from);
+        }
+    }
+
+    #[test]
+    fn unknown_kind() {
+        let kind = RIoErrorKind::from_value(200);
+        assert_eq!(kind.value(), 200);
+        assert_ne!(kind, RIoErrorKind::Other);
+        assert_eq!(kind.into_::<ErrorKind>(), ErrorKind::Other);
+        assert_eq!(format!("{:?}", kind), "RIoErrorKind(200)");
+
+        let err = RIoError::from(kind);
+        assert_eq!(err.kind().value(), 200);
+    }
 }
 
 #[cfg(all(test, not(feature = "only_new_tests")))]
@@ -508,6 +705,70 @@ mod io_error_tests {
         );
     }
 
+    #[test]
+    fn raw_os_error_roundtrip() {
+        // `ENOENT` on unix, `ERROR_FILE_NOT_FOUND` on Windows
+        let code = 2;
+        let std_err = ioError::from_raw_os_error(code);
+        let err = RIoError::from(std_err);
+
+        assert_eq!(err.raw_os_error(), Some(code));
+        assert_eq!(err.kind(), ioError::from_raw_os_error(code).kind().into_c());
+        assert!(err.get_ref().is_none());
+        check_formatting_equivalence(&ioError::from_raw_os_error(code), &err);
+
+        let back: ioError = err.into();
+        assert_eq!(back.raw_os_error(), Some(code));
+        assert_eq!(back.kind(), ErrorKind::NotFound);
+
+        assert_eq!(RIoError::from_raw_os_error(code).raw_os_error(), Some(code));
+        assert_eq!(
+            RIoError::from_kind(ErrorKind::NotFound).raw_os_error(),
+            None
+        );
+    }
+
+    #[test]
+    fn raw_os_error_from_other_library() {
+        let code = 2;
+        let err = RIoError::with_rboxerror(
+            ErrorKind::NotFound,
+            RBoxError::from_raw_os_error_in_other_library(code),
+        );
+
+        assert_eq!(err.raw_os_error(), Some(code));
+        assert!(err.get_ref().is_none());
+        check_formatting_equivalence(&ioError::from_raw_os_error(code), &err);
+
+        let back: ioError = err.into();
+        assert_eq!(back.raw_os_error(), Some(code));
+
+        let formatted: RBoxError =
+            RBoxError::from_raw_os_error_in_other_library(code).to_formatted_error();
+        assert_eq!(formatted.raw_os_error(), Some(code));
+    }
+
+    #[test]
+    fn custom_payload_roundtrip() {
+        let err = Stringy::new("What\nis\ra\tline");
+        let std_err = ioError::new(ErrorKind::InvalidData, err.clone());
+
+        let rerr = RIoError::from(std_err);
+        assert_eq!(rerr.raw_os_error(), None);
+        assert_eq!(rerr.kind(), RIoErrorKind::InvalidData);
+
+        let back: ioError = rerr.into();
+        assert_eq!(back.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            back.into_inner()
+                .unwrap()
+                .downcast::<Stringy>()
+                .unwrap()
+                .str,
+            err.str,
+        );
+    }
+
     #[test]
     fn from_rboxerror() {
         let err = Stringy::new("What\nis\ra\tline");
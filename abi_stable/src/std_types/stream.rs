@@ -0,0 +1,297 @@
+//! Contains an ffi-safe equivalent of `Pin<Box<dyn Stream>>`.
+
+use std::{
+    fmt::{self, Debug},
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{
+    marker_type::UnsyncSend,
+    pointer_trait::{AsMutPtr, TransmuteElement},
+    prefix_type::WithMetadata,
+    sabi_types::RMut,
+    std_types::{RBox, RContext, ROption, RPoll},
+};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests;
+
+/// Ffi-safe equivalent of `Pin<Box<dyn Stream<Item = T> + Send + 'a>>`,
+/// an asynchronous iterator.
+///
+/// An `RStream` can be constructed from a closure with [`RStream::from_fn`],
+/// or from a [`futures_core::Stream`] with `RStream::new`
+/// (this requires the "futures" feature).
+///
+/// With the "futures" feature enabled, `RStream` implements `futures_core::Stream`.
+///
+/// This type is `Send`, but not `Sync`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RStream, RVec};
+///
+/// use std::task::Poll;
+///
+/// # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+/// #     use std::{sync::Arc, task::{Context, Wake}};
+/// #     struct Unpark(std::thread::Thread);
+/// #     impl Wake for Unpark {
+/// #         fn wake(self: Arc<Self>) { self.0.unpark() }
+/// #     }
+/// #     let waker = Arc::new(Unpark(std::thread::current())).into();
+/// #     let mut cx = Context::from_waker(&waker);
+/// #     let mut fut = Box::pin(fut);
+/// #     loop {
+/// #         match fut.as_mut().poll(&mut cx) {
+/// #             Poll::Ready(x) => return x,
+/// #             Poll::Pending => std::thread::park(),
+/// #         }
+/// #     }
+/// # }
+/// let mut countdown = 3;
+/// let mut stream: RStream<'_, u32> = RStream::from_fn(move |_| {
+///     if countdown == 0 {
+///         Poll::Ready(None)
+///     } else {
+///         countdown -= 1;
+///         Poll::Ready(Some(countdown))
+///     }
+/// });
+///
+/// let list = block_on(async {
+///     let mut list = RVec::new();
+///     while let Some(x) = stream.next().await {
+///         list.push(x);
+///     }
+///     list
+/// });
+///
+/// assert_eq!(list, vec![2, 1, 0]);
+///
+/// ```
+///
+/// [`futures_core::Stream`]:
+/// https://docs.rs/futures-core/0.3/futures_core/stream/trait.Stream.html
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RStream<'a, T> {
+    ptr: RBox<()>,
+    vtable: StreamVtable_Ref<T>,
+    _marker: PhantomData<(&'a (), UnsyncSend)>,
+}
+
+impl<'a, T> RStream<'a, T> {
+    /// Constructs an `RStream` from a closure that polls for the next element,
+    /// returning `Poll::Ready(None)` once the stream ends.
+    ///
+    /// The closure must wake the waker of the `Context` once it can make progress,
+    /// whenever it returns `Poll::Pending`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RStream;
+    ///
+    /// use std::task::Poll;
+    ///
+    /// let mut iter = vec![3, 5, 8].into_iter();
+    /// let stream: RStream<'_, u32> = RStream::from_fn(move |_| Poll::Ready(iter.next()));
+    ///
+    /// ```
+    pub fn from_fn<F>(poll_next: F) -> Self
+    where
+        F: FnMut(&mut Context<'_>) -> Poll<Option<T>> + Send + 'a,
+    {
+        Self::from_poll_next(PollFn(poll_next))
+    }
+
+    /// Constructs an `RStream` from a `futures_core::Stream`, boxing it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RStream;
+    ///
+    /// use futures_core::Stream;
+    ///
+    /// use std::{
+    ///     pin::Pin,
+    ///     task::{Context, Poll},
+    /// };
+    ///
+    /// struct Repeat(u32);
+    ///
+    /// impl Stream for Repeat {
+    ///     type Item = u32;
+    ///
+    ///     fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<u32>> {
+    ///         Poll::Ready(Some(self.0))
+    ///     }
+    /// }
+    ///
+    /// let stream: RStream<'_, u32> = RStream::new(Repeat(3));
+    ///
+    /// ```
+    #[cfg(feature = "futures")]
+    #[cfg_attr(feature = "docsrs", doc(cfg(feature = "futures")))]
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: futures_core::Stream<Item = T> + Send + 'a,
+    {
+        Self::from_poll_next(FromStream(stream))
+    }
+
+    fn from_poll_next<S>(stream: S) -> Self
+    where
+        S: PollNext<Item = T> + Send + 'a,
+    {
+        unsafe {
+            Self {
+                ptr: RBox::new(stream).transmute_element::<()>(),
+                vtable: VTableGetter::<S>::STREAM_VTABLE,
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    /// Polls for the next element of the stream, with an `RContext`.
+    pub fn poll_next_with(&mut self, cx: RContext<'_>) -> RPoll<ROption<T>> {
+        // The stream is never moved out of the box, so it's pinned.
+        unsafe { self.vtable.poll_next()(self.ptr.as_rmut(), cx) }
+    }
+
+    /// Polls for the next element of the stream,
+    /// returning `Poll::Ready(None)` once the stream ends.
+    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let waker = cx.waker();
+        match self.get_mut().poll_next_with(RContext::from_waker(waker)) {
+            RPoll::Ready(x) => Poll::Ready(x.into_option()),
+            RPoll::Pending => Poll::Pending,
+        }
+    }
+
+    /// Returns a future which resolves to the next element of the stream,
+    /// or `None` once the stream ends.
+    pub fn next(&mut self) -> RNext<'_, 'a, T> {
+        RNext { stream: self }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<'a, T> futures_core::Stream for RStream<'a, T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        RStream::poll_next(self, cx)
+    }
+}
+
+impl<'a, T> Debug for RStream<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RStream{..}")
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// The future returned by [`RStream::next`].
+#[must_use = "futures do nothing unless polled"]
+pub struct RNext<'s, 'a, T> {
+    stream: &'s mut RStream<'a, T>,
+}
+
+impl<'s, 'a, T> Future for RNext<'s, 'a, T> {
+    type Output = Option<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        Pin::new(&mut *self.stream).poll_next(cx)
+    }
+}
+
+impl<'s, 'a, T> Debug for RNext<'s, 'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RNext{..}")
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+// The types that an `RStream` can be constructed from.
+trait PollNext {
+    type Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>>;
+}
+
+struct PollFn<F>(F);
+
+impl<F, T> PollNext for PollFn<F>
+where
+    F: FnMut(&mut Context<'_>) -> Poll<Option<T>>,
+{
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        // The closure is never pinned.
+        (unsafe { &mut self.get_unchecked_mut().0 })(cx)
+    }
+}
+
+#[cfg(feature = "futures")]
+struct FromStream<S>(S);
+
+#[cfg(feature = "futures")]
+impl<S> PollNext for FromStream<S>
+where
+    S: futures_core::Stream,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        unsafe { self.map_unchecked_mut(|x| &mut x.0) }.poll_next(cx)
+    }
+}
+
+#[derive(StableAbi)]
+#[repr(C)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+pub(crate) struct StreamVtable<T> {
+    #[sabi(last_prefix_field)]
+    poll_next: unsafe extern "C" fn(RMut<'_, ()>, RContext<'_>) -> RPoll<ROption<T>>,
+}
+
+struct VTableGetter<S>(PhantomData<S>);
+
+impl<S> VTableGetter<S>
+where
+    S: PollNext,
+{
+    staticref! {
+        const WM_STREAM: WithMetadata<StreamVtable<S::Item>> = WithMetadata::new(
+            StreamVtable {
+                poll_next: poll_next::<S>,
+            }
+        )
+    }
+
+    const STREAM_VTABLE: StreamVtable_Ref<S::Item> = StreamVtable_Ref(Self::WM_STREAM.as_prefix());
+}
+
+unsafe extern "C" fn poll_next<S>(this: RMut<'_, ()>, cx: RContext<'_>) -> RPoll<ROption<S::Item>>
+where
+    S: PollNext,
+{
+    extern_fn_panic_handling! {no_early_return;
+        let stream = unsafe { Pin::new_unchecked(this.transmute_into_mut::<S>()) };
+        cx.with_context(|cx| match stream.poll_next(cx) {
+            Poll::Ready(x) => RPoll::Ready(ROption::from(x)),
+            Poll::Pending => RPoll::Pending,
+        })
+    }
+}
@@ -0,0 +1,94 @@
+use super::*;
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use crate::{
+    std_types::{RArc, RVec},
+    test_utils::block_on,
+};
+
+fn collect<T>(mut stream: RStream<'_, T>) -> RVec<T> {
+    block_on(async move {
+        let mut list = RVec::new();
+        while let Some(x) = stream.next().await {
+            list.push(x);
+        }
+        list
+    })
+}
+
+#[test]
+fn from_fn_ready() {
+    let mut iter = vec![3, 5, 8].into_iter();
+    let stream = RStream::from_fn(move |_| Poll::Ready(iter.next()));
+    assert_eq!(collect(stream), vec![3, 5, 8]);
+}
+
+#[test]
+fn from_fn_pending() {
+    let polls = Arc::new(AtomicUsize::new(0));
+    let stream = RStream::from_fn({
+        let polls = polls.clone();
+        let mut pending = false;
+        move |cx| {
+            let n = polls.fetch_add(1, Ordering::SeqCst);
+            pending = !pending;
+            if pending {
+                // Waking from another thread
+                let waker = cx.waker().clone();
+                thread::spawn(move || waker.wake());
+                Poll::Pending
+            } else if n < 6 {
+                Poll::Ready(Some(n))
+            } else {
+                Poll::Ready(None)
+            }
+        }
+    });
+    assert_eq!(collect(stream), vec![1, 3, 5]);
+    assert_eq!(polls.load(Ordering::SeqCst), 8);
+}
+
+#[test]
+fn drops_stream() {
+    let arc = RArc::new(0);
+    let stream = RStream::from_fn({
+        let arc = arc.clone();
+        move |_| Poll::Ready(Some(*arc))
+    });
+    assert_eq!(RArc::strong_count(&arc), 2);
+    drop(stream);
+    assert_eq!(RArc::strong_count(&arc), 1);
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn from_futures_stream() {
+    struct Countdown(u32);
+
+    impl futures_core::Stream for Countdown {
+        type Item = u32;
+
+        fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<u32>> {
+            if self.0 == 0 {
+                Poll::Ready(None)
+            } else {
+                self.0 -= 1;
+                Poll::Ready(Some(self.0))
+            }
+        }
+    }
+
+    let stream = RStream::new(Countdown(3));
+    assert_eq!(collect(stream), vec![2, 1, 0]);
+
+    // `RStream` implements `Stream` itself
+    let stream = RStream::new(RStream::new(Countdown(2)));
+    assert_eq!(collect(stream), vec![1, 0]);
+}
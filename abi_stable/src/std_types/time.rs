@@ -1,6 +1,14 @@
-//! Contains ffi-safe equivalent of `std::time::Duration`.
+//! Contains ffi-safe equivalents of `std::time::{Duration, Instant, SystemTime}`.
 
-use std::time::Duration;
+use std::{
+    ops::{Add, AddAssign, Sub, SubAssign},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::sabi_types::LateStaticRef;
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
 
 /// Ffi-safe equivalent of `std::time::Duration` .
 ///
@@ -231,3 +239,568 @@ impl_into_rust_repr! {
         }
     }
 }
+
+///////////////////////////////////////////////////////////////////////////////
+
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+/// Ffi-safe equivalent of `std::time::SystemTime`.
+///
+/// This is stored as the amount of seconds and nanoseconds relative to the
+/// [`UNIX_EPOCH`](#associatedconstant.UNIX_EPOCH),
+/// with the seconds being negative for times before it.
+/// The nanoseconds are always in the `0..1_000_000_000` range,
+/// and are added to the seconds.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RDuration, RSystemTime};
+///
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+///
+/// let time = RSystemTime::from(UNIX_EPOCH + Duration::new(100, 500));
+/// assert_eq!(time.seconds(), 100);
+/// assert_eq!(time.subsec_nanos(), 500);
+///
+/// let before = RSystemTime::UNIX_EPOCH - RDuration::from_millis(1500);
+/// assert_eq!(before.seconds(), -2);
+/// assert_eq!(before.subsec_nanos(), 500_000_000);
+/// assert_eq!(
+///     SystemTime::from(before),
+///     UNIX_EPOCH - Duration::from_millis(1500),
+/// );
+///
+/// ```
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Deserialize, Serialize, StableAbi,
+)]
+#[repr(C)]
+pub struct RSystemTime {
+    seconds: i64,
+    subsec_nanos: u32,
+}
+
+impl RSystemTime {
+    /// An anchor in time, defined as `1970-01-01 00:00:00 UTC`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSystemTime;
+    ///
+    /// use std::time::UNIX_EPOCH;
+    ///
+    /// assert_eq!(RSystemTime::UNIX_EPOCH, RSystemTime::from(UNIX_EPOCH));
+    ///
+    /// ```
+    pub const UNIX_EPOCH: Self = Self::new(0, 0);
+
+    /// Constructs an `RSystemTime` from the seconds relative to the unix epoch,
+    /// and the nanoseconds added to them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `subsec_nanos` is `1_000_000_000` or larger.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RSystemTime};
+    ///
+    /// let time = RSystemTime::new(-1, 750_000_000);
+    /// assert_eq!(
+    ///     RSystemTime::UNIX_EPOCH.duration_since(time),
+    ///     Ok(RDuration::from_millis(250)),
+    /// );
+    ///
+    /// ```
+    pub const fn new(seconds: i64, subsec_nanos: u32) -> Self {
+        assert!(
+            subsec_nanos < NANOS_PER_SEC,
+            "subsec_nanos must be less than 1_000_000_000"
+        );
+        Self {
+            seconds,
+            subsec_nanos,
+        }
+    }
+
+    /// Gets the current system time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSystemTime;
+    ///
+    /// assert!(RSystemTime::now() > RSystemTime::UNIX_EPOCH);
+    ///
+    /// ```
+    pub fn now() -> Self {
+        SystemTime::now().into()
+    }
+
+    /// The amount of seconds relative to the unix epoch,
+    /// negative for times before it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSystemTime;
+    ///
+    /// assert_eq!(RSystemTime::new(-3, 10).seconds(), -3);
+    ///
+    /// ```
+    pub const fn seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    /// The amount of nanoseconds added to the [`seconds`](#method.seconds).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSystemTime;
+    ///
+    /// assert_eq!(RSystemTime::new(-3, 10).subsec_nanos(), 10);
+    ///
+    /// ```
+    pub const fn subsec_nanos(&self) -> u32 {
+        self.subsec_nanos
+    }
+
+    /// Returns the amount of time elapsed from `earlier` to `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` with the amount of time from `self` to `earlier`
+    /// if `earlier` is later than `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RSystemTime};
+    ///
+    /// let earlier = RSystemTime::new(10, 0);
+    /// let later = RSystemTime::new(12, 500);
+    ///
+    /// assert_eq!(later.duration_since(earlier), Ok(RDuration::new(2, 500)));
+    /// assert_eq!(earlier.duration_since(later), Err(RDuration::new(2, 500)));
+    ///
+    /// ```
+    pub fn duration_since(&self, earlier: RSystemTime) -> Result<RDuration, RDuration> {
+        if *self >= earlier {
+            Ok(nanos_to_duration(self.as_nanos() - earlier.as_nanos()))
+        } else {
+            Err(nanos_to_duration(earlier.as_nanos() - self.as_nanos()))
+        }
+    }
+
+    /// Returns the amount of time elapsed since `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` with the amount of time from now to `self`
+    /// if `self` is in the future.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RSystemTime};
+    ///
+    /// let time = RSystemTime::now() - RDuration::from_secs(60);
+    /// assert!(time.elapsed().unwrap() >= RDuration::from_secs(60));
+    ///
+    /// ```
+    pub fn elapsed(&self) -> Result<RDuration, RDuration> {
+        RSystemTime::now().duration_since(*self)
+    }
+
+    /// Returns `self + duration`, or `None` if the result can't be represented.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RSystemTime};
+    ///
+    /// assert_eq!(
+    ///     RSystemTime::UNIX_EPOCH.checked_add(RDuration::from_secs(5)),
+    ///     Some(RSystemTime::new(5, 0)),
+    /// );
+    /// assert_eq!(RSystemTime::new(i64::MAX, 0).checked_add(RDuration::from_secs(1)), None);
+    ///
+    /// ```
+    pub fn checked_add(&self, duration: RDuration) -> Option<RSystemTime> {
+        Self::from_nanos(self.as_nanos().checked_add(duration.as_nanos() as i128)?)
+    }
+
+    /// Returns `self - duration`, or `None` if the result can't be represented.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RSystemTime};
+    ///
+    /// assert_eq!(
+    ///     RSystemTime::UNIX_EPOCH.checked_sub(RDuration::from_nanos(1)),
+    ///     Some(RSystemTime::new(-1, 999_999_999)),
+    /// );
+    /// assert_eq!(RSystemTime::new(i64::MIN, 0).checked_sub(RDuration::from_secs(1)), None);
+    ///
+    /// ```
+    pub fn checked_sub(&self, duration: RDuration) -> Option<RSystemTime> {
+        Self::from_nanos(self.as_nanos().checked_sub(duration.as_nanos() as i128)?)
+    }
+
+    const fn as_nanos(&self) -> i128 {
+        self.seconds as i128 * NANOS_PER_SEC as i128 + self.subsec_nanos as i128
+    }
+
+    fn from_nanos(nanos: i128) -> Option<Self> {
+        let seconds = nanos.div_euclid(NANOS_PER_SEC as i128);
+        let subsec_nanos = nanos.rem_euclid(NANOS_PER_SEC as i128) as u32;
+        let seconds = i64::try_from(seconds).ok()?;
+        Some(Self::new(seconds, subsec_nanos))
+    }
+}
+
+fn nanos_to_duration(nanos: i128) -> RDuration {
+    RDuration::new(
+        (nanos / NANOS_PER_SEC as i128) as u64,
+        (nanos % NANOS_PER_SEC as i128) as u32,
+    )
+}
+
+impl Add<RDuration> for RSystemTime {
+    type Output = RSystemTime;
+
+    fn add(self, duration: RDuration) -> RSystemTime {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to RSystemTime")
+    }
+}
+
+impl AddAssign<RDuration> for RSystemTime {
+    fn add_assign(&mut self, duration: RDuration) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<RDuration> for RSystemTime {
+    type Output = RSystemTime;
+
+    fn sub(self, duration: RDuration) -> RSystemTime {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from RSystemTime")
+    }
+}
+
+impl SubAssign<RDuration> for RSystemTime {
+    fn sub_assign(&mut self, duration: RDuration) {
+        *self = *self - duration;
+    }
+}
+
+impl_from_rust_repr! {
+    impl From<SystemTime> for RSystemTime {
+        fn(this){
+            match this.duration_since(UNIX_EPOCH) {
+                Ok(dur) => RSystemTime::new(
+                    dur.as_secs() as i64,
+                    dur.subsec_nanos(),
+                ),
+                Err(e) => {
+                    let dur = e.duration();
+                    RSystemTime::from_nanos(-(dur.as_nanos() as i128))
+                        .expect("SystemTime out of the range of RSystemTime")
+                }
+            }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<SystemTime> for RSystemTime {
+        fn(this){
+            let nanos = Duration::from_nanos(this.subsec_nanos as u64);
+            if this.seconds >= 0 {
+                UNIX_EPOCH + Duration::from_secs(this.seconds as u64) + nanos
+            } else {
+                UNIX_EPOCH - Duration::from_secs(this.seconds.unsigned_abs()) + nanos
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `std::time::Instant`,
+/// a measurement of a monotonically nondecreasing clock.
+///
+/// # Comparing across libraries
+///
+/// `RInstant`s are stored as a point in a timeline shared by every dynamic library
+/// loaded by the executable, which is anchored to the [`SystemTime`]
+/// at the first time that the executable needed it.
+///
+/// Within a library, `RInstant`s have the same guarantees as `Instant`s.
+/// Across libraries, `RInstant`s can be compared and subtracted,
+/// since every library maps its `Instant`s to the timeline
+/// of the executable that loaded it.
+///
+/// Libraries that were loaded by an executable using an older version of abi_stable
+/// (or executables loading libraries that use an older version of abi_stable)
+/// use a timeline of their own, anchored at the first time that they needed it,
+/// so their `RInstant`s are only approximately comparable to the executable's.
+///
+/// Because the timeline is specific to the process,
+/// `RInstant` doesn't implement `Serialize`/`Deserialize`;
+/// [`RSystemTime`] can be used for timestamps that are stored or sent to other processes.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RDuration, RInstant};
+///
+/// let start = RInstant::now();
+/// let deadline = start + RDuration::from_secs(10);
+///
+/// assert!(RInstant::now() < deadline);
+/// assert_eq!(deadline - start, RDuration::from_secs(10));
+///
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, StableAbi)]
+#[repr(C)]
+pub struct RInstant {
+    since_epoch: RDuration,
+}
+
+/// Maps this library's `Instant`s to the shared `RInstant` timeline.
+struct InstantAnchor {
+    instant: Instant,
+    since_epoch: Duration,
+}
+
+/// The anchor of the timeline that's shared through the globals,
+/// only used by the executable/library that constructed the globals,
+/// or by libraries loaded with an older version of abi_stable.
+static TIMELINE_ANCHOR: LateStaticRef<&'static InstantAnchor> = LateStaticRef::new();
+
+/// The anchor of this library's `Instant`s in the shared timeline.
+static INSTANT_ANCHOR: LateStaticRef<&'static InstantAnchor> = LateStaticRef::new();
+
+fn instant_anchor() -> &'static InstantAnchor {
+    INSTANT_ANCHOR.init(|| {
+        let since_epoch = (crate::globals::initialized_globals_ext().instant_now())();
+        let anchor = InstantAnchor {
+            instant: Instant::now(),
+            since_epoch: since_epoch.into(),
+        };
+        Box::leak(Box::new(anchor))
+    })
+}
+
+/// Gets the current time in the shared `RInstant` timeline,
+/// stored in the globals so that all libraries use the same timeline.
+pub(crate) extern "C" fn timeline_now_for_ffi() -> RDuration {
+    let anchor = TIMELINE_ANCHOR.init(|| {
+        let anchor = InstantAnchor {
+            instant: Instant::now(),
+            since_epoch: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or(Duration::ZERO),
+        };
+        Box::leak(Box::new(anchor))
+    });
+    (anchor.since_epoch + anchor.instant.elapsed()).into()
+}
+
+impl RInstant {
+    /// Gets an `RInstant` for the current time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RInstant;
+    ///
+    /// let first = RInstant::now();
+    /// let second = RInstant::now();
+    /// assert!(first <= second);
+    ///
+    /// ```
+    pub fn now() -> Self {
+        Instant::now().into()
+    }
+
+    /// Returns the amount of time elapsed from `earlier` to `self`,
+    /// or zero if `earlier` is later than `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RInstant};
+    ///
+    /// let earlier = RInstant::now();
+    /// let later = earlier + RDuration::from_millis(300);
+    ///
+    /// assert_eq!(later.duration_since(earlier), RDuration::from_millis(300));
+    /// assert_eq!(earlier.duration_since(later), RDuration::from_secs(0));
+    ///
+    /// ```
+    pub fn duration_since(&self, earlier: RInstant) -> RDuration {
+        self.checked_duration_since(earlier)
+            .unwrap_or(RDuration::from_secs(0))
+    }
+
+    /// Returns the amount of time elapsed from `earlier` to `self`,
+    /// or `None` if `earlier` is later than `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RInstant};
+    ///
+    /// let earlier = RInstant::now();
+    /// let later = earlier + RDuration::from_millis(300);
+    ///
+    /// assert_eq!(
+    ///     later.checked_duration_since(earlier),
+    ///     Some(RDuration::from_millis(300)),
+    /// );
+    /// assert_eq!(earlier.checked_duration_since(later), None);
+    ///
+    /// ```
+    pub fn checked_duration_since(&self, earlier: RInstant) -> Option<RDuration> {
+        let this: Duration = self.since_epoch.into();
+        this.checked_sub(earlier.since_epoch.into()).map(From::from)
+    }
+
+    /// Returns the amount of time elapsed since `self` was created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RInstant};
+    ///
+    /// let start = RInstant::now();
+    /// std::thread::sleep(std::time::Duration::from_millis(10));
+    /// assert!(start.elapsed() >= RDuration::from_millis(10));
+    ///
+    /// ```
+    pub fn elapsed(&self) -> RDuration {
+        RInstant::now().duration_since(*self)
+    }
+
+    /// Returns `self + duration`, or `None` if the result can't be represented.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RInstant};
+    ///
+    /// let now = RInstant::now();
+    /// let later = now.checked_add(RDuration::from_secs(1)).unwrap();
+    /// assert_eq!(later - now, RDuration::from_secs(1));
+    ///
+    /// assert_eq!(now.checked_add(RDuration::new(u64::MAX, 0)), None);
+    ///
+    /// ```
+    pub fn checked_add(&self, duration: RDuration) -> Option<RInstant> {
+        let this: Duration = self.since_epoch.into();
+        this.checked_add(duration.into())
+            .map(Self::from_since_epoch)
+    }
+
+    /// Returns `self - duration`, or `None` if the result can't be represented.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RInstant};
+    ///
+    /// let now = RInstant::now();
+    /// let earlier = now.checked_sub(RDuration::from_secs(1)).unwrap();
+    /// assert_eq!(now - earlier, RDuration::from_secs(1));
+    ///
+    /// assert_eq!(now.checked_sub(RDuration::new(u64::MAX, 0)), None);
+    ///
+    /// ```
+    pub fn checked_sub(&self, duration: RDuration) -> Option<RInstant> {
+        let this: Duration = self.since_epoch.into();
+        this.checked_sub(duration.into())
+            .map(Self::from_since_epoch)
+    }
+
+    fn from_since_epoch(since_epoch: Duration) -> Self {
+        Self {
+            since_epoch: since_epoch.into(),
+        }
+    }
+}
+
+impl Add<RDuration> for RInstant {
+    type Output = RInstant;
+
+    fn add(self, duration: RDuration) -> RInstant {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to RInstant")
+    }
+}
+
+impl AddAssign<RDuration> for RInstant {
+    fn add_assign(&mut self, duration: RDuration) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<RDuration> for RInstant {
+    type Output = RInstant;
+
+    fn sub(self, duration: RDuration) -> RInstant {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from RInstant")
+    }
+}
+
+impl SubAssign<RDuration> for RInstant {
+    fn sub_assign(&mut self, duration: RDuration) {
+        *self = *self - duration;
+    }
+}
+
+impl Sub<RInstant> for RInstant {
+    type Output = RDuration;
+
+    /// Returns the amount of time elapsed from `other` to `self`,
+    /// or zero if `other` is later than `self`.
+    fn sub(self, other: RInstant) -> RDuration {
+        self.duration_since(other)
+    }
+}
+
+impl_from_rust_repr! {
+    impl From<Instant> for RInstant {
+        fn(this){
+            let anchor = instant_anchor();
+            let since_epoch = match this.checked_duration_since(anchor.instant) {
+                Some(after) => anchor.since_epoch + after,
+                None => anchor.since_epoch
+                    .saturating_sub(anchor.instant.duration_since(this)),
+            };
+            RInstant::from_since_epoch(since_epoch)
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<Instant> for RInstant {
+        fn(this){
+            let anchor = instant_anchor();
+            let since_epoch: Duration = this.since_epoch.into();
+            match since_epoch.checked_sub(anchor.since_epoch) {
+                Some(after) => anchor.instant + after,
+                None => anchor.instant - (anchor.since_epoch - since_epoch),
+            }
+        }
+    }
+}
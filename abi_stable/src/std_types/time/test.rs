@@ -0,0 +1,141 @@
+use super::*;
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[test]
+fn system_time_roundtrip() {
+    let list = vec![
+        UNIX_EPOCH,
+        UNIX_EPOCH + Duration::new(0, 1),
+        UNIX_EPOCH + Duration::new(1_600_000_000, 123_456_789),
+        UNIX_EPOCH - Duration::new(0, 1),
+        UNIX_EPOCH - Duration::new(1, 0),
+        UNIX_EPOCH - Duration::new(86_400, 999_999_999),
+        SystemTime::now(),
+    ];
+
+    for time in list {
+        let rtime = RSystemTime::from(time);
+        assert!(rtime.subsec_nanos() < 1_000_000_000, "{:?}", rtime);
+        assert_eq!(SystemTime::from(rtime), time);
+    }
+
+    assert_eq!(
+        RSystemTime::from(UNIX_EPOCH - Duration::new(1, 1)),
+        RSystemTime::new(-2, 999_999_999),
+    );
+}
+
+#[test]
+fn system_time_ordering() {
+    let times = [
+        RSystemTime::new(-2, 999_999_999),
+        RSystemTime::new(-1, 0),
+        RSystemTime::new(-1, 1),
+        RSystemTime::UNIX_EPOCH,
+        RSystemTime::new(0, 1),
+        RSystemTime::new(1, 0),
+    ];
+
+    for pair in times.windows(2) {
+        assert!(pair[0] < pair[1], "{:?}", pair);
+        assert!(SystemTime::from(pair[0]) < SystemTime::from(pair[1]));
+    }
+}
+
+#[test]
+fn system_time_arithmetic() {
+    let time = RSystemTime::new(-1, 250_000_000);
+
+    assert_eq!(time + RDuration::from_millis(750), RSystemTime::UNIX_EPOCH);
+    assert_eq!(
+        time + RDuration::from_millis(1000),
+        RSystemTime::new(0, 250_000_000)
+    );
+    assert_eq!(
+        time - RDuration::from_millis(500),
+        RSystemTime::new(-2, 750_000_000)
+    );
+
+    let mut assigned = time;
+    assigned += RDuration::from_secs(3);
+    assigned -= RDuration::from_secs(1);
+    assert_eq!(assigned, RSystemTime::new(1, 250_000_000));
+
+    assert_eq!(assigned.duration_since(time), Ok(RDuration::from_secs(2)));
+    assert_eq!(time.duration_since(assigned), Err(RDuration::from_secs(2)));
+    assert_eq!(
+        RSystemTime::new(i64::MAX, 0).duration_since(RSystemTime::new(i64::MIN, 0)),
+        Ok(RDuration::from_secs(u64::MAX))
+    );
+}
+
+#[test]
+fn system_time_serde() {
+    let time = RSystemTime::new(-5, 100);
+    let json = serde_json::to_string(&time).unwrap();
+    assert_eq!(json, r#"{"seconds":-5,"subsec_nanos":100}"#);
+    assert_eq!(serde_json::from_str::<RSystemTime>(&json).unwrap(), time);
+}
+
+#[test]
+fn instant_roundtrip() {
+    let now = Instant::now();
+    let list = vec![
+        now,
+        now + Duration::from_secs(1000),
+        now.checked_sub(Duration::from_millis(10)).unwrap_or(now),
+    ];
+
+    for instant in list {
+        assert_eq!(Instant::from(RInstant::from(instant)), instant);
+    }
+}
+
+#[test]
+fn instant_arithmetic() {
+    let start = RInstant::now();
+    let later = start + RDuration::new(2, 500);
+
+    assert!(start < later);
+    assert_eq!(later - start, RDuration::new(2, 500));
+    assert_eq!(start - later, RDuration::from_secs(0));
+    assert_eq!(
+        later.checked_duration_since(start),
+        Some(RDuration::new(2, 500))
+    );
+    assert_eq!(start.checked_duration_since(later), None);
+    assert_eq!(later - RDuration::new(2, 500), start);
+
+    let mut assigned = start;
+    assigned += RDuration::from_secs(10);
+    assigned -= RDuration::from_secs(4);
+    assert_eq!(assigned - start, RDuration::from_secs(6));
+
+    let std_start: Instant = start.into();
+    let std_later: Instant = later.into();
+    assert_eq!(std_later - std_start, Duration::new(2, 500));
+}
+
+#[test]
+fn instant_monotonic() {
+    let mut prev = RInstant::now();
+    for _ in 0..1000 {
+        let next = RInstant::now();
+        assert!(prev <= next);
+        prev = next;
+    }
+}
+
+#[test]
+fn instant_uses_global_timeline() {
+    let timeline_now = crate::globals::initialized_globals_ext().instant_now();
+
+    let before: Duration = timeline_now().into();
+    let now: Duration = RInstant::now().since_epoch.into();
+    let after: Duration = timeline_now().into();
+
+    let margin = Duration::from_millis(100);
+    assert!(before <= now + margin, "{:?} {:?}", before, now);
+    assert!(now <= after + margin, "{:?} {:?}", now, after);
+}
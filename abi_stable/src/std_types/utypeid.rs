@@ -106,6 +106,25 @@ impl UTypeId {
             type_id_array: get_typeid::<T>(),
         }
     }
+
+    /// Whether both `UTypeId`s were constructed in the same dynamic library/executable.
+    pub(crate) fn is_same_executable(&self, other: &Self) -> bool {
+        self.executable_identity == other.executable_identity
+    }
+
+    /// Constructs a `UTypeId` as though it was constructed in another dynamic library.
+    #[cfg(test)]
+    pub(crate) fn new_in_other_library<T>() -> Self
+    where
+        T: 'static,
+    {
+        static OTHER_LIBRARY_IDENTITY: AtomicUsize = AtomicUsize::new(1);
+
+        Self {
+            executable_identity: &OTHER_LIBRARY_IDENTITY,
+            type_id_array: get_typeid::<T>(),
+        }
+    }
 }
 
 /////////////////////////////////////////////////////////////////////////////
@@ -3,6 +3,10 @@
 use std::{
     error::Error as ErrorTrait,
     fmt::{self, Debug, Display},
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread,
 };
 
 #[allow(unused_imports)]
@@ -22,6 +26,28 @@ where
     assert_eq!(format!("{:#}", left), format!("{:#}", right));
 }
 
+/// Runs `fut` to completion on the current thread,
+/// parking the thread while the future is pending.
+pub fn block_on<F: Future>(fut: F) -> F::Output {
+    struct Unpark(thread::Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark()
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = Box::pin(fut);
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(x) => return x,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
 /// Returns the address this dereferences to.
 pub fn deref_address<D>(ptr: &D) -> usize
 where
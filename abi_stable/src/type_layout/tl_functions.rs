@@ -191,10 +191,12 @@ impl PartialEq for TLFunctionSlice {
 
 ///////////////////////////////////////////////////////////////////////////////
 
-/// Stores all the supported function qualifiers.
+/// Stores all the supported function qualifiers,
+/// including the calling convention of the function.
 ///
 /// Currently only these are supported:
 /// - `unsafe`
+/// - `extern "C-unwind"` (the `extern "C"` ABI is the default)
 ///
 /// More may be added in an ABI compatible version
 #[repr(transparent)]
@@ -206,6 +208,7 @@ impl TLFunctionQualifiers {
     pub const NEW: Self = Self(0);
 
     const UNSAFE_BIT: u16 = 1;
+    const C_UNWIND_BIT: u16 = 2;
 
     /// Whether the function is `unsafe`
     pub const fn is_unsafe(&self) -> bool {
@@ -216,6 +219,26 @@ impl TLFunctionQualifiers {
         self.0 |= Self::UNSAFE_BIT;
         self
     }
+
+    /// Whether the function uses the `extern "C-unwind"` ABI
+    pub const fn is_c_unwind(&self) -> bool {
+        (self.0 & Self::C_UNWIND_BIT) != 0
+    }
+    /// Marks the function as using the `extern "C-unwind"` ABI
+    pub const fn set_c_unwind(mut self) -> Self {
+        self.0 |= Self::C_UNWIND_BIT;
+        self
+    }
+
+    /// The name of the ABI of the function,
+    /// as written in `extern "<abi>" fn`.
+    pub const fn abi(&self) -> &'static str {
+        if self.is_c_unwind() {
+            "C-unwind"
+        } else {
+            "C"
+        }
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -393,6 +416,9 @@ impl Display for TLFunction {
         if self.fn_qualifs.is_unsafe() {
             f.write_str("unsafe ")?;
         }
+        if self.fn_qualifs.is_c_unwind() {
+            f.write_str("extern \"C-unwind\" ")?;
+        }
         f.write_str("fn(")?;
         let params = self.get_params();
         let param_count = params.len();
@@ -13,4 +13,21 @@ fn test_qualifiers() {
         assert!(!safe.is_unsafe());
         assert!(unsafe_.is_unsafe());
     }
+    {
+        let c = TLFunctionQualifiers::NEW;
+        let c_unwind = TLFunctionQualifiers::NEW.set_c_unwind();
+        let unsafe_c_unwind = c_unwind.set_unsafe();
+
+        assert_ne!(c, c_unwind);
+        assert_ne!(c_unwind, unsafe_c_unwind);
+
+        assert!(!c.is_c_unwind());
+        assert!(c_unwind.is_c_unwind());
+        assert!(unsafe_c_unwind.is_c_unwind());
+        assert!(!c_unwind.is_unsafe());
+        assert!(unsafe_c_unwind.is_unsafe());
+
+        assert_eq!(c.abi(), "C");
+        assert_eq!(c_unwind.abi(), "C-unwind");
+    }
 }
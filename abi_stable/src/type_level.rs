@@ -86,6 +86,9 @@ pub mod trait_marker {
     /// Represents the [`std::marker::Unpin`] trait.
     pub struct Unpin;
 
+    /// Represents the [`SabiKey`](crate::erased_types::SabiKey) trait.
+    pub struct SabiKey;
+
     #[doc(hidden)]
     #[allow(non_camel_case_types)]
     pub struct define_this_in_the_impl_InterfaceType_macro;
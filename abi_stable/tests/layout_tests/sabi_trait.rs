@@ -4,7 +4,7 @@ use abi_stable::{
         CheckingGlobals,
     },
     sabi_trait,
-    std_types::RBox,
+    std_types::{RBox, RString},
     type_layout::TypeLayout,
     StableAbi,
 };
@@ -200,3 +200,28 @@ fn incompatible_supertraits() {
             .any(|err| matches!(err, AbiInstability::ExtraCheckError { .. })));
     });
 }
+
+mod assoc_type {
+    use super::*;
+    #[sabi_trait]
+    pub trait Trait {
+        type Output: StableAbi;
+
+        #[sabi(last_prefix_field)]
+        fn apply(&self, l: u32, r: u32) -> Self::Output;
+    }
+}
+
+#[test]
+fn changing_assoc_types() {
+    let list = vec![
+        <assoc_type::Trait_TO<'_, RBox<()>, u32> as StableAbi>::LAYOUT,
+        <assoc_type::Trait_TO<'_, RBox<()>, u64> as StableAbi>::LAYOUT,
+        <assoc_type::Trait_TO<'_, RBox<()>, RString> as StableAbi>::LAYOUT,
+    ];
+    check_equality(&list[..], |errs| {
+        assert!(errs
+            .iter()
+            .any(|err| matches!(err, AbiInstability::Name(..))));
+    });
+}
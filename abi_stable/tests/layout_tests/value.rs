@@ -386,6 +386,15 @@ fn same_different_abi_stability() {
         ]);
     }
 
+    #[cfg(feature = "rust_1_71")]
+    {
+        list.extend(vec![
+            <fn_c_unwind::Fn>::LAYOUT,
+            <fn_unsafe_c_unwind::Fn>::LAYOUT,
+            <extern "C-unwind" fn()>::LAYOUT,
+        ]);
+    }
+
     {
         list.extend(vec![
             <[u8; 0]>::LAYOUT,
@@ -691,6 +700,39 @@ pub(super) mod fn_unsafe {
     #[derive(abi_stable::StableAbi)]
     pub struct Fn(unsafe extern "C" fn(u8));
 }
+#[cfg(feature = "rust_1_71")]
+pub(super) mod fn_c_unwind {
+    #[repr(C)]
+    #[derive(abi_stable::StableAbi)]
+    pub struct Fn(extern "C-unwind" fn(u8));
+}
+#[cfg(feature = "rust_1_71")]
+pub(super) mod fn_unsafe_c_unwind {
+    #[repr(C)]
+    #[derive(abi_stable::StableAbi)]
+    pub struct Fn(unsafe extern "C-unwind" fn(u8));
+}
+
+#[cfg(feature = "rust_1_71")]
+#[cfg_attr(not(miri), test)]
+fn different_fn_abi() {
+    let pairs = [
+        (<fn_safe::Fn>::LAYOUT, <fn_c_unwind::Fn>::LAYOUT),
+        (<fn_unsafe::Fn>::LAYOUT, <fn_unsafe_c_unwind::Fn>::LAYOUT),
+    ];
+
+    for (c_fn, c_unwind_fn) in pairs {
+        for (interface, impl_) in [(c_fn, c_unwind_fn), (c_unwind_fn, c_fn)] {
+            let errs = check_layout_compatibility(interface, impl_)
+                .unwrap_err()
+                .flatten_errors();
+
+            assert!(errs
+                .iter()
+                .any(|err| matches!(err, AbiInstability::FnQualifierMismatch { .. })));
+        }
+    }
+}
 
 //////////////////////////////////////////////////////////
 ////    Enums
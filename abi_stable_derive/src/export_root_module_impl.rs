@@ -8,7 +8,7 @@ use syn::Ident;
 
 use proc_macro2::Span;
 
-use abi_stable_shared::mangled_root_module_loader_name;
+use abi_stable_shared::{mangled_globals_ext_initializer_name, mangled_root_module_loader_name};
 
 #[doc(hidden)]
 pub fn export_root_module_attr(_attr: TokenStream1, item: TokenStream1) -> TokenStream1 {
@@ -51,6 +51,7 @@ fn export_root_module_inner(mut input: ItemFn) -> Result<TokenStream2, syn::Erro
     let original_fn_ident = &input.sig.ident;
 
     let export_name = Ident::new(&mangled_root_module_loader_name(), Span::call_site());
+    let globals_ext_name = Ident::new(&mangled_globals_ext_initializer_name(), Span::call_site());
 
     Ok(quote!(
         #input
@@ -70,6 +71,10 @@ fn export_root_module_inner(mut input: ItemFn) -> Result<TokenStream2, syn::Erro
                 )
             }
         };
+
+        #[no_mangle]
+        #vis static #globals_ext_name: ::abi_stable::library::InitGlobalsExtWith =
+            ::abi_stable::library::INIT_GLOBALS_EXT_WITH;
     ))
 }
 
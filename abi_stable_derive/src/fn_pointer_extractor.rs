@@ -64,6 +64,9 @@ pub(crate) struct Function<'a> {
 
     pub(crate) is_unsafe: bool,
 
+    /// Whether the function pointer uses the `extern "C-unwind"` ABI.
+    pub(crate) is_c_unwind: bool,
+
     /// The Span for the first time that a bound lifetime appears in the type definition.
     pub(crate) bound_lt_spans: Ignored<Vec<Option<Span>>>,
 
@@ -261,10 +264,19 @@ impl<'a> VisitMut for TypeVisitor<'a> {
 
         let is_unsafe = func.unsafety.is_some();
 
-        let abi = func.abi.as_ref().map(|x| x.name.as_ref());
+        let mut is_c_unwind = false;
+
+        let abi = func.abi.as_mut().map(|x| &mut x.name);
         const ABI_ERR: &str = "must write `extern \"C\" fn` for function pointer types.";
         match abi {
             Some(Some(abi)) if *abi == ctokens.c_abi_lit => {}
+            Some(Some(abi)) if abi.value() == "C-unwind" => {
+                // The ABI is stored in the `TLFunction` of this function pointer,
+                // the type used to get the layout of the field is an `extern "C" fn()`,
+                // so that this doesn't require `StableAbi` impls for `extern "C-unwind" fn()`.
+                is_c_unwind = true;
+                *abi = syn::LitStr::new("C", abi.span());
+            }
             Some(Some(abi)) => {
                 self.vars
                     .errors
@@ -308,6 +320,7 @@ impl<'a> VisitMut for TypeVisitor<'a> {
                 named_bound_lt_set: Ignored::new(named_bound_lt_set),
                 bound_lt_spans: Ignored::new(vec![None; bound_lts_count]),
                 is_unsafe,
+                is_c_unwind,
                 params: Vec::new(),
                 returns: None,
             },
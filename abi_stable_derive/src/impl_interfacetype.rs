@@ -65,6 +65,12 @@ impl UsableBy {
         robject: true,
         dyn_trait: true,
     };
+    /// For traits that can only be required in `InterfaceType` implementations,
+    /// and can't be supertraits of `#[sabi_trait]` traits.
+    pub const INTERFACE_TYPE: Self = Self {
+        robject: false,
+        dyn_trait: false,
+    };
 
     pub const fn robject(&self) -> bool {
         self.robject
@@ -215,6 +221,7 @@ usable_traits! {
     io_buf_read=(IoBufRead,"::std::io::BufRead",false,UB::DYN_TRAIT),
     error=(Error,"::std::error::Error",false,UB::ROBJECT_AND_DYN_TRAIT),
     unpin=(Unpin,"::std::marker::Unpin",false,UB::ROBJECT_AND_DYN_TRAIT),
+    sabi_key=(SabiKey,"::abi_stable::erased_types::SabiKey",false,UB::INTERFACE_TYPE),
 }
 
 pub(crate) fn private_associated_type() -> syn::Ident {
@@ -79,10 +79,15 @@ pub fn get_root_module_static(_: TokenStream1) -> TokenStream1 {
 pub fn __const_mangled_root_module_loader_name(_: TokenStream1) -> TokenStream1 {
     let name = abi_stable_shared::mangled_root_module_loader_name();
     let name_nulled = format!("{}\0", name);
+    let ext_name_nulled = format!(
+        "{}\0",
+        abi_stable_shared::mangled_globals_ext_initializer_name()
+    );
 
     quote!(
         const PRIV_MANGLED_ROOT_MODULE_LOADER_NAME: &str = #name;
         const PRIV_MANGLED_ROOT_MODULE_LOADER_NAME_NUL: &str = #name_nulled;
+        const PRIV_MANGLED_GLOBALS_EXT_INITIALIZER_NAME_NUL: &str = #ext_name_nulled;
     )
     .into()
 }
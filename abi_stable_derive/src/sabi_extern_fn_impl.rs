@@ -9,7 +9,7 @@ use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
 
 use quote::{quote, ToTokens};
 
-use syn::{Expr, ItemFn};
+use syn::{parse_quote, Expr, ItemFn, ReturnType};
 
 use crate::parse_or_compile_err;
 
@@ -33,23 +33,57 @@ pub enum WithEarlyReturn {
     Yes,
 }
 
-/// Converts a function into an `extern "C" fn` which aborts on panic.
-pub(crate) fn convert_to_sabi_extern_fn(with_early_return: WithEarlyReturn, item: &mut ItemFn) {
-    let no_early_return = match with_early_return {
-        WithEarlyReturn::No => Some(quote!( no_early_return; )),
-        WithEarlyReturn::Yes => None,
+/// What the generated function does when it panics.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OnPanic {
+    /// Aborts the process.
+    Abort(WithEarlyReturn),
+    /// Catches the panic, returning it in the `RErr` variant of an `RResult`.
+    Catch,
+    /// Lets the panic unwind into the caller, using the `extern "C-unwind"` ABI.
+    Unwind,
+}
+
+/// Converts a function into an `extern "C" fn` which either aborts on panic,
+/// or returns the panic as an error,
+/// or into an `extern "C-unwind" fn` which lets panics unwind.
+pub(crate) fn convert_to_sabi_extern_fn(on_panic: OnPanic, item: &mut ItemFn) {
+    let mode = match on_panic {
+        OnPanic::Abort(WithEarlyReturn::No) => Some(quote!( no_early_return; )),
+        OnPanic::Abort(WithEarlyReturn::Yes) => None,
+        OnPanic::Catch => Some(quote!( catch_panic; )),
+        OnPanic::Unwind => None,
+    };
+
+    let abi = match on_panic {
+        OnPanic::Unwind => "C-unwind",
+        _ => "C",
     };
 
     item.sig.abi = Some(syn::Abi {
         extern_token: Default::default(),
-        name: Some(syn::LitStr::new("C", Span::call_site())),
+        name: Some(syn::LitStr::new(abi, Span::call_site())),
     });
 
+    if let OnPanic::Unwind = on_panic {
+        return;
+    }
+
+    if let OnPanic::Catch = on_panic {
+        let ret_ty = match &item.sig.output {
+            ReturnType::Default => quote!(()),
+            ReturnType::Type(_, ty) => ty.to_token_stream(),
+        };
+        item.sig.output = parse_quote!(
+            -> ::abi_stable::std_types::RResult<#ret_ty, ::abi_stable::sabi_types::RPanicPayload>
+        );
+    }
+
     let statements = mem::take(&mut item.block.stmts);
 
     let x = quote! {
         ::abi_stable::extern_fn_panic_handling!(
-            #no_early_return
+            #mode
 
             #(#statements)*
         )
@@ -61,13 +95,17 @@ pub(crate) fn convert_to_sabi_extern_fn(with_early_return: WithEarlyReturn, item
 }
 
 fn sabi_extern_fn_inner(attr: TokenStream2, mut item: ItemFn) -> Result<TokenStream2, syn::Error> {
-    let with_early_return = match attr.into_iter().next() {
-        Some(TokenTree::Ident(ref ident)) if ident == "no_early_return" => WithEarlyReturn::No,
+    let on_panic = match attr.into_iter().next() {
+        Some(TokenTree::Ident(ref ident)) if ident == "no_early_return" => {
+            OnPanic::Abort(WithEarlyReturn::No)
+        }
+        Some(TokenTree::Ident(ref ident)) if ident == "catch_panic" => OnPanic::Catch,
+        Some(TokenTree::Ident(ref ident)) if ident == "c_unwind" => OnPanic::Unwind,
         Some(tt) => return_spanned_err!(tt, "Unrecognized `#[sabi_extern_fn]` parameter",),
-        None => WithEarlyReturn::Yes,
+        None => OnPanic::Abort(WithEarlyReturn::Yes),
     };
 
-    convert_to_sabi_extern_fn(with_early_return, &mut item);
+    convert_to_sabi_extern_fn(on_panic, &mut item);
 
     Ok(item.into_token_stream())
 }
@@ -78,6 +116,9 @@ mod tests {
 
     #[test]
     fn test_output() {
+        let rresult = quote!(::abi_stable::std_types::RResult);
+        let payload = quote!(::abi_stable::sabi_types::RPanicPayload);
+
         let list = vec![
             (
                 "",
@@ -120,6 +161,51 @@ mod tests {
                     }
                 ),
             ),
+            (
+                "catch_panic",
+                r##"
+                    fn hello() {
+                        println!("{}", HELLO);
+                    }
+                "##,
+                quote!(
+                    extern "C" fn hello() -> #rresult<(), #payload> {
+                        ::abi_stable::extern_fn_panic_handling!(
+                            catch_panic;
+                            println!("{}", HELLO);
+                        )
+                    }
+                ),
+            ),
+            (
+                "catch_panic",
+                r##"
+                    pub fn hello<'a>() -> RStr<'a> {
+                        "stuff".into()
+                    }
+                "##,
+                quote!(
+                    pub extern "C" fn hello<'a>() -> #rresult<RStr<'a>, #payload> {
+                        ::abi_stable::extern_fn_panic_handling!(
+                            catch_panic;
+                            "stuff".into()
+                        )
+                    }
+                ),
+            ),
+            (
+                "c_unwind",
+                r##"
+                    pub fn hello(x: u32) -> u32 {
+                        x + 1
+                    }
+                "##,
+                quote!(
+                    pub extern "C-unwind" fn hello(x: u32) -> u32 {
+                        x + 1
+                    }
+                ),
+            ),
         ];
 
         for (attr, item, expected) in list {
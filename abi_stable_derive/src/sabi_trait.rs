@@ -21,11 +21,14 @@ use as_derive_utils::{
 
 mod attribute_parsing;
 mod common_tokens;
+mod delegate;
 mod impl_delegations;
 mod lifetime_unelider;
 mod method_where_clause;
 mod methods_tokenizer;
+mod mock;
 mod replace_self_path;
+mod supertrait_impl;
 mod trait_definition;
 
 #[cfg(test)]
@@ -59,6 +62,8 @@ struct TokenizerParams<'a> {
     trait_interface: &'a syn::Ident,
     make_vtable_ident: &'a syn::Ident,
     trait_cto_ident: &'a syn::Ident,
+    trait_mock: &'a syn::Ident,
+    trait_delegate: &'a syn::Ident,
     /// TokenStreams that don't have a `'lt,` if the trait object requires
     /// `'static` to be constructed.
     lt_tokens: &'a LifetimeTokens,
@@ -90,6 +95,8 @@ pub fn derive_sabi_trait(item: ItemTrait) -> Result<TokenStream2, syn::Error> {
     let trait_interface = &parse_str_as_ident(&format!("{}_Interface", trait_ident));
     let make_vtable_ident = &parse_str_as_ident(&format!("{}_MV", trait_ident));
     let trait_cto_ident = &parse_str_as_ident(&format!("{}_CTO", trait_ident));
+    let trait_mock = &parse_str_as_ident(&format!("{}_Mock", trait_ident));
+    let trait_delegate = &parse_str_as_ident(&format!("{}_Delegate", trait_ident));
 
     let mut mod_contents = TokenStream2::default();
 
@@ -110,6 +117,8 @@ pub fn derive_sabi_trait(item: ItemTrait) -> Result<TokenStream2, syn::Error> {
         trait_interface,
         make_vtable_ident,
         trait_cto_ident,
+        trait_mock,
+        trait_delegate,
     };
 
     first_items(tokenizer_params, &mut mod_contents);
@@ -118,14 +127,24 @@ pub fn derive_sabi_trait(item: ItemTrait) -> Result<TokenStream2, syn::Error> {
 
     trait_and_impl(tokenizer_params, &mut mod_contents);
 
+    supertrait_impl::supertrait_impl_macro(tokenizer_params, &mut mod_contents);
+
+    supertrait_impl::supertrait_impls(tokenizer_params, &mut mod_contents);
+
     methods_impls(tokenizer_params, &mut mod_contents)?;
 
     declare_vtable(tokenizer_params, &mut mod_contents);
 
     vtable_impl(tokenizer_params, &mut mod_contents);
 
+    capability_impls(tokenizer_params, &mut mod_contents);
+
     impl_delegations::delegated_impls(tokenizer_params, &mut mod_contents);
 
+    mock::mock_items(tokenizer_params, &mut mod_contents);
+
+    delegate::delegate_items(tokenizer_params, &mut mod_contents);
+
     let doc_hidden_attr = config.doc_hidden_attr;
 
     let mod_docs = if doc_hidden_attr.is_none() {
@@ -141,6 +160,11 @@ pub fn derive_sabi_trait(item: ItemTrait) -> Result<TokenStream2, syn::Error> {
     }
     .into_iter();
 
+    let mock_reexport = Some(trait_mock).filter(|_| trait_def.mock).into_iter();
+    let delegate_reexport = Some(trait_delegate)
+        .filter(|_| trait_def.delegate)
+        .into_iter();
+
     let mut tokens = quote!(
         #doc_hidden_attr
         #[doc(inline)]
@@ -148,6 +172,8 @@ pub fn derive_sabi_trait(item: ItemTrait) -> Result<TokenStream2, syn::Error> {
             #trait_to,
             #trait_ident,
             #trait_cto_ident,
+            #(#mock_reexport,)*
+            #(#delegate_reexport,)*
         };
 
         #doc_hidden_attr
@@ -295,6 +321,13 @@ fn first_items(
 
     let used_trait_object = quote!(#trait_backend<#uto_params_use>);
 
+    let mut upcast_to_params = trait_def.generics_tokenizer(
+        InWhat::ItemDecl,
+        WithAssocTys::Yes(WhichSelf::NoSelf),
+        &ctokens.ts_lt_erasedptr,
+    );
+    upcast_to_params.set_no_bounds();
+
     let trait_flags = &trait_def.trait_flags;
     let send_syncness = match (trait_flags.sync, trait_flags.send) {
         (false, false) => "UnsyncUnsend",
@@ -343,6 +376,25 @@ fn first_items(
     }
     let one_lt = &lt_tokens.one_lt;
 
+    // Methods that return `Self` allocate the returned value in a new `_OrigPtr`.
+    let (orig_ptr_bounds, orig_ptr_method, orig_ptr_method_impl) =
+        if trait_def.has_self_returning_methods {
+            (
+                quote!(_OrigPtr: __sabi_re::PtrFromValue<PtrTarget = _Self>,),
+                quote!(
+                    fn from_value(value: _Self) -> Self;
+                ),
+                quote!(
+                    #[inline]
+                    fn from_value(value: _Self) -> Self {
+                        __sabi_re::PtrFromValue::from_value(value)
+                    }
+                ),
+            )
+        } else {
+            (quote!(), quote!(), quote!())
+        };
+
     quote!(
         use super::*;
 
@@ -359,6 +411,28 @@ fn first_items(
                 #vtable_argument
             >;
 
+        /// The trait object that `#[sabi_trait]` traits with this trait as a supertrait
+        /// are upcasted into.
+        ///
+        /// This always has a `'lt` parameter, even if the trait requires `'static`.
+        #[doc(hidden)]
+        #submod_vis type UpcastTO<#upcast_to_params>=
+            #trait_to<#uto_params_use>;
+
+        /// The bounds that the pointer this trait object is constructed from must satisfy,
+        /// other than pointing to a type that implements the trait.
+        #[doc(hidden)]
+        #submod_vis trait OrigPtrBounds<_Self> {
+            #orig_ptr_method
+        }
+
+        impl<_Self, _OrigPtr> OrigPtrBounds<_Self> for _OrigPtr
+        where
+            #orig_ptr_bounds
+        {
+            #orig_ptr_method_impl
+        }
+
         #[doc=#trait_cto_docs]
         #submod_vis type #trait_cto_ident<#gen_params_header_rref>=
             #trait_to<#gen_params_use_to_rref>;
@@ -625,8 +699,162 @@ fn constructor_items(params: TokenizerParams<'_>, mod_: &mut TokenStream2) {
 
     let reborrow_methods = reborrow_methods_tokenizer(params);
 
+    let supertrait_constraints = |downcasting: TokenStream2,
+                                  self_ty: TokenStream2,
+                                  erased_ptr: TokenStream2,
+                                  orig_ptr: TokenStream2| {
+        let constraints = totrait_def.sabi_supertraits.iter().map(|supertrait| {
+            let module = &supertrait.module;
+            let interface = supertrait.derived_ident("", "_Interface");
+            let args = supertrait.args(&ctokens.ts_empty);
+            quote!(
+                #module::#interface<#args>:
+                    __sabi_re::GetRObjectVTable<#downcasting, #self_ty, #erased_ptr, #orig_ptr>,
+                #orig_ptr: #module::OrigPtrBounds<#self_ty>,
+            )
+        });
+        quote!( #(#constraints)* )
+    };
+
+    let with_capabilities = quote!(__sabi_re::WithCapabilities<Downcasting, Caps>);
+
+    let supertrait_constraints_ptr = supertrait_constraints(
+        quote!(Downcasting),
+        quote!(_OrigPtr::PtrTarget),
+        quote!(_ErasedPtr),
+        quote!(_OrigPtr),
+    );
+    let supertrait_constraints_ptr_caps = supertrait_constraints(
+        with_capabilities.clone(),
+        quote!(_OrigPtr::PtrTarget),
+        quote!(_ErasedPtr),
+        quote!(_OrigPtr),
+    );
+    let supertrait_constraints_value = supertrait_constraints(
+        quote!(Downcasting),
+        quote!(_Self),
+        quote!(__sabi_re::RBox<()>),
+        quote!(__sabi_re::RBox<_Self>),
+    );
+    let supertrait_constraints_value_caps = supertrait_constraints(
+        with_capabilities.clone(),
+        quote!(_Self),
+        quote!(__sabi_re::RBox<()>),
+        quote!(__sabi_re::RBox<_Self>),
+    );
+    let supertrait_constraints_const = supertrait_constraints(
+        quote!(Downcasting),
+        quote!(_Self),
+        quote!(__sabi_re::RRef<'_sub, ()>),
+        quote!(&'_sub _Self),
+    );
+
+    let upcast_methods = upcast_methods_tokenizer(params);
+
     let plus_lt = &lt_tokens.plus_lt;
 
+    // Capabilities can only be registered in `RObject`-based trait objects.
+    let (from_ptr_with_capabilities, from_value_with_capabilities) = match totrait_def.which_object
+    {
+        WhichObject::DynTrait => (quote!(), quote!()),
+        WhichObject::RObject => {
+            let assoc_tys_a = totrait_def.assoc_tys.keys();
+            let assoc_tys_b = assoc_tys_a.clone();
+            let assoc_tys_c = assoc_tys_a.clone();
+            let assoc_tys_d = assoc_tys_a.clone();
+
+            let mut from_ptr_docs = String::new();
+            let mut from_value_docs = String::new();
+
+            if doc_hidden_attr.is_none() {
+                from_ptr_docs = format!(
+                    "Constructs this trait object from a pointer to a type that implements \
+                     `{trait_}`,registering the vtables of the `capabilities` trait objects \
+                     so that they can be queried.\n\
+                     \n\
+                     This method is automatically generated,\n\
+                     for more documentation you can look at\n\
+                     [`abi_stable::docs::sabi_trait_inherent#from_ptr_with_capabilities-method`]\n\
+                    ",
+                    trait_ = trait_ident
+                );
+
+                from_value_docs = format!(
+                    "Constructs this trait from a type that implements `{trait_}`,\
+                     registering the vtables of the `capabilities` trait objects \
+                     so that they can be queried.\n\
+                     \n\
+                     This method is automatically generated,\n\
+                     for more documentation you can look at\n\
+                     [`abi_stable::docs::sabi_trait_inherent#from_value_with_capabilities-method`]\n\
+                    ",
+                    trait_ = trait_ident
+                );
+            }
+
+            let from_ptr = quote!(
+                #[doc=#from_ptr_docs]
+                #[doc=#shared_docs]
+                #submod_vis fn from_ptr_with_capabilities<_OrigPtr,Downcasting,Caps>(
+                    ptr:_OrigPtr,
+                    can_it_downcast:Downcasting,
+                    capabilities:__sabi_re::Capabilities<Caps>,
+                )->Self
+                where
+                    _OrigPtr:
+                        __sabi_re::CanTransmuteElement<(),TransmutedPtr=_ErasedPtr>+
+                        OrigPtrBounds<_OrigPtr::PtrTarget>,
+                    _OrigPtr::PtrTarget:
+                        #trait_ident<#trait_params #( #assoc_tys_a= #assoc_tys_b, )* >+
+                        Sized
+                        #plus_lt,
+                    #trait_interface<#trait_interface_use>:
+                        __sabi_re::GetRObjectVTable<
+                            #with_capabilities,_OrigPtr::PtrTarget,_ErasedPtr,_OrigPtr
+                        >,
+                    #supertrait_constraints_ptr_caps
+                {
+                    Self::from_ptr(
+                        ptr,
+                        __sabi_re::WithCapabilities::new(can_it_downcast, capabilities),
+                    )
+                }
+            );
+
+            let from_value = quote!(
+                #[doc=#from_value_docs]
+                #[doc=#shared_docs]
+                #submod_vis fn from_value_with_capabilities<_Self,Downcasting,Caps>(
+                    ptr:_Self,
+                    can_it_downcast:Downcasting,
+                    capabilities:__sabi_re::Capabilities<Caps>,
+                )->Self
+                where
+                    _Self:
+                        #trait_ident<#trait_params #( #assoc_tys_c= #assoc_tys_d, )* >
+                        #plus_lt,
+                    #trait_interface<#trait_interface_use>:
+                        __sabi_re::GetRObjectVTable<
+                            #with_capabilities,
+                            _Self,
+                            __sabi_re::RBox<()>,
+                            __sabi_re::RBox<_Self>
+                        >,
+                    __sabi_re::RBox<_Self>: OrigPtrBounds<_Self>,
+                    #supertrait_constraints_value_caps
+                {
+                    Self::from_ptr_with_capabilities::<
+                        __sabi_re::RBox<_Self>,
+                        Downcasting,
+                        Caps
+                    >(__sabi_re::RBox::new(ptr),can_it_downcast,capabilities)
+                }
+            );
+
+            (from_ptr, from_value)
+        }
+    };
+
     let constructing_backend = match totrait_def.which_object {
         WhichObject::DynTrait => quote!(
             #trait_backend::from_const(
@@ -657,7 +885,8 @@ fn constructor_items(params: TokenizerParams<'_>, mod_: &mut TokenStream2) {
             )->Self
             where
                 _OrigPtr:
-                    __sabi_re::CanTransmuteElement<(),TransmutedPtr=_ErasedPtr>,
+                    __sabi_re::CanTransmuteElement<(),TransmutedPtr=_ErasedPtr>+
+                    OrigPtrBounds<_OrigPtr::PtrTarget>,
                 _OrigPtr::PtrTarget:
                     #trait_ident<#trait_params #( #assoc_tys_a= #assoc_tys_b, )* >+
                     Sized
@@ -667,6 +896,7 @@ fn constructor_items(params: TokenizerParams<'_>, mod_: &mut TokenStream2) {
                         Downcasting,_OrigPtr::PtrTarget,_ErasedPtr,_OrigPtr
                     >,
                 #extra_constraints_ptr
+                #supertrait_constraints_ptr
             {
                 let _can_it_downcast=can_it_downcast;
                 unsafe{
@@ -692,7 +922,11 @@ fn constructor_items(params: TokenizerParams<'_>, mod_: &mut TokenStream2) {
                 }
             }
 
+            #from_ptr_with_capabilities
+
             #reborrow_methods
+
+            #upcast_methods
         }
 
         impl<#gen_params_header_rbox> #trait_to<#gen_params_use_to_rbox> {
@@ -710,13 +944,17 @@ fn constructor_items(params: TokenizerParams<'_>, mod_: &mut TokenStream2) {
                     __sabi_re::GetRObjectVTable<
                         Downcasting,_Self,__sabi_re::RBox<()>,__sabi_re::RBox<_Self>
                     >,
+                __sabi_re::RBox<_Self>: OrigPtrBounds<_Self>,
                 #extra_constraints_value
+                #supertrait_constraints_value
             {
                 Self::from_ptr::<
                     __sabi_re::RBox<_Self>,
                     Downcasting
                 >(__sabi_re::RBox::new(ptr),can_it_downcast)
             }
+
+            #from_value_with_capabilities
         }
 
         impl<#gen_params_header_rref> #trait_to<#gen_params_use_to_rref>{
@@ -735,7 +973,9 @@ fn constructor_items(params: TokenizerParams<'_>, mod_: &mut TokenStream2) {
                     __sabi_re::GetRObjectVTable<
                         Downcasting, _Self, __sabi_re::RRef<'_sub, ()>, &'_sub _Self
                     >,
+                &'_sub _Self: OrigPtrBounds<_Self>,
                 #extra_constraints_const
+                #supertrait_constraints_const
             {
                 unsafe{
                     Self{
@@ -812,6 +1052,59 @@ fn reborrow_methods_tokenizer(
     })
 }
 
+/// Returns a tokenizer for the methods that upcast the trait object
+/// into the trait object of a `#[sabi_trait]` supertrait.
+fn upcast_methods_tokenizer(
+    TokenizerParams {
+        config,
+        totrait_def,
+        submod_vis,
+        lt_tokens,
+        ..
+    }: TokenizerParams<'_>,
+) -> impl ToTokens + '_ {
+    ToTokenFnMut::new(move |ts| {
+        let one_lt = &lt_tokens.one_lt;
+        let one_lt_erasedptr = quote!(#one_lt _ErasedPtr,);
+
+        for supertrait in &totrait_def.sabi_supertraits {
+            let module = &supertrait.module;
+            let method = supertrait.derived_ident("upcast_to_", "");
+            let field = supertrait.derived_ident("_sabi_super_", "");
+            let upcast_to_args = supertrait.args(&one_lt_erasedptr);
+
+            let docs = if config.doc_hidden_attr.is_none() {
+                format!(
+                    "Converts this trait object into the trait object of \
+                     its `{super_}` supertrait.\n\
+                     \n\
+                     This method is automatically generated,\n\
+                     for more documentation you can look at\n\
+                     [`abi_stable::docs::sabi_trait_inherent#upcast_to_supertrait-methods`]\n\
+                    ",
+                    super_ = supertrait.ident,
+                )
+            } else {
+                String::new()
+            };
+
+            quote!(
+                #[doc=#docs]
+                #[allow(non_snake_case)]
+                #submod_vis fn #method(self)->#module::UpcastTO<#upcast_to_args> {
+                    unsafe{
+                        // The vtable of the supertrait is constructed for the same type
+                        // and pointer as the vtable of this trait object.
+                        let vtable = VTable_Ref(self.obj.sabi_et_vtable()).#field();
+                        #module::UpcastTO::from_sabi(self.obj.sabi_replace_vtable(vtable))
+                    }
+                }
+            )
+            .to_tokens(ts);
+        }
+    })
+}
+
 /// Outputs the annotated trait (as modified by the proc-macro)
 /// and an implementation of the trait for the generated trait object.
 fn trait_and_impl(
@@ -835,7 +1128,11 @@ fn trait_and_impl(
     let methods_tokenizer_impl = trait_def.methods_tokenizer(WhichItem::TraitImpl);
     let lifetime_bounds_a = trait_def.lifetime_bounds.iter();
     let lifetime_bounds_c = trait_def.lifetime_bounds.iter();
-    let super_traits_a = trait_def.impld_traits.iter().map(|t| &t.bound);
+    let super_traits_a = trait_def
+        .impld_traits
+        .iter()
+        .map(|t| &t.bound)
+        .chain(trait_def.sabi_supertraits.iter().map(|t| t.bound));
     let super_traits_b = super_traits_a.clone();
 
     let assoc_tys_a = trait_def.assoc_tys.values().map(|x| &x.assoc_ty);
@@ -844,6 +1141,10 @@ fn trait_and_impl(
 
     let erased_ptr_bounds = trait_def.erased_ptr_preds();
 
+    // Methods that return `Self` can only be called on trait objects that own their pointee.
+    let self_returning_bound =
+        Some(&ctokens.ptr_smart_pointer_bound).filter(|_| trait_def.has_self_returning_methods);
+
     quote!(
         #[allow(clippy::needless_lifetimes, clippy::new_ret_no_self)]
         #( #other_attrs )*
@@ -889,6 +1190,7 @@ fn trait_and_impl(
             where
                 Self:#( #super_traits_b + )* #(#lifetime_bounds_c+)*  ,
                 #erased_ptr_bounds
+                #self_returning_bound
                 #(#where_preds_b,)*
             {
                 #( type #assoc_ty_named_a=#assoc_ty_named_b; )*
@@ -898,16 +1200,65 @@ fn trait_and_impl(
         )
         .to_tokens(mod_);
     }
+
+    if trait_def.instrument {
+        let gen_params_header = trait_def.generics_tokenizer(
+            InWhat::ImplHeader,
+            WithAssocTys::Yes(WhichSelf::NoSelf),
+            &lt_tokens.lt_erasedptr,
+        );
+        let gen_params_use_to = trait_def.generics_tokenizer(
+            InWhat::ItemUse,
+            WithAssocTys::Yes(WhichSelf::NoSelf),
+            &lt_tokens.lt_erasedptr,
+        );
+        let methods_tokenizer_instrumented =
+            trait_def.methods_tokenizer(WhichItem::InstrumentedImpl);
+        let lifetime_bounds = trait_def.lifetime_bounds.iter();
+        let super_traits = trait_def
+            .impld_traits
+            .iter()
+            .map(|t| &t.bound)
+            .chain(trait_def.sabi_supertraits.iter().map(|t| t.bound));
+        let where_preds = (&trait_def.where_preds).into_iter();
+        let erased_ptr_bounds = trait_def.erased_ptr_preds();
+
+        let assoc_ty_named_a = trait_def.assoc_tys.values().map(|x| &x.assoc_ty.ident);
+        let assoc_ty_named_b = assoc_ty_named_a.clone();
+
+        quote!(
+            #[deny(unsafe_op_in_unsafe_fn)]
+            #[allow(
+                clippy::needless_lifetimes,
+                clippy::new_ret_no_self,
+            )]
+            impl<#gen_params_header> #trait_ident<#gen_params_use_trait>
+            for __sabi_re::Instrumented<#trait_to<#gen_params_use_to>>
+            where
+                #trait_to<#gen_params_use_to>: #trait_ident<#gen_params_use_trait>,
+                Self:#( #super_traits + )* #(#lifetime_bounds+)*  ,
+                #erased_ptr_bounds
+                #(#where_preds,)*
+            {
+                #( type #assoc_ty_named_a=#assoc_ty_named_b; )*
+
+                #methods_tokenizer_instrumented
+            }
+        )
+        .to_tokens(mod_);
+    }
 }
 
 /// An inherent implementation of the generated trait object,
 /// which mirrors the trait definition.
 fn methods_impls(param: TokenizerParams, mod_: &mut TokenStream2) -> Result<(), syn::Error> {
     let TokenizerParams {
+        config,
         totrait_def,
         trait_to,
         ctokens,
         lt_tokens,
+        submod_vis,
         ..
     } = param;
 
@@ -934,6 +1285,38 @@ fn methods_impls(param: TokenizerParams, mod_: &mut TokenStream2) -> Result<(),
 
     let methods_tokenizer_def = totrait_def.methods_tokenizer(WhichItem::TraitObjectImpl);
 
+    let has_method_probes = totrait_def.methods.iter().map(|method| {
+        let method_name = method.name;
+        let probe = parse_str_as_ident(&format!("sabi_has_{}", method_name));
+        let field_index = parse_str_as_ident(&format!("field_index_for_{}", method_name));
+        let docs = if config.doc_hidden_attr.is_none() {
+            format!(
+                "Whether the vtable of this trait object has the `{}` method,\n\
+                 returning false if the trait object was constructed in a library \
+                 compiled against a version of the trait that didn't have the method.\n\
+                 \n\
+                 This method is automatically generated,\n\
+                 for more documentation you can look at\n\
+                 [`abi_stable::docs::sabi_trait_inherent#sabi_has_method-methods`]\n\
+                ",
+                method_name,
+            )
+        } else {
+            String::new()
+        };
+
+        quote_spanned!(method_name.span()=>
+            #[doc = #docs]
+            #submod_vis fn #probe(&self) -> bool {
+                self.sabi_vtable()
+                    .0
+                    .field_accessibility()
+                    .at(<VTable_Ref<#generics_use1>>::#field_index as usize)
+                    .is_accessible()
+            }
+        )
+    });
+
     quote!(
         #[allow(clippy::needless_lifetimes, clippy::new_ret_no_self)]
         impl<#gen_params_header> #trait_to<#gen_params_use_to>
@@ -951,6 +1334,8 @@ fn methods_impls(param: TokenizerParams, mod_: &mut TokenStream2) -> Result<(),
                 }
             }
 
+            #( #has_method_probes )*
+
             #methods_tokenizer_def
         }
     )
@@ -1016,14 +1401,31 @@ fn declare_vtable(
         >
     );
 
+    let supertrait_fields = vtable_trait_decl
+        .sabi_supertraits
+        .iter()
+        .map(|supertrait| supertrait.derived_ident("_sabi_super_", ""));
+
+    let supertrait_vtables = vtable_trait_decl
+        .sabi_supertraits
+        .iter()
+        .map(|supertrait| {
+            let module = &supertrait.module;
+            let args = supertrait.args(&ctokens.ts_unit_erasedptr);
+            quote!(__sabi_re::PrefixRef<#module::VTable_Prefix<#args>>)
+        })
+        .collect::<Vec<TokenStream2>>();
+
     quote!(
 
         #[repr(C)]
         #[derive(abi_stable::StableAbi)]
         #[sabi(kind(Prefix(prefix_ref = VTable_Ref)))]
         #[sabi(missing_field(panic))]
+        #[sabi(with_field_indices)]
         #( #[sabi(prefix_bound(#lifetime_bounds))] )*
         #[sabi(bound(#robject_vtable: ::abi_stable::StableAbi))]
+        #( #[sabi(bound(#supertrait_vtables: ::abi_stable::StableAbi))] )*
         #(#derive_attrs)*
         #[doc(hidden)]
         #submod_vis struct VTable<#generics_decl>
@@ -1034,6 +1436,8 @@ fn declare_vtable(
 
             _sabi_vtable:#robject_vtable,
 
+            #( #supertrait_fields: #supertrait_vtables, )*
+
             #methods_tokenizer
         }
     )
@@ -1049,6 +1453,7 @@ fn declare_vtable(
 fn vtable_impl(
     TokenizerParams {
         ctokens,
+        submod_vis,
         vtable_trait_impl,
         trait_interface,
         trait_ident,
@@ -1110,6 +1515,29 @@ fn vtable_impl(
 
     let one_lt = &lt_tokens.one_lt;
 
+    let supertrait_fields = vtable_trait_impl
+        .sabi_supertraits
+        .iter()
+        .map(|supertrait| supertrait.derived_ident("_sabi_super_", ""));
+
+    let supertrait_vtables = vtable_trait_impl.sabi_supertraits.iter().map(|supertrait| {
+        let module = &supertrait.module;
+        let make_vtable = supertrait.derived_ident("", "_MV");
+        let args = supertrait.args(&ctokens.ts_getvtable_params);
+        quote!(#module::#make_vtable::<#args>::VTABLE_INNER)
+    });
+
+    let supertrait_constraints = vtable_trait_impl.sabi_supertraits.iter().map(|supertrait| {
+        let module = &supertrait.module;
+        let interface = supertrait.derived_ident("", "_Interface");
+        let args = supertrait.args(&ctokens.ts_empty);
+        quote!(
+            #module::#interface<#args>:
+                __sabi_re::GetRObjectVTable<IA,_Self,_ErasedPtr,_OrigPtr>,
+            _OrigPtr: #module::OrigPtrBounds<_Self>,
+        )
+    });
+
     let extra_constraints = match vtable_trait_impl.which_object {
         WhichObject::DynTrait => quote!(
             #trait_interface<#trait_interface_use>:
@@ -1131,18 +1559,21 @@ fn vtable_impl(
     };
 
     quote!(
-        struct #make_vtable_ident<#struct_decl_generics>(#dummy_struct_tys);
+        #[doc(hidden)]
+        #submod_vis struct #make_vtable_ident<#struct_decl_generics>(#dummy_struct_tys);
 
         #[deny(unsafe_op_in_unsafe_fn)]
         impl<#impl_header_generics> #make_vtable_ident<#makevtable_generics>
         where
             _Self: #trait_ident<#trait_generics>,
             _OrigPtr:
-                __sabi_re::CanTransmuteElement<(), PtrTarget = _Self, TransmutedPtr = _ErasedPtr>,
+                __sabi_re::CanTransmuteElement<(), PtrTarget = _Self, TransmutedPtr = _ErasedPtr>+
+                OrigPtrBounds<_Self>,
             _ErasedPtr:__sabi_re::AsPtr<PtrTarget=()>,
             #trait_interface<#trait_interface_use>:
                 __sabi_re::GetRObjectVTable<IA,_Self,_ErasedPtr,_OrigPtr>,
             #extra_constraints
+            #( #supertrait_constraints )*
         {
             const TMP0: __sabi_re::WithMetadata<
                 VTable<#withmetadata_generics>
@@ -1151,6 +1582,9 @@ fn vtable_impl(
                     VTable{
                         _sabi_tys: __sabi_re::NonOwningPhantom::NEW,
                         _sabi_vtable:__sabi_re::GetRObjectVTable::ROBJECT_VTABLE,
+                        #(
+                            #supertrait_fields: #supertrait_vtables,
+                        )*
                         #(
                             #method_names_a:Self::#method_names_b,
                         )*
@@ -1158,7 +1592,8 @@ fn vtable_impl(
                 )
             };
 
-            const VTABLE_INNER: __sabi_re::PrefixRef<VTable_Prefix<#vtable_generics> > =unsafe{
+            #[doc(hidden)]
+            #submod_vis const VTABLE_INNER: __sabi_re::PrefixRef<VTable_Prefix<#vtable_generics> > =unsafe{
                 __sabi_re::WithMetadata::raw_as_prefix(&Self::TMP0)
                     .cast() // erasing the `_Self` parameter
             };
@@ -1169,6 +1604,160 @@ fn vtable_impl(
     .to_tokens(mod_);
 }
 
+/// Outputs the impls that allow the trait object to be registered as a capability
+/// of other trait objects, and to be queried from them.
+///
+/// This is only done for `RObject`-based trait objects of traits that
+/// have neither lifetime parameters nor associated types,
+/// since those can't be told apart by the layout of the trait object.
+fn capability_impls(
+    TokenizerParams {
+        arenas,
+        ctokens,
+        totrait_def,
+        trait_ident,
+        trait_to,
+        trait_interface,
+        make_vtable_ident,
+        lt_tokens,
+        ..
+    }: TokenizerParams,
+    mod_: &mut TokenStream2,
+) {
+    if totrait_def.which_object == WhichObject::DynTrait
+        || totrait_def.generics.lifetimes().next().is_some()
+        || !totrait_def.assoc_tys.is_empty()
+    {
+        return;
+    }
+
+    let lt = &lt_tokens.lt;
+    let one_lt = &lt_tokens.one_lt;
+    let plus_lt = &lt_tokens.plus_lt;
+
+    let query_header = totrait_def.generics_tokenizer(
+        InWhat::ImplHeader,
+        WithAssocTys::No,
+        &lt_tokens.lt_erasedptr,
+    );
+
+    let make_capability_header = totrait_def.generics_tokenizer(
+        InWhat::ImplHeader,
+        WithAssocTys::No,
+        arenas.alloc(quote!(#lt _AnyPtr, IA, _Self, _ErasedPtr, _OrigPtr,)),
+    );
+
+    let trait_to_use =
+        totrait_def.generics_tokenizer(InWhat::ItemUse, WithAssocTys::No, &lt_tokens.lt_erasedptr);
+
+    let trait_to_anyptr_use = totrait_def.generics_tokenizer(
+        InWhat::ItemUse,
+        WithAssocTys::No,
+        arenas.alloc(quote!(#lt _AnyPtr,)),
+    );
+
+    // The trait object used to identify the trait across dynamic libraries.
+    let layout_to_use = totrait_def.generics_tokenizer(
+        InWhat::ItemUse,
+        WithAssocTys::No,
+        &lt_tokens.staticlt_static_rref,
+    );
+
+    let make_vtable_args = totrait_def.generics_tokenizer(
+        InWhat::ItemUse,
+        WithAssocTys::No,
+        arenas.alloc(quote!(#one_lt #one_lt IA, _Self, _ErasedPtr, _OrigPtr,)),
+    );
+
+    let trait_params =
+        totrait_def.generics_tokenizer(InWhat::ItemUse, WithAssocTys::No, &ctokens.empty_ts);
+
+    let trait_interface_use =
+        totrait_def.generics_tokenizer(InWhat::ItemUse, WithAssocTys::No, &ctokens.ts_empty);
+
+    let vtable_args = totrait_def.generics_tokenizer(
+        InWhat::ItemUse,
+        WithAssocTys::No,
+        &ctokens.ts_unit_erasedptr,
+    );
+
+    let static_tys = totrait_def
+        .generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+
+    let where_preds = totrait_def.where_preds.iter();
+    let where_preds_b = where_preds.clone();
+
+    let supertrait_constraints = totrait_def.sabi_supertraits.iter().map(|supertrait| {
+        let module = &supertrait.module;
+        let interface = supertrait.derived_ident("", "_Interface");
+        let args = supertrait.args(&ctokens.ts_empty);
+        quote!(
+            #module::#interface<#args>:
+                __sabi_re::GetRObjectVTable<IA,_Self,_ErasedPtr,_OrigPtr>,
+            _OrigPtr: #module::OrigPtrBounds<_Self>,
+        )
+    });
+
+    let layout_to = quote!(#trait_to<#layout_to_use>);
+
+    quote!(
+        unsafe impl<#query_header> __sabi_re::QueryTarget<#one_lt _ErasedPtr>
+        for #trait_to<#trait_to_use>
+        where
+            _ErasedPtr:__GetPointerKind,
+            #( #where_preds, )*
+            #( #static_tys: 'static, )*
+            #layout_to: ::abi_stable::StableAbi,
+        {
+            type Interface = #trait_interface<#trait_interface_use>;
+
+            type VTable = VTable_Prefix<#vtable_args>;
+
+            const CAPABILITY_LAYOUT: &'static ::abi_stable::type_layout::TypeLayout =
+                <#layout_to as ::abi_stable::StableAbi>::LAYOUT;
+
+            fn from_robject(
+                obj: __sabi_re::RObject<#one_lt _ErasedPtr, Self::Interface, Self::VTable>,
+            ) -> Self {
+                Self{
+                    obj,
+                    _marker:__sabi_re::UnsafeIgnoredType::DEFAULT,
+                }
+            }
+        }
+
+        impl<#make_capability_header> __sabi_re::MakeCapability<IA,_Self,_ErasedPtr,_OrigPtr>
+        for #trait_to<#trait_to_anyptr_use>
+        where
+            _AnyPtr:__GetPointerKind,
+            #( #where_preds_b, )*
+            #( #static_tys: 'static, )*
+            #layout_to: ::abi_stable::StableAbi,
+            _Self: #trait_ident<#trait_params> #plus_lt,
+            _OrigPtr:
+                __sabi_re::CanTransmuteElement<(), PtrTarget = _Self, TransmutedPtr = _ErasedPtr>+
+                OrigPtrBounds<_Self>,
+            _ErasedPtr:__sabi_re::AsPtr<PtrTarget=()>,
+            #trait_interface<#trait_interface_use>:
+                __sabi_re::GetRObjectVTable<IA,_Self,_ErasedPtr,_OrigPtr>,
+            #( #supertrait_constraints )*
+        {
+            const CAPABILITY: __sabi_re::Capability = unsafe{
+                // The vtable is constructed for the same type and pointer type
+                // as the trait object that the capability is registered in.
+                __sabi_re::Capability::new(
+                    <#layout_to as ::abi_stable::StableAbi>::LAYOUT,
+                    #make_vtable_ident::<#make_vtable_args>::VTABLE_INNER.cast(),
+                )
+            };
+        }
+    )
+    .to_tokens(mod_);
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum SelfParam<'a> {
     ByRef {
@@ -1185,8 +1774,18 @@ pub(crate) enum WhichItem {
     Trait,
     /// the method in the trait implemetation for the generated trait object.
     TraitImpl,
+    /// the method in the trait implemetation for `Instrumented<Trait_TO>`.
+    InstrumentedImpl,
+    /// the method in the trait implemetation for `Trait_Mock`.
+    MockImpl,
+    /// the method in the `Trait_Delegate` trait definition.
+    DelegateTrait,
+    /// the method in the trait implemetation for `Trait_Delegate` implementors.
+    DelegateImpl,
     /// the methods in the inherent implemetation of the generated trait object.
     TraitObjectImpl,
+    /// the method in the trait implemetation for the trait objects of subtraits.
+    SupertraitImpl,
     /// the fields of the trait object vtable.
     VtableDecl,
     /// the methods used to construct the vtable.
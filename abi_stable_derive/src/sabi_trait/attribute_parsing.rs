@@ -2,7 +2,7 @@ use super::{TraitDefinition, *};
 
 use as_derive_utils::parse_utils::ParseBufferExt;
 
-use syn::{parse::ParseBuffer, Attribute, ItemTrait, TraitItem, TraitItemMethod};
+use syn::{parse::ParseBuffer, Attribute, Ident, ItemTrait, Token, TraitItem, TraitItemMethod};
 
 #[allow(unused_imports)]
 use core_extensions::SelfOps;
@@ -42,11 +42,17 @@ impl<'a> SabiTraitOptions<'a> {
 
 mod kw {
     syn::custom_keyword! {no_default_fallback}
+    syn::custom_keyword! {catch_panic}
+    syn::custom_keyword! {c_unwind}
     syn::custom_keyword! {debug_print_trait}
     syn::custom_keyword! {debug_output_tokens}
     syn::custom_keyword! {use_dyntrait}
     syn::custom_keyword! {use_dyn_trait}
     syn::custom_keyword! {no_trait_impl}
+    syn::custom_keyword! {instrument}
+    syn::custom_keyword! {mock}
+    syn::custom_keyword! {delegate}
+    syn::custom_keyword! {supertrait}
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -99,9 +105,23 @@ pub(super) struct SabiTraitAttrs<'a> {
     pub(super) which_object: WhichObject,
     /// If true,removes the `impl Trait for Trait_TO`
     pub(super) disable_trait_impl: bool,
+    /// If true,generates an `impl Trait for Instrumented<Trait_TO>`
+    pub(super) instrument: bool,
+    /// If true,generates a `Trait_Mock` type implementing the trait.
+    pub(super) mock: bool,
+    /// If true,generates a `Trait_Delegate` trait that forwards the methods to a field.
+    pub(super) delegate: bool,
+    /// The names of the supertraits that are `#[sabi_trait]` traits.
+    pub(super) sabi_supertraits: Vec<Ident>,
     /// If true,doesn't use the default implementation of methods when
     /// the vtable entry is absent.
     pub(super) disable_inherent_default: Vec<bool>,
+    /// If true,the vtable entry catches panics,
+    /// and the trait object resumes them on the caller's side.
+    pub(super) catch_panic: Vec<bool>,
+    /// If true,the vtable entry uses the `extern "C-unwind"` ABI,
+    /// letting panics unwind into the caller.
+    pub(super) c_unwind: Vec<bool>,
 
     pub(super) is_hidden: bool,
     pub(super) debug_output_tokens: bool,
@@ -136,6 +156,8 @@ pub(crate) fn parse_attrs_for_sabi_trait<'a>(
     this.methods_with_attrs.reserve(assoc_fns.len());
 
     this.disable_inherent_default.resize(assoc_fns.len(), false);
+    this.catch_panic.resize(assoc_fns.len(), false);
+    this.c_unwind.resize(assoc_fns.len(), false);
 
     parse_inner(&mut this, &*trait_.attrs, ParseContext::TraitAttr, arenas)?;
 
@@ -230,6 +252,28 @@ fn parse_sabi_trait_attr<'a>(
                 this.disable_inherent_default[index] = true;
             }
         }
+    } else if input.check_parse(kw::catch_panic)? {
+        match pctx {
+            ParseContext::TraitAttr => {
+                for catches in &mut this.catch_panic {
+                    *catches = true;
+                }
+            }
+            ParseContext::Method { index } => {
+                this.catch_panic[index] = true;
+            }
+        }
+    } else if input.check_parse(kw::c_unwind)? {
+        match pctx {
+            ParseContext::TraitAttr => {
+                for unwinds in &mut this.c_unwind {
+                    *unwinds = true;
+                }
+            }
+            ParseContext::Method { index } => {
+                this.c_unwind[index] = true;
+            }
+        }
     } else if input.check_parse(kw::debug_print_trait)? {
         this.debug_print_trait = true;
     } else if input.check_parse(kw::debug_output_tokens)? {
@@ -239,6 +283,19 @@ fn parse_sabi_trait_attr<'a>(
             this.which_object = WhichObject::DynTrait;
         } else if input.check_parse(kw::no_trait_impl)? {
             this.disable_trait_impl = true;
+        } else if input.check_parse(kw::instrument)? {
+            this.instrument = true;
+        } else if input.check_parse(kw::mock)? {
+            this.mock = true;
+        } else if input.check_parse(kw::delegate)? {
+            this.delegate = true;
+        } else if input.check_parse(kw::supertrait)? {
+            input
+                .parse_paren_buffer()?
+                .for_each_separated(Token!(,), |input| {
+                    this.sabi_supertraits.push(input.parse::<Ident>()?);
+                    Ok(())
+                })?;
         } else {
             push_attr(this, pctx, input, attr.clone());
         }
@@ -73,6 +73,7 @@ impl Ord for CommonTokens {
 declare_common_tokens! {
     abi[
         extern_c=r#"extern "C" "#,
+        extern_c_unwind=r#"extern "C-unwind" "#,
     ]
 
     type_param_bound[
@@ -98,6 +99,8 @@ declare_common_tokens! {
             "_ErasedPtr: __sabi_re::OwnedPointer<PtrTarget=()>,",
         ptr_val_bound=
             "_ErasedPtr: __sabi_re::OwnedPointer<PtrTarget=()>,",
+        ptr_smart_pointer_bound=
+            "_ErasedPtr: __sabi_re::GetPointerKind<Kind=__sabi_re::PK_SmartPointer>,",
 
         empty_ts="",
         ts_empty="",
@@ -110,6 +113,7 @@ declare_common_tokens! {
         ts_erasedptr_and2="_ErasedPtr,_ErasedPtr2,",
         ts_self_erasedptr="_Self,_ErasedPtr,",
         ts_unit_erasedptr="(),_ErasedPtr,",
+        ts_lt_erasedptr="'lt,_ErasedPtr,",
 
         ts_getvtable_params="'lt,'_sub,IA,_Self,_ErasedPtr,_OrigPtr,",
         ts_getvtable_dummy_struct_fields="&'lt(),&'_sub(),IA,_Self,_ErasedPtr,_OrigPtr,",
@@ -215,5 +219,6 @@ declare_lifetime_tokens! {
     static_lifetime_tokens=[
         staticlt_erasedptr2="_ErasedPtr2,",
         staticlt_erasedptr="_ErasedPtr,",
+        staticlt_static_rref="__sabi_re::RRef<'static,()>,",
     ]
 }
@@ -0,0 +1,136 @@
+use proc_macro2::TokenStream as TokenStream2;
+
+use quote::{quote, ToTokens};
+
+use as_derive_utils::gen_params_in::InWhat;
+
+use crate::sabi_trait::{TokenizerParams, WhichItem, WithAssocTys};
+
+/// Outputs the `Trait_Delegate` trait,
+/// whose methods forward to the `Trait` implementation of a delegate by default,
+/// and the blanket implementation of the trait for the implementors of `Trait_Delegate`.
+pub(super) fn delegate_items(
+    TokenizerParams {
+        config,
+        ctokens,
+        trait_def,
+        submod_vis,
+        trait_ident,
+        trait_delegate,
+        ..
+    }: TokenizerParams<'_>,
+    mod_: &mut TokenStream2,
+) {
+    if !trait_def.delegate {
+        return;
+    }
+
+    let doc_hidden_attr = config.doc_hidden_attr;
+
+    let gen_params_trait =
+        trait_def.generics_tokenizer(InWhat::ItemDecl, WithAssocTys::No, &ctokens.empty_ts);
+    let gen_params_use_trait =
+        trait_def.generics_tokenizer(InWhat::ItemUse, WithAssocTys::No, &ctokens.empty_ts);
+    let delegator_param = quote!(__Delegator,);
+    let gen_params_header =
+        trait_def.generics_tokenizer(InWhat::ImplHeader, WithAssocTys::No, &delegator_param);
+
+    let where_preds_a = (&trait_def.where_preds).into_iter();
+    let where_preds_b = where_preds_a.clone();
+    let lifetime_bounds = trait_def.lifetime_bounds.iter();
+    let super_traits = trait_def
+        .impld_traits
+        .iter()
+        .map(|t| &t.bound)
+        .chain(trait_def.sabi_supertraits.iter().map(|t| t.bound));
+
+    let assoc_tys = trait_def.assoc_tys.values().map(|x| &x.assoc_ty);
+    let assoc_ty_named_a = trait_def.assoc_tys.values().map(|x| &x.assoc_ty.ident);
+    let assoc_ty_named_b = assoc_ty_named_a.clone();
+    let assoc_ty_named_c = assoc_ty_named_a.clone();
+    let assoc_ty_named_d = assoc_ty_named_a.clone();
+
+    let unsafety = trait_def.item.unsafety;
+
+    let delegate_mut = if trait_def.has_mut_methods {
+        quote!(
+            /// Gets a mutable reference to the value that
+            /// the `&mut self` methods forward to.
+            fn sabi_delegate_mut(&mut self) -> &mut Self::Delegate;
+        )
+    } else {
+        TokenStream2::new()
+    };
+    let into_delegate = if trait_def.has_val_methods {
+        quote!(
+            /// Converts `self` into the value that the `self` methods forward to.
+            fn sabi_into_delegate(self) -> Self::Delegate
+            where
+                Self: Sized;
+        )
+    } else {
+        TokenStream2::new()
+    };
+
+    let methods_tokenizer_def = trait_def.methods_tokenizer(WhichItem::DelegateTrait);
+    let methods_tokenizer_impl = trait_def.methods_tokenizer(WhichItem::DelegateImpl);
+
+    let mut delegate_docs = String::new();
+    if doc_hidden_attr.is_none() {
+        delegate_docs = format!(
+            "Implements [{T}](trait@{T}) by forwarding its methods to \
+             the value returned by `sabi_delegate`,\
+             generated by the `#[sabi(delegate)]` attribute.\n\
+             \n\
+             Types that implement this trait get a blanket implementation of `{T}`,\
+             and only need to override the methods whose behavior they change.\n\
+             \n\
+             Methods that return `Self` have no default implementation.\
+            ",
+            T = trait_ident,
+        );
+    }
+
+    quote!(
+        #[doc = #delegate_docs]
+        #doc_hidden_attr
+        #[allow(clippy::needless_lifetimes)]
+        #submod_vis #unsafety trait #trait_delegate<#gen_params_trait>
+        where
+            #(#where_preds_a,)*
+        {
+            #( #assoc_tys )*
+
+            /// The type that the methods forward to.
+            type Delegate: #trait_ident<
+                #gen_params_use_trait
+                #( #assoc_ty_named_a = Self::#assoc_ty_named_b, )*
+            >;
+
+            /// Gets a reference to the value that the `&self` methods forward to.
+            fn sabi_delegate(&self) -> &Self::Delegate;
+
+            #delegate_mut
+
+            #into_delegate
+
+            #methods_tokenizer_def
+        }
+
+        #[deny(unsafe_op_in_unsafe_fn)]
+        #[allow(clippy::needless_lifetimes)]
+        #unsafety impl<#gen_params_header> #trait_ident<#gen_params_use_trait> for __Delegator
+        where
+            __Delegator: #trait_delegate<#gen_params_use_trait>,
+            Self:#( #super_traits + )* #(#lifetime_bounds+)*  ,
+            #(#where_preds_b,)*
+        {
+            #( type #assoc_ty_named_c = <
+                __Delegator as #trait_delegate<#gen_params_use_trait>
+            >::#assoc_ty_named_d; )*
+
+            #methods_tokenizer_impl
+        }
+    )
+    .to_tokens(mod_);
+}
@@ -1,16 +1,19 @@
 use proc_macro2::TokenStream as TokenStream2;
 
-use quote::{quote_spanned, ToTokens};
+use quote::{quote, quote_spanned, ToTokens};
 
 use as_derive_utils::gen_params_in::InWhat;
 
-use crate::sabi_trait::{TokenizerParams, WhichSelf, WithAssocTys};
+use crate::{
+    arenas::AllocMethods,
+    sabi_trait::{TokenizerParams, WhichSelf, WithAssocTys},
+};
 
 /// Generates the code that delegates the implementation of the traits
 /// to the wrapped DynTrait or RObject.
 pub(super) fn delegated_impls(
     TokenizerParams {
-        arenas: _,
+        arenas,
         ctokens,
         totrait_def,
         trait_to,
@@ -26,13 +29,6 @@ pub(super) fn delegated_impls(
     let impls = totrait_def.trait_flags;
     let spans = &totrait_def.trait_spans;
 
-    // let gen_params_deser_header=
-    //     totrait_def.generics_tokenizer(
-    //         InWhat::ImplHeader,
-    //         WithAssocTys::Yes(WhichSelf::NoSelf),
-    //         &lt_tokens.lt_de_erasedptr,
-    //     );
-
     let gen_params_header = totrait_def.generics_tokenizer(
         InWhat::ImplHeader,
         WithAssocTys::Yes(WhichSelf::NoSelf),
@@ -375,66 +371,66 @@ pub(super) fn delegated_impls(
         .to_tokens(mod_);
     }
 
-    // if let Some(deserialize_bound)=&totrait_def.deserialize_bound {
-    //     let deserialize_path=&deserialize_bound.bound.path;
-
-    //     let lifetimes=deserialize_bound.bound.lifetimes.as_ref()
-    //         .map(|x|ToTokenFnMut::new(move|ts|{
-    //             for lt in &x.lifetimes {
-    //                 lt.to_tokens(ts);
-    //                 Comma::default().to_tokens(ts);
-    //             }
-    //         }));
-
-    //     let suffix=&lt_tokens.lt_erasedptr;
-
-    //     let header_generics=arenas.alloc(quote!( #lifetimes #suffix ));
-
-    //     let gen_params_header=
-    //         totrait_def.generics_tokenizer(
-    //             InWhat::ImplHeader,
-    //             WithAssocTys::Yes(WhichSelf::NoSelf),
-    //             header_generics,
-    //         );
-
-    //     let lifetime_param=deserialize_bound.lifetime;
-
-    //     quote!(
-    //         impl<#gen_params_header> #deserialize_path for #trait_to<#gen_params_use_to>
-    //         where
-    //             #trait_backend<#gen_params_use_to>: #deserialize_path,
-    //             _ErasedPtr:__sabi_re::AsPtr<PtrTarget=()>,
-    //             #(#where_preds,)*
-    //         {
-    //             fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    //             where
-    //                 D: ::serde::Deserializer<#lifetime_param>,
-    //             {
-    //                 #trait_backend::<#gen_params_use_to>::deserialize(deserializer)
-    //                     .map(Self::from_sabi)
-    //             }
-    //         }
-    //     ).to_tokens(mod_);
-    // }
-
-    // if impls.serialize{
-
-    //     quote!(
-    //         impl<#gen_params_header> ::serde::Serialize for #trait_to<#gen_params_use_to>
-    //         where
-    //             #trait_backend<#gen_params_use_to>: ::serde::Serialize,
-    //             _ErasedPtr:__sabi_re::AsPtr<PtrTarget=()>,
-    //             #(#where_preds,)*
-    //         {
-    //             fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    //             where
-    //                 S: ::serde::Serializer,
-    //             {
-    //                 self.obj.serialize(serializer)
-    //             }
-    //         }
-    //     ).to_tokens(mod_);
-    // }
+    if impls.serialize {
+        let where_preds = where_preds.into_iter();
+
+        quote_spanned!(spans.serialize=>
+            impl<#gen_params_header> ::serde::Serialize for #trait_to<#gen_params_use_to>
+            where
+                #trait_backend<#gen_params_use_to>: ::serde::Serialize,
+                _ErasedPtr:__sabi_re::AsPtr<PtrTarget=()>,
+                #(#where_preds,)*
+            {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: ::serde::Serializer,
+                {
+                    ::serde::Serialize::serialize(&self.obj, serializer)
+                }
+            }
+        )
+        .to_tokens(mod_);
+    }
+    if impls.deserialize {
+        let where_preds = where_preds.into_iter();
+        let lt_erasedptr = &lt_tokens.lt_erasedptr;
+
+        let gen_params_de_header = totrait_def.generics_tokenizer(
+            InWhat::ImplHeader,
+            WithAssocTys::Yes(WhichSelf::NoSelf),
+            arenas.alloc(quote!('__de, #lt_erasedptr)),
+        );
+
+        let deserialize_dyn = quote!(
+            <
+                #trait_interface<#trait_interface_use> as
+                ::abi_stable::erased_types::DeserializeDyn<'__de, Self>
+            >
+        );
+
+        quote_spanned!(spans.deserialize=>
+            impl<#gen_params_de_header> ::serde::Deserialize<'__de>
+            for #trait_to<#gen_params_use_to>
+            where
+                #trait_interface<#trait_interface_use>:
+                    ::abi_stable::erased_types::DeserializeDyn<'__de, Self>,
+                #deserialize_dyn::Proxy: ::serde::Deserialize<'__de>,
+                _ErasedPtr:__sabi_re::AsPtr<PtrTarget=()>,
+                #(#where_preds,)*
+            {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: ::serde::Deserializer<'__de>,
+                {
+                    let proxy = <#deserialize_dyn::Proxy as ::serde::Deserialize<'__de>>::
+                        deserialize(deserializer)?;
+                    #deserialize_dyn::deserialize_dyn(proxy)
+                        .map_err(<D::Error as ::serde::de::Error>::custom)
+                }
+            }
+        )
+        .to_tokens(mod_);
+    }
 
     if let Some(iter_item) = &totrait_def.iterator_item {
         let one_lt = &lt_tokens.one_lt;
@@ -9,9 +9,24 @@
 //! - `WhichItem::TraitImpl`:
 //!     outputs the method in the trait implemetation for the generated trait object.
 //!
+//! - `WhichItem::InstrumentedImpl`:
+//!     outputs the method in the trait implemetation for `Instrumented<Trait_TO>`.
+//!
+//! - `WhichItem::MockImpl`:
+//!     outputs the method in the trait implemetation for `Trait_Mock`.
+//!
+//! - `WhichItem::DelegateTrait`:
+//!     outputs the method in the `Trait_Delegate` trait definition.
+//!
+//! - `WhichItem::DelegateImpl`:
+//!     outputs the method in the trait implemetation for `Trait_Delegate` implementors.
+//!
 //! - `WhichItem::TraitObjectImpl`:
 //!     outputs the methods in the inherent implemetation of the generated trait object.
 //!
+//! - `WhichItem::SupertraitImpl`:
+//!     outputs the method in the trait implemetation for the trait objects of subtraits.
+//!
 //! - `WhichItem::VtableDecl`:
 //!     outputs the fields of the trait object vtable.
 //!
@@ -63,7 +78,13 @@ impl<'a> ToTokens for MethodTokenizer<'a> {
         // vis: the visibility of the generated method,
         //      None if it's implicit,Some(_) if it's explicit.
         let (is_method, vis) = match which_item {
-            WhichItem::Trait | WhichItem::TraitImpl => (true, None),
+            WhichItem::Trait
+            | WhichItem::TraitImpl
+            | WhichItem::InstrumentedImpl
+            | WhichItem::MockImpl
+            | WhichItem::DelegateTrait
+            | WhichItem::DelegateImpl
+            | WhichItem::SupertraitImpl => (true, None),
             WhichItem::TraitObjectImpl => (true, Some(trait_def.submod_vis)),
             WhichItem::VtableDecl | WhichItem::VtableImpl => (false, Some(trait_def.submod_vis)),
         };
@@ -159,17 +180,59 @@ impl<'a> ToTokens for MethodTokenizer<'a> {
                 }
             })
         });
-        let param_ty = method.params.iter().map(|param| &param.ty);
+        let param_ty = method.params.iter().map(move |param| {
+            ToTokenFnMut::new(move |ts| {
+                if param.is_self_ref && !is_method {
+                    // `&Self` parameters are passed to the vtable as a type-erased reference.
+                    let lifetime = WriteLifetime(param.self_ref_lifetime());
+                    ts.append_all(quote_spanned!(method_span=> __sabi_re::RRef<#lifetime, ()>));
+                } else {
+                    param.ty.to_tokens(ts);
+                }
+            })
+        });
         let param_names_c = param_names_a.clone();
         let param_names_d = param_names_a.clone();
         let param_names_e = method.params.iter().map(|x| x.pattern);
-        let return_ty = method.output.iter();
+        let self_param_names = method
+            .params
+            .iter()
+            .filter(|x| x.is_self_ref)
+            .map(|x| x.name);
+        let has_self_params = method.has_self_params();
+        // The `Self` returned by the method is returned from the vtable
+        // as the pointer it is wrapped in.
+        let method_output = if method.returns_self {
+            Some(quote_spanned!(method_span=> _ErasedPtr))
+        } else {
+            method.output.as_ref().map(ToTokens::into_token_stream)
+        };
+        // The return type of the vtable entry,
+        // which wraps the return type of the method when it catches panics.
+        let vtable_return_ty = if method.catch_panic {
+            let ret = match &method_output {
+                Some(ret) => quote!(#ret),
+                None => quote!(()),
+            };
+            Some(quote_spanned!(method_span=>
+                ::abi_stable::std_types::RResult<#ret, ::abi_stable::sabi_types::RPanicPayload>
+            ))
+        } else {
+            method_output
+        };
 
         let self_is_sized_bound = Some(&ctokens.self_sized)
             .filter(|_| is_method && method.self_param == SelfParam::ByVal);
 
+        // The ABI of the vtable entry
+        let vtable_abi = if method.c_unwind {
+            &ctokens.extern_c_unwind
+        } else {
+            &ctokens.extern_c
+        };
+
         let abi = match which_item {
-            WhichItem::VtableImpl => Some(&ctokens.extern_c),
+            WhichItem::VtableImpl => Some(vtable_abi),
             _ => method.abi,
         };
 
@@ -187,6 +250,7 @@ impl<'a> ToTokens for MethodTokenizer<'a> {
 
         if WhichItem::VtableDecl == which_item {
             let optional_field = default_.as_ref().map(|_| &ctokens.missing_field_option);
+            let vtable_return_ty_iter = vtable_return_ty.iter();
             let derive_attrs = method.derive_attrs;
 
             quote_spanned!( method_span=>
@@ -194,24 +258,40 @@ impl<'a> ToTokens for MethodTokenizer<'a> {
                 #(#derive_attrs)*
                 #vis #method_name:
                     #(for< #(#lifetimes,)* >)*
-                    unsafe extern "C" fn(
+                    unsafe #vtable_abi fn(
                         #self_param,
                         #( #param_names_a:#param_ty ,)*
-                    ) #(-> #return_ty )*
+                    ) #(-> #vtable_return_ty_iter )*
             )
         } else {
             let inherent_method_docs = ToTokenFnMut::new(|ts| {
-                if WhichItem::TraitObjectImpl != which_item {
-                    return;
-                }
                 let trait_name = trait_def.name;
-                let m_docs = format!(
-                    "This is the inherent equivalent of \
-                     [the trait method of the same name](./trait.{TN}.html#tymethod.{TM})\
-                    ",
-                    TN = trait_name,
-                    TM = method_name,
-                );
+                let m_docs = match which_item {
+                    WhichItem::TraitObjectImpl => format!(
+                        "This is the inherent equivalent of \
+                         [the trait method of the same name](./trait.{TN}.html#tymethod.{TM})\
+                        ",
+                        TN = trait_name,
+                        TM = method_name,
+                    ),
+                    WhichItem::DelegateTrait if method.returns_self => format!(
+                        "The implementation of \
+                         [the trait method of the same name](./trait.{TN}.html#tymethod.{TM}),\
+                         which must be written by hand because it returns `Self`.\
+                        ",
+                        TN = trait_name,
+                        TM = method_name,
+                    ),
+                    WhichItem::DelegateTrait => format!(
+                        "The implementation of \
+                         [the trait method of the same name](./trait.{TN}.html#tymethod.{TM}),\
+                         which forwards to the delegate by default.\
+                        ",
+                        TN = trait_name,
+                        TM = method_name,
+                    ),
+                    _ => return,
+                };
 
                 ts.append_all(quote!(#[doc = #m_docs]));
             });
@@ -221,8 +301,24 @@ impl<'a> ToTokens for MethodTokenizer<'a> {
                 _ => method.unsafety,
             };
 
+            let return_ty = match which_item {
+                WhichItem::VtableImpl => vtable_return_ty,
+                // The `&Self` parameters are checked to be the same type as `self`
+                WhichItem::TraitObjectImpl if has_self_params => {
+                    let ret = match &method.output {
+                        Some(ret) => quote!(#ret),
+                        None => quote!(()),
+                    };
+                    Some(quote_spanned!(method_span=>
+                        ::std::result::Result<#ret, __sabi_re::SelfTypeMismatch>
+                    ))
+                }
+                _ => method.output.as_ref().map(ToTokens::into_token_stream),
+            };
+            let return_ty = return_ty.iter();
+
             quote_spanned!(method_span=>
-                #[allow(clippy::let_and_return)]
+                #[allow(clippy::let_and_return, clippy::redundant_closure_call)]
                 #(#other_attrs)*
                 #inherent_method_docs
                 #vis #unsafety #abi fn #method_name #(< #(#lifetimes,)* >)* (
@@ -246,6 +342,30 @@ impl<'a> ToTokens for MethodTokenizer<'a> {
             SelfParam::ByVal => &ctokens.ptr_val_bound,
         };
 
+        let self_returning_bound =
+            Some(&ctokens.ptr_smart_pointer_bound).filter(|_| method.returns_self);
+
+        // The arguments passed to the trait method from the vtable entry,
+        // `&Self` parameters are passed as `RRef<'_, ()>`.
+        let vtable_impl_args = method.params.iter().map(|param| {
+            let name = param.name;
+            if param.is_self_ref {
+                quote_spanned!(method_span=> &*#name.transmute_into_raw::<#self_ty>())
+            } else {
+                name.into_token_stream()
+            }
+        });
+
+        // Wraps the `Self` returned by the trait method in a new `_OrigPtr`,
+        // erasing its type.
+        let wrap_self_ret = |ret: TokenStream2| {
+            quote_spanned!(method_span=>
+                __sabi_re::TransmuteElement::transmute_element::<()>(
+                    <_OrigPtr as OrigPtrBounds<_Self>>::from_value(#ret)
+                )
+            )
+        };
+
         let output_safety = |output: &mut TokenStream2, input: TokenStream2| {
             output.append_all(if let Some(safety) = method.unsafety {
                 quote_spanned!(safety.span => { #safety{ #input } })
@@ -256,7 +376,15 @@ impl<'a> ToTokens for MethodTokenizer<'a> {
 
         match (which_item, &method.self_param) {
             (WhichItem::Trait, _) => {
-                method.default.as_ref().map(|x| x.block).to_tokens(ts);
+                match &method.default {
+                    Some(default) if method.is_async => {
+                        let block = &default.block;
+                        ts.append_all(quote_spanned!(method_span=>{
+                            ::abi_stable::std_types::RFuture::new(async move #block)
+                        }));
+                    }
+                    default => default.as_ref().map(|x| x.block).to_tokens(ts),
+                }
                 method.semicolon.to_tokens(ts);
             }
             (WhichItem::TraitImpl, _) => {
@@ -267,7 +395,174 @@ impl<'a> ToTokens for MethodTokenizer<'a> {
                     ),
                 );
             }
-            (WhichItem::TraitObjectImpl, _) => {
+            (WhichItem::InstrumentedImpl, _) => {
+                let trait_name = trait_def.name;
+                let split = match &method.self_param {
+                    SelfParam::ByRef {
+                        is_mutable: false, ..
+                    } => quote_spanned!(method_span=> sabi_split_ref),
+                    SelfParam::ByRef {
+                        is_mutable: true, ..
+                    } => quote_spanned!(method_span=> sabi_split_mut),
+                    SelfParam::ByVal => quote_spanned!(method_span=> sabi_split),
+                };
+                // `&Self` parameters are unwrapped into the trait object they wrap.
+                let param_names_c = method.params.iter().map(|param| {
+                    let name = param.name;
+                    if param.is_self_ref {
+                        quote_spanned!(method_span=> __sabi_re::Instrumented::inner(#name))
+                    } else {
+                        name.into_token_stream()
+                    }
+                });
+                // Only methods returning a type named `RResult` or `Result`
+                // can report that they returned an error.
+                let is_fallible = match &method.output {
+                    Some(syn::Type::Path(ty)) => ty
+                        .path
+                        .segments
+                        .last()
+                        .map_or(false, |s| s.ident == "RResult" || s.ident == "Result"),
+                    _ => false,
+                };
+                let instrument_fn = if is_fallible {
+                    quote_spanned!(method_span=> __sabi_re::instrument_fallible_call)
+                } else {
+                    quote_spanned!(method_span=> __sabi_re::instrument_call)
+                };
+                let trait_name_str = trait_name.to_string();
+                let method_name_str = method_name.to_string();
+
+                let call = quote_spanned!(method_span=>
+                    let (__inner, __hook) = __sabi_re::Instrumented::#split(self);
+                    let __ret = #instrument_fn(
+                        &__hook,
+                        #trait_name_str,
+                        #method_name_str,
+                        move || #trait_name::#method_name(__inner, #(#param_names_c,)*),
+                    );
+                );
+                output_safety(
+                    ts,
+                    if method.returns_self {
+                        quote_spanned!(method_span=>
+                            #call
+                            __sabi_re::Instrumented::new(__ret, ::std::clone::Clone::clone(&__hook))
+                        )
+                    } else {
+                        quote_spanned!(method_span=> #call __ret )
+                    },
+                );
+            }
+            (WhichItem::MockImpl, _) => {
+                let method_name_str = method_name.to_string();
+                // Unconfigured methods use the default implementation if there is one.
+                let fallback = match &method.default {
+                    Some(default) => {
+                        let block = &default.block;
+                        let block = if method.is_async {
+                            quote_spanned!(method_span=>{
+                                ::abi_stable::std_types::RFuture::new(async move #block)
+                            })
+                        } else {
+                            block.into_token_stream()
+                        };
+                        quote_spanned!(method_span=>
+                            #( let #param_names_e = #param_names_d; )*
+                            #block
+                        )
+                    }
+                    None => {
+                        let msg = format!(
+                            "`{}_Mock::{}` was called without configuring it with `expect_{}`",
+                            trait_def.name, method_name, method_name,
+                        );
+                        quote_spanned!(method_span=> panic!(#msg) )
+                    }
+                };
+                let recorded_args = method.params.iter().map(|param| {
+                    let name = param.name;
+                    quote_spanned!(method_span=> (&__sabi_re::MockArg(&#name)).sabi_mock_arg())
+                });
+                output_safety(
+                    ts,
+                    quote_spanned!(method_span=>
+                        #[allow(unused_imports)]
+                        use __sabi_re::{MockArgDebug as _, MockArgOpaque as _};
+
+                        let __args = ::std::vec![#(#recorded_args,)*];
+                        if let Some(__behavior) =
+                            self.#method_name.sabi_behavior(&self.sabi_calls, #method_name_str, __args)
+                        {
+                            return (*__behavior)(#(#param_names_c,)*);
+                        }
+                        #fallback
+                    ),
+                );
+            }
+            (WhichItem::DelegateTrait, _) if method.returns_self => {
+                // There's no way to wrap the `Self` returned by the delegate back into `Self`.
+                ts.append_all(quote_spanned!(method_span=> ;));
+            }
+            (WhichItem::DelegateTrait, self_param) => {
+                let trait_name = trait_def.name;
+                let delegate = match self_param {
+                    SelfParam::ByRef {
+                        is_mutable: false, ..
+                    } => quote_spanned!(method_span=> Self::sabi_delegate(self)),
+                    SelfParam::ByRef {
+                        is_mutable: true, ..
+                    } => quote_spanned!(method_span=> Self::sabi_delegate_mut(self)),
+                    SelfParam::ByVal => {
+                        quote_spanned!(method_span=> Self::sabi_into_delegate(self))
+                    }
+                };
+                // `&Self` parameters are replaced with their delegate.
+                let param_names_c = method.params.iter().map(|param| {
+                    let name = param.name;
+                    if param.is_self_ref {
+                        quote_spanned!(method_span=> Self::sabi_delegate(#name))
+                    } else {
+                        name.into_token_stream()
+                    }
+                });
+                output_safety(
+                    ts,
+                    quote_spanned!(method_span=>
+                        #trait_name::#method_name(#delegate, #(#param_names_c,)*)
+                    ),
+                );
+            }
+            (WhichItem::DelegateImpl, _) => {
+                let trait_delegate = parse_str_as_ident(&format!("{}_Delegate", trait_def.name));
+                output_safety(
+                    ts,
+                    quote_spanned!(method_span=>
+                        #trait_delegate::#method_name(self, #(#param_names_c,)*)
+                    ),
+                );
+            }
+            (WhichItem::TraitObjectImpl, _) | (WhichItem::SupertraitImpl, _) => {
+                let is_supertrait_impl = which_item == WhichItem::SupertraitImpl;
+
+                // Subtrait trait objects get the vtable of this trait from their own vtable,
+                // with the `|$this| $vtable` closure passed to the supertrait impl macro.
+                let vtable = if is_supertrait_impl {
+                    quote_spanned!(method_span=>
+                        ({ let $__sabi_this = &self; $__sabi_vtable })
+                    )
+                } else {
+                    quote_spanned!(method_span=> self.sabi_vtable())
+                };
+
+                let param_names_c = method.params.iter().map(|param| {
+                    let name = param.name;
+                    if param.is_self_ref {
+                        quote_spanned!(method_span=> #name.obj.sabi_as_rref())
+                    } else {
+                        name.into_token_stream()
+                    }
+                });
                 let method_call = match &method.self_param {
                     SelfParam::ByRef {
                         is_mutable: false, ..
@@ -295,45 +590,114 @@ impl<'a> ToTokens for MethodTokenizer<'a> {
                     }
                 };
 
-                match default_ {
+                let method_call = if method.catch_panic {
+                    quote_spanned!(method_span=> (#method_call).unwrap_or_resume_unwind() )
+                } else {
+                    method_call
+                };
+
+                let method_call = if method.returns_self {
+                    quote_spanned!(method_span=>
+                        let __vtable = self.obj.sabi_et_vtable();
+                        let __ret = #method_call;
+                        Self{
+                            obj: __sabi_re::RObject::from_erased_ptr(__ret, __vtable),
+                            _marker: __sabi_re::UnsafeIgnoredType::DEFAULT,
+                        }
+                    )
+                } else {
+                    method_call
+                };
+
+                // The `&Self` parameters are only checked when calling the vtable entry,
+                // because the default implementation is written against the trait object.
+                // Trait objects from poisoned libraries panic instead of calling into them.
+                let library_check = match self.trait_def.which_object {
+                    WhichObject::RObject => quote_spanned!(method_span=>
+                        self.obj.sabi_assert_library_not_poisoned();
+                    ),
+                    WhichObject::DynTrait => quote!(),
+                };
+
+                let self_checks = quote_spanned!(method_span=>
+                    #(
+                        self.obj.sabi_check_same_type(&#self_param_names.obj)?;
+                    )*
+                );
+
+                let body = match default_ {
                     Some(default_) => {
                         let block = &default_.block;
-                        ts.append_all(quote_spanned!(method_span=>
-                                #ptr_constraint
-                            {
-                                match self.sabi_vtable().#method_name() {
-                                    Some(__method)=>{
-                                        unsafe{
-                                            #method_call
-                                        }
-                                    }
-                                    None=>{
-                                        #(
-                                            let #param_names_e=#param_names_d;
-                                        )*
-                                        #block
+                        let block = if method.is_async {
+                            quote_spanned!(method_span=>{
+                                ::abi_stable::std_types::RFuture::new(async move #block)
+                            })
+                        } else {
+                            block.into_token_stream()
+                        };
+                        let default_body = quote_spanned!(method_span=>
+                            #(
+                                let #param_names_e=#param_names_d;
+                            )*
+                            #block
+                        );
+                        // The closure allows `return`s in the default implementation
+                        // when its return value is wrapped in an `Ok`.
+                        let default_body = if has_self_params {
+                            quote_spanned!(method_span=> (move||{ #default_body })() )
+                        } else {
+                            default_body
+                        };
+                        quote_spanned!(method_span=>
+                            match #vtable.#method_name() {
+                                Some(__method)=>{
+                                    #library_check
+                                    #self_checks
+                                    unsafe{
+                                        #method_call
                                     }
                                 }
-                            }
-                        ));
-                    }
-                    None => {
-                        ts.append_all(quote_spanned!(method_span=>
-                                #ptr_constraint
-                            {
-                                let __method=self.sabi_vtable().#method_name();
-                                unsafe{
-                                    #method_call
+                                None=>{
+                                    #default_body
                                 }
                             }
-                        ));
+                        )
                     }
+                    None => quote_spanned!(method_span=>
+                        let __method=#vtable.#method_name();
+                        #library_check
+                        #self_checks
+                        unsafe{
+                            #method_call
+                        }
+                    ),
+                };
+
+                let body = if has_self_params {
+                    quote_spanned!(method_span=> Ok({ #body }) )
+                } else {
+                    body
+                };
+
+                // The bounds of the supertrait impl are in its where clause,
+                // since trait impl methods can't have bounds that the trait method doesn't.
+                if is_supertrait_impl {
+                    ts.append_all(quote_spanned!(method_span=> { #body }));
+                } else {
+                    ts.append_all(quote_spanned!(method_span=>
+                            #ptr_constraint
+                            #self_returning_bound
+                        {
+                            #body
+                        }
+                    ));
                 }
             }
             (WhichItem::VtableDecl, _) => {
                 quote_spanned!(method_span=> , ).to_tokens(ts);
             }
             (WhichItem::VtableImpl, SelfParam::ByRef { is_mutable, .. }) => {
+                let param_names_c = vtable_impl_args.clone();
                 let mut_token = ToTokenFnMut::new(|ts| {
                     if *is_mutable {
                         syn::token::Mut { span: method_span }.to_tokens(ts);
@@ -357,30 +721,74 @@ impl<'a> ToTokens for MethodTokenizer<'a> {
                         // The correctness of the lifetime is guaranteed by the trait definition.
                         quote_spanned!(method_span=> __sabi_re::transmute_ignore_size(#ret) )
                     }
+                    None if method.returns_self => wrap_self_ret(quote!(#ret)),
                     None => quote_spanned!(method_span=> #ret ),
                 };
 
-                ts.append_all(quote_spanned!(method_span=>{
-                    unsafe{
-                        let #ret = ::abi_stable::extern_fn_panic_handling!{no_early_return;
-                            __Trait::#method_name(
-                                &#mut_token *_self.transmute_into_raw::<#self_ty>(),
-                                #(#param_names_c,)*
-                            )
-                        };
+                if method.catch_panic {
+                    ts.append_all(quote_spanned!(method_span=>{
+                        unsafe{
+                            ::abi_stable::extern_fn_panic_handling!{catch_panic;
+                                let #ret = __Trait::#method_name(
+                                    &#mut_token *_self.transmute_into_raw::<#self_ty>(),
+                                    #(#param_names_c,)*
+                                );
 
-                        #transmute_ret
-                    }
-                }));
-            }
-            (WhichItem::VtableImpl, SelfParam::ByVal) => {
-                ts.append_all(quote_spanned!(method_span=>{
-                    ::abi_stable::extern_fn_panic_handling!{no_early_return; unsafe{
+                                #transmute_ret
+                            }
+                        }
+                    }));
+                } else {
+                    let call = quote_spanned!(method_span=>
                         __Trait::#method_name(
-                            (_self as *mut #self_ty).read(),#(#param_names_c,)*
+                            &#mut_token *_self.transmute_into_raw::<#self_ty>(),
+                            #(#param_names_c,)*
                         )
-                    }}
-                }));
+                    );
+
+                    // `extern "C-unwind"` vtable entries let panics unwind into the caller.
+                    let call = if method.c_unwind {
+                        call
+                    } else {
+                        quote_spanned!(method_span=>
+                            ::abi_stable::extern_fn_panic_handling!{no_early_return; #call}
+                        )
+                    };
+
+                    ts.append_all(quote_spanned!(method_span=>{
+                        unsafe{
+                            let #ret = #call;
+
+                            #transmute_ret
+                        }
+                    }));
+                }
+            }
+            (WhichItem::VtableImpl, SelfParam::ByVal) => {
+                let param_names_c = vtable_impl_args.clone();
+                let call = quote_spanned!(method_span=>
+                    __Trait::#method_name(
+                        (_self as *mut #self_ty).read(),#(#param_names_c,)*
+                    )
+                );
+                let call = if method.returns_self {
+                    wrap_self_ret(call)
+                } else {
+                    call
+                };
+                let call = quote_spanned!(method_span=> unsafe{ #call } );
+
+                ts.append_all(if method.catch_panic {
+                    quote_spanned!(method_span=>{
+                        ::abi_stable::extern_fn_panic_handling!{catch_panic; #call}
+                    })
+                } else if method.c_unwind {
+                    quote_spanned!(method_span=>{ #call })
+                } else {
+                    quote_spanned!(method_span=>{
+                        ::abi_stable::extern_fn_panic_handling!{no_early_return; #call}
+                    })
+                });
             }
         }
     }
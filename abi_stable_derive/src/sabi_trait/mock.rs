@@ -0,0 +1,278 @@
+use proc_macro2::TokenStream as TokenStream2;
+
+use quote::{quote, quote_spanned, ToTokens};
+
+use syn::visit_mut::VisitMut;
+
+use as_derive_utils::gen_params_in::InWhat;
+
+use crate::{
+    parse_utils::parse_str_as_ident,
+    sabi_trait::{SelfParam, TokenizerParams, WhichItem, WhichSelf, WithAssocTys},
+};
+
+/// Outputs the `Trait_Mock` type,
+/// which implements the trait by calling closures configured for each method,
+/// recording the methods that were called and their arguments.
+pub(super) fn mock_items(
+    TokenizerParams {
+        config,
+        ctokens,
+        trait_def,
+        totrait_def,
+        submod_vis,
+        trait_ident,
+        trait_mock,
+        ..
+    }: TokenizerParams<'_>,
+    mod_: &mut TokenStream2,
+) {
+    if !trait_def.mock {
+        return;
+    }
+
+    let doc_hidden_attr = config.doc_hidden_attr;
+
+    let mock_decl = totrait_def.generics_tokenizer(
+        InWhat::ItemDecl,
+        WithAssocTys::Yes(WhichSelf::NoSelf),
+        &ctokens.ts_empty,
+    );
+    let mock_header = totrait_def.generics_tokenizer(
+        InWhat::ImplHeader,
+        WithAssocTys::Yes(WhichSelf::NoSelf),
+        &ctokens.ts_empty,
+    );
+    let mock_use = totrait_def.generics_tokenizer(
+        InWhat::ItemUse,
+        WithAssocTys::Yes(WhichSelf::NoSelf),
+        &ctokens.ts_empty,
+    );
+    let dummy_struct_generics = totrait_def.generics_tokenizer(
+        InWhat::DummyStruct,
+        WithAssocTys::Yes(WhichSelf::NoSelf),
+        &ctokens.ts_empty,
+    );
+    let gen_params_use_trait =
+        trait_def.generics_tokenizer(InWhat::ItemUse, WithAssocTys::No, &ctokens.empty_ts);
+
+    let mock_ty = quote!(#trait_mock<#mock_use>);
+
+    let method_names = totrait_def
+        .methods
+        .iter()
+        .map(|m| m.name)
+        .collect::<Vec<_>>();
+    let method_names_a = method_names.iter();
+    let method_names_b = method_names.iter();
+    let method_names_c = method_names.iter();
+
+    // The bounds of the closure that each method calls.
+    let behavior_bounds = totrait_def
+        .methods
+        .iter()
+        .map(|method| {
+            let method_span = method.name.span();
+            let lifetimes = method.lifetimes.iter().map(|l| &l.lifetime);
+            let params = method.params.iter().map(|param| {
+                if param.is_self_ref {
+                    let lifetime = param.self_ref_lifetime();
+                    quote_spanned!(method_span=> & #lifetime #mock_ty)
+                } else {
+                    param.ty.to_token_stream()
+                }
+            });
+            // Closures can't return borrows of the mock,
+            // so they return values that borrow for `'static` instead.
+            let output = if method.returns_self {
+                mock_ty.clone()
+            } else {
+                match (&method.output, &method.self_param) {
+                    (Some(output), SelfParam::ByRef { lifetime, .. }) => {
+                        let mut output = output.clone();
+                        if let Some(lifetime) = lifetime {
+                            ReplaceLifetime {
+                                replaced: lifetime,
+                                with: &ctokens.static_lifetime,
+                            }
+                            .visit_type_mut(&mut output);
+                        }
+                        output.into_token_stream()
+                    }
+                    (Some(output), SelfParam::ByVal) => output.into_token_stream(),
+                    (None, _) => quote!(()),
+                }
+            };
+            quote_spanned!(method_span=>
+                for<#(#lifetimes,)*> Fn(#(#params),*) -> #output + Send + Sync
+            )
+        })
+        .collect::<Vec<_>>();
+    let behavior_bounds_a = behavior_bounds.iter();
+
+    let expect_methods = totrait_def
+        .methods
+        .iter()
+        .zip(&behavior_bounds)
+        .map(|(method, bound)| {
+            let method_name = method.name;
+            let expect_method = parse_str_as_ident(&format!("expect_{}", method_name));
+            let docs = format!(
+                "Sets the closure that the [`{T}::{M}`](trait@{T}#tymethod.{M}) method calls,\n\
+             replacing the previously set one.\n\
+             \n\
+             The closure takes the parameters of the method (other than `self`).\n\
+             \n\
+             [`verify`](#method.verify) panics if the method isn't called afterwards.",
+                T = trait_ident,
+                M = method_name,
+            );
+            quote_spanned!(method_name.span()=>
+                #[doc = #docs]
+                #submod_vis fn #expect_method<__F>(&self, behavior: __F) -> &Self
+                where
+                    __F: #bound,
+                    __F: 'static,
+                {
+                    self.#method_name.sabi_set(
+                        &self.sabi_calls,
+                        stringify!(#method_name),
+                        ::std::sync::Arc::new(behavior),
+                    );
+                    self
+                }
+            )
+        });
+
+    let lifetime_bounds = trait_def.lifetime_bounds.iter();
+    let super_traits = trait_def.impld_traits.iter().map(|t| &t.bound);
+    let where_preds = (&trait_def.where_preds).into_iter();
+    let assoc_ty_named_a = trait_def.assoc_tys.values().map(|x| &x.assoc_ty.ident);
+    let assoc_ty_named_b = assoc_ty_named_a.clone();
+    let unsafety = trait_def.item.unsafety;
+    let methods_tokenizer = trait_def.methods_tokenizer(WhichItem::MockImpl);
+
+    let mut mock_docs = String::new();
+    if doc_hidden_attr.is_none() {
+        mock_docs = format!(
+            "A mock implementation of [{T}](trait@{T}),\
+             generated by the `#[sabi(mock)]` attribute.\n\
+             \n\
+             Each method calls the closure set with the `expect_<method>` method of this type,\n\
+             falling back to the default implementation of the method,\
+             and panicking if the method doesn't have one.\n\
+             \n\
+             Clones of this type share the closures, the recorded calls, and the expectations.\
+            ",
+            T = trait_ident,
+        );
+    }
+
+    quote!(
+        #[doc = #mock_docs]
+        #doc_hidden_attr
+        #submod_vis struct #trait_mock<#mock_decl> {
+            #( #method_names_a: __sabi_re::MockMethod<dyn #behavior_bounds_a>, )*
+            sabi_calls: __sabi_re::MockCalls,
+            _marker: __sabi_re::NonOwningPhantom<(#dummy_struct_generics)>,
+        }
+
+        #[allow(clippy::new_without_default)]
+        impl<#mock_header> #mock_ty {
+            /// Constructs a mock with no configured methods.
+            #submod_vis fn new() -> Self {
+                Self {
+                    #( #method_names_b: __sabi_re::MockMethod::new(), )*
+                    sabi_calls: __sabi_re::MockCalls::new(),
+                    _marker: __sabi_re::NonOwningPhantom::NEW,
+                }
+            }
+
+            #( #expect_methods )*
+
+            /// The names of the methods that were called, in call order.
+            #submod_vis fn calls(&self) -> ::std::vec::Vec<&'static str> {
+                self.sabi_calls.calls()
+            }
+
+            /// How many times the `method` method was called.
+            #submod_vis fn call_count(&self, method: &str) -> usize {
+                self.sabi_calls.call_count(method)
+            }
+
+            /// The arguments (other than `self`) of every call to the `method` method,
+            /// in call order.
+            ///
+            /// Arguments are formatted with `Debug`,
+            /// arguments whose type doesn't implement `Debug` are recorded as `_`.
+            #submod_vis fn call_args(
+                &self,
+                method: &str,
+            ) -> ::std::vec::Vec<::std::vec::Vec<::std::string::String>> {
+                self.sabi_calls.call_args(method)
+            }
+
+            /// Panics if any method configured with an `expect_*` method wasn't called.
+            #[track_caller]
+            #submod_vis fn verify(&self) {
+                self.sabi_calls.verify(stringify!(#trait_mock))
+            }
+
+            /// Forgets the calls recorded so far.
+            #submod_vis fn clear_calls(&self) {
+                self.sabi_calls.clear()
+            }
+        }
+
+        impl<#mock_header> ::std::clone::Clone for #mock_ty {
+            fn clone(&self) -> Self {
+                Self {
+                    #( #method_names_c: ::std::clone::Clone::clone(&self.#method_names_c), )*
+                    sabi_calls: ::std::clone::Clone::clone(&self.sabi_calls),
+                    _marker: __sabi_re::NonOwningPhantom::NEW,
+                }
+            }
+        }
+
+        impl<#mock_header> ::std::default::Default for #mock_ty {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<#mock_header> ::std::fmt::Debug for #mock_ty {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(stringify!(#trait_mock))
+                    .field("calls", &self.sabi_calls)
+                    .finish()
+            }
+        }
+
+        #[deny(unsafe_op_in_unsafe_fn)]
+        #[allow(clippy::needless_lifetimes, clippy::needless_return)]
+        #unsafety impl<#mock_header> #trait_ident<#gen_params_use_trait> for #mock_ty
+        where
+            Self:#( #super_traits + )* #(#lifetime_bounds+)*  ,
+            #(#where_preds,)*
+        {
+            #( type #assoc_ty_named_a=#assoc_ty_named_b; )*
+
+            #methods_tokenizer
+        }
+    )
+    .to_tokens(mod_);
+}
+
+/// Replaces a lifetime in a type with another one.
+struct ReplaceLifetime<'a> {
+    replaced: &'a syn::Lifetime,
+    with: &'a syn::Lifetime,
+}
+
+impl VisitMut for ReplaceLifetime<'_> {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+        if lifetime == self.replaced {
+            *lifetime = self.with.clone();
+        }
+    }
+}
@@ -113,7 +113,8 @@ where
             (0, true) | (1, true) => {
                 self.errors.push_err(spanned_err!(
                     segments,
-                    "Self can't be used in a parameter,return type,or associated type.",
+                    "Self can only be used in `&Self` parameters and as the whole return type, \
+                     it can't be used elsewhere in a parameter,return type,or associated type.",
                 ));
                 return;
             }
@@ -0,0 +1,299 @@
+//! Implements `#[sabi_trait]` supertraits for the trait objects of their subtraits.
+//!
+//! The subtrait can't see the methods of its supertraits,
+//! so each trait declares a macro that implements it for a subtrait trait object,
+//! which the subtrait invokes for each of its `#[sabi_trait]` supertraits.
+
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use proc_macro2::{Group, Punct, Spacing, TokenStream as TokenStream2, TokenTree};
+
+use quote::{quote, ToTokens, TokenStreamExt};
+
+use as_derive_utils::gen_params_in::InWhat;
+
+use crate::{
+    parse_utils::parse_str_as_ident,
+    sabi_trait::{TokenizerParams, WhichItem, WhichObject, WhichSelf, WithAssocTys},
+};
+
+/// Makes the names of the supertrait impl macros unique,
+/// since `#[macro_export]` puts them in the root of the crate.
+static MACRO_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Outputs the `__sabi_impl_supertrait` macro,
+/// which implements this trait for the trait object of a subtrait,
+/// forwarding the methods to the vtable of this trait in the vtable of the subtrait.
+///
+/// The macro is invoked like this:
+/// ```text
+/// Trait_trait::__sabi_impl_supertrait!{
+///     scope[path::to::Trait_trait::__sabi_scope]
+///     impl['lt, _ErasedPtr, ...]
+///     trait[path::to::Trait_trait::Trait<'a, u8>]
+///     type[Subtrait_TO<'lt, _ErasedPtr, ...>]
+///     where[_ErasedPtr: __GetPointerKind, ...]
+///     args['a, u8,]
+///     vtable[|this| <expression for the `Trait_trait::VTable_Ref` of `this`>]
+/// }
+/// ```
+pub(super) fn supertrait_impl_macro(
+    TokenizerParams {
+        ctokens,
+        trait_def,
+        submod_vis,
+        trait_ident,
+        ..
+    }: TokenizerParams<'_>,
+    mod_: &mut TokenStream2,
+) {
+    let macro_ident = parse_str_as_ident(&format!(
+        "__sabi_impl_supertrait_{}_{}",
+        trait_ident,
+        MACRO_COUNT.fetch_add(1, Ordering::Relaxed),
+    ));
+
+    let mut metavariables = HashMap::<String, TokenStream2>::new();
+    let mut args_matcher = TokenStream2::new();
+    for param in &trait_def.generics.params {
+        let (name, kind, fragment) = match param {
+            syn::GenericParam::Lifetime(x) => (&x.lifetime.ident, "lt", "lifetime"),
+            syn::GenericParam::Type(x) => (&x.ident, "ty", "ty"),
+            syn::GenericParam::Const(x) => (&x.ident, "ct", "tt"),
+        };
+        let metavariable = metavariable(&format!("__sabi_{}_{}", kind, name));
+        let fragment = parse_str_as_ident(fragment);
+        args_matcher.append_all(quote!(#metavariable: #fragment,));
+        metavariables.insert(name.to_string(), metavariable);
+    }
+
+    // The trait object only implements the trait in the cases where
+    // the trait object of this trait does.
+    let forwarded_impl = if trait_def.which_object == WhichObject::RObject
+        && !trait_def.disable_trait_impl
+        && trait_def.assoc_tys.is_empty()
+    {
+        let super_traits = trait_def
+            .impld_traits
+            .iter()
+            .map(|t| &t.bound)
+            .chain(trait_def.sabi_supertraits.iter().map(|t| t.bound));
+        let lifetime_bounds = trait_def.lifetime_bounds.iter();
+        let where_preds = (&trait_def.where_preds).into_iter();
+        let erased_ptr_bounds = trait_def.erased_ptr_preds();
+        let self_returning_bound =
+            Some(&ctokens.ptr_smart_pointer_bound).filter(|_| trait_def.has_self_returning_methods);
+        let methods = trait_def.methods_tokenizer(WhichItem::SupertraitImpl);
+
+        let bounds = quote!(
+            Self:#( #super_traits + )* #(#lifetime_bounds+)*  ,
+            #erased_ptr_bounds
+            #self_returning_bound
+            #(#where_preds,)*
+        );
+        let bounds = replace_generic_params(bounds, &metavariables);
+        let methods = replace_generic_params(methods.into_token_stream(), &metavariables);
+
+        quote!(
+            const _: () = {
+                #[allow(unused_imports)]
+                use $($__sabi_scope)*::*;
+
+                #[deny(unsafe_op_in_unsafe_fn)]
+                #[allow(
+                    clippy::needless_lifetimes,
+                    clippy::new_ret_no_self,
+                )]
+                impl<$($__sabi_impl_params)*> $__sabi_trait for $__sabi_self_ty
+                where
+                    #bounds
+                    $($__sabi_where_preds)*
+                {
+                    #methods
+                }
+            };
+        )
+    } else {
+        TokenStream2::new()
+    };
+
+    quote!(
+        /// Brings the items that the methods of this trait can refer to into scope
+        /// in the implementations of this trait for the trait objects of subtraits.
+        #[doc(hidden)]
+        #submod_vis mod __sabi_scope {
+            #[allow(unused_imports)]
+            pub use super::super::*;
+        }
+
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #macro_ident {
+            (
+                scope[$($__sabi_scope:tt)*]
+                impl[$($__sabi_impl_params:tt)*]
+                trait[$__sabi_trait:path]
+                type[$__sabi_self_ty:ty]
+                where[$($__sabi_where_preds:tt)*]
+                args[#args_matcher]
+                vtable[|$__sabi_this:ident| $__sabi_vtable:expr]
+            ) => {
+                #forwarded_impl
+            };
+        }
+
+        #[doc(hidden)]
+        #submod_vis use #macro_ident as __sabi_impl_supertrait;
+    )
+    .to_tokens(mod_);
+}
+
+/// Implements the `#[sabi_trait]` supertraits of this trait for the trait object,
+/// with the macros that the supertraits declare.
+pub(super) fn supertrait_impls(
+    TokenizerParams {
+        ctokens,
+        trait_def,
+        trait_to,
+        lt_tokens,
+        ..
+    }: TokenizerParams<'_>,
+    mod_: &mut TokenStream2,
+) {
+    if trait_def.sabi_supertraits.is_empty() {
+        return;
+    }
+
+    let gen_params_header = trait_def.generics_tokenizer(
+        InWhat::ImplHeader,
+        WithAssocTys::Yes(WhichSelf::NoSelf),
+        &lt_tokens.lt_erasedptr,
+    );
+    let gen_params_use_to = trait_def.generics_tokenizer(
+        InWhat::ItemUse,
+        WithAssocTys::Yes(WhichSelf::NoSelf),
+        &lt_tokens.lt_erasedptr,
+    );
+
+    for supertrait in &trait_def.sabi_supertraits {
+        let module = &supertrait.module;
+        let module_alias = supertrait.derived_ident("__sabi_", "_trait");
+        let supertrait_ident = supertrait.ident;
+        let field = supertrait.derived_ident("_sabi_super_", "");
+        let args = supertrait.args(&ctokens.ts_empty);
+        let where_preds = (&trait_def.where_preds).into_iter();
+
+        // The paths are relative to this module,
+        // because the impl is in a scope where the items that
+        // the supertrait methods refer to shadow the items of this module.
+        quote!(
+            use #module as #module_alias;
+
+            #module_alias::__sabi_impl_supertrait!{
+                scope[self::#module_alias::__sabi_scope]
+                impl[#gen_params_header]
+                trait[self::#module_alias::#supertrait_ident<#args>]
+                type[self::#trait_to<#gen_params_use_to>]
+                where[
+                    _ErasedPtr: __GetPointerKind,
+                    #(#where_preds,)*
+                ]
+                args[#args]
+                vtable[|this| self::#module_alias::VTable_Ref(
+                    self::VTable_Ref(this.obj.sabi_et_vtable()).#field()
+                )]
+            }
+        )
+        .to_tokens(mod_);
+    }
+}
+
+/// The `$name` reference to a macro metavariable.
+fn metavariable(name: &str) -> TokenStream2 {
+    let mut tokens = TokenStream2::new();
+    tokens.append(Punct::new('$', Spacing::Alone));
+    tokens.append(parse_str_as_ident(name));
+    tokens
+}
+
+/// Replaces the generic parameters of the trait with the metavariables
+/// that the generic arguments of the supertrait bound are passed through.
+fn replace_generic_params(
+    tokens: TokenStream2,
+    metavariables: &HashMap<String, TokenStream2>,
+) -> TokenStream2 {
+    let mut out = TokenStream2::new();
+    let mut tokens = tokens.into_iter().peekable();
+    // Whether the previous token means that the next identifier isn't a generic parameter,
+    // eg: `foo.T`, `foo::T`, `$T`.
+    let mut after_accessor = false;
+    // Whether the previous token is the first `:` in a `::`.
+    let mut after_joint_colon = false;
+    while let Some(tt) = tokens.next() {
+        match tt {
+            TokenTree::Group(group) => {
+                let stream = replace_generic_params(group.stream(), metavariables);
+                let mut new_group = Group::new(group.delimiter(), stream);
+                new_group.set_span(group.span());
+                out.append(new_group);
+                after_accessor = false;
+                after_joint_colon = false;
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+                let lifetime = match tokens.peek() {
+                    Some(TokenTree::Ident(ident)) => metavariables.get(&ident.to_string()),
+                    _ => None,
+                };
+                match lifetime {
+                    Some(lifetime) => {
+                        tokens.next();
+                        out.append_all(lifetime.clone());
+                    }
+                    None => out.append(punct),
+                }
+                after_accessor = false;
+                after_joint_colon = false;
+            }
+            TokenTree::Punct(punct) => {
+                let is_colon = punct.as_char() == ':';
+                after_accessor =
+                    matches!(punct.as_char(), '.' | '$') || is_colon && after_joint_colon;
+                after_joint_colon = is_colon && punct.spacing() == Spacing::Joint;
+                out.append(punct);
+            }
+            TokenTree::Ident(ident) => {
+                let param = Some(&ident)
+                    .filter(|_| !after_accessor)
+                    .and_then(|ident| metavariables.get(&ident.to_string()));
+                match param {
+                    // Types that are used as the start of a path need to be wrapped in `<>`,
+                    // since `$T::Assoc` is an error when `$T` is a type metavariable.
+                    Some(param) if starts_path(tokens.peek()) => {
+                        let mut stream = quote!(<);
+                        stream.append_all(param.clone());
+                        stream.append_all(quote!(>));
+                        out.append_all(stream);
+                    }
+                    Some(param) => out.append_all(param.clone()),
+                    None => out.append(ident),
+                }
+                after_accessor = false;
+                after_joint_colon = false;
+            }
+            TokenTree::Literal(_) => {
+                out.append(tt);
+                after_accessor = false;
+                after_joint_colon = false;
+            }
+        }
+    }
+    out
+}
+
+/// Whether the token after an identifier makes it the first segment of a path.
+fn starts_path(next: Option<&TokenTree>) -> bool {
+    matches!(next, Some(TokenTree::Punct(p)) if p.as_char() == ':' && p.spacing() == Spacing::Joint)
+}
@@ -12,18 +12,29 @@ fn must_not_pass() {
         ",
         "
             trait Bar {
-                fn bar(&self, x: &Self);
+                fn bar(&self, x: &mut Self);
             }
         ",
+        "
+            trait Bar {
+                fn bar(&self, x: Self);
+            }
+        ",
+        // `Self` can only be the whole return type.
         "
             trait Baz {
-                fn bar(&self) -> Self;
+                fn bar(&self) -> Option<Self>;
+            }
+        ",
+        "
+            trait Baz {
+                async fn bar(&self) -> Self;
             }
         ",
-        // Can't return Self,at least for now.
         "
+            #[sabi(use_dyntrait)]
             trait Baz {
-                fn bar(self) -> Self;
+                fn bar(&self) -> Self;
             }
         ",
         "
@@ -31,6 +42,64 @@ fn must_not_pass() {
                 const X: usize;
             }
         ",
+        "
+            #[sabi(supertrait(Reader))]
+            trait Qux: Reader<Item = u8> {}
+        ",
+        "
+            #[sabi(use_dyntrait)]
+            #[sabi(supertrait(Reader))]
+            trait Qux: Reader {}
+        ",
+        "
+            #[sabi(supertrait(Reader, Writer))]
+            trait Qux: Reader {}
+        ",
+        "
+            trait Qux: Serialize {}
+        ",
+        "
+            #[sabi(use_dyntrait)]
+            trait Qux: Deserialize<'static> {}
+        ",
+        "
+            #[sabi(use_dyntrait)]
+            trait Qux: for<'a> Deserialize<'a, u8> {}
+        ",
+        "
+            trait Qux: SabiKey {}
+        ",
+        "
+            #[sabi(use_dyntrait)]
+            trait Qux: SabiKey {}
+        ",
+        "
+            #[sabi(instrument)]
+            trait Qux {
+                fn qux(&self, other: &Self);
+            }
+        ",
+        "
+            #[sabi(instrument, no_trait_impl)]
+            trait Qux {
+                fn qux(&self);
+            }
+        ",
+        "
+            trait Qux: Copy {}
+        ",
+        "
+            trait Qux: std::convert::AsRef<str> {}
+        ",
+        "
+            #[sabi(mock)]
+            trait Qux: Display {}
+        ",
+        "
+            #[sabi(mock)]
+            #[sabi(supertrait(Reader))]
+            trait Qux: Reader {}
+        ",
     ];
     for elem in list {
         if derive_sabi_trait(elem).is_ok() {
@@ -39,6 +108,25 @@ fn must_not_pass() {
     }
 }
 
+#[test]
+fn unmarked_supertrait() {
+    for elem in [
+        "trait Qux: Reader {}",
+        "#[sabi(supertrait(Reader))] trait Qux: Reader + io::Named {}",
+        "trait Qux: ToString {}",
+        "trait Qux: core::hash::BuildHasher {}",
+        "trait Qux: ::std::ops::Add<u8> {}",
+    ] {
+        let err = derive_sabi_trait(elem).unwrap_err().to_string();
+        assert!(
+            err.contains("Unexpected supertrait bound"),
+            "wrong error for:\n{}\n{}",
+            elem,
+            err
+        );
+    }
+}
+
 #[test]
 fn sabi_trait_test_cases() {
     Tests::load("sabi_trait").run_test(derive_sabi_trait);
@@ -52,6 +140,62 @@ fn must_pass() {
                 fn baz(self);
             }
         ",
+        "
+            #[sabi(supertrait(Reader, Named))]
+            trait Storage<'a>: Reader + io::Named<'a, u8> {
+                fn write(&mut self, value: u32);
+            }
+        ",
+        "
+            trait Shape {
+                fn scaled(&self, factor: u32) -> Self;
+                fn into_doubled(self) -> Self;
+                fn merged<'a>(&'a self, other: &'a Self) -> Self;
+                fn same_area(&self, other: &Self) -> bool;
+            }
+        ",
+        "
+            #[sabi(use_dyntrait)]
+            trait State: Serialize + for<'de> serde::Deserialize<'de> {
+                fn tick(&mut self);
+            }
+        ",
+        "
+            #[sabi(instrument)]
+            trait Codec<'a, T>: Debug + Clone {
+                type Output;
+                fn decode(&'a self, input: &[u8]) -> RResult<Self::Output, T>;
+                unsafe fn reset(&mut self);
+                fn doubled(&self) -> Self;
+            }
+        ",
+        "
+            #[sabi(mock)]
+            trait Fetcher<'a, T>: Debug + Clone + Send + Sync + 'a {
+                type Output;
+                async fn fetch(&self, key: &str) -> Self::Output;
+                fn first<'b>(&self, list: &'b RVec<T>) -> &'b T;
+                fn name(&mut self) -> RStr<'_>;
+                fn merged(&self, other: &Self) -> Self;
+                fn into_list(self) -> RVec<T> {
+                    RVec::new()
+                }
+            }
+        ",
+        "
+            #[sabi(delegate)]
+            unsafe trait Resolver<'a, T>: Debug + Clone + 'a
+            where
+                T: Clone,
+            {
+                type Output: Debug;
+                async fn resolve(&self, name: RStr<'_>) -> Self::Output;
+                unsafe fn flush(&mut self);
+                fn same(&self, other: &Self) -> bool;
+                fn cloned(&self) -> Self;
+                fn into_output(self) -> RVec<T>;
+            }
+        ",
     ];
 
     for elem in list {
@@ -22,6 +22,7 @@ use std::{
 use core_extensions::{matches, IteratorExt};
 
 use syn::{
+    parse_quote,
     punctuated::Punctuated,
     spanned::Spanned,
     token::Unsafe,
@@ -60,14 +61,12 @@ pub(crate) struct TraitDefinition<'a> {
     /// The `Iterator::Item` type for this trait,
     /// None if it doesn't have Iterator as a supertrait.
     pub(crate) iterator_item: Option<&'a syn::Type>,
-    #[allow(dead_code)]
-    /// The path for the implemented serde::Deserialize trait
-    /// (it may reference some trait lifetime parameter)
-    pub(crate) deserialize_bound: Option<DeserializeBound>,
     /// The traits this has as supertraits.
     pub(crate) impld_traits: Vec<TraitImplness<'a>>,
     /// The traits this doesn't have as supertraits.
     pub(crate) unimpld_traits: Vec<&'a Ident>,
+    /// The `#[sabi_trait]` traits this has as supertraits.
+    pub(crate) sabi_supertraits: Vec<SabiSupertrait<'a>>,
     /// A struct describing the traits this does and doesn't have as supertraits
     /// (true means implemented,false means unimplemented)
     pub(crate) trait_flags: TraitStruct<bool>,
@@ -90,8 +89,16 @@ pub(crate) struct TraitDefinition<'a> {
     pub(crate) has_mut_methods: bool,
     /// Whether this has by-value methods.
     pub(crate) has_val_methods: bool,
+    /// Whether this has methods that return `Self`.
+    pub(crate) has_self_returning_methods: bool,
     /// Disables `ìmpl Trait for Trait_TO`
     pub(crate) disable_trait_impl: bool,
+    /// Whether to implement the trait for `Instrumented<Trait_TO>`
+    pub(crate) instrument: bool,
+    /// Whether to generate the `Trait_Mock` type.
+    pub(crate) mock: bool,
+    /// Whether to generate the `Trait_Delegate` trait.
+    pub(crate) delegate: bool,
     /// Whether this has `'static` as a supertrait syntactically.
     pub(crate) is_static: IsStaticTrait,
     /// A TokenStream with the equivalent of `<Pointer::PtrTarget as Trait>::`
@@ -109,7 +116,13 @@ impl<'a> TraitDefinition<'a> {
             methods_with_attrs,
             which_object,
             disable_trait_impl,
+            instrument,
+            mock,
+            delegate,
+            sabi_supertraits: marked_supertraits,
             disable_inherent_default,
+            catch_panic,
+            c_unwind,
             ..
         }: SabiTraitAttrs<'a>,
         arenas: &'a Arenas,
@@ -125,15 +138,24 @@ impl<'a> TraitDefinition<'a> {
         methods_with_attrs
             .into_iter()
             .zip(disable_inherent_default)
-            .filter_map(|(func, disable_inh_def)| {
-                match TraitMethod::new(func, disable_inh_def, ctokens, arenas) {
+            .zip(catch_panic)
+            .zip(c_unwind)
+            .filter_map(
+                |(((func, disable_inh_def), catch_panic), c_unwind)| match TraitMethod::new(
+                    func,
+                    disable_inh_def,
+                    catch_panic,
+                    c_unwind,
+                    ctokens,
+                    arenas,
+                ) {
                     Ok(x) => x,
                     Err(e) => {
                         errors.push_err(e);
                         None
                     }
-                }
-            })
+                },
+            )
             .extending(&mut methods);
 
         /////////////////////////////////////////////////////
@@ -151,14 +173,15 @@ impl<'a> TraitDefinition<'a> {
         let GetSupertraits {
             impld_traits,
             unimpld_traits,
+            sabi_supertraits,
             mut lifetime_bounds,
             iterator_item,
-            deserialize_bound,
             trait_flags,
             trait_spans,
             errors: supertrait_errors,
         } = get_supertraits(
             &trait_.supertraits,
+            &marked_supertraits,
             &lifetime_params,
             which_object,
             arenas,
@@ -223,6 +246,60 @@ impl<'a> TraitDefinition<'a> {
             .iter()
             .any(|m| matches!(&m.self_param, SelfParam::ByVal));
 
+        let has_self_returning_methods = methods.iter().any(|m| m.returns_self);
+
+        if which_object == WhichObject::DynTrait {
+            for method in methods.iter().filter(|m| m.uses_self()) {
+                errors.push_err(spanned_err!(
+                    method.name,
+                    "`Self` can only be used in the methods of \
+                     traits that don't use the #[sabi(use_dyntrait)] attribute.",
+                ));
+            }
+        }
+
+        if instrument && disable_trait_impl {
+            errors.push_err(spanned_err!(
+                trait_.ident,
+                "The #[sabi(instrument)] attribute can't be used along with \
+                 the #[sabi(no_trait_impl)] attribute.",
+            ));
+        }
+
+        // The trait object can't implement a trait with `&Self` parameters,
+        // since it can't do anything sensible when they wrap a different type than `self`.
+        let has_self_param_methods = methods.iter().any(|m| m.has_self_params());
+        let disable_trait_impl = disable_trait_impl || has_self_param_methods;
+
+        if instrument && has_self_param_methods {
+            errors.push_err(spanned_err!(
+                trait_.ident,
+                "The #[sabi(instrument)] attribute can't be used in traits with \
+                 `&Self` parameters, because their trait object doesn't implement the trait.",
+            ));
+        }
+
+        if mock {
+            // `Trait_Mock` can only implement these supertraits.
+            let mockable = ["Send", "Sync", "Clone", "Debug", "Default"];
+            for supertrait in impld_traits
+                .iter()
+                .filter(|t| !mockable.iter().any(|m| t.ident == m))
+            {
+                errors.push_err(spanned_err!(
+                    supertrait.ident,
+                    "The #[sabi(mock)] attribute only supports these supertraits: {}",
+                    mockable.join(", "),
+                ));
+            }
+            for supertrait in &sabi_supertraits {
+                errors.push_err(spanned_err!(
+                    supertrait.bound,
+                    "The #[sabi(mock)] attribute doesn't support #[sabi_trait] supertraits",
+                ));
+            }
+        }
+
         let ts_fq_self = {
             let (_, generics_params, _) = trait_.generics.split_for_impl();
             quote!( <_OrigPtr::PtrTarget as __Trait #generics_params >:: )
@@ -245,9 +322,9 @@ impl<'a> TraitDefinition<'a> {
             generics: &trait_.generics,
             lifetime_bounds,
             iterator_item,
-            deserialize_bound,
             impld_traits,
             unimpld_traits,
+            sabi_supertraits,
             trait_flags,
             trait_spans,
             vis,
@@ -256,7 +333,11 @@ impl<'a> TraitDefinition<'a> {
             methods,
             has_mut_methods,
             has_val_methods,
+            has_self_returning_methods,
             disable_trait_impl,
+            instrument,
+            mock,
+            delegate,
             ts_fq_self: arenas.alloc(ts_fq_self),
             is_static,
             ctokens,
@@ -273,7 +354,13 @@ impl<'a> TraitDefinition<'a> {
         let mut errors = LinearResult::ok(());
 
         let replace_with = match which_item {
-            WhichItem::Trait | WhichItem::TraitImpl => {
+            WhichItem::Trait
+            | WhichItem::TraitImpl
+            | WhichItem::InstrumentedImpl
+            | WhichItem::MockImpl
+            | WhichItem::DelegateTrait
+            | WhichItem::DelegateImpl
+            | WhichItem::SupertraitImpl => {
                 return Ok(this);
             }
             WhichItem::TraitObjectImpl => ReplaceWith::Remove,
@@ -397,6 +484,14 @@ impl<'a> TraitDefinition<'a> {
 #[derive(Debug, Clone)]
 pub(crate) struct TraitMethod<'a> {
     pub(crate) disable_inherent_default: bool,
+    /// Whether the vtable entry catches panics,
+    /// which the trait object then resumes on the caller's side.
+    pub(crate) catch_panic: bool,
+    /// Whether the vtable entry uses the `extern "C-unwind"` ABI.
+    pub(crate) c_unwind: bool,
+    /// Whether this is an `async fn` (or returns `impl Future`),
+    /// in which case `output` is the `RFuture` that the method returns.
+    pub(crate) is_async: bool,
     pub(crate) unsafety: Option<&'a Unsafe>,
     pub(crate) abi: Option<&'a Abi>,
     /// Attributes applied to the method in the vtable.
@@ -411,6 +506,9 @@ pub(crate) struct TraitMethod<'a> {
     pub(crate) params: Vec<MethodParam<'a>>,
     /// The return type of this method,if None this returns `()`.
     pub(crate) output: Option<syn::Type>,
+    /// Whether the return type is `Self`,
+    /// which the trait object returns as a trait object with the same pointer type.
+    pub(crate) returns_self: bool,
 
     /// Whether the return type borrows from self
     pub(crate) return_borrow_kind: Option<BorrowKind>,
@@ -438,12 +536,44 @@ pub(crate) struct MethodParam<'a> {
     pub(crate) ty: syn::Type,
     /// The pattern for the parameter
     pub(crate) pattern: &'a syn::Pat,
+    /// Whether the parameter type is `&Self`,
+    /// which the trait object passes through the vtable as an `RRef<'_, ()>`.
+    pub(crate) is_self_ref: bool,
+}
+
+impl<'a> MethodParam<'a> {
+    /// The lifetime of the `&Self` parameter,
+    /// `None` if it's elided or this isn't a `&Self` parameter.
+    pub(crate) fn self_ref_lifetime(&self) -> Option<&syn::Lifetime> {
+        match &self.ty {
+            syn::Type::Reference(ref_) if self.is_self_ref => ref_.lifetime.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `ty` is `Self`.
+fn is_self_ty(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path.qself.is_none() && path.path.is_ident("Self"),
+        _ => false,
+    }
+}
+
+/// Whether `ty` is `&Self` or `&'a Self`.
+fn is_self_ref_ty(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(ref_) => ref_.mutability.is_none() && is_self_ty(&ref_.elem),
+        _ => false,
+    }
 }
 
 impl<'a> TraitMethod<'a> {
     pub fn new(
         mwa: MethodWithAttrs<'a>,
         disable_inherent_default: bool,
+        catch_panic: bool,
+        c_unwind: bool,
         ctokens: &'a CommonTokens,
         arena: &'a Arenas,
     ) -> Result<Option<Self>, syn::Error> {
@@ -488,23 +618,52 @@ impl<'a> TraitMethod<'a> {
 
         let mut return_borrow_kind = None::<BorrowKind>;
 
-        let output = match &decl.output {
-            syn::ReturnType::Default => None,
-            syn::ReturnType::Type(_, ty) => {
-                let mut ty: syn::Type = (**ty).clone();
-                if let SelfParam::ByRef { lifetime, .. } = &mut self_param {
-                    let visit_data = LifetimeUnelider::new(lifetime).visit_type(&mut ty);
+        // `async fn` methods (and those returning `impl Future`)
+        // are lowered to methods returning an `RFuture` that borrows `self`.
+        let async_output = match (&decl.output, &decl.asyncness) {
+            (syn::ReturnType::Default, Some(_)) => Some(parse_quote!(())),
+            (syn::ReturnType::Type(_, ty), Some(_)) => Some((**ty).clone()),
+            (syn::ReturnType::Type(_, ty), None) => impl_future_output(ty),
+            (syn::ReturnType::Default, None) => None,
+        };
+        let is_async = async_output.is_some();
 
-                    return_borrow_kind = visit_data.found_borrow_kind;
+        let returns_self = match (&decl.output, is_async) {
+            (syn::ReturnType::Type(_, ty), false) => is_self_ty(ty),
+            _ => false,
+        };
 
-                    if let Some(lt) = visit_data.additional_lifetime_def {
-                        lifetimes.push(lt);
-                    }
-                }
-                Some(ty)
+        let output = match (async_output, &self_param) {
+            (Some(_), SelfParam::ByVal) => {
+                errors.push_err(spanned_err!(
+                    method_signature.ident,
+                    "async methods in #[sabi_trait] traits must take `&self` or `&mut self`",
+                ));
+                None
             }
+            (Some(output), SelfParam::ByRef { lifetime, .. }) => {
+                let lifetime = lifetime.cloned().unwrap_or_else(|| parse_quote!('_));
+                Some(parse_quote!(::abi_stable::std_types::RFuture<#lifetime, #output>))
+            }
+            (None, _) => match &decl.output {
+                syn::ReturnType::Default => None,
+                syn::ReturnType::Type(_, ty) => Some((**ty).clone()),
+            },
         };
 
+        let output = output.map(|mut ty: syn::Type| {
+            if let SelfParam::ByRef { lifetime, .. } = &mut self_param {
+                let visit_data = LifetimeUnelider::new(lifetime).visit_type(&mut ty);
+
+                return_borrow_kind = visit_data.found_borrow_kind;
+
+                if let Some(lt) = visit_data.additional_lifetime_def {
+                    lifetimes.push(lt);
+                }
+            }
+            ty
+        });
+
         let default = mwa
             .item
             .default
@@ -543,6 +702,7 @@ impl<'a> TraitMethod<'a> {
                 name: arena.alloc(name),
                 ty: ty.clone(),
                 pattern,
+                is_self_ref: is_self_ref_ty(ty),
             });
         }
 
@@ -550,6 +710,9 @@ impl<'a> TraitMethod<'a> {
 
         Ok(Some(Self {
             disable_inherent_default,
+            catch_panic,
+            c_unwind,
+            is_async,
             unsafety: method_signature.unsafety.as_ref(),
             abi: method_signature.abi.as_ref(),
             derive_attrs: arena.alloc(mwa.attrs.derive_attrs),
@@ -559,6 +722,7 @@ impl<'a> TraitMethod<'a> {
             self_param,
             params,
             output,
+            returns_self,
             return_borrow_kind,
             where_clause,
             default,
@@ -566,6 +730,16 @@ impl<'a> TraitMethod<'a> {
         }))
     }
 
+    /// Whether this method has `&Self` parameters or returns `Self`.
+    pub fn uses_self(&self) -> bool {
+        self.returns_self || self.has_self_params()
+    }
+
+    /// Whether this method has `&Self` parameters.
+    pub fn has_self_params(&self) -> bool {
+        self.params.iter().any(|p| p.is_self_ref)
+    }
+
     /// Returns a clone of `self`,
     /// where usages of associated types are replaced for use in `which_item`.
     ///
@@ -580,12 +754,15 @@ impl<'a> TraitMethod<'a> {
         F: FnMut(&Ident) -> Option<ReplaceWith>,
     {
         let mut errors = LinearResult::ok(());
+        let returns_self = self.returns_self;
 
+        // `&Self` parameters and `Self` return types are handled by the `MethodsTokenizer`.
         for param in self
             .params
             .iter_mut()
+            .filter(|x| !x.is_self_ref)
             .map(|x| &mut x.ty)
-            .chain(self.output.as_mut())
+            .chain(self.output.as_mut().filter(|_| !returns_self))
         {
             replace_self_path::replace_self_path(param, replace_with.clone(), &mut is_assoc_type)
                 .combine_into_err(&mut errors);
@@ -682,17 +859,13 @@ impl<'a> ToTokens for GenericsTokenizer<'a> {
 
 ////////////////////////////////////////////////////////////////////////////////
 
-/// Represents a `Deserialize<'de>` supertrait bound.
-#[derive(Debug, Clone)]
-pub(crate) struct DeserializeBound;
-
 /// Used to returns the information about supertraits,to construct TraitDefinition.
 struct GetSupertraits<'a> {
     impld_traits: Vec<TraitImplness<'a>>,
     unimpld_traits: Vec<&'a Ident>,
+    sabi_supertraits: Vec<SabiSupertrait<'a>>,
     lifetime_bounds: Punctuated<&'a Lifetime, Comma>,
     iterator_item: Option<&'a syn::Type>,
-    deserialize_bound: Option<DeserializeBound>,
     trait_flags: TraitStruct<bool>,
     trait_spans: TraitStruct<Span>,
     errors: LinearResult<()>,
@@ -707,9 +880,95 @@ pub(crate) struct TraitImplness<'a> {
     pub(crate) _marker: PhantomData<&'a ()>,
 }
 
+/// A supertrait that is itself a `#[sabi_trait]` trait,
+/// which the trait object can be upcasted into.
+#[derive(Debug, Clone)]
+pub(crate) struct SabiSupertrait<'a> {
+    /// The supertrait bound, as written in the trait definition.
+    pub(crate) bound: &'a syn::TraitBound,
+    /// The name of the supertrait.
+    pub(crate) ident: &'a Ident,
+    /// The path to the module generated by `#[sabi_trait]` for the supertrait.
+    pub(crate) module: syn::Path,
+    /// The lifetime arguments of the supertrait.
+    pub(crate) lifetime_args: Vec<&'a syn::GenericArgument>,
+    /// The type and const arguments of the supertrait.
+    pub(crate) other_args: Vec<&'a syn::GenericArgument>,
+}
+
+impl<'a> SabiSupertrait<'a> {
+    fn new(bound: &'a syn::TraitBound) -> Result<Self, syn::Error> {
+        use syn::{GenericArgument, PathArguments};
+
+        if let Some(lifetimes) = &bound.lifetimes {
+            return_spanned_err!(
+                lifetimes,
+                "higher ranked lifetimes are not supported in `#[sabi_trait]` supertraits"
+            );
+        }
+        if let syn::TraitBoundModifier::Maybe(_) = bound.modifier {
+            return_spanned_err!(bound, "unexpected `?` in supertrait bound");
+        }
+
+        let last = bound.path.segments.last().expect("BUG");
+
+        let mut lifetime_args = Vec::new();
+        let mut other_args = Vec::new();
+        match &last.arguments {
+            PathArguments::None => {}
+            PathArguments::AngleBracketed(args) => {
+                for arg in &args.args {
+                    match arg {
+                        GenericArgument::Lifetime(_) => lifetime_args.push(arg),
+                        GenericArgument::Type(_) | GenericArgument::Const(_) => {
+                            other_args.push(arg)
+                        }
+                        _ => return_spanned_err!(
+                            arg,
+                            "associated type bindings are not supported in \
+                             `#[sabi_trait]` supertraits"
+                        ),
+                    }
+                }
+            }
+            PathArguments::Parenthesized(_) => {
+                return_spanned_err!(last, "unexpected parenthesized arguments in supertrait")
+            }
+        }
+
+        let mut module = bound.path.clone();
+        let module_segment = module.segments.last_mut().expect("BUG");
+        module_segment.ident = parse_str_as_ident(&format!("{}_trait", last.ident));
+        module_segment.arguments = PathArguments::None;
+
+        Ok(Self {
+            bound,
+            ident: &last.ident,
+            module,
+            lifetime_args,
+            other_args,
+        })
+    }
+
+    /// Tokenizes the generic arguments of the supertrait,
+    /// with `after_lifetimes` after the lifetime arguments.
+    pub(crate) fn args(&self, after_lifetimes: &TokenStream2) -> TokenStream2 {
+        let lifetime_args = &self.lifetime_args;
+        let other_args = &self.other_args;
+        quote!( #(#lifetime_args,)* #after_lifetimes #(#other_args,)* )
+    }
+
+    /// Gets an identifier derived from the name of the supertrait,
+    /// eg: `Trait_Interface` from `derived_ident("", "_Interface")`.
+    pub(crate) fn derived_ident(&self, prefix: &str, suffix: &str) -> Ident {
+        parse_str_as_ident(&format!("{}{}{}", prefix, self.ident, suffix))
+    }
+}
+
 /// Processes the supertrait bounds of a trait definition.
 fn get_supertraits<'a, I>(
     supertraits: I,
+    marked_supertraits: &[Ident],
     lifetime_params: &HashSet<&'a Lifetime>,
     which_object: WhichObject,
     arenas: &'a Arenas,
@@ -732,10 +991,11 @@ where
         _marker: PhantomData,
     });
 
+    let mut sabi_supertraits = Vec::new();
+    let mut used_markers = vec![false; marked_supertraits.len()];
     let mut lifetime_bounds = Punctuated::<&'a Lifetime, Comma>::new();
     let mut iterator_item = None;
     let mut errors = LinearResult::ok(());
-    let deserialize_bound = None;
 
     for supertrait_bound in supertraits {
         match supertrait_bound {
@@ -750,6 +1010,14 @@ where
                     Some(&which_trait) => {
                         let usable_by = which_trait.usable_by();
                         match which_object {
+                            _ if !usable_by.dyn_trait() && !usable_by.robject() => {
+                                errors.push_err(spanned_err!(
+                                    trait_bound.path,
+                                    "this trait can only be used in \
+                                     #[sabi(impl_InterfaceType(...))] attributes \
+                                     and the impl_InterfaceType macro",
+                                ));
+                            }
                             WhichObject::DynTrait if !usable_by.dyn_trait() => {
                                 errors.push_err(spanned_err!(
                                     trait_bound.path,
@@ -788,16 +1056,15 @@ where
                                 iterator_item = iterator_item.or(iter_item);
                             }
                             WhichTrait::Deserialize => {
-                                errors.push_err(spanned_err!(
-                                    trait_bound.path,
-                                    "Deserialize is not currently supported."
-                                ));
-                            }
-                            WhichTrait::Serialize => {
-                                errors.push_err(spanned_err!(
-                                    trait_bound.path,
-                                    "Serialize is not currently supported."
-                                ));
+                                if let Err(e) = check_deserialize_bound(trait_bound) {
+                                    errors.push_err(e);
+                                }
+                                let deserialize = &mut trait_struct.deserialize;
+                                deserialize.bound =
+                                    parse_str_as_trait_bound("for<'de> ::serde::Deserialize<'de>")
+                                        .expect("BUG");
+                                SetSpanVisitor::new(span)
+                                    .visit_trait_bound_mut(&mut deserialize.bound);
                             }
                             WhichTrait::Eq | WhichTrait::PartialOrd => {
                                 set_impld(&mut trait_struct.partial_eq, span);
@@ -818,17 +1085,46 @@ where
                         }
                     }
                     None => {
-                        let list = trait_map
-                            .keys()
-                            .map(|x| x.to_string())
-                            .collect::<Vec<String>>();
-
-                        errors.push_err(spanned_err!(
-                            supertrait_bound,
-                            "Unexpected supertrait bound.\nExpected one of:\n{}",
-                            list.join("/"),
-                        ));
-                        break;
+                        let marker = marked_supertraits.iter().position(|x| x == trait_ident);
+                        if let Some(marker) = marker {
+                            used_markers[marker] = true;
+                        }
+
+                        match marker {
+                            Some(_) if which_object == WhichObject::DynTrait => {
+                                errors.push_err(spanned_err!(
+                                    trait_bound.path,
+                                    "`#[sabi_trait]` traits can only be supertraits of \
+                                     traits that don't use the #[sabi(use_dyntrait)] attribute.\n\
+                                     Expected one of:\n{}",
+                                    trait_map
+                                        .keys()
+                                        .map(|x| x.to_string())
+                                        .collect::<Vec<String>>()
+                                        .join("/"),
+                                ));
+                                break;
+                            }
+                            Some(_) => match SabiSupertrait::new(trait_bound) {
+                                Ok(x) => sabi_supertraits.push(x),
+                                Err(e) => errors.push_err(e),
+                            },
+                            None => {
+                                let list = trait_map
+                                    .keys()
+                                    .map(|x| x.to_string())
+                                    .collect::<Vec<String>>();
+
+                                errors.push_err(spanned_err!(
+                                    supertrait_bound,
+                                    "Unexpected supertrait bound.\nExpected one of:\n{}\n\
+                                     `#[sabi_trait]` traits must be listed in \
+                                     the #[sabi(supertrait(...))] attribute of the trait.",
+                                    list.join("/"),
+                                ));
+                                break;
+                            }
+                        }
                     }
                 }
             }
@@ -846,6 +1142,18 @@ where
         };
     }
 
+    for (marker, _) in marked_supertraits
+        .iter()
+        .zip(&used_markers)
+        .filter(|(_, used)| !**used)
+    {
+        errors.push_err(spanned_err!(
+            marker,
+            "there's no `#[sabi_trait]` supertrait named `{}` in the supertraits of this trait",
+            marker,
+        ));
+    }
+
     let iter_trait = &mut trait_struct.iterator;
     let de_iter_trait = &mut trait_struct.double_ended_iterator;
     if iter_trait.is_implemented || de_iter_trait.is_implemented {
@@ -886,9 +1194,9 @@ where
     GetSupertraits {
         impld_traits,
         unimpld_traits,
+        sabi_supertraits,
         lifetime_bounds,
         iterator_item,
-        deserialize_bound,
         trait_flags,
         trait_spans,
         errors,
@@ -940,26 +1248,63 @@ fn type_as_iter_path_arguments(ty: syn::Type) -> syn::PathArguments {
     syn::PathArguments::AngleBracketed(x)
 }
 
-/// Extracts the lifetime in `Deserialize<'lt>` out of a path component.
-#[allow(dead_code)]
-fn extract_deserialize_lifetime<'a>(
-    last_path_component: &syn::PathSegment,
-    arenas: &'a Arenas,
-) -> Result<&'a syn::Lifetime, syn::Error> {
+/// Checks that a `Deserialize` supertrait is written as `for<'a> Deserialize<'a>`,
+/// since the trait object can only be deserialized from any lifetime.
+fn check_deserialize_bound(trait_bound: &syn::TraitBound) -> Result<(), syn::Error> {
     use syn::{GenericArgument, PathArguments};
 
-    let angle_brackets = match &last_path_component.arguments {
-        PathArguments::AngleBracketed(x) => x,
-        _ => return_spanned_err!(last_path_component, "Expected a lifetime parameter inside"),
+    let last_path_component = trait_bound.path.segments.last().expect("BUG");
+
+    let hrtb_lifetimes = trait_bound
+        .lifetimes
+        .iter()
+        .flat_map(|x| &x.lifetimes)
+        .map(|x| &x.lifetime)
+        .collect::<Vec<&syn::Lifetime>>();
+
+    let is_valid = match &last_path_component.arguments {
+        PathArguments::AngleBracketed(x) if x.args.len() == 1 => match &x.args[0] {
+            GenericArgument::Lifetime(lt) => hrtb_lifetimes.contains(&lt),
+            _ => false,
+        },
+        _ => false,
     };
 
-    for gen_arg in &angle_brackets.args {
-        if let GenericArgument::Lifetime(lt) = gen_arg {
-            return Ok(arenas.alloc(lt.clone()));
-        }
+    if is_valid {
+        Ok(())
+    } else {
+        Err(spanned_err!(
+            trait_bound,
+            "Deserialize supertraits must be written as `for<'a> Deserialize<'a>`"
+        ))
     }
-    Err(spanned_err!(
-        last_path_component,
-        "Expected a lifetime parameter inside"
-    ))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Gets the `T` in an `impl Future<Output = T>` return type.
+fn impl_future_output(ty: &syn::Type) -> Option<syn::Type> {
+    let impl_trait = match ty {
+        syn::Type::ImplTrait(x) => x,
+        _ => return None,
+    };
+
+    impl_trait.bounds.iter().find_map(|bound| {
+        let last = match bound {
+            TypeParamBound::Trait(tb) => tb.path.segments.last()?,
+            TypeParamBound::Lifetime(_) => return None,
+        };
+        if last.ident != "Future" {
+            return None;
+        }
+        match &last.arguments {
+            syn::PathArguments::AngleBracketed(args) => {
+                args.args.iter().find_map(|arg| match arg {
+                    syn::GenericArgument::Binding(b) if b.ident == "Output" => Some(b.ty.clone()),
+                    _ => None,
+                })
+            }
+            _ => None,
+        }
+    })
 }
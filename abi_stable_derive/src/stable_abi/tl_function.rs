@@ -123,6 +123,7 @@ impl<'a> VisitedFieldMap<'a> {
                             paramret_lifetime_range,
                             return_type_layout,
                             is_unsafe: func.is_unsafe,
+                            is_c_unwind: func.is_c_unwind,
                         }
                     })
                     .collect::<Vec<CompTLFunction>>();
@@ -175,6 +176,7 @@ pub struct CompTLFunction {
     paramret_lifetime_range: LifetimeRange,
     param_type_layouts: TypeLayoutRange,
     is_unsafe: bool,
+    is_c_unwind: bool,
 }
 
 impl ToTokens for CompTLFunction {
@@ -192,6 +194,11 @@ impl ToTokens for CompTLFunction {
         } else {
             TokenStream2::new()
         };
+        let is_c_unwind = if self.is_c_unwind {
+            quote!( .set_c_unwind() )
+        } else {
+            TokenStream2::new()
+        };
 
         quote!(
             __CompTLFunction::new(
@@ -203,7 +210,8 @@ impl ToTokens for CompTLFunction {
                 #paramret_lifetime_range,
                 #param_type_layouts,
                 __TLFunctionQualifiers::NEW
-                    #is_unsafe,
+                    #is_unsafe
+                    #is_c_unwind,
             )
         )
         .to_tokens(ts);
@@ -81,3 +81,12 @@ where
 pub fn mangled_root_module_loader_name() -> String {
     mangle_ident("lib_header", "root module loader")
 }
+
+/// Gets the name of the static that contains the function which passes the
+/// `GlobalsExt` of the loader to an abi_stable library.
+///
+/// This does not have a trailing `'\0'`,
+/// you need to append it to pass the name to C APIs.
+pub fn mangled_globals_ext_initializer_name() -> String {
+    mangle_ident("lib_header", "globals extension initializer")
+}
@@ -1,10 +1,13 @@
 //! This crate is where extra tests which don't belong in examples go.
 
-use testing_interface_0::{ForTests, PrefixTypeMod0, TestingMod, TestingMod_Ref};
+use testing_interface_0::{
+    ForTests, Parser, Parser_TO, PrefixTypeMod0, TestingMod, TestingMod_Ref,
+};
 
 use abi_stable::{
     export_root_module, extern_fn_panic_handling,
     prefix_type::PrefixTypeTrait,
+    sabi_trait::TD_Opaque,
     std_types::{RArc, RBox, RStr, RString, RVec},
     traits::IntoReprC,
 };
@@ -23,6 +26,7 @@ pub fn get_library() -> TestingMod_Ref {
         greeter,
         for_tests,
         prefix_types_tests: PrefixTypeMod0 { field_a: 123 }.leak_into_prefix(),
+        parser,
     }
     .leak_into_prefix()
 }
@@ -55,3 +59,17 @@ pub extern "C" fn for_tests() -> ForTests {
         }
     }
 }
+
+struct DecimalParser;
+
+impl Parser for DecimalParser {
+    fn parse(&self, s: RStr<'_>) -> u32 {
+        s.parse().unwrap()
+    }
+}
+
+pub extern "C" fn parser() -> Parser_TO<'static, RBox<()>> {
+    extern_fn_panic_handling! {
+        Parser_TO::from_value(DecimalParser, TD_Opaque)
+    }
+}
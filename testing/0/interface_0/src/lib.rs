@@ -10,7 +10,7 @@
 
 use abi_stable::{
     library::RootModule,
-    package_version_strings,
+    package_version_strings, sabi_trait,
     sabi_types::VersionStrings,
     std_types::{RArc, RBox, RStr, RString, RVec},
     StableAbi,
@@ -35,6 +35,19 @@ pub struct TestingMod {
 
     /// An module used in prefix-type tests.
     pub prefix_types_tests: PrefixTypeMod0_Ref,
+
+    /// Constructs a trait object used in library poisoning tests.
+    pub parser: extern "C" fn() -> Parser_TO<'static, RBox<()>>,
+}
+
+////////////////////////////////////////////////////
+
+/// This trait is used to test that the trait objects of a poisoned library
+/// don't call into it.
+#[sabi_trait]
+pub trait Parser {
+    #[sabi(catch_panic)]
+    fn parse(&self, s: RStr<'_>) -> u32;
 }
 
 ////////////////////////////////////////////////////
@@ -3,11 +3,11 @@ use std::{io, sync::Arc};
 use core_extensions::SelfOps;
 
 use abi_stable::{
-    library::{development_utils::compute_library_path, RootModule},
+    library::{development_utils::compute_library_path, RootModule, SupervisorError},
     std_types::{RArc, RBox, RString, RVec},
 };
 
-use testing_interface_0::{PrefixTypeMod1_Ref, TestingMod_Ref};
+use testing_interface_0::{Parser, PrefixTypeMod1_Ref, TestingMod_Ref};
 
 fn main() -> io::Result<()> {
     let target: &std::path::Path = "../../../target/".as_ref();
@@ -66,6 +66,34 @@ pub fn run_dynamic_library_tests(mods: TestingMod_Ref) {
         let string_std = val.string.piped(RString::into_string);
         assert_ne!(string_std.as_ptr() as usize, val.string_address);
     }
+    {
+        let supervisor = TestingMod_Ref::supervisor();
+        supervisor.set_max_failures(1);
+
+        let parser = mods.parser()();
+        assert_eq!(supervisor.call(|| parser.parse("10".into())), Ok(10));
+
+        let res = supervisor.call(|| parser.parse("what".into()));
+        assert!(
+            matches!(res, Err(SupervisorError::Panicked { .. })),
+            "{:?}",
+            res
+        );
+        assert!(supervisor.is_poisoned());
+
+        // Calling the trait object doesn't call into the poisoned library.
+        let res = supervisor.call(|| parser.parse("10".into()));
+        assert!(
+            matches!(res, Err(SupervisorError::Poisoned { .. })),
+            "{:?}",
+            res
+        );
+        assert!(parser.obj.sabi_check_library().is_err());
+        assert_eq!(supervisor.failure_count(), 1);
+
+        supervisor.reset();
+        assert_eq!(parser.parse("3".into()), 3);
+    }
 
     println!();
     println!(".-------------------------.");